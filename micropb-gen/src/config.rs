@@ -307,6 +307,33 @@ config_decl! {
     /// ```
     bytes_type: [deref] Option<String>,
 
+    /// Validate `string` fields as UTF-8 when decoding.
+    ///
+    /// By default, `string` fields are validated unless the Editions feature `utf8_validation` is
+    /// set to `NONE`. Setting this option overrides the feature, which also allows validation to be
+    /// turned off for proto2 and proto3 fields.
+    ///
+    /// Fields without validation are generated with the container from
+    /// [`bytes_type`](Config::bytes_type) instead of [`string_type`](Config::string_type), since
+    /// they may hold invalid UTF-8. They're decoded without checking their contents, so they'll
+    /// never fail with `DecodeError::Utf8`. Unless [`no_accessors`](Config::no_accessors) is set,
+    /// singular fields get an additional `<field>_str()` accessor that checks the contents and
+    /// returns them as a `&str`, wrapped in an `Option` if the field has explicit presence. For
+    /// repeated and `map` fields, use `core::str::from_utf8`.
+    ///
+    /// The text format, JSON, and `serde` still treat these fields as strings. Invalid UTF-8 is
+    /// escaped in the text format and replaced with `U+FFFD` in JSON, while `serde` serialization
+    /// fails on it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use micropb_gen::{Generator, Config};
+    /// # let mut generator = micropb_gen::Generator::new();
+    /// // Decode `log_line` as raw bytes without validating them
+    /// generator.configure(".pkg.Telemetry.log_line", Config::new().validate_utf8(false));
+    /// ```
+    validate_utf8: Option<bool>,
+
     /// Container type that's generated for `map` fields.
     ///
     /// For decoding, the provided type must implement `PbMap`. For encoding, the type must
@@ -453,6 +480,61 @@ config_decl! {
     /// maximum encoded size of the enum, since signed integers always have a max size of 10 bytes.
     enum_unsigned: Option<bool>,
 
+    /// Generate closed enums as Rust enums.
+    ///
+    /// By default, all Protobuf enums are generated as open enums, which are newtypes around the
+    /// integer with an associated constant for each variant. If this is set on a closed enum
+    /// (proto2 enums, or editions enums with `enum_type = CLOSED`), it's generated as a Rust
+    /// `enum` with `#[repr]` of the [`enum_int_size`](Config::enum_int_size), along with a
+    /// `TryFrom<i32>` impl for converting numbers to variants. Open enums are not affected by
+    /// this option.
+    ///
+    /// Values of closed enums that don't match any variant are treated as unknown fields when
+    /// decoding. They are passed to the [`unknown_handler`](Config::unknown_handler) of the
+    /// message if there is one, and dropped otherwise. The same goes for map entries with
+    /// unknown enum values, which are passed to the handler in full.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use micropb_gen::{Config, Generator};
+    /// # let mut generator = Generator::new();
+    /// // Generate all closed enums as Rust enums
+    /// generator.configure(".", Config::new().closed_enum_as_rust_enum(true));
+    /// ```
+    ///
+    /// # Note
+    /// Enums from [`extern_type_path`](crate::Generator::extern_type_path) are assumed to be
+    /// open, so closed enums that are generated as Rust enums should not be used as extern
+    /// types.
+    closed_enum_as_rust_enum: Option<bool>,
+
+    /// Generate open enums as Rust enums with an `Unknown` variant.
+    ///
+    /// Instead of a newtype with associated constants, open enums are generated as a Rust `enum`
+    /// with a unit variant for each Protobuf variant, plus an `Unknown` variant that holds any
+    /// other integer value. This allows exhaustive `match` on enum values, so the compiler can
+    /// flag code that doesn't handle newly added variants. Conversions to and from the integer
+    /// type, `_MAX_SIZE`, `Debug` output, and the wire format are the same as the newtype
+    /// representation.
+    ///
+    /// `Unknown` should only hold values that don't match any variant, since `Unknown(1)` is not
+    /// equal to the variant with value 1. Convert from integers with `From` to uphold this.
+    ///
+    /// If [`closed_enum_as_rust_enum`](Config::closed_enum_as_rust_enum) isn't set, closed enums
+    /// are treated as open enums and are also affected by this option.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use micropb_gen::{Config, Generator};
+    /// # let mut generator = Generator::new();
+    /// generator.configure(".pkg.Enum", Config::new().open_enum_as_rust_enum(true));
+    /// ```
+    ///
+    /// # Note
+    /// Enums from [`extern_type_path`](crate::Generator::extern_type_path) are assumed to be
+    /// newtypes, so enums generated with this option should not be used as extern types.
+    open_enum_as_rust_enum: Option<bool>,
+
     /// Set attributes for generated types, such as messages and enums.
     ///
     /// The attribute string will be placed before type definitions. The string must be in the
@@ -496,6 +578,50 @@ config_decl! {
     /// Upper-level messages that reference this message will also have the trait disabled.
     no_clone_impl: Option<bool>,
 
+    /// Include message types in the `AnyMessage` registry enum.
+    ///
+    /// If any message has this option set, an enum named `AnyMessage` with a variant for each
+    /// such message is generated at the root of the output module. `AnyMessage::unpack` dispatches
+    /// a `google.protobuf.Any` to the matching variant based on its type URL, and
+    /// `AnyMessage::pack` packs the contained message back into an `Any`.
+    ///
+    /// Since the size of the enum is determined by its largest message, apply this only to the
+    /// messages that are actually carried inside `Any`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use micropb_gen::{Config, Generator};
+    /// # let mut generator = Generator::new();
+    /// // Register all messages in the `cmd` package
+    /// generator.configure(".cmd", Config::new().any_registry(true));
+    /// ```
+    any_registry: Option<bool>,
+
+    /// Retain the extension fields of messages that have `extensions` ranges.
+    ///
+    /// If set on a message with extension ranges, a field named `_extensions` of type
+    /// `micropb::extension::ExtensionFields` is added to the message struct, and the message
+    /// implements `micropb::extension::Extendable`. Fields within the extension ranges are stored
+    /// as raw bytes in `_extensions`, rather than being skipped or passed to the
+    /// [`unknown_handler`](Config::unknown_handler). Their values can be accessed with
+    /// `get_extension` and `set_extension`, using the descriptors generated from `extend` blocks.
+    ///
+    /// The storage is a bytes container, so it's configured with
+    /// [`bytes_type`](Config::bytes_type) and [`max_bytes`](Config::max_bytes) on the
+    /// `._extensions` path of the message. Messages without extension ranges are not affected by
+    /// this option.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use micropb_gen::{Config, Generator};
+    /// # let mut generator = Generator::new();
+    /// generator.use_container_heapless();
+    /// // Retain up to 32 bytes of extensions in `Request`
+    /// generator.configure(".vendor.Request", Config::new().retain_extensions(true));
+    /// generator.configure(".vendor.Request._extensions", Config::new().max_bytes(32));
+    /// ```
+    retain_extensions: Option<bool>,
+
     /// Add a custom handler on a message struct for handling unknown fields.
     ///
     /// When decoding a message, unknown fields are skipped by default. If a message has
@@ -7,6 +7,29 @@ pub mod google_ {
             pub r#file: ::std::vec::Vec<FileDescriptorProto>,
         }
         impl FileDescriptorSet {}
+        impl ::micropb::MessageName for FileDescriptorSet {
+            const FULL_NAME: &'static str = "google.protobuf.FileDescriptorSet";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FileDescriptorSet";
+        }
+        impl ::micropb::MessageMerge for FileDescriptorSet {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#file.pb_reserve(other.r#file.len());
+                for val_ref in other.r#file.iter() {
+                    self.r#file
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FileDescriptorSet {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -17,14 +40,18 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
                             let mut val: FileDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<FileDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#file.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -118,6 +145,111 @@ pub mod google_ {
                 self._has.r#edition().then_some(&self.r#edition)
             }
         }
+        impl ::micropb::MessageName for FileDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.FileDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FileDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for FileDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#package() {
+                    self.r#package = val_ref.clone();
+                    self._has.set_package();
+                }
+                self.r#dependency.pb_reserve(other.r#dependency.len());
+                for val_ref in other.r#dependency.iter() {
+                    self.r#dependency
+                        .pb_push(val_ref.clone())
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#public_dependency.pb_reserve(other.r#public_dependency.len());
+                for val_ref in other.r#public_dependency.iter() {
+                    self.r#public_dependency
+                        .pb_push(*val_ref)
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#weak_dependency.pb_reserve(other.r#weak_dependency.len());
+                for val_ref in other.r#weak_dependency.iter() {
+                    self.r#weak_dependency
+                        .pb_push(*val_ref)
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#message_type.pb_reserve(other.r#message_type.len());
+                for val_ref in other.r#message_type.iter() {
+                    self.r#message_type
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#enum_type.pb_reserve(other.r#enum_type.len());
+                for val_ref in other.r#enum_type.iter() {
+                    self.r#enum_type
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#service.pb_reserve(other.r#service.len());
+                for val_ref in other.r#service.iter() {
+                    self.r#service
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#extension.pb_reserve(other.r#extension.len());
+                for val_ref in other.r#extension.iter() {
+                    self.r#extension
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#source_code_info()
+                {
+                    if !self._has.r#source_code_info() {
+                        self.r#source_code_info = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(
+                        &mut self.r#source_code_info,
+                        val_ref,
+                    )?;
+                    self._has.set_source_code_info();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#syntax() {
+                    self.r#syntax = val_ref.clone();
+                    self._has.set_syntax();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#edition() {
+                    self.r#edition = *val_ref;
+                    self._has.set_edition();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FileDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -128,6 +260,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -153,6 +288,7 @@ pub mod google_ {
                                 decoder
                                     .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
                             };
+                            decoder.spend_elem_budget::<::std::string::String>()?;
                             if let (Err(_), false) = (
                                 self.r#dependency.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -168,6 +304,7 @@ pub mod google_ {
                                         |decoder| decoder.decode_int32().map(|v| v as _),
                                     )?;
                             } else {
+                                decoder.spend_elem_budget::<i32>()?;
                                 if let (Err(_), false) = (
                                     self
                                         .r#public_dependency
@@ -186,6 +323,7 @@ pub mod google_ {
                                         |decoder| decoder.decode_int32().map(|v| v as _),
                                     )?;
                             } else {
+                                decoder.spend_elem_budget::<i32>()?;
                                 if let (Err(_), false) = (
                                     self
                                         .r#weak_dependency
@@ -200,8 +338,9 @@ pub mod google_ {
                             let mut val: DescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<DescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#message_type.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -213,8 +352,9 @@ pub mod google_ {
                             let mut val: EnumDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<EnumDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#enum_type.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -226,8 +366,9 @@ pub mod google_ {
                             let mut val: ServiceDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<ServiceDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#service.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -239,8 +380,9 @@ pub mod google_ {
                             let mut val: FieldDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<FieldDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#extension.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -251,14 +393,14 @@ pub mod google_ {
                         8u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
                         9u32 => {
                             let mut_ref = &mut self.r#source_code_info;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_source_code_info();
                         }
@@ -555,6 +697,106 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for DescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.DescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.DescriptorProto";
+        }
+        impl ::micropb::MessageMerge for DescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                self.r#field.pb_reserve(other.r#field.len());
+                for val_ref in other.r#field.iter() {
+                    self.r#field
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#extension.pb_reserve(other.r#extension.len());
+                for val_ref in other.r#extension.iter() {
+                    self.r#extension
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#nested_type.pb_reserve(other.r#nested_type.len());
+                for val_ref in other.r#nested_type.iter() {
+                    self.r#nested_type
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#enum_type.pb_reserve(other.r#enum_type.len());
+                for val_ref in other.r#enum_type.iter() {
+                    self.r#enum_type
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#extension_range.pb_reserve(other.r#extension_range.len());
+                for val_ref in other.r#extension_range.iter() {
+                    self.r#extension_range
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#oneof_decl.pb_reserve(other.r#oneof_decl.len());
+                for val_ref in other.r#oneof_decl.iter() {
+                    self.r#oneof_decl
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                self.r#reserved_range.pb_reserve(other.r#reserved_range.len());
+                for val_ref in other.r#reserved_range.iter() {
+                    self.r#reserved_range
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#reserved_name.pb_reserve(other.r#reserved_name.len());
+                for val_ref in other.r#reserved_name.iter() {
+                    self.r#reserved_name
+                        .pb_push(val_ref.clone())
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for DescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -565,6 +807,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -579,8 +824,9 @@ pub mod google_ {
                             let mut val: FieldDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<FieldDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#field.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -592,8 +838,9 @@ pub mod google_ {
                             let mut val: FieldDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<FieldDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#extension.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -605,8 +852,9 @@ pub mod google_ {
                             let mut val: DescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<DescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#nested_type.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -618,8 +866,9 @@ pub mod google_ {
                             let mut val: EnumDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<EnumDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#enum_type.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -631,8 +880,10 @@ pub mod google_ {
                             let mut val: DescriptorProto_::ExtensionRange = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<DescriptorProto_::ExtensionRange>()?;
                             if let (Err(_), false) = (
                                 self.r#extension_range.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -644,8 +895,9 @@ pub mod google_ {
                             let mut val: OneofDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<OneofDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#oneof_decl.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -656,7 +908,7 @@ pub mod google_ {
                         7u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -664,8 +916,10 @@ pub mod google_ {
                             let mut val: DescriptorProto_::ReservedRange = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<DescriptorProto_::ReservedRange>()?;
                             if let (Err(_), false) = (
                                 self.r#reserved_range.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -680,6 +934,7 @@ pub mod google_ {
                                 decoder
                                     .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
                             };
+                            decoder.spend_elem_budget::<::std::string::String>()?;
                             if let (Err(_), false) = (
                                 self.r#reserved_name.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -843,6 +1098,37 @@ pub mod google_ {
                     self
                 }
             }
+            impl ::micropb::MessageName for ExtensionRange {
+                const FULL_NAME: &'static str = "google.protobuf.DescriptorProto.ExtensionRange";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.DescriptorProto.ExtensionRange";
+            }
+            impl ::micropb::MessageMerge for ExtensionRange {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#start() {
+                        self.r#start = *val_ref;
+                        self._has.set_start();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#end() {
+                        self.r#end = *val_ref;
+                        self._has.set_end();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                        if !self._has.r#options() {
+                            self.r#options = ::core::default::Default::default();
+                        }
+                        ::micropb::MessageMerge::merge_from(
+                            &mut self.r#options,
+                            val_ref,
+                        )?;
+                        self._has.set_options();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for ExtensionRange {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -853,6 +1139,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -874,7 +1163,7 @@ pub mod google_ {
                             3u32 => {
                                 let mut_ref = &mut self.r#options;
                                 {
-                                    mut_ref.decode_len_delimited(decoder)?;
+                                    decoder.decode_nested_message(mut_ref)?;
                                 };
                                 self._has.set_options();
                             }
@@ -1070,6 +1359,27 @@ pub mod google_ {
                     self
                 }
             }
+            impl ::micropb::MessageName for ReservedRange {
+                const FULL_NAME: &'static str = "google.protobuf.DescriptorProto.ReservedRange";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.DescriptorProto.ReservedRange";
+            }
+            impl ::micropb::MessageMerge for ReservedRange {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#start() {
+                        self.r#start = *val_ref;
+                        self._has.set_start();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#end() {
+                        self.r#end = *val_ref;
+                        self._has.set_end();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for ReservedRange {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -1080,6 +1390,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -1276,6 +1589,51 @@ pub mod google_ {
                 self._has.r#verification().then_some(&self.r#verification)
             }
         }
+        impl ::micropb::MessageName for ExtensionRangeOptions {
+            const FULL_NAME: &'static str = "google.protobuf.ExtensionRangeOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.ExtensionRangeOptions";
+        }
+        impl ::micropb::MessageMerge for ExtensionRangeOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#declaration.pb_reserve(other.r#declaration.len());
+                for val_ref in other.r#declaration.iter() {
+                    self.r#declaration
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#verification() {
+                    self.r#verification = *val_ref;
+                    self._has.set_verification();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for ExtensionRangeOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -1286,14 +1644,18 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         999u32 => {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -1305,8 +1667,12 @@ pub mod google_ {
                             let mut val: ExtensionRangeOptions_::Declaration = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<
+                                    ExtensionRangeOptions_::Declaration,
+                                >()?;
                             if let (Err(_), false) = (
                                 self.r#declaration.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -1317,7 +1683,7 @@ pub mod google_ {
                         50u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -1403,6 +1769,39 @@ pub mod google_ {
                     self._has.r#repeated().then_some(&self.r#repeated)
                 }
             }
+            impl ::micropb::MessageName for Declaration {
+                const FULL_NAME: &'static str = "google.protobuf.ExtensionRangeOptions.Declaration";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.ExtensionRangeOptions.Declaration";
+            }
+            impl ::micropb::MessageMerge for Declaration {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#number() {
+                        self.r#number = *val_ref;
+                        self._has.set_number();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#full_name() {
+                        self.r#full_name = val_ref.clone();
+                        self._has.set_full_name();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#type() {
+                        self.r#type = val_ref.clone();
+                        self._has.set_type();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#reserved() {
+                        self.r#reserved = *val_ref;
+                        self._has.set_reserved();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#repeated() {
+                        self.r#repeated = *val_ref;
+                        self._has.set_repeated();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for Declaration {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -1413,6 +1812,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -1611,6 +2013,37 @@ pub mod google_ {
                 /// All the extensions of the range must be declared.
                 pub const Declaration: Self = Self(0);
                 pub const Unverified: Self = Self(1);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Declaration,
+                    Self::Unverified,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        VerificationState::Declaration => {
+                            ::core::option::Option::Some("DECLARATION")
+                        }
+                        VerificationState::Unverified => {
+                            ::core::option::Option::Some("UNVERIFIED")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "DECLARATION" => ::core::option::Option::Some(Self::Declaration),
+                        "UNVERIFIED" => ::core::option::Option::Some(Self::Unverified),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for VerificationState {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for VerificationState {
                 fn default() -> Self {
@@ -2245,6 +2678,67 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for FieldDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.FieldDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FieldDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for FieldDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#number() {
+                    self.r#number = *val_ref;
+                    self._has.set_number();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#label() {
+                    self.r#label = *val_ref;
+                    self._has.set_label();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#type() {
+                    self.r#type = *val_ref;
+                    self._has.set_type();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#type_name() {
+                    self.r#type_name = val_ref.clone();
+                    self._has.set_type_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#extendee() {
+                    self.r#extendee = val_ref.clone();
+                    self._has.set_extendee();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#default_value() {
+                    self.r#default_value = val_ref.clone();
+                    self._has.set_default_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#oneof_index() {
+                    self.r#oneof_index = *val_ref;
+                    self._has.set_oneof_index();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#json_name() {
+                    self.r#json_name = val_ref.clone();
+                    self._has.set_json_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#proto3_optional()
+                {
+                    self.r#proto3_optional = *val_ref;
+                    self._has.set_proto3_optional();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FieldDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -2255,6 +2749,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -2336,7 +2833,7 @@ pub mod google_ {
                         8u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -2397,6 +2894,81 @@ pub mod google_ {
                 pub const Sint32: Self = Self(17);
                 /// Uses ZigZag encoding.
                 pub const Sint64: Self = Self(18);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Double,
+                    Self::Float,
+                    Self::Int64,
+                    Self::Uint64,
+                    Self::Int32,
+                    Self::Fixed64,
+                    Self::Fixed32,
+                    Self::Bool,
+                    Self::String,
+                    Self::Group,
+                    Self::Message,
+                    Self::Bytes,
+                    Self::Uint32,
+                    Self::Enum,
+                    Self::Sfixed32,
+                    Self::Sfixed64,
+                    Self::Sint32,
+                    Self::Sint64,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        Type::Double => ::core::option::Option::Some("TYPE_DOUBLE"),
+                        Type::Float => ::core::option::Option::Some("TYPE_FLOAT"),
+                        Type::Int64 => ::core::option::Option::Some("TYPE_INT64"),
+                        Type::Uint64 => ::core::option::Option::Some("TYPE_UINT64"),
+                        Type::Int32 => ::core::option::Option::Some("TYPE_INT32"),
+                        Type::Fixed64 => ::core::option::Option::Some("TYPE_FIXED64"),
+                        Type::Fixed32 => ::core::option::Option::Some("TYPE_FIXED32"),
+                        Type::Bool => ::core::option::Option::Some("TYPE_BOOL"),
+                        Type::String => ::core::option::Option::Some("TYPE_STRING"),
+                        Type::Group => ::core::option::Option::Some("TYPE_GROUP"),
+                        Type::Message => ::core::option::Option::Some("TYPE_MESSAGE"),
+                        Type::Bytes => ::core::option::Option::Some("TYPE_BYTES"),
+                        Type::Uint32 => ::core::option::Option::Some("TYPE_UINT32"),
+                        Type::Enum => ::core::option::Option::Some("TYPE_ENUM"),
+                        Type::Sfixed32 => ::core::option::Option::Some("TYPE_SFIXED32"),
+                        Type::Sfixed64 => ::core::option::Option::Some("TYPE_SFIXED64"),
+                        Type::Sint32 => ::core::option::Option::Some("TYPE_SINT32"),
+                        Type::Sint64 => ::core::option::Option::Some("TYPE_SINT64"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "TYPE_DOUBLE" => ::core::option::Option::Some(Self::Double),
+                        "TYPE_FLOAT" => ::core::option::Option::Some(Self::Float),
+                        "TYPE_INT64" => ::core::option::Option::Some(Self::Int64),
+                        "TYPE_UINT64" => ::core::option::Option::Some(Self::Uint64),
+                        "TYPE_INT32" => ::core::option::Option::Some(Self::Int32),
+                        "TYPE_FIXED64" => ::core::option::Option::Some(Self::Fixed64),
+                        "TYPE_FIXED32" => ::core::option::Option::Some(Self::Fixed32),
+                        "TYPE_BOOL" => ::core::option::Option::Some(Self::Bool),
+                        "TYPE_STRING" => ::core::option::Option::Some(Self::String),
+                        "TYPE_GROUP" => ::core::option::Option::Some(Self::Group),
+                        "TYPE_MESSAGE" => ::core::option::Option::Some(Self::Message),
+                        "TYPE_BYTES" => ::core::option::Option::Some(Self::Bytes),
+                        "TYPE_UINT32" => ::core::option::Option::Some(Self::Uint32),
+                        "TYPE_ENUM" => ::core::option::Option::Some(Self::Enum),
+                        "TYPE_SFIXED32" => ::core::option::Option::Some(Self::Sfixed32),
+                        "TYPE_SFIXED64" => ::core::option::Option::Some(Self::Sfixed64),
+                        "TYPE_SINT32" => ::core::option::Option::Some(Self::Sint32),
+                        "TYPE_SINT64" => ::core::option::Option::Some(Self::Sint64),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for Type {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for Type {
                 fn default() -> Self {
@@ -2449,6 +3021,36 @@ pub mod google_ {
                 /// it's explicitly prohibited.  In Editions, the `field_presence` feature
                 /// can be used to get this behavior.
                 pub const Required: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Optional,
+                    Self::Repeated,
+                    Self::Required,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        Label::Optional => ::core::option::Option::Some("LABEL_OPTIONAL"),
+                        Label::Repeated => ::core::option::Option::Some("LABEL_REPEATED"),
+                        Label::Required => ::core::option::Option::Some("LABEL_REQUIRED"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "LABEL_OPTIONAL" => ::core::option::Option::Some(Self::Optional),
+                        "LABEL_REPEATED" => ::core::option::Option::Some(Self::Repeated),
+                        "LABEL_REQUIRED" => ::core::option::Option::Some(Self::Required),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for Label {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for Label {
                 fn default() -> Self {
@@ -2853,6 +3455,30 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for OneofDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.OneofDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.OneofDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for OneofDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for OneofDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -2863,6 +3489,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -2876,7 +3505,7 @@ pub mod google_ {
                         2u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -2983,6 +3612,56 @@ pub mod google_ {
                 self._has.r#options().then_some(&self.r#options)
             }
         }
+        impl ::micropb::MessageName for EnumDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.EnumDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.EnumDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for EnumDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                self.r#value.pb_reserve(other.r#value.len());
+                for val_ref in other.r#value.iter() {
+                    self.r#value
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                self.r#reserved_range.pb_reserve(other.r#reserved_range.len());
+                for val_ref in other.r#reserved_range.iter() {
+                    self.r#reserved_range
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#reserved_name.pb_reserve(other.r#reserved_name.len());
+                for val_ref in other.r#reserved_name.iter() {
+                    self.r#reserved_name
+                        .pb_push(val_ref.clone())
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for EnumDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -2993,6 +3672,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -3007,8 +3689,9 @@ pub mod google_ {
                             let mut val: EnumValueDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<EnumValueDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#value.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -3019,7 +3702,7 @@ pub mod google_ {
                         3u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -3027,8 +3710,12 @@ pub mod google_ {
                             let mut val: EnumDescriptorProto_::EnumReservedRange = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<
+                                    EnumDescriptorProto_::EnumReservedRange,
+                                >()?;
                             if let (Err(_), false) = (
                                 self.r#reserved_range.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -3043,6 +3730,7 @@ pub mod google_ {
                                 decoder
                                     .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
                             };
+                            decoder.spend_elem_budget::<::std::string::String>()?;
                             if let (Err(_), false) = (
                                 self.r#reserved_name.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -3091,6 +3779,27 @@ pub mod google_ {
                     self._has.r#end().then_some(&self.r#end)
                 }
             }
+            impl ::micropb::MessageName for EnumReservedRange {
+                const FULL_NAME: &'static str = "google.protobuf.EnumDescriptorProto.EnumReservedRange";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.EnumDescriptorProto.EnumReservedRange";
+            }
+            impl ::micropb::MessageMerge for EnumReservedRange {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#start() {
+                        self.r#start = *val_ref;
+                        self._has.set_start();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#end() {
+                        self.r#end = *val_ref;
+                        self._has.set_end();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for EnumReservedRange {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -3101,6 +3810,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -3388,6 +4100,34 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for EnumValueDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.EnumValueDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.EnumValueDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for EnumValueDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#number() {
+                    self.r#number = *val_ref;
+                    self._has.set_number();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for EnumValueDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -3398,6 +4138,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -3419,7 +4162,7 @@ pub mod google_ {
                         3u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -3542,6 +4285,40 @@ pub mod google_ {
                 self._has.r#options().then_some(&self.r#options)
             }
         }
+        impl ::micropb::MessageName for ServiceDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.ServiceDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.ServiceDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for ServiceDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                self.r#method.pb_reserve(other.r#method.len());
+                for val_ref in other.r#method.iter() {
+                    self.r#method
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for ServiceDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -3552,6 +4329,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -3566,8 +4346,9 @@ pub mod google_ {
                             let mut val: MethodDescriptorProto = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<MethodDescriptorProto>()?;
                             if let (Err(_), false) = (
                                 self.r#method.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -3578,7 +4359,7 @@ pub mod google_ {
                         3u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -3727,6 +4508,48 @@ pub mod google_ {
                 self._has.r#server_streaming().then_some(&self.r#server_streaming)
             }
         }
+        impl ::micropb::MessageName for MethodDescriptorProto {
+            const FULL_NAME: &'static str = "google.protobuf.MethodDescriptorProto";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.MethodDescriptorProto";
+        }
+        impl ::micropb::MessageMerge for MethodDescriptorProto {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#name() {
+                    self.r#name = val_ref.clone();
+                    self._has.set_name();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#input_type() {
+                    self.r#input_type = val_ref.clone();
+                    self._has.set_input_type();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#output_type() {
+                    self.r#output_type = val_ref.clone();
+                    self._has.set_output_type();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#options() {
+                    if !self._has.r#options() {
+                        self.r#options = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#options, val_ref)?;
+                    self._has.set_options();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#client_streaming()
+                {
+                    self.r#client_streaming = *val_ref;
+                    self._has.set_client_streaming();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#server_streaming()
+                {
+                    self.r#server_streaming = *val_ref;
+                    self._has.set_server_streaming();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for MethodDescriptorProto {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -3737,6 +4560,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -3766,7 +4592,7 @@ pub mod google_ {
                         4u32 => {
                             let mut_ref = &mut self.r#options;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_options();
                         }
@@ -4252,6 +5078,134 @@ pub mod google_ {
                 self._has.r#features().then_some(&self.r#features)
             }
         }
+        impl ::micropb::MessageName for FileOptions {
+            const FULL_NAME: &'static str = "google.protobuf.FileOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FileOptions";
+        }
+        impl ::micropb::MessageMerge for FileOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#java_package() {
+                    self.r#java_package = val_ref.clone();
+                    self._has.set_java_package();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#java_outer_classname()
+                {
+                    self.r#java_outer_classname = val_ref.clone();
+                    self._has.set_java_outer_classname();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#java_multiple_files()
+                {
+                    self.r#java_multiple_files = *val_ref;
+                    self._has.set_java_multiple_files();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#java_generate_equals_and_hash()
+                {
+                    self.r#java_generate_equals_and_hash = *val_ref;
+                    self._has.set_java_generate_equals_and_hash();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#java_string_check_utf8()
+                {
+                    self.r#java_string_check_utf8 = *val_ref;
+                    self._has.set_java_string_check_utf8();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#optimize_for() {
+                    self.r#optimize_for = *val_ref;
+                    self._has.set_optimize_for();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#go_package() {
+                    self.r#go_package = val_ref.clone();
+                    self._has.set_go_package();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#cc_generic_services()
+                {
+                    self.r#cc_generic_services = *val_ref;
+                    self._has.set_cc_generic_services();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#java_generic_services()
+                {
+                    self.r#java_generic_services = *val_ref;
+                    self._has.set_java_generic_services();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#py_generic_services()
+                {
+                    self.r#py_generic_services = *val_ref;
+                    self._has.set_py_generic_services();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#cc_enable_arenas()
+                {
+                    self.r#cc_enable_arenas = *val_ref;
+                    self._has.set_cc_enable_arenas();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#objc_class_prefix()
+                {
+                    self.r#objc_class_prefix = val_ref.clone();
+                    self._has.set_objc_class_prefix();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#csharp_namespace()
+                {
+                    self.r#csharp_namespace = val_ref.clone();
+                    self._has.set_csharp_namespace();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#swift_prefix() {
+                    self.r#swift_prefix = val_ref.clone();
+                    self._has.set_swift_prefix();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#php_class_prefix()
+                {
+                    self.r#php_class_prefix = val_ref.clone();
+                    self._has.set_php_class_prefix();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#php_namespace() {
+                    self.r#php_namespace = val_ref.clone();
+                    self._has.set_php_namespace();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#php_metadata_namespace()
+                {
+                    self.r#php_metadata_namespace = val_ref.clone();
+                    self._has.set_php_metadata_namespace();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#ruby_package() {
+                    self.r#ruby_package = val_ref.clone();
+                    self._has.set_ruby_package();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FileOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -4262,6 +5216,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -4421,7 +5378,7 @@ pub mod google_ {
                         50u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -4429,8 +5386,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -4463,6 +5421,40 @@ pub mod google_ {
                 pub const CodeSize: Self = Self(2);
                 /// Generate code using MessageLite and the lite runtime.
                 pub const LiteRuntime: Self = Self(3);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Speed,
+                    Self::CodeSize,
+                    Self::LiteRuntime,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        OptimizeMode::Speed => ::core::option::Option::Some("SPEED"),
+                        OptimizeMode::CodeSize => {
+                            ::core::option::Option::Some("CODE_SIZE")
+                        }
+                        OptimizeMode::LiteRuntime => {
+                            ::core::option::Option::Some("LITE_RUNTIME")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "SPEED" => ::core::option::Option::Some(Self::Speed),
+                        "CODE_SIZE" => ::core::option::Option::Some(Self::CodeSize),
+                        "LITE_RUNTIME" => ::core::option::Option::Some(Self::LiteRuntime),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for OptimizeMode {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for OptimizeMode {
                 fn default() -> Self {
@@ -5373,6 +6365,63 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for MessageOptions {
+            const FULL_NAME: &'static str = "google.protobuf.MessageOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.MessageOptions";
+        }
+        impl ::micropb::MessageMerge for MessageOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#message_set_wire_format()
+                {
+                    self.r#message_set_wire_format = *val_ref;
+                    self._has.set_message_set_wire_format();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#no_standard_descriptor_accessor()
+                {
+                    self.r#no_standard_descriptor_accessor = *val_ref;
+                    self._has.set_no_standard_descriptor_accessor();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#map_entry() {
+                    self.r#map_entry = *val_ref;
+                    self._has.set_map_entry();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#deprecated_legacy_json_field_conflicts()
+                {
+                    self.r#deprecated_legacy_json_field_conflicts = *val_ref;
+                    self._has.set_deprecated_legacy_json_field_conflicts();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for MessageOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -5383,6 +6432,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -5429,7 +6481,7 @@ pub mod google_ {
                         12u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -5437,8 +6489,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -6209,6 +7262,101 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for FieldOptions {
+            const FULL_NAME: &'static str = "google.protobuf.FieldOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FieldOptions";
+        }
+        impl ::micropb::MessageMerge for FieldOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#ctype() {
+                    self.r#ctype = *val_ref;
+                    self._has.set_ctype();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#packed() {
+                    self.r#packed = *val_ref;
+                    self._has.set_packed();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#jstype() {
+                    self.r#jstype = *val_ref;
+                    self._has.set_jstype();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#lazy() {
+                    self.r#lazy = *val_ref;
+                    self._has.set_lazy();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#unverified_lazy()
+                {
+                    self.r#unverified_lazy = *val_ref;
+                    self._has.set_unverified_lazy();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#weak() {
+                    self.r#weak = *val_ref;
+                    self._has.set_weak();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#debug_redact() {
+                    self.r#debug_redact = *val_ref;
+                    self._has.set_debug_redact();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#retention() {
+                    self.r#retention = *val_ref;
+                    self._has.set_retention();
+                }
+                self.r#targets.pb_reserve(other.r#targets.len());
+                for val_ref in other.r#targets.iter() {
+                    self.r#targets
+                        .pb_push(*val_ref)
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                self.r#edition_defaults.pb_reserve(other.r#edition_defaults.len());
+                for val_ref in other.r#edition_defaults.iter() {
+                    self.r#edition_defaults
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#feature_support()
+                {
+                    if !self._has.r#feature_support() {
+                        self.r#feature_support = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(
+                        &mut self.r#feature_support,
+                        val_ref,
+                    )?;
+                    self._has.set_feature_support();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FieldOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -6219,6 +7367,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -6312,6 +7463,8 @@ pub mod google_ {
                                         },
                                     )?;
                             } else {
+                                decoder
+                                    .spend_elem_budget::<FieldOptions_::OptionTargetType>()?;
                                 if let (Err(_), false) = (
                                     self
                                         .r#targets
@@ -6330,8 +7483,10 @@ pub mod google_ {
                             let mut val: FieldOptions_::EditionDefault = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<FieldOptions_::EditionDefault>()?;
                             if let (Err(_), false) = (
                                 self.r#edition_defaults.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -6342,14 +7497,14 @@ pub mod google_ {
                         21u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
                         22u32 => {
                             let mut_ref = &mut self.r#feature_support;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_feature_support();
                         }
@@ -6357,8 +7512,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -6475,6 +7631,27 @@ pub mod google_ {
                     self
                 }
             }
+            impl ::micropb::MessageName for EditionDefault {
+                const FULL_NAME: &'static str = "google.protobuf.FieldOptions.EditionDefault";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FieldOptions.EditionDefault";
+            }
+            impl ::micropb::MessageMerge for EditionDefault {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#edition() {
+                        self.r#edition = *val_ref;
+                        self._has.set_edition();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#value() {
+                        self.r#value = val_ref.clone();
+                        self._has.set_value();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for EditionDefault {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -6485,6 +7662,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             3u32 => {
@@ -6813,6 +7993,43 @@ pub mod google_ {
                     self
                 }
             }
+            impl ::micropb::MessageName for FeatureSupport {
+                const FULL_NAME: &'static str = "google.protobuf.FieldOptions.FeatureSupport";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FieldOptions.FeatureSupport";
+            }
+            impl ::micropb::MessageMerge for FeatureSupport {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#edition_introduced()
+                    {
+                        self.r#edition_introduced = *val_ref;
+                        self._has.set_edition_introduced();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#edition_deprecated()
+                    {
+                        self.r#edition_deprecated = *val_ref;
+                        self._has.set_edition_deprecated();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#deprecation_warning()
+                    {
+                        self.r#deprecation_warning = val_ref.clone();
+                        self._has.set_deprecation_warning();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#edition_removed()
+                    {
+                        self.r#edition_removed = *val_ref;
+                        self._has.set_edition_removed();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for FeatureSupport {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -6823,6 +8040,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -7000,6 +8220,38 @@ pub mod google_ {
                 /// alias the original buffer.
                 pub const Cord: Self = Self(1);
                 pub const StringPiece: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::String,
+                    Self::Cord,
+                    Self::StringPiece,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        CType::String => ::core::option::Option::Some("STRING"),
+                        CType::Cord => ::core::option::Option::Some("CORD"),
+                        CType::StringPiece => {
+                            ::core::option::Option::Some("STRING_PIECE")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "STRING" => ::core::option::Option::Some(Self::String),
+                        "CORD" => ::core::option::Option::Some(Self::Cord),
+                        "STRING_PIECE" => ::core::option::Option::Some(Self::StringPiece),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for CType {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for CType {
                 fn default() -> Self {
@@ -7036,6 +8288,36 @@ pub mod google_ {
                 pub const JsString: Self = Self(1);
                 /// Use JavaScript numbers.
                 pub const JsNumber: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::JsNormal,
+                    Self::JsString,
+                    Self::JsNumber,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        JSType::JsNormal => ::core::option::Option::Some("JS_NORMAL"),
+                        JSType::JsString => ::core::option::Option::Some("JS_STRING"),
+                        JSType::JsNumber => ::core::option::Option::Some("JS_NUMBER"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "JS_NORMAL" => ::core::option::Option::Some(Self::JsNormal),
+                        "JS_STRING" => ::core::option::Option::Some(Self::JsString),
+                        "JS_NUMBER" => ::core::option::Option::Some(Self::JsNumber),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for JSType {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for JSType {
                 fn default() -> Self {
@@ -7072,6 +8354,48 @@ pub mod google_ {
                 pub const RetentionUnknown: Self = Self(0);
                 pub const RetentionRuntime: Self = Self(1);
                 pub const RetentionSource: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::RetentionUnknown,
+                    Self::RetentionRuntime,
+                    Self::RetentionSource,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        OptionRetention::RetentionUnknown => {
+                            ::core::option::Option::Some("RETENTION_UNKNOWN")
+                        }
+                        OptionRetention::RetentionRuntime => {
+                            ::core::option::Option::Some("RETENTION_RUNTIME")
+                        }
+                        OptionRetention::RetentionSource => {
+                            ::core::option::Option::Some("RETENTION_SOURCE")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "RETENTION_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::RetentionUnknown)
+                        }
+                        "RETENTION_RUNTIME" => {
+                            ::core::option::Option::Some(Self::RetentionRuntime)
+                        }
+                        "RETENTION_SOURCE" => {
+                            ::core::option::Option::Some(Self::RetentionSource)
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for OptionRetention {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for OptionRetention {
                 fn default() -> Self {
@@ -7122,6 +8446,97 @@ pub mod google_ {
                 pub const TargetTypeEnumEntry: Self = Self(7);
                 pub const TargetTypeService: Self = Self(8);
                 pub const TargetTypeMethod: Self = Self(9);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::TargetTypeUnknown,
+                    Self::TargetTypeFile,
+                    Self::TargetTypeExtensionRange,
+                    Self::TargetTypeMessage,
+                    Self::TargetTypeField,
+                    Self::TargetTypeOneof,
+                    Self::TargetTypeEnum,
+                    Self::TargetTypeEnumEntry,
+                    Self::TargetTypeService,
+                    Self::TargetTypeMethod,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        OptionTargetType::TargetTypeUnknown => {
+                            ::core::option::Option::Some("TARGET_TYPE_UNKNOWN")
+                        }
+                        OptionTargetType::TargetTypeFile => {
+                            ::core::option::Option::Some("TARGET_TYPE_FILE")
+                        }
+                        OptionTargetType::TargetTypeExtensionRange => {
+                            ::core::option::Option::Some("TARGET_TYPE_EXTENSION_RANGE")
+                        }
+                        OptionTargetType::TargetTypeMessage => {
+                            ::core::option::Option::Some("TARGET_TYPE_MESSAGE")
+                        }
+                        OptionTargetType::TargetTypeField => {
+                            ::core::option::Option::Some("TARGET_TYPE_FIELD")
+                        }
+                        OptionTargetType::TargetTypeOneof => {
+                            ::core::option::Option::Some("TARGET_TYPE_ONEOF")
+                        }
+                        OptionTargetType::TargetTypeEnum => {
+                            ::core::option::Option::Some("TARGET_TYPE_ENUM")
+                        }
+                        OptionTargetType::TargetTypeEnumEntry => {
+                            ::core::option::Option::Some("TARGET_TYPE_ENUM_ENTRY")
+                        }
+                        OptionTargetType::TargetTypeService => {
+                            ::core::option::Option::Some("TARGET_TYPE_SERVICE")
+                        }
+                        OptionTargetType::TargetTypeMethod => {
+                            ::core::option::Option::Some("TARGET_TYPE_METHOD")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "TARGET_TYPE_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::TargetTypeUnknown)
+                        }
+                        "TARGET_TYPE_FILE" => {
+                            ::core::option::Option::Some(Self::TargetTypeFile)
+                        }
+                        "TARGET_TYPE_EXTENSION_RANGE" => {
+                            ::core::option::Option::Some(Self::TargetTypeExtensionRange)
+                        }
+                        "TARGET_TYPE_MESSAGE" => {
+                            ::core::option::Option::Some(Self::TargetTypeMessage)
+                        }
+                        "TARGET_TYPE_FIELD" => {
+                            ::core::option::Option::Some(Self::TargetTypeField)
+                        }
+                        "TARGET_TYPE_ONEOF" => {
+                            ::core::option::Option::Some(Self::TargetTypeOneof)
+                        }
+                        "TARGET_TYPE_ENUM" => {
+                            ::core::option::Option::Some(Self::TargetTypeEnum)
+                        }
+                        "TARGET_TYPE_ENUM_ENTRY" => {
+                            ::core::option::Option::Some(Self::TargetTypeEnumEntry)
+                        }
+                        "TARGET_TYPE_SERVICE" => {
+                            ::core::option::Option::Some(Self::TargetTypeService)
+                        }
+                        "TARGET_TYPE_METHOD" => {
+                            ::core::option::Option::Some(Self::TargetTypeMethod)
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for OptionTargetType {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for OptionTargetType {
                 fn default() -> Self {
@@ -7477,6 +8892,37 @@ pub mod google_ {
                 self._has.r#features().then_some(&self.r#features)
             }
         }
+        impl ::micropb::MessageName for OneofOptions {
+            const FULL_NAME: &'static str = "google.protobuf.OneofOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.OneofOptions";
+        }
+        impl ::micropb::MessageMerge for OneofOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for OneofOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -7487,12 +8933,15 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -7500,8 +8949,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -7625,6 +9075,51 @@ pub mod google_ {
                 self._has.r#features().then_some(&self.r#features)
             }
         }
+        impl ::micropb::MessageName for EnumOptions {
+            const FULL_NAME: &'static str = "google.protobuf.EnumOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.EnumOptions";
+        }
+        impl ::micropb::MessageMerge for EnumOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#allow_alias() {
+                    self.r#allow_alias = *val_ref;
+                    self._has.set_allow_alias();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#deprecated_legacy_json_field_conflicts()
+                {
+                    self.r#deprecated_legacy_json_field_conflicts = *val_ref;
+                    self._has.set_deprecated_legacy_json_field_conflicts();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for EnumOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -7635,6 +9130,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         2u32 => {
@@ -7665,7 +9163,7 @@ pub mod google_ {
                         7u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -7673,8 +9171,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -7873,6 +9372,56 @@ pub mod google_ {
                 self._has.r#feature_support().then_some(&self.r#feature_support)
             }
         }
+        impl ::micropb::MessageName for EnumValueOptions {
+            const FULL_NAME: &'static str = "google.protobuf.EnumValueOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.EnumValueOptions";
+        }
+        impl ::micropb::MessageMerge for EnumValueOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#debug_redact() {
+                    self.r#debug_redact = *val_ref;
+                    self._has.set_debug_redact();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#feature_support()
+                {
+                    if !self._has.r#feature_support() {
+                        self.r#feature_support = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(
+                        &mut self.r#feature_support,
+                        val_ref,
+                    )?;
+                    self._has.set_feature_support();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for EnumValueOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -7883,6 +9432,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -7896,7 +9448,7 @@ pub mod google_ {
                         2u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -7911,7 +9463,7 @@ pub mod google_ {
                         4u32 => {
                             let mut_ref = &mut self.r#feature_support;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_feature_support();
                         }
@@ -7919,8 +9471,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -8089,6 +9642,41 @@ pub mod google_ {
                 self._has.r#deprecated().then_some(&self.r#deprecated)
             }
         }
+        impl ::micropb::MessageName for ServiceOptions {
+            const FULL_NAME: &'static str = "google.protobuf.ServiceOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.ServiceOptions";
+        }
+        impl ::micropb::MessageMerge for ServiceOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for ServiceOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -8099,12 +9687,15 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         34u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -8120,8 +9711,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -8250,6 +9842,47 @@ pub mod google_ {
                 self._has.r#features().then_some(&self.r#features)
             }
         }
+        impl ::micropb::MessageName for MethodOptions {
+            const FULL_NAME: &'static str = "google.protobuf.MethodOptions";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.MethodOptions";
+        }
+        impl ::micropb::MessageMerge for MethodOptions {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#deprecated() {
+                    self.r#deprecated = *val_ref;
+                    self._has.set_deprecated();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#idempotency_level()
+                {
+                    self.r#idempotency_level = *val_ref;
+                    self._has.set_idempotency_level();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#features() {
+                    if !self._has.r#features() {
+                        self.r#features = ::core::default::Default::default();
+                    }
+                    ::micropb::MessageMerge::merge_from(&mut self.r#features, val_ref)?;
+                    self._has.set_features();
+                }
+                self.r#uninterpreted_option
+                    .pb_reserve(other.r#uninterpreted_option.len());
+                for val_ref in other.r#uninterpreted_option.iter() {
+                    self.r#uninterpreted_option
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for MethodOptions {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -8260,6 +9893,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         33u32 => {
@@ -8283,7 +9919,7 @@ pub mod google_ {
                         35u32 => {
                             let mut_ref = &mut self.r#features;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
                             self._has.set_features();
                         }
@@ -8291,8 +9927,9 @@ pub mod google_ {
                             let mut val: UninterpretedOption = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<UninterpretedOption>()?;
                             if let (Err(_), false) = (
                                 self.r#uninterpreted_option.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -8324,6 +9961,46 @@ pub mod google_ {
                 pub const NoSideEffects: Self = Self(1);
                 /// idempotent, but may have side effects
                 pub const Idempotent: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::IdempotencyUnknown,
+                    Self::NoSideEffects,
+                    Self::Idempotent,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        IdempotencyLevel::IdempotencyUnknown => {
+                            ::core::option::Option::Some("IDEMPOTENCY_UNKNOWN")
+                        }
+                        IdempotencyLevel::NoSideEffects => {
+                            ::core::option::Option::Some("NO_SIDE_EFFECTS")
+                        }
+                        IdempotencyLevel::Idempotent => {
+                            ::core::option::Option::Some("IDEMPOTENT")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "IDEMPOTENCY_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::IdempotencyUnknown)
+                        }
+                        "NO_SIDE_EFFECTS" => {
+                            ::core::option::Option::Some(Self::NoSideEffects)
+                        }
+                        "IDEMPOTENT" => ::core::option::Option::Some(Self::Idempotent),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for IdempotencyLevel {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for IdempotencyLevel {
                 fn default() -> Self {
@@ -8503,6 +10180,59 @@ pub mod google_ {
                 self._has.r#aggregate_value().then_some(&self.r#aggregate_value)
             }
         }
+        impl ::micropb::MessageName for UninterpretedOption {
+            const FULL_NAME: &'static str = "google.protobuf.UninterpretedOption";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.UninterpretedOption";
+        }
+        impl ::micropb::MessageMerge for UninterpretedOption {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#name.pb_reserve(other.r#name.len());
+                for val_ref in other.r#name.iter() {
+                    self.r#name
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#identifier_value()
+                {
+                    self.r#identifier_value = val_ref.clone();
+                    self._has.set_identifier_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#positive_int_value()
+                {
+                    self.r#positive_int_value = *val_ref;
+                    self._has.set_positive_int_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#negative_int_value()
+                {
+                    self.r#negative_int_value = *val_ref;
+                    self._has.set_negative_int_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#double_value() {
+                    self.r#double_value = *val_ref;
+                    self._has.set_double_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#string_value() {
+                    self.r#string_value = val_ref.clone();
+                    self._has.set_string_value();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#aggregate_value()
+                {
+                    self.r#aggregate_value = val_ref.clone();
+                    self._has.set_aggregate_value();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for UninterpretedOption {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -8513,14 +10243,19 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         2u32 => {
                             let mut val: UninterpretedOption_::NamePart = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<UninterpretedOption_::NamePart>()?;
                             if let (Err(_), false) = (
                                 self.r#name.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -8614,6 +10349,28 @@ pub mod google_ {
                     self._has.r#is_extension().then_some(&self.r#is_extension)
                 }
             }
+            impl ::micropb::MessageName for NamePart {
+                const FULL_NAME: &'static str = "google.protobuf.UninterpretedOption.NamePart";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.UninterpretedOption.NamePart";
+            }
+            impl ::micropb::MessageMerge for NamePart {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#name_part() {
+                        self.r#name_part = val_ref.clone();
+                        self._has.set_name_part();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#is_extension()
+                    {
+                        self.r#is_extension = *val_ref;
+                        self._has.set_is_extension();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for NamePart {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -8624,6 +10381,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -9198,6 +10958,47 @@ pub mod google_ {
                 self
             }
         }
+        impl ::micropb::MessageName for FeatureSet {
+            const FULL_NAME: &'static str = "google.protobuf.FeatureSet";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FeatureSet";
+        }
+        impl ::micropb::MessageMerge for FeatureSet {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let ::core::option::Option::Some(val_ref) = other.r#field_presence() {
+                    self.r#field_presence = *val_ref;
+                    self._has.set_field_presence();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#enum_type() {
+                    self.r#enum_type = *val_ref;
+                    self._has.set_enum_type();
+                }
+                if let ::core::option::Option::Some(val_ref) = other
+                    .r#repeated_field_encoding()
+                {
+                    self.r#repeated_field_encoding = *val_ref;
+                    self._has.set_repeated_field_encoding();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#utf8_validation()
+                {
+                    self.r#utf8_validation = *val_ref;
+                    self._has.set_utf8_validation();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#message_encoding()
+                {
+                    self.r#message_encoding = *val_ref;
+                    self._has.set_message_encoding();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#json_format() {
+                    self.r#json_format = *val_ref;
+                    self._has.set_json_format();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FeatureSet {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -9208,6 +11009,9 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
@@ -9290,6 +11094,51 @@ pub mod google_ {
                 pub const Explicit: Self = Self(1);
                 pub const Implicit: Self = Self(2);
                 pub const LegacyRequired: Self = Self(3);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::Explicit,
+                    Self::Implicit,
+                    Self::LegacyRequired,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        FieldPresence::Unknown => {
+                            ::core::option::Option::Some("FIELD_PRESENCE_UNKNOWN")
+                        }
+                        FieldPresence::Explicit => {
+                            ::core::option::Option::Some("EXPLICIT")
+                        }
+                        FieldPresence::Implicit => {
+                            ::core::option::Option::Some("IMPLICIT")
+                        }
+                        FieldPresence::LegacyRequired => {
+                            ::core::option::Option::Some("LEGACY_REQUIRED")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "FIELD_PRESENCE_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "EXPLICIT" => ::core::option::Option::Some(Self::Explicit),
+                        "IMPLICIT" => ::core::option::Option::Some(Self::Implicit),
+                        "LEGACY_REQUIRED" => {
+                            ::core::option::Option::Some(Self::LegacyRequired)
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for FieldPresence {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for FieldPresence {
                 fn default() -> Self {
@@ -9326,6 +11175,40 @@ pub mod google_ {
                 pub const Unknown: Self = Self(0);
                 pub const Open: Self = Self(1);
                 pub const Closed: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::Open,
+                    Self::Closed,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        EnumType::Unknown => {
+                            ::core::option::Option::Some("ENUM_TYPE_UNKNOWN")
+                        }
+                        EnumType::Open => ::core::option::Option::Some("OPEN"),
+                        EnumType::Closed => ::core::option::Option::Some("CLOSED"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "ENUM_TYPE_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "OPEN" => ::core::option::Option::Some(Self::Open),
+                        "CLOSED" => ::core::option::Option::Some(Self::Closed),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for EnumType {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for EnumType {
                 fn default() -> Self {
@@ -9359,6 +11242,46 @@ pub mod google_ {
                 pub const Unknown: Self = Self(0);
                 pub const Packed: Self = Self(1);
                 pub const Expanded: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::Packed,
+                    Self::Expanded,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        RepeatedFieldEncoding::Unknown => {
+                            ::core::option::Option::Some(
+                                "REPEATED_FIELD_ENCODING_UNKNOWN",
+                            )
+                        }
+                        RepeatedFieldEncoding::Packed => {
+                            ::core::option::Option::Some("PACKED")
+                        }
+                        RepeatedFieldEncoding::Expanded => {
+                            ::core::option::Option::Some("EXPANDED")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "REPEATED_FIELD_ENCODING_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "PACKED" => ::core::option::Option::Some(Self::Packed),
+                        "EXPANDED" => ::core::option::Option::Some(Self::Expanded),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for RepeatedFieldEncoding {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for RepeatedFieldEncoding {
                 fn default() -> Self {
@@ -9394,6 +11317,40 @@ pub mod google_ {
                 pub const Unknown: Self = Self(0);
                 pub const Verify: Self = Self(2);
                 pub const None: Self = Self(3);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::Verify,
+                    Self::None,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        Utf8Validation::Unknown => {
+                            ::core::option::Option::Some("UTF8_VALIDATION_UNKNOWN")
+                        }
+                        Utf8Validation::Verify => ::core::option::Option::Some("VERIFY"),
+                        Utf8Validation::None => ::core::option::Option::Some("NONE"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "UTF8_VALIDATION_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "VERIFY" => ::core::option::Option::Some(Self::Verify),
+                        "NONE" => ::core::option::Option::Some(Self::None),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for Utf8Validation {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for Utf8Validation {
                 fn default() -> Self {
@@ -9427,6 +11384,46 @@ pub mod google_ {
                 pub const Unknown: Self = Self(0);
                 pub const LengthPrefixed: Self = Self(1);
                 pub const Delimited: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::LengthPrefixed,
+                    Self::Delimited,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        MessageEncoding::Unknown => {
+                            ::core::option::Option::Some("MESSAGE_ENCODING_UNKNOWN")
+                        }
+                        MessageEncoding::LengthPrefixed => {
+                            ::core::option::Option::Some("LENGTH_PREFIXED")
+                        }
+                        MessageEncoding::Delimited => {
+                            ::core::option::Option::Some("DELIMITED")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "MESSAGE_ENCODING_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "LENGTH_PREFIXED" => {
+                            ::core::option::Option::Some(Self::LengthPrefixed)
+                        }
+                        "DELIMITED" => ::core::option::Option::Some(Self::Delimited),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for MessageEncoding {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for MessageEncoding {
                 fn default() -> Self {
@@ -9462,6 +11459,44 @@ pub mod google_ {
                 pub const Unknown: Self = Self(0);
                 pub const Allow: Self = Self(1);
                 pub const LegacyBestEffort: Self = Self(2);
+                /// All known values of the enum, in declaration order, excluding aliases
+                pub const VALUES: &'static [Self] = &[
+                    Self::Unknown,
+                    Self::Allow,
+                    Self::LegacyBestEffort,
+                ];
+                /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                    match *self {
+                        JsonFormat::Unknown => {
+                            ::core::option::Option::Some("JSON_FORMAT_UNKNOWN")
+                        }
+                        JsonFormat::Allow => ::core::option::Option::Some("ALLOW"),
+                        JsonFormat::LegacyBestEffort => {
+                            ::core::option::Option::Some("LEGACY_BEST_EFFORT")
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                /// Get the enum value from its Protobuf name
+                pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                    match name {
+                        "JSON_FORMAT_UNKNOWN" => {
+                            ::core::option::Option::Some(Self::Unknown)
+                        }
+                        "ALLOW" => ::core::option::Option::Some(Self::Allow),
+                        "LEGACY_BEST_EFFORT" => {
+                            ::core::option::Option::Some(Self::LegacyBestEffort)
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            impl ::core::str::FromStr for JsonFormat {
+                type Err = ::micropb::UnknownEnumName;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
             }
             impl core::default::Default for JsonFormat {
                 fn default() -> Self {
@@ -9683,6 +11718,39 @@ pub mod google_ {
                 self._has.r#maximum_edition().then_some(&self.r#maximum_edition)
             }
         }
+        impl ::micropb::MessageName for FeatureSetDefaults {
+            const FULL_NAME: &'static str = "google.protobuf.FeatureSetDefaults";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FeatureSetDefaults";
+        }
+        impl ::micropb::MessageMerge for FeatureSetDefaults {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#defaults.pb_reserve(other.r#defaults.len());
+                for val_ref in other.r#defaults.iter() {
+                    self.r#defaults
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#minimum_edition()
+                {
+                    self.r#minimum_edition = *val_ref;
+                    self._has.set_minimum_edition();
+                }
+                if let ::core::option::Option::Some(val_ref) = other.r#maximum_edition()
+                {
+                    self.r#maximum_edition = *val_ref;
+                    self._has.set_maximum_edition();
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for FeatureSetDefaults {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -9693,14 +11761,21 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
                             let mut val: FeatureSetDefaults_::FeatureSetEditionDefault = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<
+                                    FeatureSetDefaults_::FeatureSetEditionDefault,
+                                >()?;
                             if let (Err(_), false) = (
                                 self.r#defaults.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -9776,6 +11851,47 @@ pub mod google_ {
                     self._has.r#fixed_features().then_some(&self.r#fixed_features)
                 }
             }
+            impl ::micropb::MessageName for FeatureSetEditionDefault {
+                const FULL_NAME: &'static str = "google.protobuf.FeatureSetDefaults.FeatureSetEditionDefault";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FeatureSetDefaults.FeatureSetEditionDefault";
+            }
+            impl ::micropb::MessageMerge for FeatureSetEditionDefault {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    if let ::core::option::Option::Some(val_ref) = other.r#edition() {
+                        self.r#edition = *val_ref;
+                        self._has.set_edition();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#overridable_features()
+                    {
+                        if !self._has.r#overridable_features() {
+                            self.r#overridable_features = ::core::default::Default::default();
+                        }
+                        ::micropb::MessageMerge::merge_from(
+                            &mut self.r#overridable_features,
+                            val_ref,
+                        )?;
+                        self._has.set_overridable_features();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#fixed_features()
+                    {
+                        if !self._has.r#fixed_features() {
+                            self.r#fixed_features = ::core::default::Default::default();
+                        }
+                        ::micropb::MessageMerge::merge_from(
+                            &mut self.r#fixed_features,
+                            val_ref,
+                        )?;
+                        self._has.set_fixed_features();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for FeatureSetEditionDefault {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -9786,6 +11902,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             3u32 => {
@@ -9801,14 +11920,14 @@ pub mod google_ {
                             4u32 => {
                                 let mut_ref = &mut self.r#overridable_features;
                                 {
-                                    mut_ref.decode_len_delimited(decoder)?;
+                                    decoder.decode_nested_message(mut_ref)?;
                                 };
                                 self._has.set_overridable_features();
                             }
                             5u32 => {
                                 let mut_ref = &mut self.r#fixed_features;
                                 {
-                                    mut_ref.decode_len_delimited(decoder)?;
+                                    decoder.decode_nested_message(mut_ref)?;
                                 };
                                 self._has.set_fixed_features();
                             }
@@ -10019,6 +12138,29 @@ pub mod google_ {
             pub r#location: ::std::vec::Vec<SourceCodeInfo_::Location>,
         }
         impl SourceCodeInfo {}
+        impl ::micropb::MessageName for SourceCodeInfo {
+            const FULL_NAME: &'static str = "google.protobuf.SourceCodeInfo";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.SourceCodeInfo";
+        }
+        impl ::micropb::MessageMerge for SourceCodeInfo {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#location.pb_reserve(other.r#location.len());
+                for val_ref in other.r#location.iter() {
+                    self.r#location
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for SourceCodeInfo {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -10029,14 +12171,18 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
                             let mut val: SourceCodeInfo_::Location = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder.spend_elem_budget::<SourceCodeInfo_::Location>()?;
                             if let (Err(_), false) = (
                                 self.r#location.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -10158,6 +12304,50 @@ pub mod google_ {
                     self._has.r#trailing_comments().then_some(&self.r#trailing_comments)
                 }
             }
+            impl ::micropb::MessageName for Location {
+                const FULL_NAME: &'static str = "google.protobuf.SourceCodeInfo.Location";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.SourceCodeInfo.Location";
+            }
+            impl ::micropb::MessageMerge for Location {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    self.r#path.pb_reserve(other.r#path.len());
+                    for val_ref in other.r#path.iter() {
+                        self.r#path
+                            .pb_push(*val_ref)
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                    self.r#span.pb_reserve(other.r#span.len());
+                    for val_ref in other.r#span.iter() {
+                        self.r#span
+                            .pb_push(*val_ref)
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#leading_comments()
+                    {
+                        self.r#leading_comments = val_ref.clone();
+                        self._has.set_leading_comments();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other
+                        .r#trailing_comments()
+                    {
+                        self.r#trailing_comments = val_ref.clone();
+                        self._has.set_trailing_comments();
+                    }
+                    self.r#leading_detached_comments
+                        .pb_reserve(other.r#leading_detached_comments.len());
+                    for val_ref in other.r#leading_detached_comments.iter() {
+                        self.r#leading_detached_comments
+                            .pb_push(val_ref.clone())
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for Location {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -10168,6 +12358,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -10178,6 +12371,7 @@ pub mod google_ {
                                             |decoder| decoder.decode_int32().map(|v| v as _),
                                         )?;
                                 } else {
+                                    decoder.spend_elem_budget::<i32>()?;
                                     if let (Err(_), false) = (
                                         self.r#path.pb_push(decoder.decode_int32()? as _),
                                         decoder.ignore_repeated_cap_err,
@@ -10194,6 +12388,7 @@ pub mod google_ {
                                             |decoder| decoder.decode_int32().map(|v| v as _),
                                         )?;
                                 } else {
+                                    decoder.spend_elem_budget::<i32>()?;
                                     if let (Err(_), false) = (
                                         self.r#span.pb_push(decoder.decode_int32()? as _),
                                         decoder.ignore_repeated_cap_err,
@@ -10225,6 +12420,7 @@ pub mod google_ {
                                     decoder
                                         .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
                                 };
+                                decoder.spend_elem_budget::<::std::string::String>()?;
                                 if let (Err(_), false) = (
                                     self.r#leading_detached_comments.pb_push(val),
                                     decoder.ignore_repeated_cap_err,
@@ -10314,6 +12510,29 @@ pub mod google_ {
             pub r#annotation: ::std::vec::Vec<GeneratedCodeInfo_::Annotation>,
         }
         impl GeneratedCodeInfo {}
+        impl ::micropb::MessageName for GeneratedCodeInfo {
+            const FULL_NAME: &'static str = "google.protobuf.GeneratedCodeInfo";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.GeneratedCodeInfo";
+        }
+        impl ::micropb::MessageMerge for GeneratedCodeInfo {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#annotation.pb_reserve(other.r#annotation.len());
+                for val_ref in other.r#annotation.iter() {
+                    self.r#annotation
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
         impl ::micropb::MessageDecode for GeneratedCodeInfo {
             fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                 &mut self,
@@ -10324,14 +12543,19 @@ pub mod google_ {
                 let before = decoder.bytes_read();
                 while decoder.bytes_read() - before < len {
                     let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
                     match tag.field_num() {
                         0 => return Err(::micropb::DecodeError::ZeroField),
                         1u32 => {
                             let mut val: GeneratedCodeInfo_::Annotation = ::core::default::Default::default();
                             let mut_ref = &mut val;
                             {
-                                mut_ref.decode_len_delimited(decoder)?;
+                                decoder.decode_nested_message(mut_ref)?;
                             };
+                            decoder
+                                .spend_elem_budget::<GeneratedCodeInfo_::Annotation>()?;
                             if let (Err(_), false) = (
                                 self.r#annotation.pb_push(val),
                                 decoder.ignore_repeated_cap_err,
@@ -10400,6 +12624,42 @@ pub mod google_ {
                     self._has.r#semantic().then_some(&self.r#semantic)
                 }
             }
+            impl ::micropb::MessageName for Annotation {
+                const FULL_NAME: &'static str = "google.protobuf.GeneratedCodeInfo.Annotation";
+                const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.GeneratedCodeInfo.Annotation";
+            }
+            impl ::micropb::MessageMerge for Annotation {
+                fn merge_from(
+                    &mut self,
+                    other: &Self,
+                ) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    self.r#path.pb_reserve(other.r#path.len());
+                    for val_ref in other.r#path.iter() {
+                        self.r#path
+                            .pb_push(*val_ref)
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#source_file()
+                    {
+                        self.r#source_file = val_ref.clone();
+                        self._has.set_source_file();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#begin() {
+                        self.r#begin = *val_ref;
+                        self._has.set_begin();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#end() {
+                        self.r#end = *val_ref;
+                        self._has.set_end();
+                    }
+                    if let ::core::option::Option::Some(val_ref) = other.r#semantic() {
+                        self.r#semantic = *val_ref;
+                        self._has.set_semantic();
+                    }
+                    Ok(())
+                }
+            }
             impl ::micropb::MessageDecode for Annotation {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
                     &mut self,
@@ -10410,6 +12670,9 @@ pub mod google_ {
                     let before = decoder.bytes_read();
                     while decoder.bytes_read() - before < len {
                         let tag = decoder.decode_tag()?;
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                            return decoder.end_group(tag);
+                        }
                         match tag.field_num() {
                             0 => return Err(::micropb::DecodeError::ZeroField),
                             1u32 => {
@@ -10420,6 +12683,7 @@ pub mod google_ {
                                             |decoder| decoder.decode_int32().map(|v| v as _),
                                         )?;
                                 } else {
+                                    decoder.spend_elem_budget::<i32>()?;
                                     if let (Err(_), false) = (
                                         self.r#path.pb_push(decoder.decode_int32()? as _),
                                         decoder.ignore_repeated_cap_err,
@@ -10486,6 +12750,36 @@ pub mod google_ {
                     pub const Set: Self = Self(1);
                     /// An alias to the element is returned.
                     pub const Alias: Self = Self(2);
+                    /// All known values of the enum, in declaration order, excluding aliases
+                    pub const VALUES: &'static [Self] = &[
+                        Self::None,
+                        Self::Set,
+                        Self::Alias,
+                    ];
+                    /// Protobuf name of the enum value, or `None` if the value is unrecognized
+                    pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                        match *self {
+                            Semantic::None => ::core::option::Option::Some("NONE"),
+                            Semantic::Set => ::core::option::Option::Some("SET"),
+                            Semantic::Alias => ::core::option::Option::Some("ALIAS"),
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                    /// Get the enum value from its Protobuf name
+                    pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                        match name {
+                            "NONE" => ::core::option::Option::Some(Self::None),
+                            "SET" => ::core::option::Option::Some(Self::Set),
+                            "ALIAS" => ::core::option::Option::Some(Self::Alias),
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                }
+                impl ::core::str::FromStr for Semantic {
+                    type Err = ::micropb::UnknownEnumName;
+                    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                        Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                    }
                 }
                 impl core::default::Default for Semantic {
                     fn default() -> Self {
@@ -10658,6 +12952,83 @@ pub mod google_ {
             /// ever be used by plugins that can expect to never require any changes to
             /// support a new edition.
             pub const Max: Self = Self(2147483647);
+            /// All known values of the enum, in declaration order, excluding aliases
+            pub const VALUES: &'static [Self] = &[
+                Self::Unknown,
+                Self::Legacy,
+                Self::Proto2,
+                Self::Proto3,
+                Self::_2023,
+                Self::_2024,
+                Self::_1TestOnly,
+                Self::_2TestOnly,
+                Self::_99997TestOnly,
+                Self::_99998TestOnly,
+                Self::_99999TestOnly,
+                Self::Max,
+            ];
+            /// Protobuf name of the enum value, or `None` if the value is unrecognized
+            pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                match *self {
+                    Edition::Unknown => ::core::option::Option::Some("EDITION_UNKNOWN"),
+                    Edition::Legacy => ::core::option::Option::Some("EDITION_LEGACY"),
+                    Edition::Proto2 => ::core::option::Option::Some("EDITION_PROTO2"),
+                    Edition::Proto3 => ::core::option::Option::Some("EDITION_PROTO3"),
+                    Edition::_2023 => ::core::option::Option::Some("EDITION_2023"),
+                    Edition::_2024 => ::core::option::Option::Some("EDITION_2024"),
+                    Edition::_1TestOnly => {
+                        ::core::option::Option::Some("EDITION_1_TEST_ONLY")
+                    }
+                    Edition::_2TestOnly => {
+                        ::core::option::Option::Some("EDITION_2_TEST_ONLY")
+                    }
+                    Edition::_99997TestOnly => {
+                        ::core::option::Option::Some("EDITION_99997_TEST_ONLY")
+                    }
+                    Edition::_99998TestOnly => {
+                        ::core::option::Option::Some("EDITION_99998_TEST_ONLY")
+                    }
+                    Edition::_99999TestOnly => {
+                        ::core::option::Option::Some("EDITION_99999_TEST_ONLY")
+                    }
+                    Edition::Max => ::core::option::Option::Some("EDITION_MAX"),
+                    _ => ::core::option::Option::None,
+                }
+            }
+            /// Get the enum value from its Protobuf name
+            pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    "EDITION_UNKNOWN" => ::core::option::Option::Some(Self::Unknown),
+                    "EDITION_LEGACY" => ::core::option::Option::Some(Self::Legacy),
+                    "EDITION_PROTO2" => ::core::option::Option::Some(Self::Proto2),
+                    "EDITION_PROTO3" => ::core::option::Option::Some(Self::Proto3),
+                    "EDITION_2023" => ::core::option::Option::Some(Self::_2023),
+                    "EDITION_2024" => ::core::option::Option::Some(Self::_2024),
+                    "EDITION_1_TEST_ONLY" => {
+                        ::core::option::Option::Some(Self::_1TestOnly)
+                    }
+                    "EDITION_2_TEST_ONLY" => {
+                        ::core::option::Option::Some(Self::_2TestOnly)
+                    }
+                    "EDITION_99997_TEST_ONLY" => {
+                        ::core::option::Option::Some(Self::_99997TestOnly)
+                    }
+                    "EDITION_99998_TEST_ONLY" => {
+                        ::core::option::Option::Some(Self::_99998TestOnly)
+                    }
+                    "EDITION_99999_TEST_ONLY" => {
+                        ::core::option::Option::Some(Self::_99999TestOnly)
+                    }
+                    "EDITION_MAX" => ::core::option::Option::Some(Self::Max),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+        impl ::core::str::FromStr for Edition {
+            type Err = ::micropb::UnknownEnumName;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+            }
         }
         impl core::default::Default for Edition {
             fn default() -> Self {
@@ -19,10 +19,10 @@ use crate::{
         FeatureSet_::{
             EnumType, FieldPresence, MessageEncoding, RepeatedFieldEncoding, Utf8Validation,
         },
-        FileDescriptorProto, FileDescriptorSet, ServiceDescriptorProto,
+        FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet, ServiceDescriptorProto,
     },
-    error::pkg_error,
-    generator::{r#enum::Enum, graph::TypeGraph},
+    error::{field_error, msg_error, pkg_error},
+    generator::{r#enum::Enum, extension::Extension, graph::TypeGraph},
     pathtree::{Node, PathTree},
     service::{MethodView, ServiceGenerator, ServiceView, TypeResolver},
     split_pkg_name,
@@ -31,11 +31,13 @@ use crate::{
 use self::message::Message;
 
 pub(crate) mod r#enum;
+mod extension;
 pub(crate) mod field;
 mod graph;
 pub(crate) mod location;
 pub(crate) mod message;
 pub(crate) mod oneof;
+mod registry;
 pub(crate) mod type_spec;
 
 fn derive_msg_attr(
@@ -103,6 +105,54 @@ pub(crate) enum EncodeFunc {
     Encode(Ident),
     PopulateCache(Ident),
     EncodeCached(Ident, Ident),
+    /// Async encoding. The flag determines whether nested message futures need to be boxed.
+    EncodeAsync(Ident, bool),
+}
+
+impl EncodeFunc {
+    /// Suffix appended to calls that return futures
+    pub(crate) fn awaiter(&self) -> Option<TokenStream> {
+        matches!(self, Self::EncodeAsync(..)).then(|| quote! { .await })
+    }
+
+    /// Prefix for closures passed to encoder methods
+    pub(crate) fn closure_prefix(&self) -> Option<TokenStream> {
+        matches!(self, Self::EncodeAsync(..)).then(|| quote! { async })
+    }
+}
+
+pub(crate) enum DecodeFunc {
+    Decode(Ident),
+    /// Async decoding. The flag determines whether nested message futures need to be boxed.
+    DecodeAsync(Ident, bool),
+}
+
+impl DecodeFunc {
+    pub(crate) fn decoder(&self) -> &Ident {
+        match self {
+            Self::Decode(decoder) | Self::DecodeAsync(decoder, _) => decoder,
+        }
+    }
+
+    /// Suffix appended to calls that return futures
+    pub(crate) fn awaiter(&self) -> Option<TokenStream> {
+        matches!(self, Self::DecodeAsync(..)).then(|| quote! { .await })
+    }
+
+    /// Prefix for closures passed to decoder methods
+    pub(crate) fn closure_prefix(&self) -> Option<TokenStream> {
+        matches!(self, Self::DecodeAsync(..)).then(|| quote! { async })
+    }
+
+    /// Generate call to `decode_field` on a custom field
+    pub(crate) fn generate_custom_decode(&self, field: &Ident, tag: &Ident) -> TokenStream {
+        match self {
+            Self::Decode(decoder) => quote! { self.#field.decode_field(#tag, #decoder)? },
+            Self::DecodeAsync(decoder, _) => {
+                quote! { self.#field.decode_field_async(#tag, #decoder).await? }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
@@ -124,6 +174,12 @@ pub(crate) struct Params {
     pub(crate) single_oneof_msg_as_enum: bool,
     pub(crate) encode_cache: bool,
     pub(crate) cache_extern_types: bool,
+    pub(crate) async_encode_decode: bool,
+    pub(crate) unknown_fields: Option<String>,
+    pub(crate) text_format: bool,
+    pub(crate) json: bool,
+    pub(crate) serde: bool,
+    pub(crate) generate_merge: bool,
 }
 
 pub(crate) struct Context<'proto> {
@@ -152,6 +208,12 @@ impl<'proto> Context<'proto> {
                 single_oneof_msg_as_enum: generator.single_oneof_msg_as_enum,
                 encode_cache: generator.encode_cache,
                 cache_extern_types: generator.cache_extern_types,
+                async_encode_decode: generator.async_encode_decode,
+                unknown_fields: generator.unknown_fields,
+                text_format: generator.text_format,
+                json: generator.json,
+                serde: generator.serde,
+                generate_merge: generator.generate_merge,
             },
             warning_cb: generator.warning_cb,
             graph: TypeGraph::default(),
@@ -181,27 +243,17 @@ impl<'proto> Context<'proto> {
             if let Some(fp) = new.field_presence() {
                 mergee.set_field_presence(*fp);
             }
-            if let Some(et) = new.enum_type()
-                && *et != EnumType::Open
-            {
-                (self.warning_cb)(format_args!(
-                    "Editions feature {et:?} is not supported, since micropb only supports open enums"
-                ));
+            if let Some(et) = new.enum_type() {
+                mergee.set_enum_type(*et);
             }
             if let Some(rfe) = new.repeated_field_encoding() {
                 mergee.set_repeated_field_encoding(*rfe);
             }
-            if let Some(utf8) = new.utf8_validation()
-                && *utf8 != Utf8Validation::Verify
-            {
-                (self.warning_cb)(format_args!(
-                    "Editions feature {utf8:?} is not supported, since Rust strings must be in UTF8"
-                ));
+            if let Some(utf8) = new.utf8_validation() {
+                mergee.set_utf8_validation(*utf8);
             }
-            if let Some(me) = new.message_encoding()
-                && *me == MessageEncoding::Delimited
-            {
-                return Err(format!("Editions feature {me:?} is not supported",));
+            if let Some(me) = new.message_encoding() {
+                mergee.set_message_encoding(*me);
             }
             // enforce_naming_style and default_symbol_visibility should be checked by protoc
             // itself, so no need to check them here
@@ -214,16 +266,30 @@ impl<'proto> Context<'proto> {
         match self.syntax {
             Syntax::Proto2 => FeatureSet::default()
                 .init_field_presence(FieldPresence::Explicit)
-                .init_repeated_field_encoding(RepeatedFieldEncoding::Expanded),
+                .init_enum_type(EnumType::Closed)
+                .init_repeated_field_encoding(RepeatedFieldEncoding::Expanded)
+                .init_message_encoding(MessageEncoding::LengthPrefixed)
+                // Proto2 strings are unvalidated by spec, but they've always been generated as
+                // Rust strings, so keep validating them unless configured otherwise
+                .init_utf8_validation(Utf8Validation::Verify),
             Syntax::Proto3 => FeatureSet::default()
                 .init_field_presence(FieldPresence::Implicit)
-                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed),
+                .init_enum_type(EnumType::Open)
+                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed)
+                .init_message_encoding(MessageEncoding::LengthPrefixed)
+                .init_utf8_validation(Utf8Validation::Verify),
             Syntax::E2023 => FeatureSet::default()
                 .init_field_presence(FieldPresence::Explicit)
-                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed),
+                .init_enum_type(EnumType::Open)
+                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed)
+                .init_message_encoding(MessageEncoding::LengthPrefixed)
+                .init_utf8_validation(Utf8Validation::Verify),
             Syntax::E2024 => FeatureSet::default()
                 .init_field_presence(FieldPresence::Explicit)
-                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed),
+                .init_enum_type(EnumType::Open)
+                .init_repeated_field_encoding(RepeatedFieldEncoding::Packed)
+                .init_message_encoding(MessageEncoding::LengthPrefixed)
+                .init_utf8_validation(Utf8Validation::Verify),
         }
     }
 
@@ -249,6 +315,10 @@ impl<'proto> Context<'proto> {
             comment_trees.push(comment_tree);
         }
 
+        if generator.use_micropb_types {
+            map_micropb_types(&mut generator, fdset)?;
+        }
+
         let (mut ctx, config_tree) = Context::new(generator);
 
         // First, convert and accumulate all message and enum types
@@ -280,7 +350,8 @@ impl<'proto> Context<'proto> {
             }
         }
 
-        let module = ctx.generate_mod_tree(&mut mod_tree.root);
+        let mut module = ctx.generate_mod_tree(&mut mod_tree.root);
+        module.extend(ctx.generate_any_registry());
         ctx.warn_unused_configs(&config_tree);
         Ok(module)
     }
@@ -360,6 +431,15 @@ impl<'proto> Context<'proto> {
                 e,
                 cur_config.next_conf(&e.name),
                 comment_tree.root.next(&location::path::fdset_enum(i)),
+                &feature_set,
+            )?;
+        }
+        for (i, f) in fdproto.extension.iter().enumerate() {
+            self.add_extension(
+                f,
+                cur_config.next_conf(&f.name),
+                comment_tree.root.next(&location::path::fdset_extension(i)),
+                &feature_set,
             )?;
         }
 
@@ -379,6 +459,9 @@ impl<'proto> Context<'proto> {
             let e = self.graph.get_enum(&self.fq_proto_name(&proto.name));
             out.extend(self.generate_enum(e));
         }
+        for proto in fdproto.extension.iter() {
+            out.extend(self.generate_extension(&proto.name)?);
+        }
         out.extend(self.generate_services(fdproto));
 
         Ok(out)
@@ -418,7 +501,11 @@ impl<'proto> Context<'proto> {
     fn generate_enum(&self, e: Option<&Enum>) -> TokenStream {
         // None means enum has been skipped
         let Some(e) = e else { return quote! {} };
-        e.generate_decl()
+        let decl = e.generate_decl();
+        // JSON uses the text format enum names
+        let text = (self.params.text_format || self.params.json).then(|| e.generate_text_impl());
+        let serde = self.params.serde.then(|| e.generate_serde_impl());
+        quote! { #decl #text #serde }
     }
 
     fn generate_msg_mod(
@@ -444,6 +531,9 @@ impl<'proto> Context<'proto> {
             let e = self.graph.get_enum(&enum_fq_name);
             msg_mod_body.extend(self.generate_enum(e));
         }
+        for f in proto.extension.iter() {
+            msg_mod_body.extend(self.generate_extension(&f.name)?);
+        }
 
         if !msg.as_oneof_enum {
             for o in &msg.oneofs {
@@ -508,6 +598,15 @@ impl<'proto> Context<'proto> {
                 e,
                 msg_conf.next_conf(&e.name),
                 next_comment_node(comment_node, location::path::msg_enum(i)),
+                feature_set,
+            )?;
+        }
+        for (i, f) in proto.extension.iter().enumerate() {
+            self.add_extension(
+                f,
+                msg_conf.next_conf(&f.name),
+                next_comment_node(comment_node, location::path::msg_extension(i)),
+                feature_set,
             )?;
         }
         self.type_path.borrow_mut().pop();
@@ -520,18 +619,56 @@ impl<'proto> Context<'proto> {
         proto: &'proto EnumDescriptorProto,
         enum_conf: CurrentConfig,
         comment_node: Option<&'proto CommentNode>,
+        feature_set: &FeatureSet,
     ) -> crate::Result<()> {
         let fq_name = self.fq_proto_name(&proto.name);
         if self.params.extern_paths.contains_key(&fq_name) {
             return Ok(());
         }
-        let Some(e) = Enum::from_proto(proto, self, &enum_conf, comment_node)? else {
+        let Some(e) = Enum::from_proto(proto, self, &enum_conf, comment_node, feature_set)? else {
             return Ok(());
         };
         self.graph.add_enum(fq_name, e);
         Ok(())
     }
 
+    fn add_extension(
+        &mut self,
+        proto: &'proto FieldDescriptorProto,
+        ext_conf: CurrentConfig,
+        comment_node: Option<&'proto CommentNode>,
+        feature_set: &FeatureSet,
+    ) -> crate::Result<()> {
+        let fq_name = self.fq_proto_name(&proto.name);
+        let Some(ext) = Extension::from_proto(proto, &ext_conf, comment_node, self, feature_set)
+            .map_err(|e| msg_error(&self.pkg, &self.scoped_name(&proto.name), &e))?
+        else {
+            return Ok(());
+        };
+        self.graph.add_extension(fq_name, ext);
+        Ok(())
+    }
+
+    fn generate_extension(&self, name: &str) -> crate::Result<TokenStream> {
+        let fq_name = self.fq_proto_name(name);
+        // None means extension has been skipped
+        let Some(ext) = self.graph.get_extension(&fq_name) else {
+            return Ok(quote! {});
+        };
+        ext.generate(self, &fq_name)
+            .map_err(|e| msg_error(&self.pkg, &self.scoped_name(name), &e))
+    }
+
+    /// Name of a type or extension relative to the package, used for error messages
+    fn scoped_name(&self, name: &str) -> String {
+        let mut scoped = self.type_path.borrow().join(".");
+        if !scoped.is_empty() {
+            scoped.push('.');
+        }
+        scoped.push_str(name);
+        scoped
+    }
+
     fn generate_msg(
         &self,
         msg: Option<&Message>,
@@ -551,6 +688,17 @@ impl<'proto> Context<'proto> {
         let partial_eq = msg.hazzer.as_ref().map(|_| msg.generate_partial_eq());
         let decl = msg.generate_decl(self, proto_default)?;
         let msg_impl = msg.generate_impl(self)?;
+        let extendable = msg.generate_extendable_impl();
+        let fq_name = self.fq_proto_name(msg.name);
+        let name = msg.generate_name_trait(&fq_name);
+        // Merging needs mutable containers, which is only guaranteed when decoding is enabled
+        let merge = (self.params.generate_merge && self.params.encode_decode.is_decode())
+            .then(|| msg.generate_merge_trait(self));
+        let any = if fq_name == ".google.protobuf.Any" {
+            msg.generate_any_trait(self)?
+        } else {
+            None
+        };
         let decode = self
             .params
             .encode_decode
@@ -562,14 +710,62 @@ impl<'proto> Context<'proto> {
             .encode_decode
             .is_encode()
             .then(|| msg.generate_encode_trait(self));
+        let (async_decode, async_encode) = if self.params.async_encode_decode {
+            (
+                self.params
+                    .encode_decode
+                    .is_decode()
+                    .then(|| msg.generate_async_decode_trait(self))
+                    .transpose()?,
+                self.params
+                    .encode_decode
+                    .is_encode()
+                    .then(|| msg.generate_async_encode_trait(self)),
+            )
+        } else {
+            (None, None)
+        };
+        let (text_encode, text_decode) = if self.params.text_format {
+            (
+                Some(msg.generate_text_encode_trait(self)),
+                Some(msg.generate_text_decode_trait(self)?),
+            )
+        } else {
+            (None, None)
+        };
+        let (json_encode, json_decode) = if self.params.json {
+            (
+                Some(msg.generate_json_encode_trait(self)),
+                Some(msg.generate_json_decode_trait(self)?),
+            )
+        } else {
+            (None, None)
+        };
+
+        let serde = if self.params.serde {
+            Some(msg.generate_serde_traits(self)?)
+        } else {
+            None
+        };
 
         Ok(quote! {
             #decl
             #default
             #partial_eq
             #msg_impl
+            #extendable
+            #name
+            #merge
+            #any
             #decode
             #encode
+            #async_decode
+            #async_encode
+            #text_encode
+            #text_decode
+            #json_encode
+            #json_decode
+            #serde
             #msg_mod
         })
     }
@@ -597,12 +793,14 @@ impl<'proto> Context<'proto> {
         fq_proto_name
     }
 
-    /// Convert variant name to Pascal-case, then strip the enum name from it
+    /// Convert variant name to Pascal-case, then strip the enum name from it, unless the variant
+    /// name consists of only the enum name
     fn enum_variant_name(&self, variant_name: &str, enum_name: &Ident) -> Ident {
         let variant_name_cased = variant_name.to_case(Case::Pascal);
         let stripped = if !self.params.retain_enum_prefix {
             variant_name_cased
                 .strip_prefix(&enum_name.to_string())
+                .filter(|s| !s.is_empty())
                 .unwrap_or(&variant_name_cased)
         } else {
             &variant_name_cased
@@ -637,6 +835,104 @@ impl<'proto> Context<'proto> {
     }
 }
 
+/// Well-known types provided by `micropb-types`
+const WELL_KNOWN_TYPES: &[&str] = &[
+    "Any",
+    "BoolValue",
+    "BytesValue",
+    "DoubleValue",
+    "Duration",
+    "Empty",
+    "FieldMask",
+    "FloatValue",
+    "Int32Value",
+    "Int64Value",
+    "ListValue",
+    "NullValue",
+    "StringValue",
+    "Struct",
+    "Timestamp",
+    "UInt32Value",
+    "UInt64Value",
+    "Value",
+];
+
+/// Map the well-known types to `micropb-types`, skipping types that are defined in the file
+/// descriptor set or already have an extern path
+fn map_micropb_types(generator: &mut Generator, fdset: &FileDescriptorSet) -> crate::Result<()> {
+    let mut mapped = vec![];
+    for name in WELL_KNOWN_TYPES {
+        let defined = fdset
+            .file
+            .iter()
+            .filter(|file| file.package().map(String::as_str) == Some("google.protobuf"))
+            .any(|file| {
+                file.message_type.iter().any(|m| m.name == *name)
+                    || file.enum_type.iter().any(|e| e.name == *name)
+            });
+        let fq_name = format!(".google.protobuf.{name}");
+        if !defined && !generator.extern_paths.contains_key(&fq_name) {
+            let rust_path = format!("::micropb_types::{name}");
+            generator.extern_paths.insert(
+                fq_name.clone(),
+                syn::parse_str(&rust_path).expect("failed to tokenize extern path"),
+            );
+            mapped.push(fq_name);
+        }
+    }
+
+    // micropb-types doesn't implement text format, JSON, or serde, so reject any references to
+    // the mapped types if those impls are generated
+    if generator.text_format || generator.json || generator.serde {
+        for file in &fdset.file {
+            let pkg = file.package().map(String::as_str).unwrap_or("");
+            check_wkt_refs(pkg, "", &file.message_type, &file.extension, &mapped)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_wkt_refs(
+    pkg: &str,
+    prefix: &str,
+    messages: &[DescriptorProto],
+    extensions: &[FieldDescriptorProto],
+    mapped: &[String],
+) -> crate::Result<()> {
+    let check = |msg_name: &str, field: &FieldDescriptorProto| {
+        if mapped.contains(&field.type_name) {
+            return Err(field_error(
+                pkg,
+                msg_name,
+                &field.name,
+                format_args!(
+                    "{} is mapped to `micropb-types`, which doesn't support text format, JSON, \
+                    or serde. Compile the well-known .proto files along with this file instead.",
+                    field.type_name
+                ),
+            ));
+        }
+        Ok(())
+    };
+    for f in extensions {
+        check(prefix.trim_end_matches('.'), f)?;
+    }
+    for m in messages {
+        let msg_name = format!("{prefix}{}", m.name);
+        for f in &m.field {
+            check(&msg_name, f)?;
+        }
+        check_wkt_refs(
+            pkg,
+            &format!("{msg_name}."),
+            &m.nested_type,
+            &m.extension,
+            mapped,
+        )?;
+    }
+    Ok(())
+}
+
 struct RustTypeResolver<'a> {
     params: &'a Params,
     pkg_path: &'a [String],
@@ -798,6 +1094,10 @@ mod tests {
             ctx.enum_variant_name("ALIEN", &enum_name).to_string(),
             "Alien"
         );
+        assert_eq!(
+            ctx.enum_variant_name("ENUM", &enum_name).to_string(),
+            "Enum"
+        );
 
         ctx.params.retain_enum_prefix = true;
         assert_eq!(
@@ -806,6 +1106,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn enum_type_feature() {
+        let mut ctx = make_ctx();
+        ctx.syntax = Syntax::Proto2;
+        assert_eq!(
+            ctx.default_feature_set().enum_type(),
+            Some(&EnumType::Closed)
+        );
+
+        ctx.syntax = Syntax::E2023;
+        let mut features = ctx.default_feature_set();
+        assert_eq!(features.enum_type(), Some(&EnumType::Open));
+        let closed = FeatureSet::default().init_enum_type(EnumType::Closed);
+        ctx.merge_feature_sets(&mut features, Some(&closed))
+            .unwrap();
+        assert_eq!(features.enum_type(), Some(&EnumType::Closed));
+    }
+
     #[test]
     fn resolve_type_name() {
         let mut ctx = make_ctx();
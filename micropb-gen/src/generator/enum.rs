@@ -1,27 +1,41 @@
-use micropb::size::sizeof_varint32;
-use proc_macro2::{Literal, TokenStream};
+use micropb::size::{sizeof_int32, sizeof_varint32};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use syn::{Attribute, Ident};
 
 use super::location::{CommentNode, Comments, get_comments, next_comment_node};
 use crate::{
     config::IntSize,
-    descriptor::{EnumDescriptorProto, FeatureSet},
+    descriptor::{EnumDescriptorProto, FeatureSet, FeatureSet_::EnumType},
     error::msg_error,
-    generator::{Context, CurrentConfig, derive_enum_attr, location, sanitized_ident},
+    generator::{Context, CurrentConfig, Syntax, derive_enum_attr, location, sanitized_ident},
 };
 
 pub(crate) struct Variant<'proto> {
     pub(crate) num: u32,
+    /// Protobuf name
+    pub(crate) name: &'proto str,
     pub(crate) rust_name: Ident,
     pub(crate) comments: Option<&'proto Comments>,
 }
 
+/// Rust representation of a Protobuf enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EnumRepr {
+    /// Newtype around the integer, with an associated constant for each variant
+    Newtype,
+    /// Rust enum that can only hold the known variants
+    Closed,
+    /// Rust enum with an extra variant that holds unrecognized values
+    Open,
+}
+
 pub(crate) struct Enum<'proto> {
     /// Sanitized Rust ident, used for struct name
     pub(crate) rust_name: Ident,
     pub(crate) int_type: IntSize,
     pub(crate) signed: bool,
+    pub(crate) repr: EnumRepr,
     pub(crate) variants: Vec<Variant<'proto>>,
     pub(crate) attrs: Vec<Attribute>,
     pub(crate) comments: Option<&'proto Comments>,
@@ -33,17 +47,36 @@ impl<'proto> Enum<'proto> {
         ctx: &Context<'proto>,
         enum_conf: &CurrentConfig,
         comment_node: Option<&'proto CommentNode>,
+        feature_set: &FeatureSet,
     ) -> crate::Result<Option<Self>> {
         if enum_conf.config.skip.unwrap_or(false) {
             return Ok(None);
         }
 
         let name = &proto.name;
-        // This exists purely to print warnings about enum_type to CLOSED
-        let _ = ctx.merge_feature_sets(
-            &mut FeatureSet::default(),
+        let mut feature_set = feature_set.to_owned();
+        ctx.merge_feature_sets(
+            &mut feature_set,
             proto.options().and_then(|opt| opt.features()),
-        );
+        )
+        .map_err(|e| msg_error(&ctx.pkg, name, &e))?;
+        let closed = feature_set.enum_type() == Some(&EnumType::Closed);
+        let repr = if closed && enum_conf.config.closed_enum_as_rust_enum.unwrap_or(false) {
+            EnumRepr::Closed
+        } else {
+            // Closed proto2 enums have always been generated as open enums, so only warn for
+            // editions, where the enum type is explicitly requested
+            if closed && ctx.syntax != Syntax::Proto2 {
+                (ctx.warning_cb)(format_args!(
+                    "Enum {name} is closed, but will be generated as an open enum unless closed_enum_as_rust_enum is set"
+                ));
+            }
+            if enum_conf.config.open_enum_as_rust_enum.unwrap_or(false) {
+                EnumRepr::Open
+            } else {
+                EnumRepr::Newtype
+            }
+        };
 
         let rust_name = sanitized_ident(name);
         let int_type = enum_conf.config.enum_int_size.unwrap_or(IntSize::S32);
@@ -68,6 +101,7 @@ impl<'proto> Enum<'proto> {
                 let var_comments = get_comments(var_comment_node);
                 Variant {
                     num,
+                    name: var_name,
                     rust_name: var_rust_name,
                     comments: var_comments,
                 }
@@ -78,13 +112,153 @@ impl<'proto> Enum<'proto> {
             rust_name,
             int_type,
             signed,
+            repr,
             variants,
             attrs,
             comments,
         }))
     }
 
+    /// Variants that aren't aliases of an earlier variant with the same number
+    fn unique_variants(&self) -> impl Iterator<Item = &Variant<'proto>> {
+        self.variants
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| self.variants.iter().position(|u| u.num == v.num) == Some(*i))
+            .map(|(_, v)| v)
+    }
+
+    /// The variant that an alias refers to, which is the first variant with the same number
+    fn aliased_variant(&self, alias: &Variant<'proto>) -> &Variant<'proto> {
+        self.variants
+            .iter()
+            .find(|v| v.num == alias.num)
+            .expect("alias should match itself")
+    }
+
+    /// Name of the variant that holds unrecognized values of open Rust enums. Falls back to
+    /// `_Unknown` if a Protobuf variant is already called `Unknown`.
+    pub(crate) fn unknown_variant(&self) -> Ident {
+        let name = if self.variants.iter().any(|v| v.rust_name == "Unknown") {
+            "_Unknown"
+        } else {
+            "Unknown"
+        };
+        Ident::new(name, Span::call_site())
+    }
+
+    /// Rust enums can't have duplicate variants, so aliases become constants instead
+    fn generate_aliases(&self) -> impl Iterator<Item = TokenStream> {
+        self.variants
+            .iter()
+            .filter(|v| self.aliased_variant(v).rust_name != v.rust_name)
+            .map(|v| {
+                let var_name = &v.rust_name;
+                let target = &self.aliased_variant(v).rust_name;
+                let var_comments = v.comments.map(Comments::lines).into_iter().flatten();
+                quote! { #(#[doc = #var_comments])* pub const #var_name: Self = Self::#target; }
+            })
+    }
+
+    /// Max encoded size of open enums, which can hold any value of the integer type
+    fn open_max_size(&self) -> usize {
+        if self.signed {
+            10
+        } else {
+            sizeof_varint32(self.int_type.max_value().try_into().unwrap_or(u32::MAX))
+        }
+    }
+
+    /// Associated items for converting between the enum and its Protobuf variant names, along
+    /// with the list of all known values
+    fn generate_name_items(&self) -> TokenStream {
+        let name = &self.rust_name;
+        let to_name = match self.repr {
+            EnumRepr::Newtype => {
+                let branches = self.unique_variants().map(|v| {
+                    let var_name = &v.rust_name;
+                    let pb_name = v.name;
+                    quote! { #name::#var_name => ::core::option::Option::Some(#pb_name), }
+                });
+                quote! {
+                    match *self {
+                        #(#branches)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+            EnumRepr::Closed => {
+                let branches = self.unique_variants().map(|v| {
+                    let var_name = &v.rust_name;
+                    let pb_name = v.name;
+                    quote! { Self::#var_name => #pb_name, }
+                });
+                quote! { ::core::option::Option::Some(match *self { #(#branches)* }) }
+            }
+            EnumRepr::Open => {
+                let branches = self.unique_variants().map(|v| {
+                    let var_name = &v.rust_name;
+                    let pb_name = v.name;
+                    quote! { Self::#var_name => ::core::option::Option::Some(#pb_name), }
+                });
+                let unknown = self.unknown_variant();
+                quote! {
+                    match *self {
+                        #(#branches)*
+                        Self::#unknown(_) => ::core::option::Option::None,
+                    }
+                }
+            }
+        };
+        // Aliases are accepted as input, but only the first name of each value is returned
+        let from_name = self.variants.iter().map(|v| {
+            let var_name = &v.rust_name;
+            let pb_name = v.name;
+            quote! { #pb_name => ::core::option::Option::Some(Self::#var_name), }
+        });
+        let values = self.unique_variants().map(|v| &v.rust_name);
+
+        quote! {
+            #[doc = " All known values of the enum, in declaration order, excluding aliases"]
+            pub const VALUES: &'static [Self] = &[#(Self::#values),*];
+
+            #[doc = " Protobuf name of the enum value, or `None` if the value is unrecognized"]
+            pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                #to_name
+            }
+
+            #[doc = " Get the enum value from its Protobuf name"]
+            pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    #(#from_name)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    }
+
+    fn generate_from_str_impl(&self) -> TokenStream {
+        let name = &self.rust_name;
+        quote! {
+            impl ::core::str::FromStr for #name {
+                type Err = ::micropb::UnknownEnumName;
+
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+                }
+            }
+        }
+    }
+
     pub(crate) fn generate_decl(&self) -> TokenStream {
+        match self.repr {
+            EnumRepr::Newtype => self.generate_newtype_decl(),
+            EnumRepr::Closed => self.generate_closed_decl(),
+            EnumRepr::Open => self.generate_open_decl(),
+        }
+    }
+
+    fn generate_newtype_decl(&self) -> TokenStream {
         let variants = self.variants.iter().map(|v| {
             let num = Literal::i32_unsuffixed(v.num as i32);
             let var_name = &v.rust_name;
@@ -96,13 +270,11 @@ impl<'proto> Enum<'proto> {
         let default_num = Literal::i32_unsuffixed(self.variants[0].num as i32);
         let derive_enum = derive_enum_attr();
         let itype = self.int_type.type_name(self.signed);
-        let max_size = if self.signed {
-            10
-        } else {
-            sizeof_varint32(self.int_type.max_value().try_into().unwrap_or(u32::MAX))
-        };
+        let max_size = self.open_max_size();
         let comments = self.comments.map(Comments::lines).into_iter().flatten();
         let attrs = &self.attrs;
+        let name_items = self.generate_name_items();
+        let from_str = self.generate_from_str_impl();
 
         let debug_variants = self.variants.iter().map(|v| {
             let var_name = &v.rust_name;
@@ -121,8 +293,11 @@ impl<'proto> Enum<'proto> {
                 #[doc = " Maximum encoded size of the enum"]
                 pub const _MAX_SIZE: usize = #max_size;
                 #(#variants)*
+                #name_items
             }
 
+            #from_str
+
             impl core::default::Default for #name {
                 fn default() -> Self {
                     Self(#default_num)
@@ -145,4 +320,287 @@ impl<'proto> Enum<'proto> {
             }
         }
     }
+
+    fn generate_closed_decl(&self) -> TokenStream {
+        let variants = self.unique_variants().map(|v| {
+            let num = Literal::i32_unsuffixed(v.num as i32);
+            let var_name = &v.rust_name;
+            let var_comments = v.comments.map(Comments::lines).into_iter().flatten();
+            quote! { #(#[doc = #var_comments])* #var_name = #num, }
+        });
+        let aliases = self.generate_aliases();
+        let try_from_branches = self.unique_variants().map(|v| {
+            let num = Literal::i32_unsuffixed(v.num as i32);
+            let var_name = &v.rust_name;
+            quote! { #num => ::core::result::Result::Ok(Self::#var_name), }
+        });
+        let debug_variants = self.unique_variants().map(|v| {
+            let var_name = &v.rust_name;
+            let var_str = var_name.to_string();
+            quote! { Self::#var_name => formatter.write_str(#var_str), }
+        });
+
+        let name = &self.rust_name;
+        let default_variant = &self.variants[0].rust_name;
+        let derive_enum = derive_enum_attr();
+        let itype = self.int_type.type_name(self.signed);
+        // Only the known variants can be encoded, so the max size depends on their values
+        let max_size = self
+            .unique_variants()
+            .map(|v| sizeof_int32(v.num as i32))
+            .max()
+            .unwrap_or(0);
+        let comments = self.comments.map(Comments::lines).into_iter().flatten();
+        let attrs = &self.attrs;
+        let name_items = self.generate_name_items();
+        let from_str = self.generate_from_str_impl();
+
+        quote! {
+            #(#[doc = #comments])*
+            #derive_enum
+            #[repr(#itype)]
+            #(#attrs)*
+            pub enum #name {
+                #(#variants)*
+            }
+
+            impl #name {
+                #[doc = " Maximum encoded size of the enum"]
+                pub const _MAX_SIZE: usize = #max_size;
+                #(#aliases)*
+                #name_items
+            }
+
+            #from_str
+
+            impl core::default::Default for #name {
+                fn default() -> Self {
+                    Self::#default_variant
+                }
+            }
+
+            impl core::convert::TryFrom<i32> for #name {
+                type Error = i32;
+
+                fn try_from(val: i32) -> ::core::result::Result<Self, i32> {
+                    match val {
+                        #(#try_from_branches)*
+                        _ => ::core::result::Result::Err(val),
+                    }
+                }
+            }
+
+            impl core::convert::From<#name> for #itype {
+                fn from(val: #name) -> Self {
+                    val as _
+                }
+            }
+
+            impl core::fmt::Debug for #name {
+                fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    match *self {
+                        #(#debug_variants)*
+                    }
+                }
+            }
+        }
+    }
+
+    fn generate_open_decl(&self) -> TokenStream {
+        let name = &self.rust_name;
+        let variants = self.unique_variants().map(|v| {
+            let var_name = &v.rust_name;
+            let var_comments = v.comments.map(Comments::lines).into_iter().flatten();
+            quote! { #(#[doc = #var_comments])* #var_name, }
+        });
+        let aliases = self.generate_aliases();
+        let from_int_branches = self.unique_variants().map(|v| {
+            let num = Literal::i32_unsuffixed(v.num as i32);
+            let var_name = &v.rust_name;
+            quote! { #num => Self::#var_name, }
+        });
+        let to_int_branches = self.unique_variants().map(|v| {
+            let num = Literal::i32_unsuffixed(v.num as i32);
+            let var_name = &v.rust_name;
+            quote! { #name::#var_name => #num, }
+        });
+        let debug_variants = self.unique_variants().map(|v| {
+            let var_name = &v.rust_name;
+            let var_str = var_name.to_string();
+            quote! { Self::#var_name => formatter.write_str(#var_str), }
+        });
+
+        let unknown = self.unknown_variant();
+        let default_variant = &self.variants[0].rust_name;
+        let derive_enum = derive_enum_attr();
+        let itype = self.int_type.type_name(self.signed);
+        let max_size = self.open_max_size();
+        let comments = self.comments.map(Comments::lines).into_iter().flatten();
+        let attrs = &self.attrs;
+        let name_items = self.generate_name_items();
+        let from_str = self.generate_from_str_impl();
+
+        quote! {
+            #(#[doc = #comments])*
+            #derive_enum
+            #(#attrs)*
+            pub enum #name {
+                #(#variants)*
+                #[doc = " Value that doesn't match any variant"]
+                #unknown(#itype),
+            }
+
+            impl #name {
+                #[doc = " Maximum encoded size of the enum"]
+                pub const _MAX_SIZE: usize = #max_size;
+                #(#aliases)*
+                #name_items
+            }
+
+            #from_str
+
+            impl core::default::Default for #name {
+                fn default() -> Self {
+                    Self::#default_variant
+                }
+            }
+
+            impl core::convert::From<#itype> for #name {
+                fn from(val: #itype) -> Self {
+                    match val {
+                        #(#from_int_branches)*
+                        _ => Self::#unknown(val),
+                    }
+                }
+            }
+
+            impl core::convert::From<#name> for #itype {
+                fn from(val: #name) -> Self {
+                    match val {
+                        #(#to_int_branches)*
+                        #name::#unknown(n) => n,
+                    }
+                }
+            }
+
+            impl core::fmt::Debug for #name {
+                fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    match *self {
+                        #(#debug_variants)*
+                        Self::#unknown(n) => formatter.debug_tuple("_Unknown").field(&n).finish(),
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generate_text_impl(&self) -> TokenStream {
+        let name = &self.rust_name;
+        let itype = self.int_type.type_name(self.signed);
+        let (to_num, from_num) = match self.repr {
+            EnumRepr::Newtype => (
+                quote! { self.0 as i64 },
+                quote! { #itype::try_from(num).ok().map(Self) },
+            ),
+            EnumRepr::Closed => (
+                quote! { *self as i64 },
+                quote! {
+                    i32::try_from(num)
+                        .ok()
+                        .and_then(|n| ::core::convert::TryFrom::try_from(n).ok())
+                },
+            ),
+            EnumRepr::Open => (
+                quote! { #itype::from(*self) as i64 },
+                quote! { #itype::try_from(num).ok().map(Self::from) },
+            ),
+        };
+
+        quote! {
+            impl ::micropb::text::TextEnum for #name {
+                fn text_name(&self) -> ::core::option::Option<&'static str> {
+                    self.as_str_name()
+                }
+
+                fn from_text_name(name: &str) -> ::core::option::Option<Self> {
+                    Self::from_str_name(name)
+                }
+
+                fn text_number(&self) -> i64 {
+                    #to_num
+                }
+
+                fn from_text_number(num: i64) -> ::core::option::Option<Self> {
+                    #from_num
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generate_serde_impl(&self) -> TokenStream {
+        let name = &self.rust_name;
+        let itype = self.int_type.type_name(self.signed);
+
+        let (serialize, deserialize) = match self.repr {
+            EnumRepr::Newtype => (
+                quote! {
+                    ::micropb::serde::serialize_enum(serializer, self.0, self.as_str_name())
+                },
+                quote! {
+                    ::micropb::serde::deserialize_enum(deserializer, |name| {
+                        Self::from_str_name(name).map(|val| val.0)
+                    })
+                    .map(Self)
+                },
+            ),
+            EnumRepr::Closed => (
+                quote! {
+                    ::micropb::serde::serialize_enum(serializer, *self as #itype, self.as_str_name())
+                },
+                quote! {
+                    let num: #itype = ::micropb::serde::deserialize_enum(deserializer, |name| {
+                        Self::from_str_name(name).map(|val| val as #itype)
+                    })?;
+                    i32::try_from(num)
+                        .ok()
+                        .and_then(|n| ::core::convert::TryFrom::try_from(n).ok())
+                        .ok_or_else(|| {
+                            <IMPL_MICROPB_DESERIALIZER::Error as ::micropb::serde::de::Error>::custom(
+                                "unknown value of closed enum",
+                            )
+                        })
+                },
+            ),
+            EnumRepr::Open => (
+                quote! {
+                    ::micropb::serde::serialize_enum(serializer, #itype::from(*self), self.as_str_name())
+                },
+                quote! {
+                    ::micropb::serde::deserialize_enum(deserializer, |name| {
+                        Self::from_str_name(name).map(#itype::from)
+                    })
+                    .map(Self::from)
+                },
+            ),
+        };
+
+        quote! {
+            impl ::micropb::serde::Serialize for #name {
+                fn serialize<IMPL_MICROPB_SERIALIZER: ::micropb::serde::Serializer>(
+                    &self,
+                    serializer: IMPL_MICROPB_SERIALIZER,
+                ) -> Result<IMPL_MICROPB_SERIALIZER::Ok, IMPL_MICROPB_SERIALIZER::Error> {
+                    #serialize
+                }
+            }
+
+            impl<'de> ::micropb::serde::Deserialize<'de> for #name {
+                fn deserialize<IMPL_MICROPB_DESERIALIZER: ::micropb::serde::Deserializer<'de>>(
+                    deserializer: IMPL_MICROPB_DESERIALIZER,
+                ) -> Result<Self, IMPL_MICROPB_DESERIALIZER::Error> {
+                    #deserialize
+                }
+            }
+        }
+    }
 }
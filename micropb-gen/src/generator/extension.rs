@@ -0,0 +1,346 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    config::vec_type_parsed,
+    descriptor::{FeatureSet, FieldDescriptorProto},
+    generator::{
+        Context, CurrentConfig, DecodeFunc, EncodeFunc,
+        field::{Field, FieldType},
+        location::{self, CommentNode, Comments},
+        sanitized_ident,
+        type_spec::TypeSpec,
+    },
+    utils::find_lifetime_from_str,
+};
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+enum ExtensionValue<'proto> {
+    Single(TypeSpec<'proto>),
+    Repeated {
+        typ: TypeSpec<'proto>,
+        packed: bool,
+        typestr: String,
+        max_len: Option<u32>,
+    },
+}
+
+/// Extension field declared in an `extend` block
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub(crate) struct Extension<'proto> {
+    num: u32,
+    /// Sanitized Rust ident of the descriptor struct
+    rust_name: Ident,
+    /// Fully-qualified Protobuf name of the extended message
+    extendee: &'proto str,
+    value: ExtensionValue<'proto>,
+    comments: Option<&'proto Comments>,
+}
+
+impl<'proto> Extension<'proto> {
+    pub(crate) fn from_proto(
+        proto: &'proto FieldDescriptorProto,
+        ext_conf: &CurrentConfig,
+        comment_node: Option<&'proto CommentNode>,
+        ctx: &Context<'proto>,
+        feature_set: &FeatureSet,
+    ) -> Result<Option<Self>, String> {
+        let Some(field) = Field::from_proto(proto, ext_conf, None, ctx, None, feature_set)? else {
+            return Ok(None);
+        };
+        let value = match field.ftype {
+            FieldType::Single(typ) | FieldType::Optional(typ, _) => ExtensionValue::Single(typ),
+            FieldType::Repeated {
+                typ,
+                packed,
+                typestr,
+                max_len,
+                ..
+            } => ExtensionValue::Repeated {
+                typ,
+                packed,
+                typestr,
+                max_len,
+            },
+            FieldType::Map { .. } | FieldType::Custom(_) => {
+                return Err("Extensions can't be custom fields".to_owned());
+            }
+        };
+        if field.boxed {
+            return Err("Extensions can't be boxed".to_owned());
+        }
+
+        Ok(Some(Self {
+            num: field.num,
+            rust_name: sanitized_ident(&field.rust_name.to_case(Case::UpperSnake)),
+            extendee: &proto.extendee,
+            value,
+            comments: location::get_comments(comment_node),
+        }))
+    }
+
+    fn tag(&self) -> micropb::Tag {
+        let wire_type = match &self.value {
+            ExtensionValue::Single(typ)
+            | ExtensionValue::Repeated {
+                typ, packed: false, ..
+            } => typ.wire_type(),
+            ExtensionValue::Repeated { packed: true, .. } => micropb::WIRE_TYPE_LEN,
+        };
+        micropb::Tag::from_parts(self.num, wire_type)
+    }
+
+    fn generate_value_type(&self, ctx: &Context<'proto>) -> Result<TokenStream, String> {
+        let typ = match &self.value {
+            ExtensionValue::Single(typ) => typ.generate_rust_type(ctx)?,
+            ExtensionValue::Repeated {
+                typ,
+                typestr,
+                max_len,
+                ..
+            } => {
+                let inner = typ.generate_rust_type(ctx)?;
+                let ty = vec_type_parsed(typestr, inner, *max_len)?;
+                quote! { #ty }
+            }
+        };
+        Ok(typ)
+    }
+
+    fn generate_decode(&self, ctx: &Context<'proto>) -> Result<TokenStream, String> {
+        let decoder = Ident::new("decoder", Span::call_site());
+        let mut_ref = Ident::new("mut_ref", Span::call_site());
+        let func = DecodeFunc::Decode(decoder.clone());
+
+        let decode_code = match &self.value {
+            ExtensionValue::Single(typ) => {
+                typ.generate_decode_mut(ctx, false, &func, &mut_ref, None)?
+            }
+            ExtensionValue::Repeated { typ, .. } => {
+                let rust_type = typ.generate_rust_type(ctx)?;
+                if let Some(val) = typ.generate_decode_closed_enum(ctx, &func, None) {
+                    // Values that don't match any variant are dropped
+                    quote! {
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_LEN {
+                            #decoder.decode_packed_filtered(#mut_ref, |#decoder| #val)?;
+                        } else if let ::core::option::Option::Some(val) = #val? {
+                            #decoder.spend_elem_budget::<#rust_type>()?;
+                            if let (Err(_), false) = (#mut_ref.pb_push(val), #decoder.ignore_repeated_cap_err) {
+                                return Err(::micropb::DecodeError::Capacity);
+                            }
+                        }
+                    }
+                } else if let Some(val) = typ.generate_decode_val(ctx, &func) {
+                    let decode_packed = if typ.is_fixed_width() {
+                        quote! { #decoder.decode_packed_fixed(#mut_ref)? }
+                    } else {
+                        quote! { #decoder.decode_packed(#mut_ref, |#decoder| #val.map(|v| v as _))? }
+                    };
+                    quote! {
+                        if tag.wire_type() == ::micropb::WIRE_TYPE_LEN {
+                            #decode_packed;
+                        } else {
+                            #decoder.spend_elem_budget::<#rust_type>()?;
+                            if let (Err(_), false) = (#mut_ref.pb_push(#val? as _), #decoder.ignore_repeated_cap_err) {
+                                return Err(::micropb::DecodeError::Capacity);
+                            }
+                        }
+                    }
+                } else {
+                    let decode_expr = typ.generate_decode_mut(ctx, false, &func, &mut_ref, None)?;
+                    quote! {
+                        let mut val: #rust_type = ::core::default::Default::default();
+                        {
+                            let #mut_ref = &mut val;
+                            #decode_expr
+                        }
+                        #decoder.spend_elem_budget::<#rust_type>()?;
+                        if let (Err(_), false) = (#mut_ref.pb_push(val), #decoder.ignore_repeated_cap_err) {
+                            return Err(::micropb::DecodeError::Capacity);
+                        }
+                    }
+                }
+            }
+        };
+
+        let rust_name = &self.rust_name;
+        Ok(quote! {
+            impl ::micropb::extension::ExtensionDecode for #rust_name {
+                fn decode_value<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                    #mut_ref: &mut Self::Value,
+                    tag: ::micropb::Tag,
+                    #decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>>
+                {
+                    use ::micropb::{PbBytes, PbString, PbVec};
+                    #decode_code
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn generate_encode(&self, ctx: &Context<'proto>) -> TokenStream {
+        let encoder = Ident::new("encoder", Span::call_site());
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let value = Ident::new("value", Span::call_site());
+        let func = EncodeFunc::Encode(encoder.clone());
+        let tag = self.tag();
+        let tag_val = tag.varint();
+        let tag_len = ::micropb::size::sizeof_tag(tag);
+
+        let (encode_code, sizeof_code) = match &self.value {
+            ExtensionValue::Single(typ) => {
+                let encode_expr = typ.generate_encode_expr(ctx, &func, &val_ref);
+                let sizeof_expr = typ.generate_sizeof(ctx, &val_ref);
+                (
+                    quote! {
+                        let #val_ref = #value;
+                        #encoder.encode_varint32(#tag_val)?;
+                        #encode_expr?;
+                    },
+                    quote! {
+                        let #val_ref = #value;
+                        #tag_len + #sizeof_expr
+                    },
+                )
+            }
+
+            ExtensionValue::Repeated {
+                typ, packed: false, ..
+            } => {
+                let encode_expr = typ.generate_encode_expr(ctx, &func, &val_ref);
+                let sizeof_code = if let Some(fixed) = typ.fixed_size() {
+                    quote! { #value.len() * (#tag_len + #fixed) }
+                } else {
+                    let sizeof_expr = typ.generate_sizeof(ctx, &val_ref);
+                    quote! { #value.iter().map(|#val_ref| #tag_len + #sizeof_expr).sum() }
+                };
+                (
+                    quote! {
+                        for #val_ref in #value.iter() {
+                            #encoder.encode_varint32(#tag_val)?;
+                            #encode_expr?;
+                        }
+                    },
+                    sizeof_code,
+                )
+            }
+
+            ExtensionValue::Repeated {
+                typ, packed: true, ..
+            } => {
+                let len = if let Some(fixed) = typ.fixed_size() {
+                    quote! { #value.len() * #fixed }
+                } else {
+                    let sizeof_expr = typ.generate_sizeof(ctx, &val_ref);
+                    quote! { ::micropb::size::sizeof_packed(#value, |#val_ref| #sizeof_expr) }
+                };
+                let encode_packed = if typ.is_fixed_width() {
+                    quote! { #encoder.encode_packed_fixed(#value)?; }
+                } else {
+                    let encode_expr = typ.generate_encode_expr(ctx, &func, &val_ref);
+                    quote! {
+                        let len = #len;
+                        #encoder.encode_packed(len, #value, |#encoder, val| {let #val_ref = &val; #encode_expr})?;
+                    }
+                };
+                (
+                    quote! {
+                        if !#value.is_empty() {
+                            #encoder.encode_varint32(#tag_val)?;
+                            #encode_packed
+                        }
+                    },
+                    quote! {
+                        if #value.is_empty() {
+                            0
+                        } else {
+                            #tag_len + ::micropb::size::sizeof_len_record(#len)
+                        }
+                    },
+                )
+            }
+        };
+
+        let rust_name = &self.rust_name;
+        quote! {
+            impl ::micropb::extension::ExtensionEncode for #rust_name {
+                fn encode_value<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                    #value: &Self::Value,
+                    #encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                ) -> Result<(), IMPL_MICROPB_WRITE::Error>
+                {
+                    use ::micropb::MessageEncode;
+                    #encode_code
+                    Ok(())
+                }
+
+                fn compute_value_size(#value: &Self::Value) -> usize {
+                    use ::micropb::MessageEncode;
+                    #sizeof_code
+                }
+            }
+        }
+    }
+
+    /// Generate the descriptor struct of the extension, along with its trait impls
+    pub(crate) fn generate(
+        &self,
+        ctx: &Context<'proto>,
+        fq_proto_name: &str,
+    ) -> Result<TokenStream, String> {
+        let extendee_msg = ctx.graph.get_message(self.extendee);
+        if extendee_msg.is_some_and(|m| m.lifetime.is_some()) {
+            return Err("Messages with lifetimes can't be extended".to_owned());
+        }
+        let value_type = self.generate_value_type(ctx)?;
+        if find_lifetime_from_str(&value_type.to_string()).is_some() {
+            return Err("Extension values can't have lifetimes".to_owned());
+        }
+
+        let rust_name = &self.rust_name;
+        let extendee = ctx.resolve_type_name(self.extendee);
+        let num = self.num;
+        let full_name = fq_proto_name.trim_start_matches('.');
+        let comments = self.comments.map(Comments::lines).into_iter().flatten();
+        let summary = format!(
+            " Extension of `{}` with field number {num}",
+            self.extendee.trim_start_matches('.')
+        );
+        let empty_line = self.comments.map(|_| "").into_iter();
+
+        let decode = ctx
+            .params
+            .encode_decode
+            .is_decode()
+            .then(|| self.generate_decode(ctx))
+            .transpose()?;
+        let encode = ctx
+            .params
+            .encode_decode
+            .is_encode()
+            .then(|| self.generate_encode(ctx));
+
+        Ok(quote! {
+            #(#[doc = #comments])*
+            #(#[doc = #empty_line])*
+            #[doc = #summary]
+            #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+            #[allow(non_camel_case_types)]
+            pub struct #rust_name;
+
+            impl ::micropb::extension::Extension for #rust_name {
+                type Extendee = #extendee;
+                type Value = #value_type;
+                const NUMBER: u32 = #num;
+                const FULL_NAME: &'static str = #full_name;
+            }
+
+            #decode
+            #encode
+        })
+    }
+}
@@ -14,7 +14,10 @@ use crate::utils::{find_lifetime_from_str, find_lifetime_from_type};
 
 use super::Syntax;
 use super::location::{self, CommentNode, Comments};
-use super::{CurrentConfig, EncodeFunc, type_spec::TypeSpec};
+use super::{
+    CurrentConfig, DecodeFunc, EncodeFunc,
+    type_spec::{TypeSpec, UnknownEnumSink},
+};
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub(crate) enum CustomField {
@@ -46,12 +49,48 @@ pub(crate) enum FieldType<'proto> {
     Custom(CustomField),
 }
 
+/// Use the JSON name from the descriptor, falling back to the same lowerCamelCase conversion as
+/// protoc if it's missing
+pub(crate) fn json_name(proto: &FieldDescriptorProto) -> String {
+    match proto.json_name() {
+        Some(json_name) => json_name.clone(),
+        None => to_json_name(&proto.name),
+    }
+}
+
+pub(crate) fn to_json_name(name: &str) -> String {
+    let mut json_name = String::with_capacity(name.len());
+    let mut capitalize = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            json_name.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
+/// Match both the JSON name and the Protobuf name of a field
+pub(crate) fn json_key_pattern(json_name: &str, pb_name: &str) -> TokenStream {
+    if json_name == pb_name {
+        quote! { #pb_name }
+    } else {
+        quote! { #json_name | #pb_name }
+    }
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub(crate) struct Field<'proto> {
     pub(crate) num: u32,
     pub(crate) ftype: FieldType<'proto>,
     /// Protobuf name
     pub(crate) name: &'proto str,
+    /// Name used in JSON
+    pub(crate) json_name: String,
     /// Non-sanitized Rust name after renaming, used for accessor names
     pub(crate) rust_name: String,
     /// Sanitized Rust ident after renaming, used for field name
@@ -81,7 +120,7 @@ impl<'proto> Field<'proto> {
             FieldType::Repeated { typ, .. } => typ,
             FieldType::Custom(_) => return None,
         };
-        if let TypeSpec::Message(name) = typ {
+        if let TypeSpec::Message(name, _) = typ {
             Some(name)
         } else {
             None
@@ -150,8 +189,21 @@ impl<'proto> Field<'proto> {
             (Some(t), _, _) => FieldType::Custom(t),
 
             (None, Some(map_msg), _) => {
-                let key = TypeSpec::from_proto(&map_msg.field[0], &field_conf.next_conf("key"))?;
-                let val = TypeSpec::from_proto(&map_msg.field[1], &field_conf.next_conf("value"))?;
+                // Map values are always length-prefixed, regardless of message encoding
+                let mut map_features = FeatureSet::default();
+                if let Some(utf8) = feature_set.utf8_validation() {
+                    map_features.set_utf8_validation(*utf8);
+                }
+                let key = TypeSpec::from_proto(
+                    &map_msg.field[0],
+                    &field_conf.next_conf("key"),
+                    &map_features,
+                )?;
+                let val = TypeSpec::from_proto(
+                    &map_msg.field[1],
+                    &field_conf.next_conf("value"),
+                    &map_features,
+                )?;
                 let typestr = field_conf
                     .config
                     .map_type
@@ -181,7 +233,7 @@ impl<'proto> Field<'proto> {
             }
 
             (None, None, Label::Repeated) => {
-                let typ = TypeSpec::from_proto(proto, &field_conf.next_conf("elem"))?;
+                let typ = TypeSpec::from_proto(proto, &field_conf.next_conf("elem"), &feature_set)?;
                 let typestr = field_conf
                     .config
                     .vec_type
@@ -225,6 +277,7 @@ impl<'proto> Field<'proto> {
                     && matches!(label, Label::Required | Label::Optional))
                     || proto.proto3_optional
                     || proto.r#type == Type::Message
+                    || proto.r#type == Type::Group
                     || feature_set.field_presence() == Some(&FieldPresence::Explicit)
                     || feature_set.field_presence() == Some(&FieldPresence::LegacyRequired) =>
             {
@@ -233,10 +286,12 @@ impl<'proto> Field<'proto> {
                 } else {
                     OptionalRepr::Hazzer
                 });
-                FieldType::Optional(TypeSpec::from_proto(proto, field_conf)?, repr)
+                FieldType::Optional(TypeSpec::from_proto(proto, field_conf, &feature_set)?, repr)
             }
 
-            (None, None, _) => FieldType::Single(TypeSpec::from_proto(proto, field_conf)?),
+            (None, None, _) => {
+                FieldType::Single(TypeSpec::from_proto(proto, field_conf, &feature_set)?)
+            }
         };
         let encoded_max_size = field_conf.config.encoded_max_size;
         let attrs = field_conf.config.field_attr_parsed()?;
@@ -246,6 +301,7 @@ impl<'proto> Field<'proto> {
             num,
             ftype,
             name,
+            json_name: json_name(proto),
             rust_name,
             san_rust_name,
             default: proto.default_value().map(String::as_str),
@@ -504,7 +560,8 @@ impl<'proto> Field<'proto> {
                                 }
                             }
                         }
-                    })
+                    });
+                    accessors.extend(self.generate_str_accessor(type_spec, true));
                 }
                 Ok(accessors)
             }
@@ -524,7 +581,7 @@ impl<'proto> Field<'proto> {
                     self.rust_name
                 );
 
-                let accessors = quote! {
+                let mut accessors = quote! {
                     #[doc = #getter_doc]
                     #[inline]
                     pub fn #fname(&self) -> &#type_name {
@@ -551,35 +608,124 @@ impl<'proto> Field<'proto> {
                         self
                     }
                 };
+                accessors.extend(self.generate_str_accessor(type_spec, false));
                 Ok(accessors)
             }
             _ => Ok(quote! {}),
         }
     }
 
+    /// Generate an accessor that checks the contents of a `string` field without UTF-8
+    /// validation, which is stored as bytes
+    fn generate_str_accessor(&self, type_spec: &TypeSpec, optional: bool) -> Option<TokenStream> {
+        let TypeSpec::String {
+            validate_utf8: false,
+            ..
+        } = type_spec
+        else {
+            return None;
+        };
+        let fname = &self.san_rust_name;
+        let str_name = format_ident!("{}_str", self.rust_name);
+        let str_type = quote! { ::core::result::Result<&str, ::core::str::Utf8Error> };
+        let accessor = if optional {
+            let doc = format!(
+                " Return `{}` as a string slice, or an error if it isn't valid UTF-8, as an `Option`",
+                self.rust_name
+            );
+            quote! {
+                #[doc = #doc]
+                #[inline]
+                pub fn #str_name(&self) -> ::core::option::Option<#str_type> {
+                    self.#fname().map(|val| ::core::str::from_utf8(val))
+                }
+            }
+        } else {
+            let doc = format!(
+                " Return `{}` as a string slice, or an error if it isn't valid UTF-8",
+                self.rust_name
+            );
+            quote! {
+                #[doc = #doc]
+                #[inline]
+                pub fn #str_name(&self) -> #str_type {
+                    ::core::str::from_utf8(&self.#fname)
+                }
+            }
+        };
+        Some(accessor)
+    }
+
+    /// `unknown` is the unknown field handler of the message, which receives closed enum values
+    /// that don't match any variant
     pub(crate) fn generate_decode_branch(
         &self,
         ctx: &Context<'proto>,
         tag: &Ident,
-        decoder: &Ident,
+        func: &DecodeFunc,
+        unknown: Option<&Ident>,
     ) -> Result<TokenStream, String> {
         let fnum = self.num;
         let fname = &self.san_rust_name;
         let mut_ref = Ident::new("mut_ref", Span::call_site());
         let extra_deref = self.boxed.then(|| quote! { * });
+        let decoder = func.decoder();
+        let awaiter = func.awaiter();
+        let closure = func.closure_prefix();
+        let handler = unknown.map(|unknown| quote! { &mut self.#unknown });
+        let sink = handler
+            .as_ref()
+            .map(|handler| UnknownEnumSink { handler, fnum });
+        let sink = sink.as_ref();
 
         let decode_code = match &self.ftype {
+            FieldType::Map { key, val, .. } if val.is_closed_enum(ctx) => {
+                // The value is decoded as a number so that entries with unknown enum values can be
+                // passed to the unknown handler in full
+                let key_decode_expr = key.generate_decode_mut(ctx, false, func, &mut_ref, None)?;
+                let key_type = key.generate_rust_type(ctx)?;
+                let val_type = val.generate_rust_type(ctx)?;
+                let key_ref = Ident::new("key_ref", Span::call_site());
+                let unknown_entry = match (unknown, key.generate_map_key(&key_ref)) {
+                    (Some(unknown), Some(map_key)) => quote! {
+                        ::core::result::Result::Err(n) => {
+                            let #key_ref = &k;
+                            #decoder.decode_unknown_map_entry(&mut self.#unknown, #fnum, #map_key, n as u64)#awaiter?;
+                        }
+                    },
+                    _ => quote! { ::core::result::Result::Err(_) => {} },
+                };
+                quote! {
+                    if let Some((k, n)) = #decoder.decode_map_elem(
+                        #closure |#mut_ref: &mut #key_type, #decoder| { #key_decode_expr; Ok(()) },
+                        #closure |#mut_ref: &mut i32, #decoder| { *#mut_ref = #decoder.decode_int32()#awaiter?; Ok(()) },
+                    )#awaiter?
+                    {
+                        match <#val_type as ::core::convert::TryFrom<i32>>::try_from(n) {
+                            ::core::result::Result::Ok(v) => {
+                                #decoder.spend_elem_budget::<(#key_type, #val_type)>()?;
+                                if let (Err(_), false) = (self.#fname.pb_insert(k, v), #decoder.ignore_repeated_cap_err) {
+                                    return Err(::micropb::DecodeError::Capacity);
+                                }
+                            }
+                            #unknown_entry
+                        }
+                    }
+                }
+            }
+
             FieldType::Map { key, val, .. } => {
-                let key_decode_expr = key.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
-                let val_decode_expr = val.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+                let key_decode_expr = key.generate_decode_mut(ctx, false, func, &mut_ref, None)?;
+                let val_decode_expr = val.generate_decode_mut(ctx, false, func, &mut_ref, None)?;
                 let key_type = key.generate_rust_type(ctx)?;
                 let val_type = val.generate_rust_type(ctx)?;
                 quote! {
                     if let Some((k, v)) = #decoder.decode_map_elem(
-                        |#mut_ref: &mut #key_type, #decoder| { #key_decode_expr; Ok(()) },
-                        |#mut_ref: &mut #val_type, #decoder| { #val_decode_expr; Ok(()) },
-                    )?
+                        #closure |#mut_ref: &mut #key_type, #decoder| { #key_decode_expr; Ok(()) },
+                        #closure |#mut_ref: &mut #val_type, #decoder| { #val_decode_expr; Ok(()) },
+                    )#awaiter?
                     {
+                        #decoder.spend_elem_budget::<(#key_type, #val_type)>()?;
                         if let (Err(_), false) = (self.#fname.pb_insert(k, v), #decoder.ignore_repeated_cap_err) {
                             return Err(::micropb::DecodeError::Capacity);
                         }
@@ -588,7 +734,7 @@ impl<'proto> Field<'proto> {
             }
 
             FieldType::Single(tspec) => {
-                let decode_stmts = tspec.generate_decode_mut(ctx, true, decoder, &mut_ref)?;
+                let decode_stmts = tspec.generate_decode_mut(ctx, true, func, &mut_ref, sink)?;
                 quote! {
                     let #mut_ref = &mut #extra_deref self.#fname;
                     { #decode_stmts };
@@ -596,15 +742,29 @@ impl<'proto> Field<'proto> {
             }
 
             FieldType::Optional(tspec, OptionalRepr::None) => {
-                let decode_stmts = tspec.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+                let decode_stmts = tspec.generate_decode_mut(ctx, false, func, &mut_ref, sink)?;
                 quote! {
                     let #mut_ref = &mut #extra_deref self.#fname;
                     { #decode_stmts };
                 }
             }
 
+            FieldType::Optional(tspec, OptionalRepr::Hazzer) if tspec.is_closed_enum(ctx) => {
+                // Only set the hazzer if the value matches a variant
+                let val_expr = tspec
+                    .generate_decode_closed_enum(ctx, func, sink)
+                    .expect("should be closed enum");
+                let setter = format_ident!("set_{}", self.rust_name);
+                quote! {
+                    if let ::core::option::Option::Some(val) = #val_expr? {
+                        #extra_deref self.#fname = val;
+                        self._has.#setter();
+                    }
+                }
+            }
+
             FieldType::Optional(tspec, OptionalRepr::Hazzer) => {
-                let decode_expr = tspec.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+                let decode_expr = tspec.generate_decode_mut(ctx, false, func, &mut_ref, sink)?;
                 let setter = format_ident!("set_{}", self.rust_name);
                 quote! {
                     let #mut_ref = &mut #extra_deref self.#fname;
@@ -613,34 +773,83 @@ impl<'proto> Field<'proto> {
                 }
             }
 
+            FieldType::Optional(tspec, OptionalRepr::Option) if tspec.is_closed_enum(ctx) => {
+                let val_expr = tspec
+                    .generate_decode_closed_enum(ctx, func, sink)
+                    .expect("should be closed enum");
+                let value = ctx.wrapped_value(quote! { val }, self.boxed, true);
+                quote! {
+                    if let ::core::option::Option::Some(val) = #val_expr? {
+                        self.#fname = #value;
+                    }
+                }
+            }
+
             FieldType::Optional(tspec, OptionalRepr::Option) => {
-                let decode_stmts = tspec.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+                let decode_stmts = tspec.generate_decode_mut(ctx, false, func, &mut_ref, sink)?;
                 quote! {
                     let #mut_ref = &mut #extra_deref *self.#fname.get_or_insert_with(::core::default::Default::default);
                     { #decode_stmts };
                 }
             }
 
+            FieldType::Repeated { typ, .. } if typ.is_closed_enum(ctx) => {
+                // The unknown handler is borrowed separately so that the packed decoding closure
+                // doesn't capture all of `self`
+                let packed_handler = unknown.map(|_| quote! { &mut *unknown });
+                let packed_sink = packed_handler
+                    .as_ref()
+                    .map(|handler| UnknownEnumSink { handler, fnum });
+                let packed_val = typ
+                    .generate_decode_closed_enum(ctx, func, packed_sink.as_ref())
+                    .expect("should be closed enum");
+                let borrow_unknown =
+                    unknown.map(|unknown| quote! { let unknown = &mut self.#unknown; });
+                let val = typ
+                    .generate_decode_closed_enum(ctx, func, sink)
+                    .expect("should be closed enum");
+                let rust_type = typ.generate_rust_type(ctx)?;
+                quote! {
+                    if #tag.wire_type() == ::micropb::WIRE_TYPE_LEN {
+                        #borrow_unknown
+                        #decoder.decode_packed_filtered(&mut #extra_deref self.#fname, #closure |#decoder| #packed_val)#awaiter?;
+                    } else if let ::core::option::Option::Some(val) = #val? {
+                        #decoder.spend_elem_budget::<#rust_type>()?;
+                        if let (Err(_), false) = (self.#fname.pb_push(val), #decoder.ignore_repeated_cap_err) {
+                            return Err(::micropb::DecodeError::Capacity);
+                        }
+                    }
+                }
+            }
+
             FieldType::Repeated { typ, .. } => {
                 // Type can be packed and is Copy, so we check the wire type to see if we can
                 // do packed decoding
-                if let Some(val) = typ.generate_decode_val(ctx, decoder) {
+                let rust_type = typ.generate_rust_type(ctx)?;
+                if let Some(val) = typ.generate_decode_val(ctx, func) {
+                    let decode_packed = if typ.is_fixed_width() {
+                        // Copy the elements from the wire in bulk
+                        quote! { #decoder.decode_packed_fixed(&mut #extra_deref self.#fname)#awaiter? }
+                    } else {
+                        quote! { #decoder.decode_packed(&mut #extra_deref self.#fname, #closure |#decoder| #val.map(|v| v as _))#awaiter? }
+                    };
                     quote! {
                         if #tag.wire_type() == ::micropb::WIRE_TYPE_LEN {
-                            #decoder.decode_packed(&mut #extra_deref self.#fname, |#decoder| #val.map(|v| v as _))?;
+                            #decode_packed;
                         } else {
+                            #decoder.spend_elem_budget::<#rust_type>()?;
                             if let (Err(_), false) = (self.#fname.pb_push(#val? as _), #decoder.ignore_repeated_cap_err) {
                                 return Err(::micropb::DecodeError::Capacity);
                             }
                         }
                     }
                 } else {
-                    let decode_expr = typ.generate_decode_mut(ctx, false, decoder, &mut_ref)?;
-                    let rust_type = typ.generate_rust_type(ctx)?;
+                    let decode_expr = typ.generate_decode_mut(ctx, false, func, &mut_ref, None)?;
                     quote! {
                         let mut val: #rust_type = ::core::default::Default::default();
                         let #mut_ref = &mut val;
                         { #decode_expr };
+                        #decoder.spend_elem_budget::<#rust_type>()?;
                         if let (Err(_), false) = (self.#fname.pb_push(val), #decoder.ignore_repeated_cap_err) {
                             return Err(::micropb::DecodeError::Capacity);
                         }
@@ -649,11 +858,13 @@ impl<'proto> Field<'proto> {
             }
 
             FieldType::Custom(CustomField::Type(_)) => {
-                quote! { if !self.#fname.decode_field(#tag, #decoder)? { return Err(::micropb::DecodeError::CustomField) } }
+                let decode_field = func.generate_custom_decode(fname, tag);
+                quote! { if !#decode_field { return Err(::micropb::DecodeError::CustomField) } }
             }
 
             FieldType::Custom(CustomField::Delegate(field)) => {
-                quote! { if !self.#field.decode_field(#tag, #decoder)? { return Err(::micropb::DecodeError::CustomField) } }
+                let decode_field = func.generate_custom_decode(field, tag);
+                quote! { if !#decode_field { return Err(::micropb::DecodeError::CustomField) } }
             }
         };
 
@@ -833,6 +1044,8 @@ impl<'proto> Field<'proto> {
         let tag = micropb::Tag::from_parts(self.num, wire_type);
         let tag_val = tag.varint();
         let tag_len = ::micropb::size::sizeof_tag(tag);
+        let awaiter = func_type.awaiter();
+        let closure = func_type.closure_prefix();
 
         let sizeof_code = match &self.ftype {
             FieldType::Map { key, val, .. } => {
@@ -862,8 +1075,10 @@ impl<'proto> Field<'proto> {
                         )
                     }
 
-                    EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
-                        let key_encode = key.generate_encode_expr(ctx, encoder, &val_ref);
+                    EncodeFunc::Encode(encoder)
+                    | EncodeFunc::EncodeCached(encoder, _)
+                    | EncodeFunc::EncodeAsync(encoder, _) => {
+                        let key_encode = key.generate_encode_expr(ctx, func_type, &val_ref);
                         let key_wtype = key.wire_type();
                         let val_wtype = val.wire_type();
 
@@ -878,17 +1093,17 @@ impl<'proto> Field<'proto> {
                             )
                         } else {
                             (
-                                val.generate_encode_expr(ctx, encoder, &val_ref),
+                                val.generate_encode_expr(ctx, func_type, &val_ref),
                                 val.generate_sizeof(ctx, &val_ref),
                             )
                         };
                         let stmts = quote! {
-                            #encoder.encode_varint32(#tag_val)?;
+                            #encoder.encode_varint32(#tag_val)#awaiter?;
                             #encoder.encode_map_elem(
                                 len, k, #key_wtype, v, #val_wtype,
-                                |#encoder, #val_ref| { #key_encode },
-                                |#encoder, #val_ref| { #val_encode }
-                            )?;
+                                #closure |#encoder, #val_ref| { #key_encode },
+                                #closure |#encoder, #val_ref| { #val_encode }
+                            )#awaiter?;
                         };
                         (val_sizeof, stmts)
                     }
@@ -905,7 +1120,8 @@ impl<'proto> Field<'proto> {
                 let check = if let FieldType::Optional(..) = self.ftype {
                     quote! { if let ::core::option::Option::Some(#val_ref) = self.#fname() }
                 } else {
-                    let implicit_presence_check = tspec.generate_implicit_presence_check(&val_ref);
+                    let implicit_presence_check =
+                        tspec.generate_implicit_presence_check(ctx, &val_ref);
                     quote! {
                         let #val_ref = &#extra_deref self.#fname;
                         #implicit_presence_check
@@ -928,10 +1144,10 @@ impl<'proto> Field<'proto> {
                         }
                     }
 
-                    EncodeFunc::Encode(encoder) => {
-                        let encode_expr = tspec.generate_encode_expr(ctx, encoder, &val_ref);
+                    EncodeFunc::Encode(encoder) | EncodeFunc::EncodeAsync(encoder, _) => {
+                        let encode_expr = tspec.generate_encode_expr(ctx, func_type, &val_ref);
                         quote! {
-                            #encoder.encode_varint32(#tag_val)?;
+                            #encoder.encode_varint32(#tag_val)#awaiter?;
                             #encode_expr?;
                         }
                     }
@@ -939,7 +1155,7 @@ impl<'proto> Field<'proto> {
                         let encode_expr = if tspec.is_cached(ctx) {
                             quote! { #val_ref.encode_len_delimited_cached(#encoder, &#cache.#fname) }
                         } else {
-                            tspec.generate_encode_expr(ctx, encoder, &val_ref)
+                            tspec.generate_encode_expr(ctx, func_type, &val_ref)
                         };
                         quote! {
                             #encoder.encode_varint32(#tag_val)?;
@@ -981,10 +1197,10 @@ impl<'proto> Field<'proto> {
                         }
                     }
 
-                    (EncodeFunc::Encode(encoder), _) => {
-                        let encode_expr = typ.generate_encode_expr(ctx, encoder, &val_ref);
+                    (EncodeFunc::Encode(encoder) | EncodeFunc::EncodeAsync(encoder, _), _) => {
+                        let encode_expr = typ.generate_encode_expr(ctx, func_type, &val_ref);
                         quote! {
-                            #encoder.encode_varint32(#tag_val)?;
+                            #encoder.encode_varint32(#tag_val)#awaiter?;
                             #encode_expr?;
                         }
                     }
@@ -992,7 +1208,7 @@ impl<'proto> Field<'proto> {
                         let encode_expr = if typ.is_cached(ctx) {
                             quote! { #val_ref.encode_len_delimited_cached(#encoder, &#cache.#fname[i]) }
                         } else {
-                            typ.generate_encode_expr(ctx, encoder, &val_ref)
+                            typ.generate_encode_expr(ctx, func_type, &val_ref)
                         };
                         quote! {
                             #encoder.encode_varint32(#tag_val)?;
@@ -1029,11 +1245,28 @@ impl<'proto> Field<'proto> {
                         }
                     }
 
-                    EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
-                        let encode_expr = typ.generate_encode_expr(ctx, encoder, &val_ref);
+                    // Elements are copied onto the wire in bulk, and the length is computed by
+                    // the encoder
+                    EncodeFunc::Encode(encoder)
+                    | EncodeFunc::EncodeCached(encoder, _)
+                    | EncodeFunc::EncodeAsync(encoder, _)
+                        if typ.is_fixed_width() =>
+                    {
+                        return quote! {{
+                            if !self.#fname.is_empty() {
+                                #encoder.encode_varint32(#tag_val)#awaiter?;
+                                #encoder.encode_packed_fixed(& #extra_deref self.#fname)#awaiter?;
+                            }
+                        }};
+                    }
+
+                    EncodeFunc::Encode(encoder)
+                    | EncodeFunc::EncodeCached(encoder, _)
+                    | EncodeFunc::EncodeAsync(encoder, _) => {
+                        let encode_expr = typ.generate_encode_expr(ctx, func_type, &val_ref);
                         quote! {
-                            #encoder.encode_varint32(#tag_val)?;
-                            #encoder.encode_packed(len, & #extra_deref self.#fname, |#encoder, val| {let #val_ref = &val; #encode_expr})?;
+                            #encoder.encode_varint32(#tag_val)#awaiter?;
+                            #encoder.encode_packed(len, & #extra_deref self.#fname, #closure |#encoder, val| {let #val_ref = &val; #encode_expr})#awaiter?;
                         }
                     }
                 };
@@ -1053,6 +1286,9 @@ impl<'proto> Field<'proto> {
                 EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
                     quote! { self.#fname.encode_fields(#encoder)?; }
                 }
+                EncodeFunc::EncodeAsync(encoder, _) => {
+                    quote! { self.#fname.encode_fields_async(#encoder).await?; }
+                }
             },
 
             FieldType::Custom(CustomField::Delegate(_)) => quote! {},
@@ -1062,6 +1298,497 @@ impl<'proto> Field<'proto> {
             #sizeof_code
         }}
     }
+
+    pub(crate) fn generate_text_decode_branch(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> Result<TokenStream, String> {
+        let fname = &self.san_rust_name;
+        let pb_name = self.name;
+        let mut_ref = Ident::new("mut_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        let decode_code = match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_type = key.generate_rust_type(ctx)?;
+                let val_type = val.generate_rust_type(ctx)?;
+                let key_colon = key.generate_text_colon();
+                let val_colon = val.generate_text_colon();
+                let key_decode = key.generate_parse_mut(&mut_ref);
+                let val_decode = val.generate_parse_mut(&mut_ref);
+                quote! {
+                    decoder.decode_colon(false)?;
+                    decoder.decode_list(|decoder| {
+                        let mut k: #key_type = ::core::default::Default::default();
+                        let mut v: #val_type = ::core::default::Default::default();
+                        decoder.decode_nested(|decoder| {
+                            while let ::core::option::Option::Some(name) = decoder.next_field()? {
+                                match name {
+                                    "key" => { let #mut_ref = &mut k; #key_colon #key_decode }
+                                    "value" => { let #mut_ref = &mut v; #val_colon #val_decode }
+                                    _ => return Err(::micropb::text::TextError::UnknownField),
+                                }
+                            }
+                            Ok(())
+                        })?;
+                        self.#fname.pb_insert(k, v).map_err(|_| ::micropb::text::TextError::Capacity)
+                    })?;
+                }
+            }
+
+            FieldType::Single(tspec) | FieldType::Optional(tspec, OptionalRepr::None) => {
+                let colon = tspec.generate_text_colon();
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                quote! {
+                    #colon
+                    let #mut_ref = &mut #extra_deref self.#fname;
+                    #decode_stmts
+                }
+            }
+
+            FieldType::Optional(tspec, OptionalRepr::Hazzer) => {
+                let colon = tspec.generate_text_colon();
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                let setter = format_ident!("set_{}", self.rust_name);
+                quote! {
+                    #colon
+                    let #mut_ref = &mut #extra_deref self.#fname;
+                    #decode_stmts
+                    self._has.#setter();
+                }
+            }
+
+            FieldType::Optional(tspec, OptionalRepr::Option) => {
+                let colon = tspec.generate_text_colon();
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                quote! {
+                    #colon
+                    let #mut_ref = &mut #extra_deref *self.#fname.get_or_insert_with(::core::default::Default::default);
+                    #decode_stmts
+                }
+            }
+
+            FieldType::Repeated { typ, .. } => {
+                let colon = typ.generate_text_colon();
+                let decode_stmts = typ.generate_parse_mut(&mut_ref);
+                let rust_type = typ.generate_rust_type(ctx)?;
+                quote! {
+                    #colon
+                    decoder.decode_list(|decoder| {
+                        let mut val: #rust_type = ::core::default::Default::default();
+                        let #mut_ref = &mut val;
+                        #decode_stmts
+                        self.#fname.pb_push(val).map_err(|_| ::micropb::text::TextError::Capacity)
+                    })?;
+                }
+            }
+
+            // Custom fields aren't part of the text format
+            FieldType::Custom(_) => return Ok(quote! {}),
+        };
+
+        Ok(quote! {
+            #pb_name => { #decode_code }
+        })
+    }
+
+    pub(crate) fn generate_text_encode(&self, ctx: &Context<'proto>) -> TokenStream {
+        let fname = &self.san_rust_name;
+        let pb_name = self.name;
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_encode = key.generate_text_encode("key", &val_ref);
+                let val_encode = val.generate_text_encode("value", &val_ref);
+                quote! {
+                    for (k, v) in (&#extra_deref self.#fname).into_iter() {
+                        encoder.begin_message(#pb_name)?;
+                        let #val_ref = k;
+                        #key_encode?;
+                        let #val_ref = v;
+                        #val_encode?;
+                        encoder.end_message()?;
+                    }
+                }
+            }
+
+            FieldType::Single(tspec) => {
+                let implicit_presence_check = tspec.generate_implicit_presence_check(ctx, &val_ref);
+                let encode_expr = tspec.generate_text_encode(pb_name, &val_ref);
+                quote! {{
+                    let #val_ref = &#extra_deref self.#fname;
+                    #implicit_presence_check {
+                        #encode_expr?;
+                    }
+                }}
+            }
+
+            FieldType::Optional(tspec, _) => {
+                let encode_expr = tspec.generate_text_encode(pb_name, &val_ref);
+                quote! {
+                    if let ::core::option::Option::Some(#val_ref) = self.#fname() {
+                        #encode_expr?;
+                    }
+                }
+            }
+
+            FieldType::Repeated { typ, .. } => {
+                let encode_expr = typ.generate_text_encode(pb_name, &val_ref);
+                quote! {
+                    for #val_ref in self.#fname.iter() {
+                        #encode_expr?;
+                    }
+                }
+            }
+
+            FieldType::Custom(_) => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_json_decode_branch(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> Result<TokenStream, String> {
+        let fname = &self.san_rust_name;
+        let key_pattern = json_key_pattern(&self.json_name, self.name);
+        let mut_ref = Ident::new("mut_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        let decode_code = match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_type = key.generate_rust_type(ctx)?;
+                let val_type = val.generate_rust_type(ctx)?;
+                let key_ident = Ident::new("key", Span::call_site());
+                let key_decode = key.generate_json_key_decode(&key_ident, &mut_ref);
+                let val_decode = val.generate_parse_mut(&mut_ref);
+                quote! {
+                    decoder.decode_nested(|decoder| {
+                        while let ::core::option::Option::Some(#key_ident) = decoder.next_key()? {
+                            let mut k: #key_type = ::core::default::Default::default();
+                            let mut v: #val_type = ::core::default::Default::default();
+                            { let #mut_ref = &mut k; #key_decode }
+                            { let #mut_ref = &mut v; #val_decode }
+                            self.#fname.pb_insert(k, v).map_err(|_| ::micropb::json::JsonError::Capacity)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+            }
+
+            FieldType::Single(tspec) | FieldType::Optional(tspec, OptionalRepr::None) => {
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                quote! {
+                    let #mut_ref = &mut #extra_deref self.#fname;
+                    #decode_stmts
+                }
+            }
+
+            FieldType::Optional(tspec, OptionalRepr::Hazzer) => {
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                let setter = format_ident!("set_{}", self.rust_name);
+                quote! {
+                    let #mut_ref = &mut #extra_deref self.#fname;
+                    #decode_stmts
+                    self._has.#setter();
+                }
+            }
+
+            FieldType::Optional(tspec, OptionalRepr::Option) => {
+                let decode_stmts = tspec.generate_parse_mut(&mut_ref);
+                quote! {
+                    let #mut_ref = &mut #extra_deref *self.#fname.get_or_insert_with(::core::default::Default::default);
+                    #decode_stmts
+                }
+            }
+
+            FieldType::Repeated { typ, .. } => {
+                let decode_stmts = typ.generate_parse_mut(&mut_ref);
+                let rust_type = typ.generate_rust_type(ctx)?;
+                quote! {
+                    decoder.decode_array(|decoder| {
+                        let mut val: #rust_type = ::core::default::Default::default();
+                        let #mut_ref = &mut val;
+                        #decode_stmts
+                        self.#fname.pb_push(val).map_err(|_| ::micropb::json::JsonError::Capacity)
+                    })?;
+                }
+            }
+
+            // Custom fields aren't part of the JSON encoding
+            FieldType::Custom(_) => return Ok(quote! {}),
+        };
+
+        // Null values leave the field untouched
+        Ok(quote! {
+            #key_pattern => if !decoder.decode_null()? { #decode_code }
+        })
+    }
+
+    fn generate_serde_mode(&self, ctx: &Context<'proto>) -> Result<TokenStream, String> {
+        Ok(match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_type = key.generate_rust_type(ctx)?;
+                let val_type = val.generate_rust_type(ctx)?;
+                let key_mode = key.generate_serde_mode();
+                let val_mode = val.generate_serde_mode();
+                quote! { ::micropb::serde::Map<#key_type, #val_type, #key_mode, #val_mode> }
+            }
+            FieldType::Single(tspec) | FieldType::Optional(tspec, _) => tspec.generate_serde_mode(),
+            FieldType::Repeated { typ, .. } => {
+                let elem_type = typ.generate_rust_type(ctx)?;
+                let elem_mode = typ.generate_serde_mode();
+                quote! { ::micropb::serde::Repeated<#elem_type, #elem_mode> }
+            }
+            FieldType::Custom(_) => quote! {},
+        })
+    }
+
+    /// Generate code that runs `body` with `val_ref` bound to the field value if the field
+    /// should be serialized
+    fn generate_serde_if_present(&self, ctx: &Context<'proto>, body: TokenStream) -> TokenStream {
+        let fname = &self.san_rust_name;
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        match &self.ftype {
+            FieldType::Map { .. } => quote! {
+                if (&#extra_deref self.#fname).into_iter().next().is_some() {
+                    let #val_ref = &#extra_deref self.#fname;
+                    #body
+                }
+            },
+            FieldType::Single(tspec) => {
+                let implicit_presence_check = tspec.generate_implicit_presence_check(ctx, &val_ref);
+                quote! {{
+                    let #val_ref = &#extra_deref self.#fname;
+                    #implicit_presence_check { #body }
+                }}
+            }
+            FieldType::Optional(..) => quote! {
+                if let ::core::option::Option::Some(#val_ref) = self.#fname() {
+                    #body
+                }
+            },
+            FieldType::Repeated { .. } => quote! {
+                if !self.#fname.is_empty() {
+                    let #val_ref = &#extra_deref self.#fname;
+                    #body
+                }
+            },
+            FieldType::Custom(_) => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_serde_count(&self, ctx: &Context<'proto>) -> TokenStream {
+        self.generate_serde_if_present(ctx, quote! { len += 1; })
+    }
+
+    pub(crate) fn generate_serialize(&self, ctx: &Context<'proto>) -> Result<TokenStream, String> {
+        let json_name = &self.json_name;
+        let mode = self.generate_serde_mode(ctx)?;
+        Ok(self.generate_serde_if_present(ctx, quote! {
+            state.serialize_field(#json_name, &::micropb::serde::With::<_, #mode>::new(val_ref))?;
+        }))
+    }
+
+    pub(crate) fn generate_deserialize_branch(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> Result<TokenStream, String> {
+        let fname = &self.san_rust_name;
+        let key_pattern = json_key_pattern(&self.json_name, self.name);
+        let extra_deref = self.boxed.then(|| quote! { * });
+        let mode = self.generate_serde_mode(ctx)?;
+
+        let (place, after) = match &self.ftype {
+            FieldType::Optional(_, OptionalRepr::Hazzer) => {
+                let setter = format_ident!("set_{}", self.rust_name);
+                (
+                    quote! { &mut #extra_deref self.#fname },
+                    quote! { self._has.#setter(); },
+                )
+            }
+            FieldType::Optional(_, OptionalRepr::Option) => (
+                quote! { &mut #extra_deref *self.#fname.get_or_insert_with(::core::default::Default::default) },
+                quote! {},
+            ),
+            FieldType::Custom(_) => return Ok(quote! {}),
+            _ => (quote! { &mut #extra_deref self.#fname }, quote! {}),
+        };
+        Ok(quote! {
+            #key_pattern => {
+                map.next_value_seed(::micropb::serde::Place::<_, #mode>::new(#place))?;
+                #after
+            }
+        })
+    }
+
+    /// Keys accepted when deserializing this field
+    pub(crate) fn serde_keys(&self) -> Vec<&str> {
+        match &self.ftype {
+            FieldType::Custom(_) => vec![],
+            _ if self.json_name == self.name => vec![self.name],
+            _ => vec![&self.json_name, self.name],
+        }
+    }
+
+    pub(crate) fn generate_json_encode(&self, ctx: &Context<'proto>) -> TokenStream {
+        let fname = &self.san_rust_name;
+        let json_name = &self.json_name;
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_encode = key.generate_json_key_encode(&val_ref);
+                let val_encode = val.generate_json_encode(&val_ref);
+                quote! {{
+                    let mut iter = (&#extra_deref self.#fname).into_iter().peekable();
+                    if iter.peek().is_some() {
+                        encoder.write_key(#json_name)?;
+                        encoder.begin_object()?;
+                        for (k, v) in iter {
+                            let #val_ref = k;
+                            #key_encode?;
+                            let #val_ref = v;
+                            #val_encode?;
+                        }
+                        encoder.end_object()?;
+                    }
+                }}
+            }
+
+            FieldType::Single(tspec) => {
+                let implicit_presence_check = tspec.generate_implicit_presence_check(ctx, &val_ref);
+                let encode_expr = tspec.generate_json_encode(&val_ref);
+                quote! {{
+                    let #val_ref = &#extra_deref self.#fname;
+                    #implicit_presence_check {
+                        encoder.write_key(#json_name)?;
+                        #encode_expr?;
+                    }
+                }}
+            }
+
+            FieldType::Optional(tspec, _) => {
+                let encode_expr = tspec.generate_json_encode(&val_ref);
+                quote! {
+                    if let ::core::option::Option::Some(#val_ref) = self.#fname() {
+                        encoder.write_key(#json_name)?;
+                        #encode_expr?;
+                    }
+                }
+            }
+
+            FieldType::Repeated { typ, .. } => {
+                let encode_expr = typ.generate_json_encode(&val_ref);
+                quote! {
+                    if !self.#fname.is_empty() {
+                        encoder.write_key(#json_name)?;
+                        encoder.begin_array()?;
+                        for #val_ref in self.#fname.iter() {
+                            #encode_expr?;
+                        }
+                        encoder.end_array()?;
+                    }
+                }
+            }
+
+            FieldType::Custom(_) => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_merge(&self, ctx: &Context<'proto>) -> TokenStream {
+        let fname = &self.san_rust_name;
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        match &self.ftype {
+            FieldType::Map { key, val, .. } => {
+                let key_ref = Ident::new("key_ref", Span::call_site());
+                let key_val = key.generate_merge_value(ctx, &key_ref);
+                let val_val = val.generate_merge_value(ctx, &val_ref);
+                quote! {
+                    for (#key_ref, #val_ref) in &#extra_deref other.#fname {
+                        self.#fname
+                            .pb_insert(#key_val, #val_val)
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                }
+            }
+
+            FieldType::Single(TypeSpec::Message(..)) => quote! {
+                ::micropb::MessageMerge::merge_from(&mut #extra_deref self.#fname, &#extra_deref other.#fname)?;
+            },
+
+            FieldType::Single(tspec) => {
+                let implicit_presence_check = tspec.generate_implicit_presence_check(ctx, &val_ref);
+                let val = tspec.generate_merge_value(ctx, &val_ref);
+                quote! {{
+                    let #val_ref = &#extra_deref other.#fname;
+                    #implicit_presence_check {
+                        #extra_deref self.#fname = #val;
+                    }
+                }}
+            }
+
+            FieldType::Optional(tspec, repr) => {
+                // Destination of the merge, inserting the field if it's missing
+                let dest = match repr {
+                    OptionalRepr::Option => quote! {
+                        *#extra_deref self.#fname.get_or_insert_with(::core::default::Default::default)
+                    },
+                    OptionalRepr::Hazzer | OptionalRepr::None => {
+                        quote! { #extra_deref self.#fname }
+                    }
+                };
+                let set_presence = matches!(repr, OptionalRepr::Hazzer).then(|| {
+                    let setter = format_ident!("set_{}", self.rust_name);
+                    quote! { self._has.#setter(); }
+                });
+
+                let stmts = if let TypeSpec::Message(..) = tspec {
+                    // With hazzers, a message that isn't present may still contain stale data
+                    let reset = matches!(repr, OptionalRepr::Hazzer).then(|| {
+                        quote! {
+                            if !self._has.#fname() {
+                                self.#fname = ::core::default::Default::default();
+                            }
+                        }
+                    });
+                    quote! {
+                        #reset
+                        ::micropb::MessageMerge::merge_from(&mut #dest, #val_ref)?;
+                    }
+                } else {
+                    let val = tspec.generate_merge_value(ctx, &val_ref);
+                    quote! { #dest = #val; }
+                };
+                quote! {
+                    if let ::core::option::Option::Some(#val_ref) = other.#fname() {
+                        #stmts
+                        #set_presence
+                    }
+                }
+            }
+
+            FieldType::Repeated { typ, .. } => {
+                let val = typ.generate_merge_value(ctx, &val_ref);
+                quote! {
+                    self.#fname.pb_reserve(other.#fname.len());
+                    for #val_ref in other.#fname.iter() {
+                        self.#fname
+                            .pb_push(#val)
+                            .map_err(|_| ::micropb::CapacityExceeded)?;
+                    }
+                }
+            }
+
+            FieldType::Custom(_) => quote! {},
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1075,6 +1802,7 @@ pub(crate) fn make_test_field<'a>(
         num,
         ftype,
         name,
+        json_name: to_json_name(name),
         rust_name: name.to_owned(),
         san_rust_name: Ident::new_raw(name, proc_macro2::Span::call_site()),
         default: None,
@@ -1125,6 +1853,19 @@ mod tests {
         Field::from_proto(proto, conf, None, ctx, None, &FeatureSet::default()).unwrap()
     }
 
+    #[test]
+    fn json_names() {
+        assert_eq!(to_json_name("field"), "field");
+        assert_eq!(to_json_name("snake_case_name"), "snakeCaseName");
+        assert_eq!(to_json_name("field_2"), "field2");
+        assert_eq!(to_json_name("_leading__double"), "LeadingDouble");
+
+        let mut proto = field_proto(1, "some_field", None, false);
+        assert_eq!(json_name(&proto), "someField");
+        proto.set_json_name("custom".to_owned());
+        assert_eq!(json_name(&proto), "custom");
+    }
+
     #[test]
     fn from_proto_skipped() {
         let config = Box::new(Config::new().skip(true));
@@ -1156,6 +1897,7 @@ mod tests {
                 num: 2,
                 ftype: FieldType::Single(TypeSpec::Bool),
                 name: "field",
+                json_name: "field".to_owned(),
                 rust_name: "field".to_owned(),
                 san_rust_name: Ident::new_raw("field", Span::call_site()),
                 default: None,
@@ -1187,6 +1929,7 @@ mod tests {
                 num: 2,
                 ftype: FieldType::Single(TypeSpec::Bool),
                 name: "field",
+                json_name: "field".to_owned(),
                 rust_name: "renamed".to_owned(),
                 san_rust_name: Ident::new("renamed", Span::call_site()),
                 default: Some("true"),
@@ -1457,7 +2200,8 @@ mod tests {
                 key: TypeSpec::Int(PbInt::Int32, IntSize::S8),
                 val: TypeSpec::String {
                     typestr: "std::String".to_owned(),
-                    max_bytes: None
+                    max_bytes: None,
+                    validate_utf8: true,
                 },
                 typestr: "std::Map".to_owned(),
                 cache_vec_typestr: None,
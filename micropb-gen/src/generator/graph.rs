@@ -1,6 +1,8 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::generator::{Context, r#enum::Enum, field::FieldType, message::Message, oneof::Oneof};
+use crate::generator::{
+    Context, r#enum::Enum, extension::Extension, field::FieldType, message::Message, oneof::Oneof,
+};
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 #[derive(Clone, Copy)]
@@ -67,6 +69,7 @@ impl Position {
 pub(crate) struct TypeGraph<'proto> {
     messages: BTreeMap<String, Message<'proto>>,
     enums: BTreeMap<String, Enum<'proto>>,
+    extensions: BTreeMap<String, Extension<'proto>>,
 }
 
 impl<'proto> TypeGraph<'proto> {
@@ -78,6 +81,10 @@ impl<'proto> TypeGraph<'proto> {
         self.enums.insert(fq_proto_name, e);
     }
 
+    pub(crate) fn add_extension(&mut self, fq_proto_name: String, ext: Extension<'proto>) {
+        self.extensions.insert(fq_proto_name, ext);
+    }
+
     pub(crate) fn get_message(&self, fq_proto_name: &str) -> Option<&Message<'proto>> {
         self.messages.get(fq_proto_name)
     }
@@ -85,6 +92,14 @@ impl<'proto> TypeGraph<'proto> {
     pub(crate) fn get_enum(&self, fq_proto_name: &str) -> Option<&Enum<'proto>> {
         self.enums.get(fq_proto_name)
     }
+
+    pub(crate) fn get_extension(&self, fq_proto_name: &str) -> Option<&Extension<'proto>> {
+        self.extensions.get(fq_proto_name)
+    }
+
+    pub(crate) fn messages(&self) -> impl Iterator<Item = (&str, &Message<'proto>)> {
+        self.messages.iter().map(|(name, msg)| (name.as_str(), msg))
+    }
 }
 
 impl<'proto> Context<'proto> {
@@ -330,6 +345,19 @@ impl<'proto> Context<'proto> {
         );
     }
 
+    /// Detect cycles in the message graph via DFS and break those cycles by boxing the futures of
+    /// nested messages in async code, since recursive futures can't have a known size.
+    fn async_cyclic_dependencies(&mut self) {
+        let messages: Vec<_> = self.graph.messages.keys().cloned().collect();
+
+        self.forward_dfs(
+            &messages,
+            |_, _| true,
+            |_, msg| msg.async_box_futures = true,
+            |_, _| {},
+        );
+    }
+
     fn propagate_derive_copy(&mut self) {
         let messages: Vec<_> = self.graph.messages.keys().cloned().collect();
 
@@ -360,6 +388,9 @@ impl<'proto> Context<'proto> {
         // Cyclic dependencies
         self.box_cyclic_dependencies();
         self.max_size_cyclic_dependencies();
+        if self.params.async_encode_decode {
+            self.async_cyclic_dependencies();
+        }
 
         self.propagate_derive_copy();
     }
@@ -416,7 +447,7 @@ mod tests {
             num,
             fname,
             boxed,
-            FieldType::Optional(TypeSpec::Message(type_name), OptionalRepr::Option),
+            FieldType::Optional(TypeSpec::Message(type_name, None), OptionalRepr::Option),
         );
         field.max_size_override = max_size_override;
         msg.fields.push(field);
@@ -434,7 +465,8 @@ mod tests {
         let oneof_fields = msg.oneofs[0].otype.fields_mut().unwrap();
         msg.message_edges
             .push((Position::Oneof(oneof_idx, oneof_fields.len()), type_name));
-        let mut field = make_test_oneof_field(num, fname, boxed, TypeSpec::Message(type_name));
+        let mut field =
+            make_test_oneof_field(num, fname, boxed, TypeSpec::Message(type_name, None));
         field.max_size_override = max_size_override;
         oneof_fields.push(field);
     }
@@ -479,6 +511,7 @@ mod tests {
 
         ctx.box_cyclic_dependencies();
         ctx.max_size_cyclic_dependencies();
+        ctx.async_cyclic_dependencies();
 
         // Verification
         let alpha = ctx.graph.get_message(".pkg.Alpha").unwrap();
@@ -506,6 +539,13 @@ mod tests {
         assert!(
             matches!(&sigma.fields[0].max_size_override, Some(Err(e)) if e.contains("cyclical reference"))
         );
+
+        // Every cycle should have a message with boxed futures, even if the cycle already has a
+        // boxed field (A -> B -> O)
+        let box_futures = |name| ctx.graph.get_message(name).unwrap().async_box_futures;
+        assert!(box_futures(".pkg.Alpha") || box_futures(".pkg.Beta") || box_futures(".pkg.Gamma"));
+        assert!(box_futures(".pkg.Alpha") || box_futures(".pkg.Beta") || box_futures(".pkg.Omega"));
+        assert!(box_futures(".pkg.Sigma"));
     }
 
     #[test]
@@ -39,8 +39,60 @@ impl Comments {
     }
 }
 
+/// Split comment into lines. Indented code blocks are wrapped in `text` fences, since rustdoc
+/// would otherwise try to compile Protobuf examples as Rust doctests.
 fn get_lines(comment: &str) -> Vec<String> {
-    comment.lines().map(ToOwned::to_owned).collect()
+    let indent = |line: &str| line.len() - line.trim_start_matches(' ').len();
+    let is_blank = |line: &str| line.trim().is_empty();
+    // Rustdoc strips the common indentation of all doc lines before parsing Markdown
+    let base = comment
+        .lines()
+        .filter(|l| !is_blank(l))
+        .map(indent)
+        .min()
+        .unwrap_or(0);
+    let fence = format!("{:base$}```", "");
+
+    let mut lines: Vec<String> = vec![];
+    let mut in_fence = false;
+    let mut in_code_block = false;
+    let mut prev_blank = true;
+    for line in comment.lines() {
+        let blank = is_blank(line);
+        let fence_line = line.trim_start().starts_with("```");
+        let code = !in_fence && !fence_line && !blank && indent(line) >= base + 4;
+        if in_code_block && !blank && !code {
+            close_code_block(&mut lines, &fence);
+            in_code_block = false;
+        }
+        if fence_line {
+            in_fence = !in_fence;
+        } else if !in_code_block && code && prev_blank {
+            lines.push(format!("{fence}text"));
+            in_code_block = true;
+        }
+        prev_blank = blank;
+
+        if in_code_block && !blank {
+            lines.push(format!("{:base$}{}", "", &line[base + 4..]));
+        } else {
+            lines.push(line.to_owned());
+        }
+    }
+    if in_code_block {
+        close_code_block(&mut lines, &fence);
+    }
+    lines
+}
+
+/// Insert the closing fence before any trailing blank lines
+fn close_code_block(lines: &mut Vec<String>, fence: &str) {
+    let trailing_blanks = lines
+        .iter()
+        .rev()
+        .take_while(|l| l.trim().is_empty())
+        .count();
+    lines.insert(lines.len() - trailing_blanks, fence.to_owned());
 }
 
 pub(crate) type CommentNode = Node<Comments, (i32, i32)>;
@@ -91,6 +143,10 @@ pub(crate) mod path {
         (5, idx as i32)
     }
 
+    pub(crate) fn fdset_extension(idx: usize) -> (i32, i32) {
+        (7, idx as i32)
+    }
+
     pub(crate) fn msg_field(idx: usize) -> (i32, i32) {
         (2, idx as i32)
     }
@@ -103,6 +159,10 @@ pub(crate) mod path {
         (4, idx as i32)
     }
 
+    pub(crate) fn msg_extension(idx: usize) -> (i32, i32) {
+        (6, idx as i32)
+    }
+
     pub(crate) fn msg_oneof(idx: usize) -> (i32, i32) {
         (8, idx as i32)
     }
@@ -111,3 +171,39 @@ pub(crate) mod path {
         (2, idx as i32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fence_code_blocks() {
+        assert_eq!(
+            get_lines(" Just text\n   indented"),
+            [" Just text", "   indented"]
+        );
+
+        let comment = " Example:\n\n     Foo foo;\n\n       foo.bar();\n\n Done\n";
+        assert_eq!(
+            get_lines(comment),
+            [
+                " Example:",
+                "",
+                " ```text",
+                " Foo foo;",
+                "",
+                "   foo.bar();",
+                " ```",
+                "",
+                " Done"
+            ]
+        );
+
+        // Code blocks at the end of the comment, and existing fences, are also handled
+        let comment = "     code\n ```\n     fenced\n ```";
+        assert_eq!(
+            get_lines(comment),
+            [" ```text", " code", " ```", " ```", "     fenced", " ```"]
+        );
+    }
+}
@@ -5,10 +5,11 @@ use quote::{format_ident, quote};
 use syn::Ident;
 
 use crate::{
+    config::byte_string_type_parsed,
     descriptor::{DescriptorProto, FeatureSet},
     error::{field_error, msg_error},
     generator::{
-        Context, EncodeFunc,
+        Context, DecodeFunc, EncodeFunc,
         field::{CustomField, FieldType},
         graph::Position,
         location::{self, next_comment_node},
@@ -37,6 +38,17 @@ pub(crate) struct Hazzer {
 pub(crate) struct Unknown {
     handler: syn::Type,
     field_attrs: Vec<syn::Attribute>,
+    /// Whether the handler comes from `retain_unknown_fields`, which makes it an `UnknownFields`
+    retained: bool,
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub(crate) struct Extensions {
+    /// Inclusive ranges of extension field numbers
+    ranges: Vec<(u32, u32)>,
+    /// Bytes container for storing the extension fields
+    buf_type: syn::Type,
+    field_attrs: Vec<syn::Attribute>,
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
@@ -51,9 +63,11 @@ pub(crate) struct Message<'proto> {
     pub(crate) impl_default: bool,
     pub(crate) impl_partial_eq: bool,
     pub(crate) derive_clone: bool,
+    pub(crate) any_registry: bool,
     // Will be populated by graph resolver
     pub(crate) attrs: Vec<syn::Attribute>,
     pub(crate) unknown: Option<Unknown>,
+    pub(crate) extensions: Option<Extensions>,
     pub(crate) as_oneof_enum: bool,
     pub(crate) hazzer: Option<Hazzer>,
     comments: Option<&'proto Comments>,
@@ -63,6 +77,8 @@ pub(crate) struct Message<'proto> {
     pub(crate) parent_edges: Vec<(Position, String)>,
     pub(crate) is_copy: bool,
     pub(crate) lifetime: Option<syn::Lifetime>,
+    /// Whether nested message futures in async code need to be boxed to break recursion
+    pub(crate) async_box_futures: bool,
 }
 
 impl<'proto> Message<'proto> {
@@ -145,11 +161,16 @@ impl<'proto> Message<'proto> {
                                 ..
                             }) => {
                                 // Oneof field
-                                if let Some(field) =
-                                    OneofField::from_proto(f, &field_conf, field_comments)
-                                        .map_err(|e| field_error(&ctx.pkg, msg_name, &f.name, &e))?
+                                if let Some(field) = OneofField::from_proto(
+                                    f,
+                                    &field_conf,
+                                    field_comments,
+                                    ctx,
+                                    &feature_set,
+                                )
+                                .map_err(|e| field_error(&ctx.pkg, msg_name, &f.name, &e))?
                                 {
-                                    if let TypeSpec::Message(field_name) = field.tspec {
+                                    if let TypeSpec::Message(field_name, _) = field.tspec {
                                         message_edges.push((
                                             Position::Oneof(idx as usize, oneof_fields.len()),
                                             field_name,
@@ -202,14 +223,30 @@ impl<'proto> Message<'proto> {
             && oneofs.len() == 1
             && matches!(oneofs[0].otype, OneofType::Enum { .. });
 
-        let unknown = if let Some(handler) = msg_conf
-            .config
-            .unknown_handler_parsed()
-            .map_err(|e| msg_error(&ctx.pkg, msg_name, &e))?
+        let unknown_handler = match msg_conf.config.unknown_handler_parsed() {
+            Ok(None) => ctx
+                .params
+                .unknown_fields
+                .as_deref()
+                .map(|t| {
+                    syn::parse_str(t)
+                        .map(|handler| (handler, true))
+                        .map_err(|e| {
+                            format!(
+                                "Failed to parse retain_unknown_fields \"{t}\" as Rust type: {e}"
+                            )
+                        })
+                })
+                .transpose(),
+            res => res.map(|handler| handler.map(|handler| (handler, false))),
+        };
+        let unknown = if let Some((handler, retained)) =
+            unknown_handler.map_err(|e| msg_error(&ctx.pkg, msg_name, &e))?
         {
             let unknown_conf = msg_conf.next_conf("_unknown");
             Some(Unknown {
                 handler,
+                retained,
                 field_attrs: unknown_conf
                     .config
                     .field_attr_parsed()
@@ -219,6 +256,36 @@ impl<'proto> Message<'proto> {
             None
         };
 
+        let extensions = if msg_conf.config.retain_extensions.unwrap_or(false)
+            && !proto.extension_range.is_empty()
+        {
+            let ext_conf = msg_conf.next_conf("_extensions");
+            let typestr = ext_conf.config.bytes_type.as_deref().ok_or_else(|| {
+                field_error(
+                    &ctx.pkg,
+                    msg_name,
+                    "_extensions",
+                    "bytes_type not configured",
+                )
+            })?;
+            let buf_type = byte_string_type_parsed(typestr, ext_conf.config.max_bytes)
+                .map_err(|e| field_error(&ctx.pkg, msg_name, "_extensions", &e))?;
+            Some(Extensions {
+                ranges: proto
+                    .extension_range
+                    .iter()
+                    .map(|r| (r.start as u32, r.end as u32 - 1))
+                    .collect(),
+                buf_type,
+                field_attrs: ext_conf
+                    .config
+                    .field_attr_parsed()
+                    .map_err(|e| field_error(&ctx.pkg, msg_name, "_extensions", &e))?,
+            })
+        } else {
+            None
+        };
+
         let is_hazzer = !as_enum && fields.iter().any(|f| f.is_hazzer());
         let hazzer = if is_hazzer {
             let hazzer_conf = msg_conf.next_conf("_has");
@@ -245,8 +312,10 @@ impl<'proto> Message<'proto> {
             impl_default: msg_conf.impl_default(),
             impl_partial_eq: msg_conf.derive_partial_eq(),
             derive_clone: msg_conf.derive_clone(),
+            any_registry: msg_conf.config.any_registry.unwrap_or(false),
             attrs,
             unknown,
+            extensions,
             as_oneof_enum: as_enum,
             hazzer,
             comments: location::get_comments(comment_node),
@@ -255,6 +324,7 @@ impl<'proto> Message<'proto> {
             parent_edges: vec![],
             lifetime: None,
             is_copy: false,
+            async_box_futures: false,
         }))
     }
 
@@ -282,6 +352,7 @@ impl<'proto> Message<'proto> {
 
     pub(crate) fn is_copy(&self, ctx: &Context<'proto>) -> bool {
         self.unknown.is_none()
+            && self.extensions.is_none()
             && self.oneofs.iter().all(|oneof| oneof.is_copy(ctx))
             && self.fields.iter().all(|f| f.is_copy(ctx))
     }
@@ -429,6 +500,13 @@ impl<'proto> Message<'proto> {
             } else {
                 quote! {}
             };
+            let extensions_field = if let Some(ext) = &self.extensions {
+                let buf_type = &ext.buf_type;
+                let field_attr = &ext.field_attrs;
+                quote! { #[doc = " Extension fields on the wire"] #(#field_attr)* pub _extensions: ::micropb::extension::ExtensionFields<#buf_type>, }
+            } else {
+                quote! {}
+            };
             let hazzer_field_attr = self.hazzer.iter().map(|h| &h.field_attrs);
 
             Ok(quote! {
@@ -440,6 +518,7 @@ impl<'proto> Message<'proto> {
                     #(#oneof_fields)*
                     #(#[doc = " Tracks presence of optional and message fields"] #(#hazzer_field_attr)* pub _has: #msg_mod_name::_Hazzer,)*
                     #unknown_field
+                    #extensions_field
                 }
             })
         }
@@ -488,6 +567,10 @@ impl<'proto> Message<'proto> {
             .unknown
             .as_ref()
             .map(|_| quote! { _unknown: ::core::default::Default::default(), });
+        let extensions_default = self
+            .extensions
+            .as_ref()
+            .map(|_| quote! { _extensions: ::core::default::Default::default(), });
         let rust_name = &self.rust_name;
         let lifetime = &self.lifetime;
 
@@ -499,6 +582,7 @@ impl<'proto> Message<'proto> {
                         #(#oneof_names: ::core::default::Default::default(),)*
                         #hazzer_default
                         #unknown_default
+                        #extensions_default
                     }
                 }
             }
@@ -542,6 +626,10 @@ impl<'proto> Message<'proto> {
             }
         }
 
+        if self.extensions.is_some() {
+            body.extend(quote! { #ret_name &= (self._extensions == #other_name._extensions); });
+        }
+
         let rust_name = &self.rust_name;
         let lifetime = &self.lifetime;
         quote! {
@@ -577,15 +665,38 @@ impl<'proto> Message<'proto> {
         })
     }
 
-    pub(crate) fn generate_decode_trait(
+    pub(crate) fn generate_extendable_impl(&self) -> Option<TokenStream> {
+        let ext = self.extensions.as_ref().filter(|_| !self.as_oneof_enum)?;
+        let buf_type = &ext.buf_type;
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        Some(quote! {
+            impl<#lifetime> ::micropb::extension::Extendable for #name<#lifetime> {
+                type Buffer = #buf_type;
+
+                fn extensions(&self) -> &::micropb::extension::ExtensionFields<Self::Buffer> {
+                    &self._extensions
+                }
+
+                fn extensions_mut(&mut self) -> &mut ::micropb::extension::ExtensionFields<Self::Buffer> {
+                    &mut self._extensions
+                }
+            }
+        })
+    }
+
+    fn generate_decode_func(
         &self,
         ctx: &Context<'proto>,
+        func: &DecodeFunc,
     ) -> crate::Result<TokenStream> {
-        let name = &self.rust_name;
-        let lifetime = &self.lifetime;
         let tag = Ident::new("tag", Span::call_site());
-        let decoder = Ident::new("decoder", Span::call_site());
+        let decoder = func.decoder();
+        let awaiter = func.awaiter();
         let mod_name = resolve_path_elem(self.name, true);
+        // Ignore unknown handler if the message is an enum
+        let unknown = (self.unknown.is_some() && !self.as_oneof_enum)
+            .then(|| Ident::new("_unknown", Span::call_site()));
 
         let branches = if self.as_oneof_enum {
             let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
@@ -594,7 +705,7 @@ impl<'proto> Message<'proto> {
             let variant_branches = fields
                 .iter()
                 .map(|f| {
-                    f.generate_decode_branch_in_enum_msg(&decoder, ctx)
+                    f.generate_decode_branch_in_enum_msg(func, ctx)
                         .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
                 })
                 .try_into_tokens()?;
@@ -604,7 +715,7 @@ impl<'proto> Message<'proto> {
                 .fields
                 .iter()
                 .map(|f| {
-                    f.generate_decode_branch(ctx, &tag, &decoder)
+                    f.generate_decode_branch(ctx, &tag, func, unknown.as_ref())
                         .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
                 })
                 .try_into_tokens()?;
@@ -612,7 +723,7 @@ impl<'proto> Message<'proto> {
                 .oneofs
                 .iter()
                 .map(|o| {
-                    o.generate_decode_branches(ctx, &mod_name, &tag, &decoder)
+                    o.generate_decode_branches(ctx, &mod_name, &tag, func, unknown.as_ref())
                         .map_err(|e| field_error(&ctx.pkg, self.name, o.name, &e))
                 })
                 .try_into_tokens()?;
@@ -623,14 +734,59 @@ impl<'proto> Message<'proto> {
             }
         };
 
-        // Ignore unknown handler if the message is an enum
-        let unknown_branch = if self.unknown.is_some() && !self.as_oneof_enum {
+        let unknown_branch = if let Some(unknown) = &unknown {
+            let decode_field = func.generate_custom_decode(unknown, &tag);
             // If the unknown handler can't handle a field, skip it
-            quote! { if !self._unknown.decode_field(#tag, #decoder)? { #decoder.skip_wire_value(#tag.wire_type())?; } }
+            quote! { if !#decode_field { #decoder.skip_wire_value(#tag.wire_type())#awaiter?; } }
         } else {
-            quote! { #decoder.skip_wire_value(#tag.wire_type())?; }
+            quote! { #decoder.skip_wire_value(#tag.wire_type())#awaiter?; }
         };
 
+        let extensions_branch = self
+            .extensions
+            .as_ref()
+            .filter(|_| !self.as_oneof_enum)
+            .map(|ext| {
+                let ranges = ext
+                    .ranges
+                    .iter()
+                    .map(|(start, end)| quote! { #start..=#end });
+                let extensions = Ident::new("_extensions", Span::call_site());
+                let decode_field = func.generate_custom_decode(&extensions, &tag);
+                quote! {
+                    #(#ranges)|* => {
+                        if !#decode_field { #decoder.skip_wire_value(#tag.wire_type())#awaiter?; }
+                    }
+                }
+            });
+
+        Ok(quote! {
+            let before = #decoder.bytes_read();
+            while #decoder.bytes_read() - before < len {
+                let #tag = #decoder.decode_tag()#awaiter?;
+                if #tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                    return #decoder.end_group(#tag);
+                }
+                match #tag.field_num() {
+                    0 => return Err(::micropb::DecodeError::ZeroField),
+                    #branches
+                    #extensions_branch
+                    _ => { #unknown_branch }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    pub(crate) fn generate_decode_trait(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<TokenStream> {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let decoder = Ident::new("decoder", Span::call_site());
+        let body = self.generate_decode_func(ctx, &DecodeFunc::Decode(decoder.clone()))?;
+
         let tok = quote! {
             impl<#lifetime> ::micropb::MessageDecode for #name<#lifetime> {
                 fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
@@ -641,16 +797,36 @@ impl<'proto> Message<'proto> {
                 {
                     use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
 
-                    let before = #decoder.bytes_read();
-                    while #decoder.bytes_read() - before < len {
-                        let #tag = #decoder.decode_tag()?;
-                        match #tag.field_num() {
-                            0 => return Err(::micropb::DecodeError::ZeroField),
-                            #branches
-                            _ => { #unknown_branch }
-                        }
-                    }
-                    Ok(())
+                    #body
+                }
+            }
+        };
+        Ok(tok)
+    }
+
+    pub(crate) fn generate_async_decode_trait(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<TokenStream> {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let decoder = Ident::new("decoder", Span::call_site());
+        let body = self.generate_decode_func(
+            ctx,
+            &DecodeFunc::DecodeAsync(decoder.clone(), self.async_box_futures),
+        )?;
+
+        let tok = quote! {
+            impl<#lifetime> ::micropb::AsyncMessageDecode for #name<#lifetime> {
+                async fn decode_async<IMPL_MICROPB_READ: ::micropb::AsyncPbRead>(
+                    &mut self,
+                    #decoder: &mut ::micropb::AsyncPbDecoder<IMPL_MICROPB_READ>,
+                    len: usize,
+                ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>>
+                {
+                    use ::micropb::{PbBytes, PbString, PbVec, PbMap, AsyncFieldDecode, AsyncMessageDecode};
+
+                    #body
                 }
             }
         };
@@ -724,6 +900,28 @@ impl<'proto> Message<'proto> {
                     EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
                         quote! { self._unknown.encode_fields(#encoder)?; }
                     }
+                    EncodeFunc::EncodeAsync(encoder, _) => {
+                        quote! { self._unknown.encode_fields_async(#encoder).await?; }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            let extensions_logic = if self.extensions.is_some() {
+                match func_type {
+                    EncodeFunc::Sizeof(size) => {
+                        quote! { #size += self._extensions.compute_fields_size(); }
+                    }
+                    EncodeFunc::PopulateCache(cache) => {
+                        quote! { #cache._size += self._extensions.compute_fields_size(); }
+                    }
+                    EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
+                        quote! { self._extensions.encode_fields(#encoder)?; }
+                    }
+                    EncodeFunc::EncodeAsync(encoder, _) => {
+                        quote! { self._extensions.encode_fields_async(#encoder).await?; }
+                    }
                 }
             } else {
                 quote! {}
@@ -733,6 +931,7 @@ impl<'proto> Message<'proto> {
                 #(#field_logic)*
                 #(#oneof_logic)*
                 #unknown_logic
+                #extensions_logic
             }
         }
     }
@@ -759,7 +958,16 @@ impl<'proto> Message<'proto> {
                 .map(|u| &u.handler)
                 .map(|handler| quote! { <#handler as ::micropb::field::FieldEncode>::MAX_SIZE })
         };
-        let sizes = field_sizes.chain(oneof_sizes).chain(unknown_size);
+        let extensions_size = self
+            .extensions
+            .as_ref()
+            .filter(|_| !self.as_oneof_enum)
+            .map(|ext| &ext.buf_type)
+            .map(|buf_type| quote! { <::micropb::extension::ExtensionFields<#buf_type> as ::micropb::field::FieldEncode>::MAX_SIZE });
+        let sizes = field_sizes
+            .chain(oneof_sizes)
+            .chain(unknown_size)
+            .chain(extensions_size);
 
         quote! {
             const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
@@ -854,6 +1062,489 @@ impl<'proto> Message<'proto> {
             }
         }
     }
+
+    pub(crate) fn generate_async_encode_trait(&self, ctx: &Context<'proto>) -> TokenStream {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let encode = self.generate_encode_func(
+            ctx,
+            &EncodeFunc::EncodeAsync(
+                Ident::new("encoder", Span::call_site()),
+                self.async_box_futures,
+            ),
+        );
+
+        quote! {
+            impl<#lifetime> ::micropb::AsyncMessageEncode for #name<#lifetime> {
+                async fn encode_async<IMPL_MICROPB_WRITE: ::micropb::AsyncPbWrite>(
+                    &self,
+                    encoder: &mut ::micropb::AsyncPbEncoder<IMPL_MICROPB_WRITE>,
+                ) -> Result<(), IMPL_MICROPB_WRITE::Error>
+                {
+                    use ::micropb::{PbMap, AsyncFieldEncode, AsyncMessageEncode, MessageEncode};
+                    #encode
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generate_text_encode_trait(&self, ctx: &Context<'proto>) -> TokenStream {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let body = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_text_encode_branch(&quote! {Self}));
+            quote! {
+                match &self {
+                    #(#variant_branches)*
+                    Self::None => {}
+                }
+            }
+        } else {
+            let field_logic = self.fields.iter().map(|f| f.generate_text_encode(ctx));
+            let oneof_logic = self
+                .oneofs
+                .iter()
+                .map(|o| o.generate_text_encode(&mod_name));
+            quote! {
+                #(#field_logic)*
+                #(#oneof_logic)*
+            }
+        };
+
+        quote! {
+            impl<#lifetime> ::micropb::text::TextEncode for #name<#lifetime> {
+                fn encode_text<IMPL_MICROPB_WRITE: ::core::fmt::Write>(
+                    &self,
+                    encoder: &mut ::micropb::text::TextEncoder<IMPL_MICROPB_WRITE>,
+                ) -> ::core::fmt::Result
+                {
+                    use ::micropb::PbMap;
+                    #body
+                    Ok(())
+                }
+            }
+
+            impl<#lifetime> ::core::fmt::Display for #name<#lifetime> {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::micropb::text::TextEncode::encode_text(self, &mut ::micropb::text::TextEncoder::new(f))
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generate_text_decode_trait(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<TokenStream> {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let branches = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_text_decode_branch_in_enum_msg(ctx));
+            quote! { #(#variant_branches)* }
+        } else {
+            let field_branches = self
+                .fields
+                .iter()
+                .map(|f| {
+                    f.generate_text_decode_branch(ctx)
+                        .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
+                })
+                .try_into_tokens()?;
+            let oneof_branches = self
+                .oneofs
+                .iter()
+                .map(|o| o.generate_text_decode_branches(ctx, &mod_name));
+            quote! {
+                #field_branches
+                #(#oneof_branches)*
+            }
+        };
+
+        Ok(quote! {
+            impl<#lifetime> ::micropb::text::TextDecode for #name<#lifetime> {
+                fn decode_text(
+                    &mut self,
+                    decoder: &mut ::micropb::text::TextDecoder<'_>,
+                ) -> Result<(), ::micropb::text::TextError>
+                {
+                    use ::micropb::{PbBytes, PbString, PbVec, PbMap};
+
+                    while let ::core::option::Option::Some(name) = decoder.next_field()? {
+                        match name {
+                            #branches
+                            _ => return Err(::micropb::text::TextError::UnknownField),
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub(crate) fn generate_serde_traits(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<TokenStream> {
+        let name = &self.rust_name;
+        let pb_name = self.name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let (count, serialize, branches, keys) = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_serialize_branch(&quote! {Self}));
+            let deserialize_branches = fields
+                .iter()
+                .map(|f| f.generate_deserialize_branch(None, &quote! {Self}, false, ctx));
+            (
+                quote! { let len = if let Self::None = self { 0 } else { 1 }; },
+                quote! {
+                    match self {
+                        #(#variant_branches)*
+                        Self::None => {}
+                    }
+                },
+                quote! { #(#deserialize_branches)* },
+                self.oneofs[0].serde_keys(),
+            )
+        } else {
+            let field_counts = self.fields.iter().map(|f| f.generate_serde_count(ctx));
+            let oneof_counts = self.oneofs.iter().map(|o| o.generate_serde_count());
+            // Avoid an unused `mut` for messages without fields
+            let count = if self.fields.is_empty() && self.oneofs.is_empty() {
+                quote! { let len = 0; }
+            } else {
+                quote! {
+                    let mut len = 0;
+                    #(#field_counts)*
+                    #(#oneof_counts)*
+                }
+            };
+
+            let field_serialize = self
+                .fields
+                .iter()
+                .map(|f| {
+                    f.generate_serialize(ctx)
+                        .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
+                })
+                .try_into_tokens()?;
+            let oneof_serialize = self.oneofs.iter().map(|o| o.generate_serialize(&mod_name));
+
+            let field_branches = self
+                .fields
+                .iter()
+                .map(|f| {
+                    f.generate_deserialize_branch(ctx)
+                        .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
+                })
+                .try_into_tokens()?;
+            let oneof_branches = self
+                .oneofs
+                .iter()
+                .map(|o| o.generate_deserialize_branches(ctx, &mod_name));
+
+            let keys = self
+                .fields
+                .iter()
+                .flat_map(|f| f.serde_keys())
+                .chain(self.oneofs.iter().flat_map(|o| o.serde_keys()))
+                .collect();
+            (
+                count,
+                quote! {
+                    #field_serialize
+                    #(#oneof_serialize)*
+                },
+                quote! {
+                    #field_branches
+                    #(#oneof_branches)*
+                },
+                keys,
+            )
+        };
+
+        Ok(quote! {
+            impl<#lifetime> ::micropb::serde::Serialize for #name<#lifetime> {
+                fn serialize<IMPL_MICROPB_SERIALIZER: ::micropb::serde::Serializer>(
+                    &self,
+                    serializer: IMPL_MICROPB_SERIALIZER,
+                ) -> Result<IMPL_MICROPB_SERIALIZER::Ok, IMPL_MICROPB_SERIALIZER::Error>
+                {
+                    use ::micropb::serde::ser::SerializeStruct;
+
+                    #count
+                    let mut state = serializer.serialize_struct(#pb_name, len)?;
+                    #serialize
+                    state.end()
+                }
+            }
+
+            impl<'de, #lifetime> ::micropb::serde::Deserialize<'de> for #name<#lifetime> {
+                fn deserialize<IMPL_MICROPB_DESERIALIZER: ::micropb::serde::Deserializer<'de>>(
+                    deserializer: IMPL_MICROPB_DESERIALIZER,
+                ) -> Result<Self, IMPL_MICROPB_DESERIALIZER::Error>
+                {
+                    ::micropb::serde::deserialize_message(deserializer)
+                }
+            }
+
+            impl<'de, #lifetime> ::micropb::serde::DeserializeMessage<'de> for #name<#lifetime> {
+                const NAME: &'static str = #pb_name;
+                const FIELDS: &'static [&'static str] = &[#(#keys),*];
+
+                fn deserialize_field<IMPL_MICROPB_MAP: ::micropb::serde::de::MapAccess<'de>>(
+                    &mut self,
+                    key: &'static str,
+                    map: &mut IMPL_MICROPB_MAP,
+                ) -> Result<(), IMPL_MICROPB_MAP::Error>
+                {
+                    match key {
+                        #branches
+                        _ => {
+                            map.next_value::<::micropb::serde::de::IgnoredAny>()?;
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub(crate) fn generate_json_encode_trait(&self, ctx: &Context<'proto>) -> TokenStream {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let body = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_json_encode_branch(&quote! {Self}));
+            quote! {
+                match &self {
+                    #(#variant_branches)*
+                    Self::None => {}
+                }
+            }
+        } else {
+            let field_logic = self.fields.iter().map(|f| f.generate_json_encode(ctx));
+            let oneof_logic = self
+                .oneofs
+                .iter()
+                .map(|o| o.generate_json_encode(&mod_name));
+            quote! {
+                #(#field_logic)*
+                #(#oneof_logic)*
+            }
+        };
+
+        quote! {
+            impl<#lifetime> ::micropb::json::JsonEncode for #name<#lifetime> {
+                fn encode_json<IMPL_MICROPB_WRITE: ::core::fmt::Write>(
+                    &self,
+                    encoder: &mut ::micropb::json::JsonEncoder<IMPL_MICROPB_WRITE>,
+                ) -> ::core::fmt::Result
+                {
+                    use ::micropb::PbMap;
+                    #body
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generate_json_decode_trait(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<TokenStream> {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let branches = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_json_decode_branch_in_enum_msg(ctx));
+            quote! { #(#variant_branches)* }
+        } else {
+            let field_branches = self
+                .fields
+                .iter()
+                .map(|f| {
+                    f.generate_json_decode_branch(ctx)
+                        .map_err(|e| field_error(&ctx.pkg, self.name, f.name, &e))
+                })
+                .try_into_tokens()?;
+            let oneof_branches = self
+                .oneofs
+                .iter()
+                .map(|o| o.generate_json_decode_branches(ctx, &mod_name));
+            quote! {
+                #field_branches
+                #(#oneof_branches)*
+            }
+        };
+
+        Ok(quote! {
+            impl<#lifetime> ::micropb::json::JsonDecode for #name<#lifetime> {
+                fn decode_json(
+                    &mut self,
+                    decoder: &mut ::micropb::json::JsonDecoder<'_>,
+                ) -> Result<(), ::micropb::json::JsonError>
+                {
+                    use ::micropb::{PbBytes, PbString, PbVec, PbMap};
+
+                    while let ::core::option::Option::Some(key) = decoder.next_key()? {
+                        match key {
+                            #branches
+                            _ => decoder.skip_unknown_field()?,
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub(crate) fn generate_name_trait(&self, fq_proto_name: &str) -> TokenStream {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let full_name = fq_proto_name.trim_start_matches('.');
+        let type_url = format!("type.googleapis.com/{full_name}");
+        quote! {
+            impl<#lifetime> ::micropb::MessageName for #name<#lifetime> {
+                const FULL_NAME: &'static str = #full_name;
+                const TYPE_URL: &'static str = #type_url;
+            }
+        }
+    }
+
+    pub(crate) fn generate_merge_trait(&self, ctx: &Context<'proto>) -> TokenStream {
+        let name = &self.rust_name;
+        let lifetime = &self.lifetime;
+        let mod_name = resolve_path_elem(self.name, true);
+
+        let body = if self.as_oneof_enum {
+            let OneofType::Enum { fields, .. } = &self.oneofs[0].otype else {
+                unreachable!("shouldn't generate enum with custom oneof")
+            };
+            let variant_branches = fields
+                .iter()
+                .map(|f| f.generate_merge_branch_in_enum_msg(ctx));
+            quote! {
+                match other {
+                    #(#variant_branches)*
+                    Self::None => {}
+                }
+            }
+        } else {
+            let field_logic = self.fields.iter().map(|f| f.generate_merge(ctx));
+            let oneof_logic = self.oneofs.iter().map(|o| o.generate_merge(ctx, &mod_name));
+            let extensions_logic = self
+                .extensions
+                .as_ref()
+                .map(|_| quote! { self._extensions.merge_from(&other._extensions)?; });
+            // Custom unknown handlers are opaque, so only retained unknown fields are merged
+            let unknown_logic = self
+                .unknown
+                .as_ref()
+                .filter(|unknown| unknown.retained)
+                .map(|_| quote! { self._unknown.merge_from(&other._unknown)?; });
+            quote! {
+                #(#field_logic)*
+                #(#oneof_logic)*
+                #extensions_logic
+                #unknown_logic
+            }
+        };
+
+        quote! {
+            impl<#lifetime> ::micropb::MessageMerge for #name<#lifetime> {
+                fn merge_from(&mut self, other: &Self) -> Result<(), ::micropb::CapacityExceeded> {
+                    use ::micropb::{PbVec, PbMap};
+                    #body
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Generate `PbAny` for `google.protobuf.Any`, as long as its fields haven't been customized
+    /// into something other than plain string and bytes containers
+    pub(crate) fn generate_any_trait(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> crate::Result<Option<TokenStream>> {
+        let find_field = |num, is_string| {
+            self.fields.iter().find(|f| {
+                f.num == num
+                    && !f.boxed
+                    && match &f.ftype {
+                        FieldType::Single(TypeSpec::String {
+                            validate_utf8: true,
+                            ..
+                        }) => is_string,
+                        FieldType::Single(TypeSpec::Bytes { .. }) => !is_string,
+                        _ => false,
+                    }
+            })
+        };
+        let (Some(type_url), Some(value)) = (find_field(1, true), find_field(2, false)) else {
+            return Ok(None);
+        };
+        if self.lifetime.is_some() {
+            return Ok(None);
+        }
+
+        let name = &self.rust_name;
+        let type_url_name = &type_url.san_rust_name;
+        let value_name = &value.san_rust_name;
+        let type_url_type = type_url
+            .generate_rust_type(ctx)
+            .map_err(|e| field_error(&ctx.pkg, self.name, type_url.name, &e))?;
+        let value_type = value
+            .generate_rust_type(ctx)
+            .map_err(|e| field_error(&ctx.pkg, self.name, value.name, &e))?;
+        Ok(Some(quote! {
+            impl ::micropb::any::PbAny for #name {
+                type TypeUrl = #type_url_type;
+                type Value = #value_type;
+
+                fn any_parts(&self) -> (&Self::TypeUrl, &Self::Value) {
+                    (&self.#type_url_name, &self.#value_name)
+                }
+
+                fn any_parts_mut(&mut self) -> (&mut Self::TypeUrl, &mut Self::Value) {
+                    (&mut self.#type_url_name, &mut self.#value_name)
+                }
+            }
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -867,9 +1558,11 @@ pub(crate) fn make_test_msg(name: &str) -> Message<'_> {
         impl_default: true,
         impl_partial_eq: true,
         derive_clone: true,
+        any_registry: false,
         is_copy: false,
         attrs: vec![],
         unknown: None,
+        extensions: None,
         lifetime: None,
         as_oneof_enum: false,
         hazzer: None,
@@ -877,6 +1570,7 @@ pub(crate) fn make_test_msg(name: &str) -> Message<'_> {
 
         message_edges: vec![],
         parent_edges: vec![],
+        async_box_futures: false,
     }
 }
 
@@ -1111,6 +1805,7 @@ mod tests {
         expected.unknown = Some(Unknown {
             handler: syn::parse_str("UnknownType").unwrap(),
             field_attrs: vec![],
+            retained: false,
         });
 
         assert_eq!(from_msg_proto(&proto, &ctx, &msg_conf).unwrap(), expected)
@@ -1196,13 +1891,13 @@ mod tests {
                 1,
                 "internal",
                 false,
-                FieldType::Optional(TypeSpec::Message(".Internal"), OptionalRepr::Option),
+                FieldType::Optional(TypeSpec::Message(".Internal", None), OptionalRepr::Option),
             ),
             make_test_field(
                 2,
                 "external",
                 false,
-                FieldType::Optional(TypeSpec::Message(".External"), OptionalRepr::Option),
+                FieldType::Optional(TypeSpec::Message(".External", None), OptionalRepr::Option),
             ),
         ];
         // Only the first field should be an edge, since the second field is external
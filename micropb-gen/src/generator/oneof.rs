@@ -4,11 +4,16 @@ use quote::{format_ident, quote};
 use syn::{Ident, Lifetime};
 
 use crate::{
-    descriptor::{FieldDescriptorProto, OneofDescriptorProto},
+    descriptor::{FeatureSet, FieldDescriptorProto, OneofDescriptorProto},
     error::field_error,
     generator::{
-        Context, CurrentConfig, EncodeFunc, derive_msg_attr, field::CustomField, field_error_str,
-        location::get_comments, message::Message, sanitized_ident, type_spec::TypeSpec,
+        Context, CurrentConfig, DecodeFunc, EncodeFunc, derive_msg_attr,
+        field::{CustomField, json_key_pattern, json_name},
+        field_error_str,
+        location::get_comments,
+        message::Message,
+        sanitized_ident,
+        type_spec::{TypeSpec, UnknownEnumSink},
     },
     utils::{TryIntoTokens, find_lifetime_from_type},
 };
@@ -19,9 +24,10 @@ use super::location::{self, CommentNode, Comments};
 pub(crate) struct OneofField<'proto> {
     pub(crate) num: u32,
     pub(crate) tspec: TypeSpec<'proto>,
-    #[allow(unused)]
     /// Protobuf name
     pub(crate) name: &'proto str,
+    /// Name used in JSON
+    pub(crate) json_name: String,
     /// Sanitized Rust ident after renaming, used for field name
     pub(crate) rust_name: Ident,
     pub(crate) boxed: bool,
@@ -39,11 +45,19 @@ impl<'proto> OneofField<'proto> {
         proto: &'proto FieldDescriptorProto,
         field_conf: &CurrentConfig,
         comment_node: Option<&'proto CommentNode>,
+        ctx: &Context<'proto>,
+        feature_set: &FeatureSet,
     ) -> Result<Option<Self>, String> {
         if field_conf.config.skip.unwrap_or(false) {
             return Ok(None);
         }
 
+        let mut feature_set = feature_set.to_owned();
+        ctx.merge_feature_sets(
+            &mut feature_set,
+            proto.options().and_then(|opt| opt.features()),
+        )?;
+
         let name = &proto.name;
         // Oneof fields have camelcased variant names
         let rust_name = sanitized_ident(
@@ -54,13 +68,14 @@ impl<'proto> OneofField<'proto> {
                 .to_case(Case::Pascal),
         );
         let num = proto.number as u32;
-        let tspec = TypeSpec::from_proto(proto, field_conf)?;
+        let tspec = TypeSpec::from_proto(proto, field_conf, &feature_set)?;
         let attrs = field_conf.config.field_attr_parsed()?;
 
         Ok(Some(OneofField {
             num,
             tspec,
             name,
+            json_name: json_name(proto),
             rust_name,
             max_size_override: field_conf.config.encoded_max_size.map(Ok),
             boxed: field_conf.config.boxed.unwrap_or(false),
@@ -92,7 +107,8 @@ impl<'proto> OneofField<'proto> {
         oneof_type: &TokenStream,
         oneof_boxed: bool,
         ctx: &Context<'proto>,
-        decoder: &Ident,
+        func: &DecodeFunc,
+        unknown: Option<&Ident>,
     ) -> Result<TokenStream, String> {
         let fnum = self.num;
         let mut_ref = Ident::new("mut_ref", Span::call_site());
@@ -100,9 +116,34 @@ impl<'proto> OneofField<'proto> {
         let extra_deref_of = oneof_boxed.then(|| quote! { * });
         let extra_deref_var = self.boxed.then(|| quote! { * });
 
+        if let Some(val_expr) = self.tspec.generate_decode_closed_enum(
+            ctx,
+            func,
+            unknown
+                .map(|unknown| quote! { &mut self.#unknown })
+                .as_ref()
+                .map(|handler| UnknownEnumSink { handler, fnum })
+                .as_ref(),
+        ) {
+            // Only switch the oneof variant if the value matches an enum variant
+            let val = ctx.wrapped_value(quote! { val }, self.boxed, false);
+            let value = ctx.wrapped_value(
+                quote! { #oneof_type::#variant_name(#val) },
+                oneof_boxed,
+                true,
+            );
+            return Ok(quote! {
+                #fnum => {
+                    if let ::core::option::Option::Some(val) = #val_expr? {
+                        self.#oneof_name = #value;
+                    }
+                }
+            });
+        }
+
         let decode_stmts = self
             .tspec
-            .generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+            .generate_decode_mut(ctx, false, func, &mut_ref, None)?;
         let value = ctx.wrapped_value(
             quote! { #oneof_type::#variant_name(::core::default::Default::default()) },
             oneof_boxed,
@@ -126,7 +167,7 @@ impl<'proto> OneofField<'proto> {
 
     pub(crate) fn generate_decode_branch_in_enum_msg(
         &self,
-        decoder: &Ident,
+        func: &DecodeFunc,
         ctx: &Context<'proto>,
     ) -> Result<TokenStream, String> {
         let fnum = self.num;
@@ -136,7 +177,7 @@ impl<'proto> OneofField<'proto> {
 
         let decode_stmts = self
             .tspec
-            .generate_decode_mut(ctx, false, decoder, &mut_ref)?;
+            .generate_decode_mut(ctx, false, func, &mut_ref, None)?;
         let tok = quote! {
             #fnum => {
                 let #mut_ref = loop {
@@ -185,10 +226,11 @@ impl<'proto> OneofField<'proto> {
                 }
             }
 
-            EncodeFunc::Encode(encoder) => {
-                let encode_expr = self.tspec.generate_encode_expr(ctx, encoder, &val_ref);
+            EncodeFunc::Encode(encoder) | EncodeFunc::EncodeAsync(encoder, _) => {
+                let encode_expr = self.tspec.generate_encode_expr(ctx, func_type, &val_ref);
+                let awaiter = func_type.awaiter();
                 quote! {
-                    #encoder.encode_varint32(#tag_val)?;
+                    #encoder.encode_varint32(#tag_val)#awaiter?;
                     #encode_expr?;
                 }
             }
@@ -202,7 +244,7 @@ impl<'proto> OneofField<'proto> {
                         }
                     }
                 } else {
-                    self.tspec.generate_encode_expr(ctx, encoder, &val_ref)
+                    self.tspec.generate_encode_expr(ctx, func_type, &val_ref)
                 };
                 quote! {
                     #encoder.encode_varint32(#tag_val)?;
@@ -218,6 +260,235 @@ impl<'proto> OneofField<'proto> {
             }
         }
     }
+
+    /// Generate expression that switches the oneof to this variant if it isn't already, then
+    /// returns a mutable reference to the variant's value.
+    ///
+    /// If `oneof_name` is `None`, then the oneof is the message itself
+    fn generate_find_variant(
+        &self,
+        oneof_name: Option<&Ident>,
+        oneof_type: &TokenStream,
+        oneof_boxed: bool,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        let variant_name = &self.rust_name;
+        let extra_deref_of = oneof_boxed.then(|| quote! { * });
+        let extra_deref_var = self.boxed.then(|| quote! { * });
+
+        if let Some(oneof_name) = oneof_name {
+            let value = ctx.wrapped_value(
+                quote! { #oneof_type::#variant_name(::core::default::Default::default()) },
+                oneof_boxed,
+                true,
+            );
+            quote! {
+                loop {
+                    if let ::core::option::Option::Some(variant) = &mut self.#oneof_name {
+                        if let #oneof_type::#variant_name(variant) = &mut #extra_deref_of *variant {
+                            break &mut #extra_deref_var *variant;
+                        }
+                    }
+                    self.#oneof_name = #value;
+                }
+            }
+        } else {
+            quote! {
+                loop {
+                    if let Self::#variant_name(variant) = self {
+                        break &mut #extra_deref_var *variant;
+                    }
+                    *self = Self::#variant_name(::core::default::Default::default());
+                }
+            }
+        }
+    }
+
+    /// If `oneof_name` is `None`, then the oneof is the message itself
+    fn generate_text_decode_branch(
+        &self,
+        oneof_name: Option<&Ident>,
+        oneof_type: &TokenStream,
+        oneof_boxed: bool,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        let pb_name = self.name;
+        let mut_ref = Ident::new("mut_ref", Span::call_site());
+        let colon = self.tspec.generate_text_colon();
+        let decode_stmts = self.tspec.generate_parse_mut(&mut_ref);
+        let find_variant = self.generate_find_variant(oneof_name, oneof_type, oneof_boxed, ctx);
+        quote! {
+            #pb_name => {
+                #colon
+                let #mut_ref = #find_variant;
+                #decode_stmts
+            }
+        }
+    }
+
+    pub(crate) fn generate_text_encode_branch(&self, oneof_type: &TokenStream) -> TokenStream {
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let variant_name = &self.rust_name;
+        let extra_deref = self.boxed.then(|| quote! { * });
+        let encode_expr = self.tspec.generate_text_encode(self.name, &val_ref);
+        quote! {
+            #oneof_type::#variant_name(#val_ref) => {
+                let #val_ref = &* #extra_deref #val_ref;
+                #encode_expr?;
+            }
+        }
+    }
+
+    pub(crate) fn generate_text_decode_branch_in_enum_msg(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        self.generate_text_decode_branch(None, &quote! { Self }, false, ctx)
+    }
+
+    /// If `oneof_name` is `None`, then the oneof is the message itself
+    fn generate_json_decode_branch(
+        &self,
+        oneof_name: Option<&Ident>,
+        oneof_type: &TokenStream,
+        oneof_boxed: bool,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        let key_pattern = json_key_pattern(&self.json_name, self.name);
+        let mut_ref = Ident::new("mut_ref", Span::call_site());
+        let decode_stmts = self.tspec.generate_parse_mut(&mut_ref);
+        let find_variant = self.generate_find_variant(oneof_name, oneof_type, oneof_boxed, ctx);
+        quote! {
+            #key_pattern => if !decoder.decode_null()? {
+                let #mut_ref = #find_variant;
+                #decode_stmts
+            }
+        }
+    }
+
+    pub(crate) fn generate_json_encode_branch(&self, oneof_type: &TokenStream) -> TokenStream {
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let variant_name = &self.rust_name;
+        let json_name = &self.json_name;
+        let extra_deref = self.boxed.then(|| quote! { * });
+        let encode_expr = self.tspec.generate_json_encode(&val_ref);
+        quote! {
+            #oneof_type::#variant_name(#val_ref) => {
+                let #val_ref = &* #extra_deref #val_ref;
+                encoder.write_key(#json_name)?;
+                #encode_expr?;
+            }
+        }
+    }
+
+    /// Generate a match branch that merges the variant from `other`. If `oneof_name` is `None`,
+    /// then the oneof is the enum message itself.
+    fn generate_merge_branch(
+        &self,
+        oneof_name: Option<&Ident>,
+        oneof_type: &TokenStream,
+        oneof_boxed: bool,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let variant_name = &self.rust_name;
+        let extra_deref = self.boxed.then(|| quote! { * });
+
+        let val = ctx.wrapped_value(
+            self.tspec.generate_merge_value(ctx, &val_ref),
+            self.boxed,
+            false,
+        );
+        let new_variant = quote! { #oneof_type::#variant_name(#val) };
+        let set_variant = if let Some(oneof_name) = oneof_name {
+            let value = ctx.wrapped_value(new_variant, oneof_boxed, true);
+            quote! { self.#oneof_name = #value; }
+        } else {
+            quote! { *self = #new_variant; }
+        };
+        let stmts = if let TypeSpec::Message(..) = self.tspec {
+            // Merge into the existing message if the same variant is set
+            let existing = if let Some(oneof_name) = oneof_name {
+                let as_mut = if oneof_boxed {
+                    quote! { as_deref_mut }
+                } else {
+                    quote! { as_mut }
+                };
+                quote! { ::core::option::Option::Some(#oneof_type::#variant_name(dest)) = self.#oneof_name.#as_mut() }
+            } else {
+                quote! { Self::#variant_name(dest) = self }
+            };
+            quote! {
+                if let #existing {
+                    ::micropb::MessageMerge::merge_from(&mut #extra_deref *dest, #val_ref)?;
+                } else {
+                    #set_variant
+                }
+            }
+        } else {
+            set_variant
+        };
+        let unbox = self.boxed.then(|| quote! { let #val_ref = &**#val_ref; });
+        quote! {
+            #oneof_type::#variant_name(#val_ref) => {
+                #unbox
+                #stmts
+            }
+        }
+    }
+
+    pub(crate) fn generate_merge_branch_in_enum_msg(&self, ctx: &Context<'proto>) -> TokenStream {
+        self.generate_merge_branch(None, &quote! { Self }, false, ctx)
+    }
+
+    pub(crate) fn generate_json_decode_branch_in_enum_msg(
+        &self,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        self.generate_json_decode_branch(None, &quote! { Self }, false, ctx)
+    }
+
+    pub(crate) fn generate_serialize_branch(&self, oneof_type: &TokenStream) -> TokenStream {
+        let val_ref = Ident::new("val_ref", Span::call_site());
+        let variant_name = &self.rust_name;
+        let json_name = &self.json_name;
+        let extra_deref = self.boxed.then(|| quote! { * });
+        let mode = self.tspec.generate_serde_mode();
+        quote! {
+            #oneof_type::#variant_name(#val_ref) => {
+                let #val_ref = &* #extra_deref #val_ref;
+                state.serialize_field(#json_name, &::micropb::serde::With::<_, #mode>::new(#val_ref))?;
+            }
+        }
+    }
+
+    /// If `oneof_name` is `None`, then the oneof is the message itself
+    pub(crate) fn generate_deserialize_branch(
+        &self,
+        oneof_name: Option<&Ident>,
+        oneof_type: &TokenStream,
+        oneof_boxed: bool,
+        ctx: &Context<'proto>,
+    ) -> TokenStream {
+        let key_pattern = json_key_pattern(&self.json_name, self.name);
+        let mode = self.tspec.generate_serde_mode();
+        let find_variant = self.generate_find_variant(oneof_name, oneof_type, oneof_boxed, ctx);
+        quote! {
+            #key_pattern => {
+                let place = #find_variant;
+                map.next_value_seed(::micropb::serde::Place::<_, #mode>::new(place))?;
+            }
+        }
+    }
+
+    /// Keys accepted when deserializing this field
+    pub(crate) fn serde_keys(&self) -> Vec<&str> {
+        if self.json_name == self.name {
+            vec![self.name]
+        } else {
+            vec![&self.json_name, self.name]
+        }
+    }
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
@@ -448,7 +719,8 @@ impl<'proto> Oneof<'proto> {
         ctx: &Context<'proto>,
         msg_mod_name: &Ident,
         tag: &Ident,
-        decoder: &Ident,
+        func: &DecodeFunc,
+        unknown: Option<&Ident>,
     ) -> Result<TokenStream, String> {
         let name = &self.san_rust_name;
         let tok = match &self.otype {
@@ -456,7 +728,9 @@ impl<'proto> Oneof<'proto> {
                 let oneof_type = quote! { #msg_mod_name::#type_name };
                 let branches = fields
                     .iter()
-                    .map(|f| f.generate_decode_branch(name, &oneof_type, self.boxed, ctx, decoder))
+                    .map(|f| {
+                        f.generate_decode_branch(name, &oneof_type, self.boxed, ctx, func, unknown)
+                    })
                     .try_into_tokens()?;
                 quote! { #branches }
             }
@@ -465,8 +739,9 @@ impl<'proto> Oneof<'proto> {
                 nums,
             } => {
                 let nums = nums.iter().map(|n| Literal::i32_unsuffixed(*n));
+                let decode_field = func.generate_custom_decode(name, tag);
                 quote! {
-                    #(#nums)|* => { if !self.#name.decode_field(#tag, #decoder)? { return Err(::micropb::DecodeError::CustomField) } }
+                    #(#nums)|* => { if !#decode_field { return Err(::micropb::DecodeError::CustomField) } }
                 }
             }
             OneofType::Custom {
@@ -474,8 +749,9 @@ impl<'proto> Oneof<'proto> {
                 nums,
             } => {
                 let nums = nums.iter().map(|n| Literal::i32_unsuffixed(*n));
+                let decode_field = func.generate_custom_decode(field, tag);
                 quote! {
-                    #(#nums)|* => { if !self.#field.decode_field(#tag, #decoder)? { return Err(::micropb::DecodeError::CustomField) } }
+                    #(#nums)|* => { if !#decode_field { return Err(::micropb::DecodeError::CustomField) } }
                 }
             }
         };
@@ -524,6 +800,9 @@ impl<'proto> Oneof<'proto> {
                 EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => {
                     quote! { self.#name.encode_fields(#encoder)?; }
                 }
+                EncodeFunc::EncodeAsync(encoder, _) => {
+                    quote! { self.#name.encode_fields_async(#encoder).await?; }
+                }
             },
 
             OneofType::Custom {
@@ -533,6 +812,171 @@ impl<'proto> Oneof<'proto> {
         }
     }
 
+    pub(crate) fn generate_text_decode_branches(
+        &self,
+        ctx: &Context<'proto>,
+        msg_mod_name: &Ident,
+    ) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { fields, type_name } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let branches = fields.iter().map(|f| {
+                    f.generate_text_decode_branch(Some(name), &oneof_type, self.boxed, ctx)
+                });
+                quote! { #(#branches)* }
+            }
+            // Custom fields aren't part of the text format
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_text_encode(&self, msg_mod_name: &Ident) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { type_name, fields } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let extra_deref = self.boxed.then(|| quote! { * });
+                let branches = fields
+                    .iter()
+                    .map(|f| f.generate_text_encode_branch(&oneof_type));
+                quote! {
+                    if let Some(oneof) = & self.#name {
+                        match &#extra_deref *oneof {
+                            #(#branches)*
+                        }
+                    }
+                }
+            }
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_json_decode_branches(
+        &self,
+        ctx: &Context<'proto>,
+        msg_mod_name: &Ident,
+    ) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { fields, type_name } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let branches = fields.iter().map(|f| {
+                    f.generate_json_decode_branch(Some(name), &oneof_type, self.boxed, ctx)
+                });
+                quote! { #(#branches)* }
+            }
+            // Custom fields aren't part of the JSON encoding
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_json_encode(&self, msg_mod_name: &Ident) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { type_name, fields } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let extra_deref = self.boxed.then(|| quote! { * });
+                let branches = fields
+                    .iter()
+                    .map(|f| f.generate_json_encode_branch(&oneof_type));
+                quote! {
+                    if let Some(oneof) = & self.#name {
+                        match &#extra_deref *oneof {
+                            #(#branches)*
+                        }
+                    }
+                }
+            }
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_merge(
+        &self,
+        ctx: &Context<'proto>,
+        msg_mod_name: &Ident,
+    ) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { type_name, fields } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let extra_deref = self.boxed.then(|| quote! { * });
+                let branches = fields
+                    .iter()
+                    .map(|f| f.generate_merge_branch(Some(name), &oneof_type, self.boxed, ctx));
+                quote! {
+                    if let Some(oneof) = &other.#name {
+                        match &#extra_deref *oneof {
+                            #(#branches)*
+                        }
+                    }
+                }
+            }
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_serde_count(&self) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { .. } => quote! {
+                if self.#name.is_some() {
+                    len += 1;
+                }
+            },
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_serialize(&self, msg_mod_name: &Ident) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { type_name, fields } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let extra_deref = self.boxed.then(|| quote! { * });
+                let branches = fields
+                    .iter()
+                    .map(|f| f.generate_serialize_branch(&oneof_type));
+                quote! {
+                    if let Some(oneof) = & self.#name {
+                        match &#extra_deref *oneof {
+                            #(#branches)*
+                        }
+                    }
+                }
+            }
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    pub(crate) fn generate_deserialize_branches(
+        &self,
+        ctx: &Context<'proto>,
+        msg_mod_name: &Ident,
+    ) -> TokenStream {
+        let name = &self.san_rust_name;
+        match &self.otype {
+            OneofType::Enum { fields, type_name } => {
+                let oneof_type = quote! { #msg_mod_name::#type_name };
+                let branches = fields.iter().map(|f| {
+                    f.generate_deserialize_branch(Some(name), &oneof_type, self.boxed, ctx)
+                });
+                quote! { #(#branches)* }
+            }
+            // Custom fields aren't serialized
+            OneofType::Custom { .. } => quote! {},
+        }
+    }
+
+    /// Keys accepted when deserializing fields of this oneof
+    pub(crate) fn serde_keys(&self) -> Vec<&str> {
+        match &self.otype {
+            OneofType::Enum { fields, .. } => fields.iter().flat_map(|f| f.serde_keys()).collect(),
+            OneofType::Custom { .. } => vec![],
+        }
+    }
+
     pub(crate) fn generate_max_size(
         &self,
         ctx: &Context<'proto>,
@@ -600,6 +1044,7 @@ pub(crate) fn make_test_oneof_field<'a>(
     OneofField {
         num,
         name,
+        json_name: super::field::to_json_name(name),
         tspec,
         rust_name: Ident::new(&name.to_case(Case::Pascal), Span::call_site()),
         boxed,
@@ -659,9 +1104,15 @@ mod tests {
         };
         let field = field_proto(1, "field");
         assert!(
-            OneofField::from_proto(&field, &oneof_conf, None)
-                .unwrap()
-                .is_none()
+            OneofField::from_proto(
+                &field,
+                &oneof_conf,
+                None,
+                &make_ctx(),
+                &FeatureSet::default()
+            )
+            .unwrap()
+            .is_none()
         );
         let oneof = OneofDescriptorProto::default();
         assert!(
@@ -680,13 +1131,20 @@ mod tests {
         };
         let field = field_proto(1, "field");
         assert_eq!(
-            OneofField::from_proto(&field, &field_conf, None)
-                .unwrap()
-                .unwrap(),
+            OneofField::from_proto(
+                &field,
+                &field_conf,
+                None,
+                &make_ctx(),
+                &FeatureSet::default()
+            )
+            .unwrap()
+            .unwrap(),
             OneofField {
                 num: 1,
                 tspec: TypeSpec::Bool,
                 name: "field",
+                json_name: "field".to_owned(),
                 rust_name: Ident::new("Field", Span::call_site()),
                 boxed: false,
                 max_size_override: None,
@@ -703,13 +1161,20 @@ mod tests {
             config: Cow::Borrowed(&config),
         };
         assert_eq!(
-            OneofField::from_proto(&field, &field_conf, None)
-                .unwrap()
-                .unwrap(),
+            OneofField::from_proto(
+                &field,
+                &field_conf,
+                None,
+                &make_ctx(),
+                &FeatureSet::default()
+            )
+            .unwrap()
+            .unwrap(),
             OneofField {
                 num: 1,
                 tspec: TypeSpec::Bool,
                 name: "field",
+                json_name: "field".to_owned(),
                 rust_name: Ident::new("Renamed", Span::call_site()),
                 max_size_override: None,
                 boxed: true,
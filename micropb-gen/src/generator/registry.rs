@@ -0,0 +1,110 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use super::{Context, sanitized_ident};
+
+impl Context<'_> {
+    /// Generate the `AnyMessage` enum from all messages configured with `any_registry`, or nothing
+    /// if there are no such messages
+    pub(crate) fn generate_any_registry(&mut self) -> TokenStream {
+        let msgs: Vec<_> = self
+            .graph
+            .messages()
+            .filter(|(_, msg)| msg.any_registry && msg.lifetime.is_none())
+            .collect();
+        if msgs.is_empty() {
+            return quote! {};
+        }
+
+        // The registry lives in the root module, so resolve type names from there
+        self.pkg_path = vec![];
+        self.type_path = RefCell::new(vec![]);
+
+        let mut name_counts = HashMap::new();
+        for (_, msg) in &msgs {
+            *name_counts.entry(msg.name).or_insert(0) += 1;
+        }
+
+        let mut variants = vec![];
+        let mut full_names = vec![];
+        let mut types = vec![];
+        for (fq_name, msg) in &msgs {
+            let full_name = fq_name.trim_start_matches('.');
+            // Use the full Protobuf path for the variant name if the message name is ambiguous
+            let variant = if name_counts[msg.name] > 1 {
+                Ident::new(
+                    &full_name.replace('.', "_").to_case(Case::Pascal),
+                    Span::call_site(),
+                )
+            } else {
+                sanitized_ident(msg.name)
+            };
+            variants.push(variant);
+            full_names.push(full_name.to_owned());
+            types.push(self.resolve_type_name(fq_name));
+        }
+        let variant_docs = full_names.iter().map(|n| format!(" `{n}`"));
+
+        let derive_dbg = msgs.iter().all(|(_, msg)| msg.derive_dbg);
+        let derive_clone = msgs.iter().all(|(_, msg)| msg.derive_clone);
+        let derive_partial_eq = msgs.iter().all(|(_, msg)| msg.impl_partial_eq);
+        let derives = [
+            derive_dbg.then(|| quote! { Debug }),
+            derive_clone.then(|| quote! { Clone }),
+            derive_partial_eq.then(|| quote! { PartialEq }),
+        ]
+        .into_iter()
+        .flatten();
+
+        let unpack = self.params.encode_decode.is_decode().then(|| {
+            quote! {
+                /// Unpack a `google.protobuf.Any` into the registered message matching its type
+                /// URL. Returns `None` if no registered message matches.
+                pub fn unpack<IMPL_MICROPB_ANY: ::micropb::any::PbAny>(
+                    any: &IMPL_MICROPB_ANY,
+                ) -> Result<Option<Self>, ::micropb::DecodeError<::core::convert::Infallible>> {
+                    match any.type_name() {
+                        #(#full_names => Ok(any.unpack()?.map(Self::#variants)),)*
+                        _ => Ok(None),
+                    }
+                }
+            }
+        });
+        let pack = self.params.encode_decode.is_encode().then(|| {
+            quote! {
+                /// Pack the message into a new `google.protobuf.Any`
+                pub fn pack<IMPL_MICROPB_ANY: ::micropb::any::PbAny + Default>(
+                    &self,
+                ) -> Result<IMPL_MICROPB_ANY, ::micropb::BufferTooSmall> {
+                    match self {
+                        #(Self::#variants(msg) => IMPL_MICROPB_ANY::pack(msg),)*
+                    }
+                }
+            }
+        });
+
+        quote! {
+            /// Registry of messages that can be packed into `google.protobuf.Any`
+            #[derive(#(#derives),*)]
+            pub enum AnyMessage {
+                #(#[doc = #variant_docs] #variants(#types),)*
+            }
+
+            impl AnyMessage {
+                /// Fully-qualified Protobuf name of the message
+                pub fn full_name(&self) -> &'static str {
+                    match self {
+                        #(Self::#variants(_) => #full_names,)*
+                    }
+                }
+
+                #unpack
+                #pack
+            }
+        }
+    }
+}
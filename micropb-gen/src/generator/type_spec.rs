@@ -1,15 +1,22 @@
 use convert_case::{Case, Casing};
 use micropb::size::{
-    sizeof_len_record, sizeof_sint32, sizeof_sint64, sizeof_varint32, sizeof_varint64,
+    sizeof_len_record, sizeof_sint32, sizeof_sint64, sizeof_tag, sizeof_varint32, sizeof_varint64,
 };
 use proc_macro2::{Literal, Span, TokenStream};
-use quote::quote;
+use quote::{ToTokens, quote};
 use syn::{Ident, Lifetime};
 
 use crate::{
     config::{IntSize, byte_string_type_parsed, contains_len_param},
-    descriptor::{FieldDescriptorProto, FieldDescriptorProto_::Type},
-    generator::{Context, field_error_str, sanitized_ident},
+    descriptor::{
+        FeatureSet,
+        FeatureSet_::{MessageEncoding, Utf8Validation},
+        FieldDescriptorProto,
+        FieldDescriptorProto_::Type,
+    },
+    generator::{
+        Context, DecodeFunc, EncodeFunc, r#enum::EnumRepr, field_error_str, sanitized_ident,
+    },
     utils::{find_lifetime_from_str, path_suffix, unescape_c_escape_string},
 };
 
@@ -30,6 +37,13 @@ pub(crate) enum PbInt {
 }
 
 impl PbInt {
+    fn is_64bit(&self) -> bool {
+        matches!(
+            self,
+            PbInt::Int64 | PbInt::Uint64 | PbInt::Sint64 | PbInt::Sfixed64 | PbInt::Fixed64
+        )
+    }
+
     fn is_signed(&self) -> bool {
         matches!(
             self,
@@ -110,9 +124,23 @@ impl PbInt {
     }
 }
 
+/// Size of the end-group tag of a group field
+fn sizeof_end_group(num: u32) -> usize {
+    sizeof_tag(micropb::Tag::from_parts(num, micropb::WIRE_TYPE_EGROUP))
+}
+
+/// Destination of closed enum values that don't match any variant
+pub(crate) struct UnknownEnumSink<'a> {
+    /// Expression that evaluates to a mutable reference to the unknown field handler
+    pub(crate) handler: &'a TokenStream,
+    /// Field number to record the unknown value under
+    pub(crate) fnum: u32,
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub(crate) enum TypeSpec<'proto> {
-    Message(&'proto str),
+    /// Message type, along with the field number if the message is encoded as a group
+    Message(&'proto str, Option<u32>),
     Enum(&'proto str),
     Float,
     Double,
@@ -121,6 +149,8 @@ pub(crate) enum TypeSpec<'proto> {
     String {
         typestr: String,
         max_bytes: Option<u32>,
+        /// If false, the string is stored in a bytes container and isn't checked for UTF-8
+        validate_utf8: bool,
     },
     Bytes {
         typestr: String,
@@ -143,7 +173,7 @@ impl<'proto> TypeSpec<'proto> {
 
     pub(crate) fn packable(&self) -> bool {
         match self {
-            TypeSpec::Message(_) | TypeSpec::String { .. } | TypeSpec::Bytes { .. } => false,
+            TypeSpec::Message(..) | TypeSpec::String { .. } | TypeSpec::Bytes { .. } => false,
             TypeSpec::Enum(_)
             | TypeSpec::Float
             | TypeSpec::Double
@@ -189,9 +219,44 @@ impl<'proto> TypeSpec<'proto> {
         }
     }
 
+    /// Whether the Rust type has the same size as the fixed-width wire representation, allowing
+    /// packed fields to be copied to and from the wire in bulk
+    pub(crate) fn is_fixed_width(&self) -> bool {
+        matches!(
+            self,
+            TypeSpec::Float
+                | TypeSpec::Double
+                | TypeSpec::Int(PbInt::Fixed32 | PbInt::Sfixed32, IntSize::S32)
+                | TypeSpec::Int(PbInt::Fixed64 | PbInt::Sfixed64, IntSize::S64)
+        )
+    }
+
+    /// Integer type of enums that are generated as open Rust enums. Returns `None` for other
+    /// types, including extern enums, which are assumed to be newtypes.
+    fn open_enum_int_type(&self, ctx: &Context<'proto>) -> Option<TokenStream> {
+        let TypeSpec::Enum(tname) = self else {
+            return None;
+        };
+        ctx.graph
+            .get_enum(tname)
+            .filter(|e| e.repr == EnumRepr::Open)
+            .map(|e| e.int_type.type_name(e.signed).into_token_stream())
+    }
+
+    /// Whether the type is an enum that's generated as a closed Rust enum
+    pub(crate) fn is_closed_enum(&self, ctx: &Context<'proto>) -> bool {
+        match self {
+            TypeSpec::Enum(tname) => ctx
+                .graph
+                .get_enum(tname)
+                .is_some_and(|e| e.repr == EnumRepr::Closed),
+            _ => false,
+        }
+    }
+
     pub(crate) fn is_copy(&self, ctx: &Context<'proto>) -> bool {
         match self {
-            TypeSpec::Message(name) => ctx
+            TypeSpec::Message(name, _) => ctx
                 .graph
                 .get_message(name)
                 .map(|msg| msg.is_copy)
@@ -207,24 +272,36 @@ impl<'proto> TypeSpec<'proto> {
         }
     }
 
+    /// `feature_set` must be the resolved features of the field
     pub(crate) fn from_proto(
         proto: &'proto FieldDescriptorProto,
         type_conf: &CurrentConfig,
+        feature_set: &FeatureSet,
     ) -> Result<Self, String> {
         let conf = &type_conf.config;
         let res = match proto.r#type {
-            Type::Group => return Err("Group fields are unsupported".to_owned()),
+            Type::Group => TypeSpec::Message(&proto.type_name, Some(proto.number as u32)),
             Type::Double => TypeSpec::Double,
             Type::Float => TypeSpec::Float,
             Type::Bool => TypeSpec::Bool,
             Type::String => {
-                let typestr = conf
-                    .string_type
-                    .clone()
-                    .ok_or_else(|| "string_type not configured".to_owned())?;
+                let validate_utf8 = conf
+                    .validate_utf8
+                    .unwrap_or(feature_set.utf8_validation() != Some(&Utf8Validation::None));
+                // Unvalidated strings may not be UTF-8, so they need a bytes container
+                let typestr = if validate_utf8 {
+                    conf.string_type
+                        .clone()
+                        .ok_or_else(|| "string_type not configured".to_owned())?
+                } else {
+                    conf.bytes_type
+                        .clone()
+                        .ok_or_else(|| "bytes_type not configured".to_owned())?
+                };
                 TypeSpec::String {
                     max_bytes: conf.max_bytes.filter(|_| contains_len_param(&typestr)),
                     typestr,
+                    validate_utf8,
                 }
             }
             Type::Bytes => {
@@ -237,7 +314,12 @@ impl<'proto> TypeSpec<'proto> {
                     typestr,
                 }
             }
-            Type::Message => TypeSpec::Message(&proto.type_name),
+            Type::Message
+                if feature_set.message_encoding() == Some(&MessageEncoding::Delimited) =>
+            {
+                TypeSpec::Message(&proto.type_name, Some(proto.number as u32))
+            }
+            Type::Message => TypeSpec::Message(&proto.type_name, None),
             Type::Enum => TypeSpec::Enum(&proto.type_name),
             Type::Uint32 => TypeSpec::Int(PbInt::Uint32, conf.int_size.unwrap_or(IntSize::S32)),
             Type::Int64 => TypeSpec::Int(PbInt::Int64, conf.int_size.unwrap_or(IntSize::S64)),
@@ -263,7 +345,9 @@ impl<'proto> TypeSpec<'proto> {
             TypeSpec::Float => quote! {f32},
             TypeSpec::Double => quote! {f64},
             TypeSpec::Bool => quote! {bool},
-            TypeSpec::String { typestr, max_bytes } => {
+            TypeSpec::String {
+                typestr, max_bytes, ..
+            } => {
                 let ty = byte_string_type_parsed(typestr, *max_bytes)?;
                 quote! { #ty }
             }
@@ -272,7 +356,7 @@ impl<'proto> TypeSpec<'proto> {
                 quote! { #ty }
             }
 
-            TypeSpec::Message(tname) => {
+            TypeSpec::Message(tname, _) => {
                 let rust_type = ctx.resolve_type_name(tname);
                 if let Some(lifetime) = ctx
                     .graph
@@ -299,10 +383,15 @@ impl<'proto> TypeSpec<'proto> {
         fname: &'proto str,
     ) -> TokenStream {
         match self {
-            TypeSpec::Message(tname) => {
+            TypeSpec::Message(tname, None) => {
                 let rust_type = ctx.resolve_type_name(tname);
                 return quote! { ::micropb::const_map!(<#rust_type as ::micropb::MessageEncode>::MAX_SIZE, |size| ::micropb::size::sizeof_len_record(size)) };
             }
+            TypeSpec::Message(tname, Some(num)) => {
+                let rust_type = ctx.resolve_type_name(tname);
+                let end_tag_len = sizeof_end_group(*num);
+                return quote! { ::micropb::const_map!(<#rust_type as ::micropb::MessageEncode>::MAX_SIZE, |size| size + #end_tag_len) };
+            }
             TypeSpec::Enum(tname) => {
                 let rust_type = ctx.resolve_type_name(tname);
                 return quote! { ::core::result::Result::Ok(#rust_type::_MAX_SIZE) };
@@ -325,13 +414,21 @@ impl<'proto> TypeSpec<'proto> {
         ctx: &Context<'proto>,
     ) -> Result<TokenStream, String> {
         let out = match self {
-            TypeSpec::String { max_bytes, .. } => match *max_bytes {
+            TypeSpec::String {
+                max_bytes,
+                validate_utf8,
+                ..
+            } => match *max_bytes {
                 Some(max_bytes) if default.len() > max_bytes as usize => {
                     return Err(format!(
                         "String field is limited to {max_bytes} bytes, but its default value is {} bytes",
                         default.len()
                     ));
                 }
+                _ if !validate_utf8 => {
+                    let default_bytes = Literal::byte_string(default.as_bytes());
+                    quote! { ::core::convert::TryFrom::try_from(#default_bytes.as_slice()).unwrap_or_default() }
+                }
                 _ => quote! { ::core::convert::TryFrom::try_from(#default).unwrap_or_default() },
             },
 
@@ -390,13 +487,21 @@ impl<'proto> TypeSpec<'proto> {
                 | PbInt::Sint64,
                 _,
             ) => micropb::WIRE_TYPE_VARINT,
-            TypeSpec::Message(..) | TypeSpec::String { .. } | TypeSpec::Bytes { .. } => {
+            TypeSpec::Message(_, Some(_)) => micropb::WIRE_TYPE_SGROUP,
+            TypeSpec::Message(_, None) | TypeSpec::String { .. } | TypeSpec::Bytes { .. } => {
                 micropb::WIRE_TYPE_LEN
             }
         }
     }
 
-    pub(crate) fn generate_implicit_presence_check(&self, val_ref: &Ident) -> TokenStream {
+    pub(crate) fn generate_implicit_presence_check(
+        &self,
+        ctx: &Context<'proto>,
+        val_ref: &Ident,
+    ) -> TokenStream {
+        if let Some(itype) = self.open_enum_int_type(ctx) {
+            return quote! { if #itype::from(*#val_ref) != 0 };
+        }
         match self {
             TypeSpec::Message(..) => quote! {},
             TypeSpec::Enum(_) => quote! { if #val_ref.0 != 0 },
@@ -408,36 +513,140 @@ impl<'proto> TypeSpec<'proto> {
         }
     }
 
+    /// Generate an owned copy of the value behind `val_ref` when merging messages.
+    ///
+    /// Messages aren't cloned, since they may not implement `Clone`. Instead, they're merged into
+    /// a default instance.
+    pub(crate) fn generate_merge_value(
+        &self,
+        ctx: &Context<'proto>,
+        val_ref: &Ident,
+    ) -> TokenStream {
+        match self {
+            TypeSpec::Message(..) => quote! {{
+                let mut msg = ::core::default::Default::default();
+                ::micropb::MessageMerge::merge_from(&mut msg, #val_ref)?;
+                msg
+            }},
+            _ if self.is_copy(ctx) => quote! { *#val_ref },
+            _ => quote! { #val_ref.clone() },
+        }
+    }
+
     /// Generate decode value expressions (Result<T, DecodeError>) for "packable" types
+    ///
+    /// Closed enums aren't handled here, since not every value on the wire can be decoded into
+    /// them. Use [`Self::generate_decode_closed_enum`] instead.
     pub(crate) fn generate_decode_val(
         &self,
         ctx: &Context<'proto>,
-        decoder: &Ident,
+        func: &DecodeFunc,
     ) -> Option<TokenStream> {
+        let decoder = func.decoder();
+        let awaiter = func.awaiter();
         match self {
-            TypeSpec::Float => Some(quote! { #decoder.decode_float() }),
-            TypeSpec::Double => Some(quote! { #decoder.decode_double() }),
-            TypeSpec::Bool => Some(quote! { #decoder.decode_bool() }),
+            TypeSpec::Enum(_) if self.is_closed_enum(ctx) => None,
+            TypeSpec::Enum(tpath) if let Some(itype) = self.open_enum_int_type(ctx) => {
+                let enum_path = ctx.resolve_type_name(tpath);
+                Some(
+                    quote! { #decoder.decode_int32()#awaiter.map(|n| #enum_path::from(n as #itype)) },
+                )
+            }
+            TypeSpec::Float => Some(quote! { #decoder.decode_float()#awaiter }),
+            TypeSpec::Double => Some(quote! { #decoder.decode_double()#awaiter }),
+            TypeSpec::Bool => Some(quote! { #decoder.decode_bool()#awaiter }),
             TypeSpec::Int(pbint, int_size) => {
                 let func = pbint.generate_decode_func(int_size);
-                Some(quote! { #decoder.#func() })
+                Some(quote! { #decoder.#func()#awaiter })
             }
             // Enum is actually packable due to https://github.com/protocolbuffers/protobuf/issues/15480
             TypeSpec::Enum(tpath) => {
                 let enum_path = ctx.resolve_type_name(tpath);
-                Some(quote! { #decoder.decode_int32().map(|n| #enum_path(n as _)) })
+                Some(quote! { #decoder.decode_int32()#awaiter.map(|n| #enum_path(n as _)) })
             }
             _ => None,
         }
     }
 
+    /// Generate decode expression (Result<Option<T>, DecodeError>) for closed enums. Values that
+    /// don't match any variant evaluate to `None` and are passed to the unknown handler, if any.
+    /// Returns `None` if the type isn't a closed enum.
+    pub(crate) fn generate_decode_closed_enum(
+        &self,
+        ctx: &Context<'proto>,
+        func: &DecodeFunc,
+        unknown: Option<&UnknownEnumSink>,
+    ) -> Option<TokenStream> {
+        let TypeSpec::Enum(tpath) = self else {
+            return None;
+        };
+        if !self.is_closed_enum(ctx) {
+            return None;
+        }
+        let decoder = func.decoder();
+        let awaiter = func.awaiter();
+        let enum_path = ctx.resolve_type_name(tpath);
+        let try_from = quote! { <#enum_path as ::core::convert::TryFrom<i32>>::try_from };
+
+        let expr = if let Some(UnknownEnumSink { handler, fnum }) = unknown {
+            quote! {{
+                let n = #decoder.decode_int32()#awaiter?;
+                match #try_from(n) {
+                    ::core::result::Result::Ok(v) => ::core::result::Result::Ok(::core::option::Option::Some(v)),
+                    ::core::result::Result::Err(n) => #decoder
+                        .decode_unknown_varint(#handler, #fnum, n as u64)#awaiter
+                        .map(|_| ::core::option::Option::None),
+                }
+            }}
+        } else {
+            quote! { #decoder.decode_int32()#awaiter.map(|n| #try_from(n).ok()) }
+        };
+        Some(expr)
+    }
+
+    /// Generate the `MapKey` of the map key behind `key_ref`, for passing map entries to the
+    /// unknown handler. Returns `None` if the type can't be a map key.
+    pub(crate) fn generate_map_key(&self, key_ref: &Ident) -> Option<TokenStream> {
+        let tok = match self {
+            TypeSpec::Bool
+            | TypeSpec::Int(PbInt::Int32 | PbInt::Int64 | PbInt::Uint32 | PbInt::Uint64, _) => {
+                quote! { ::micropb::MapKey::Varint(*#key_ref as u64) }
+            }
+            TypeSpec::Int(PbInt::Sint32 | PbInt::Sint64, _) => quote! {
+                ::micropb::MapKey::Varint({
+                    let k = *#key_ref as i64;
+                    ((k << 1) ^ (k >> 63)) as u64
+                })
+            },
+            TypeSpec::Int(PbInt::Fixed32 | PbInt::Sfixed32, _) => {
+                quote! { ::micropb::MapKey::I32(*#key_ref as u32) }
+            }
+            TypeSpec::Int(PbInt::Fixed64 | PbInt::Sfixed64, _) => {
+                quote! { ::micropb::MapKey::I64(*#key_ref as u64) }
+            }
+            // Works for both validated strings and strings stored in bytes containers
+            TypeSpec::String { .. } => {
+                quote! { ::micropb::MapKey::Len(::core::convert::AsRef::<[u8]>::as_ref(&**#key_ref)) }
+            }
+            TypeSpec::Message(..)
+            | TypeSpec::Enum(_)
+            | TypeSpec::Float
+            | TypeSpec::Double
+            | TypeSpec::Bytes { .. } => return None,
+        };
+        Some(tok)
+    }
+
     pub(crate) fn generate_decode_mut(
         &self,
         ctx: &Context<'proto>,
         implicit_presence: bool,
-        decoder: &Ident,
+        func: &DecodeFunc,
         mut_ref: &Ident,
+        unknown: Option<&UnknownEnumSink>,
     ) -> Result<TokenStream, String> {
+        let decoder = func.decoder();
+        let awaiter = func.awaiter();
         let presence = if implicit_presence {
             "Implicit"
         } else {
@@ -446,18 +655,44 @@ impl<'proto> TypeSpec<'proto> {
         let presence_ident = Ident::new(presence, Span::call_site());
 
         let tok = match self {
-            TypeSpec::Message(..) => quote! { #mut_ref.decode_len_delimited(#decoder)?; },
+            TypeSpec::Message(_, group) => {
+                // Go through the decoder so that it can enforce the max nesting depth
+                let decode_call = match group {
+                    Some(num) => quote! { #decoder.decode_nested_group(#mut_ref, #num) },
+                    None => quote! { #decoder.decode_nested_message(#mut_ref) },
+                };
+                match func {
+                    DecodeFunc::Decode(_) => quote! { #decode_call?; },
+                    // Recursive futures have infinite size, so they need to be boxed
+                    DecodeFunc::DecodeAsync(_, true) => quote! {
+                        ::alloc::boxed::Box::pin(#decode_call).await?;
+                    },
+                    DecodeFunc::DecodeAsync(_, false) => quote! { #decode_call.await?; },
+                }
+            }
+            // Closed enums never have implicit presence, since protoc rejects that combination
+            TypeSpec::Enum(_) if self.is_closed_enum(ctx) => {
+                let val_expr = self
+                    .generate_decode_closed_enum(ctx, func, unknown)
+                    .expect("should be closed enum");
+                // Unknown values leave the field untouched
+                quote! {
+                    if let ::core::option::Option::Some(val) = #val_expr? {
+                        *#mut_ref = val;
+                    }
+                }
+            }
             TypeSpec::Enum(_)
             | TypeSpec::Float
             | TypeSpec::Double
             | TypeSpec::Bool
             | TypeSpec::Int(..) => {
                 let val_expr = self
-                    .generate_decode_val(ctx, decoder)
+                    .generate_decode_val(ctx, func)
                     .expect("ints should be packable");
                 let setter = if implicit_presence {
                     let val_ref = Ident::new("val_ref", Span::call_site());
-                    let presence_check = self.generate_implicit_presence_check(&val_ref);
+                    let presence_check = self.generate_implicit_presence_check(ctx, &val_ref);
                     quote! {
                         let #val_ref = &val;
                         #presence_check {
@@ -472,11 +707,14 @@ impl<'proto> TypeSpec<'proto> {
                     #setter
                 }
             }
-            TypeSpec::String { .. } => {
-                quote! { #decoder.decode_string(#mut_ref, ::micropb::Presence::#presence_ident)?; }
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => {
+                quote! { #decoder.decode_string(#mut_ref, ::micropb::Presence::#presence_ident)#awaiter?; }
             }
-            TypeSpec::Bytes { .. } => {
-                quote! { #decoder.decode_bytes(#mut_ref, ::micropb::Presence::#presence_ident)?; }
+            TypeSpec::String { .. } | TypeSpec::Bytes { .. } => {
+                quote! { #decoder.decode_bytes(#mut_ref, ::micropb::Presence::#presence_ident)#awaiter?; }
             }
         };
         Ok(tok)
@@ -484,13 +722,20 @@ impl<'proto> TypeSpec<'proto> {
 
     /// Only return Some for internal message types (external message types are dependent on
     /// generator options). Even though strings and bytes are also length-delimited, we don't need
-    /// to cache them because calculating their lengths is trivial.
+    /// to cache them because calculating their lengths is trivial. Groups aren't cached either,
+    /// since they're encoded without a length prefix.
     pub(crate) fn generate_cache_type(&self, ctx: &Context<'proto>) -> Option<TokenStream> {
-        if let TypeSpec::Message(tname) = self
+        if let TypeSpec::Message(tname, None) = self
             && (ctx.params.cache_extern_types || !ctx.params.extern_paths.contains_key(*tname))
         {
-            let cache_name = (*tname).to_owned() + "._Cache";
-            let cache_type = ctx.resolve_type_name(&cache_name);
+            // External types may live in another crate, so name their cache through the trait
+            let cache_type = if ctx.params.extern_paths.contains_key(*tname) {
+                let msg_type = ctx.resolve_type_name(tname);
+                quote! { <#msg_type as ::micropb::MessageEncodeCached>::Cache }
+            } else {
+                let cache_name = (*tname).to_owned() + "._Cache";
+                ctx.resolve_type_name(&cache_name)
+            };
             return Some(cache_type);
         }
         None
@@ -498,18 +743,28 @@ impl<'proto> TypeSpec<'proto> {
 
     /// This method must be in sync with [`generate_cache_type`]
     pub(crate) fn is_cached(&self, ctx: &Context<'proto>) -> bool {
-        if let TypeSpec::Message(tname) = self {
+        if let TypeSpec::Message(tname, None) = self {
             ctx.params.cache_extern_types || !ctx.params.extern_paths.contains_key(*tname)
         } else {
             false
         }
     }
 
-    pub(crate) fn generate_sizeof(&self, _ctx: &Context<'proto>, val_ref: &Ident) -> TokenStream {
+    pub(crate) fn generate_sizeof(&self, ctx: &Context<'proto>, val_ref: &Ident) -> TokenStream {
         match self {
-            TypeSpec::Message(..) => {
+            TypeSpec::Message(_, None) => {
                 quote! { ::micropb::size::sizeof_len_record(#val_ref.compute_size()) }
             }
+            TypeSpec::Message(_, Some(num)) => {
+                let end_tag_len = sizeof_end_group(*num);
+                quote! { #val_ref.compute_size() + #end_tag_len }
+            }
+            TypeSpec::Enum(_) if self.is_closed_enum(ctx) => {
+                quote! { ::micropb::size::sizeof_int32(*#val_ref as i32) }
+            }
+            TypeSpec::Enum(_) if let Some(itype) = self.open_enum_int_type(ctx) => {
+                quote! { ::micropb::size::sizeof_int32(#itype::from(*#val_ref) as _) }
+            }
             TypeSpec::Enum(_) => quote! { ::micropb::size::sizeof_int32(#val_ref.0 as _) },
             TypeSpec::Float => quote! { 4 },
             TypeSpec::Double => quote! { 8 },
@@ -524,22 +779,183 @@ impl<'proto> TypeSpec<'proto> {
 
     pub(crate) fn generate_encode_expr(
         &self,
-        _ctx: &Context<'proto>,
-        encoder: &Ident,
+        ctx: &Context<'proto>,
+        func: &EncodeFunc,
         val_ref: &Ident,
     ) -> TokenStream {
+        let (encoder, awaiter) = match func {
+            EncodeFunc::Encode(encoder) | EncodeFunc::EncodeCached(encoder, _) => (encoder, None),
+            EncodeFunc::EncodeAsync(encoder, _) => (encoder, func.awaiter()),
+            EncodeFunc::Sizeof(_) | EncodeFunc::PopulateCache(_) => {
+                unreachable!("encode expression requires an encoder")
+            }
+        };
         match self {
-            TypeSpec::Message(..) => quote! { #val_ref.encode_len_delimited(#encoder) },
-            TypeSpec::Enum(_) => quote! { #encoder.encode_int32(#val_ref.0 as _) },
-            TypeSpec::Float => quote! { #encoder.encode_float(* #val_ref) },
-            TypeSpec::Double => quote! { #encoder.encode_double(* #val_ref) },
-            TypeSpec::Bool => quote! { #encoder.encode_bool(* #val_ref) },
+            TypeSpec::Message(_, group) => {
+                let (encode_call, encode_async_call) = match group {
+                    Some(num) => (
+                        quote! { #val_ref.encode_group(#encoder, #num) },
+                        quote! { #val_ref.encode_group_async(#encoder, #num) },
+                    ),
+                    None => (
+                        quote! { #val_ref.encode_len_delimited(#encoder) },
+                        quote! { #val_ref.encode_len_delimited_async(#encoder) },
+                    ),
+                };
+                match func {
+                    // Recursive futures have infinite size, so they need to be boxed
+                    EncodeFunc::EncodeAsync(_, true) => quote! {
+                        ::alloc::boxed::Box::pin(#encode_async_call).await
+                    },
+                    EncodeFunc::EncodeAsync(_, false) => quote! { #encode_async_call.await },
+                    _ => encode_call,
+                }
+            }
+            TypeSpec::Enum(_) if self.is_closed_enum(ctx) => {
+                quote! { #encoder.encode_int32(*#val_ref as i32)#awaiter }
+            }
+            TypeSpec::Enum(_) if let Some(itype) = self.open_enum_int_type(ctx) => {
+                quote! { #encoder.encode_int32(#itype::from(*#val_ref) as _)#awaiter }
+            }
+            TypeSpec::Enum(_) => quote! { #encoder.encode_int32(#val_ref.0 as _)#awaiter },
+            TypeSpec::Float => quote! { #encoder.encode_float(* #val_ref)#awaiter },
+            TypeSpec::Double => quote! { #encoder.encode_double(* #val_ref)#awaiter },
+            TypeSpec::Bool => quote! { #encoder.encode_bool(* #val_ref)#awaiter },
             TypeSpec::Int(pbint, int_size) => {
                 let func = pbint.generate_encode_func(int_size);
-                quote! { #encoder.#func(* #val_ref as _) }
+                quote! { #encoder.#func(* #val_ref as _)#awaiter }
+            }
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { #encoder.encode_string(#val_ref)#awaiter },
+            TypeSpec::String { .. } | TypeSpec::Bytes { .. } => {
+                quote! { #encoder.encode_bytes(#val_ref)#awaiter }
+            }
+        }
+    }
+
+    /// Generate text format encode expression (fmt::Result) that writes the field `name`
+    pub(crate) fn generate_text_encode(&self, name: &str, val_ref: &Ident) -> TokenStream {
+        match self {
+            TypeSpec::Message(..) => quote! { encoder.write_message(#name, #val_ref) },
+            TypeSpec::Enum(_) => quote! { encoder.write_enum(#name, #val_ref) },
+            TypeSpec::Float => quote! { encoder.write_float(#name, * #val_ref) },
+            TypeSpec::Double => quote! { encoder.write_double(#name, * #val_ref) },
+            TypeSpec::Bool => quote! { encoder.write_bool(#name, * #val_ref) },
+            TypeSpec::Int(pbint, _) if pbint.is_signed() => {
+                quote! { encoder.write_int(#name, * #val_ref as _) }
+            }
+            TypeSpec::Int(..) => quote! { encoder.write_uint(#name, * #val_ref as _) },
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { encoder.write_str(#name, #val_ref) },
+            TypeSpec::String { .. } => quote! { encoder.write_str_bytes(#name, #val_ref) },
+            TypeSpec::Bytes { .. } => quote! { encoder.write_bytes(#name, #val_ref) },
+        }
+    }
+
+    /// Generate text format decode statement that parses the colon after the field name
+    pub(crate) fn generate_text_colon(&self) -> TokenStream {
+        // Colon is optional before message values
+        let required = !matches!(self, TypeSpec::Message(..));
+        quote! { decoder.decode_colon(#required)?; }
+    }
+
+    pub(crate) fn generate_json_encode(&self, val_ref: &Ident) -> TokenStream {
+        match self {
+            TypeSpec::Message(..) => quote! { encoder.write_message(#val_ref) },
+            TypeSpec::Enum(_) => quote! { encoder.write_enum(#val_ref) },
+            TypeSpec::Float => quote! { encoder.write_float(* #val_ref) },
+            TypeSpec::Double => quote! { encoder.write_double(* #val_ref) },
+            TypeSpec::Bool => quote! { encoder.write_bool(* #val_ref) },
+            // 64-bit integers are written as strings in JSON
+            TypeSpec::Int(pbint, _) if pbint.is_64bit() => {
+                if pbint.is_signed() {
+                    quote! { encoder.write_int64(* #val_ref as _) }
+                } else {
+                    quote! { encoder.write_uint64(* #val_ref as _) }
+                }
+            }
+            TypeSpec::Int(pbint, _) if pbint.is_signed() => {
+                quote! { encoder.write_int(* #val_ref as _) }
+            }
+            TypeSpec::Int(..) => quote! { encoder.write_uint(* #val_ref as _) },
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { encoder.write_str(#val_ref) },
+            TypeSpec::String { .. } => quote! { encoder.write_str_bytes(#val_ref) },
+            TypeSpec::Bytes { .. } => quote! { encoder.write_bytes(#val_ref) },
+        }
+    }
+
+    /// Type from `micropb::serde` that describes how to serialize values of this type
+    pub(crate) fn generate_serde_mode(&self) -> TokenStream {
+        match self {
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { ::micropb::serde::Str },
+            TypeSpec::String { .. } => quote! { ::micropb::serde::StrBytes },
+            TypeSpec::Bytes { .. } => quote! { ::micropb::serde::Bytes },
+            _ => quote! { ::micropb::serde::Native },
+        }
+    }
+
+    /// Map keys are always JSON strings
+    pub(crate) fn generate_json_key_encode(&self, val_ref: &Ident) -> TokenStream {
+        match self {
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { encoder.write_key(#val_ref) },
+            TypeSpec::String { .. } => quote! { encoder.write_key_bytes(#val_ref) },
+            _ => quote! { encoder.write_key_display(#val_ref) },
+        }
+    }
+
+    pub(crate) fn generate_json_key_decode(&self, key: &Ident, mut_ref: &Ident) -> TokenStream {
+        match self {
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => {
+                quote! { ::micropb::json::JsonDecoder::decode_key_str(#key, #mut_ref)?; }
+            }
+            TypeSpec::String { .. } => {
+                quote! { ::micropb::json::JsonDecoder::decode_key_bytes(#key, #mut_ref)?; }
+            }
+            TypeSpec::Bool => {
+                quote! { *#mut_ref = ::micropb::json::JsonDecoder::decode_key_bool(#key)?; }
+            }
+            TypeSpec::Int(pbint, _) if pbint.is_signed() => {
+                quote! { *#mut_ref = ::micropb::json::JsonDecoder::decode_key_int(#key)?; }
+            }
+            _ => quote! { *#mut_ref = ::micropb::json::JsonDecoder::decode_key_uint(#key)?; },
+        }
+    }
+
+    /// Generate text format decode statements that parse a value into `mut_ref`
+    /// Text format and JSON decoders have the same methods for parsing values
+    pub(crate) fn generate_parse_mut(&self, mut_ref: &Ident) -> TokenStream {
+        match self {
+            TypeSpec::Message(..) => quote! { decoder.decode_message(#mut_ref)?; },
+            TypeSpec::Enum(_) => quote! { *#mut_ref = decoder.decode_enum()?; },
+            TypeSpec::Float => quote! { *#mut_ref = decoder.decode_float()?; },
+            TypeSpec::Double => quote! { *#mut_ref = decoder.decode_double()?; },
+            TypeSpec::Bool => quote! { *#mut_ref = decoder.decode_bool()?; },
+            TypeSpec::Int(pbint, _) if pbint.is_signed() => {
+                quote! { *#mut_ref = decoder.decode_int()?; }
             }
-            TypeSpec::String { .. } => quote! { #encoder.encode_string(#val_ref) },
-            TypeSpec::Bytes { .. } => quote! { #encoder.encode_bytes(#val_ref) },
+            TypeSpec::Int(..) => quote! { *#mut_ref = decoder.decode_uint()?; },
+            TypeSpec::String {
+                validate_utf8: true,
+                ..
+            } => quote! { decoder.decode_string(#mut_ref)?; },
+            TypeSpec::String { .. } => quote! { decoder.decode_string_bytes(#mut_ref)?; },
+            TypeSpec::Bytes { .. } => quote! { decoder.decode_bytes(#mut_ref)?; },
         }
     }
 }
@@ -552,6 +968,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn fixed_width() {
+        assert!(TypeSpec::Float.is_fixed_width());
+        assert!(TypeSpec::Double.is_fixed_width());
+        assert!(TypeSpec::Int(PbInt::Fixed32, IntSize::S32).is_fixed_width());
+        assert!(TypeSpec::Int(PbInt::Sfixed64, IntSize::S64).is_fixed_width());
+        // Rust type is a different size from the wire representation
+        assert!(!TypeSpec::Int(PbInt::Fixed32, IntSize::S16).is_fixed_width());
+        assert!(!TypeSpec::Int(PbInt::Fixed64, IntSize::S32).is_fixed_width());
+        // Not fixed-size on the wire
+        assert!(!TypeSpec::Int(PbInt::Uint32, IntSize::S32).is_fixed_width());
+        assert!(!TypeSpec::Bool.is_fixed_width());
+    }
+
     #[test]
     fn max_size() {
         assert_eq!(TypeSpec::Float.max_size(), Ok(4));
@@ -587,7 +1017,8 @@ mod tests {
         assert_eq!(
             TypeSpec::String {
                 typestr: "test".to_owned(),
-                max_bytes: Some(12)
+                max_bytes: Some(12),
+                validate_utf8: true,
             }
             .max_size(),
             Ok(13)
@@ -595,7 +1026,8 @@ mod tests {
         assert_eq!(
             TypeSpec::String {
                 typestr: "test".to_owned(),
-                max_bytes: None
+                max_bytes: None,
+                validate_utf8: true,
             }
             .max_size(),
             Err("unbounded string or bytes")
@@ -628,6 +1060,58 @@ mod tests {
         f
     }
 
+    #[test]
+    fn utf8_validation() {
+        let mut config = Box::new(Config::new().string_type("String").bytes_type("Vec<u8>"));
+        let no_validation = FeatureSet::default().init_utf8_validation(Utf8Validation::None);
+        let proto = field_proto(Type::String, "");
+        let string_type = |config: &Config, features: &FeatureSet| {
+            let type_conf = CurrentConfig {
+                node: None,
+                config: Cow::Owned(Box::new(config.clone())),
+            };
+            TypeSpec::from_proto(&proto, &type_conf, features).unwrap()
+        };
+
+        assert_eq!(
+            string_type(&config, &FeatureSet::default()),
+            TypeSpec::String {
+                typestr: "String".to_owned(),
+                max_bytes: None,
+                validate_utf8: true,
+            }
+        );
+        // Unvalidated strings are stored in bytes containers
+        assert_eq!(
+            string_type(&config, &no_validation),
+            TypeSpec::String {
+                typestr: "Vec<u8>".to_owned(),
+                max_bytes: None,
+                validate_utf8: false,
+            }
+        );
+
+        // Config overrides the feature in both directions
+        config.validate_utf8 = Some(true);
+        assert_eq!(
+            string_type(&config, &no_validation),
+            TypeSpec::String {
+                typestr: "String".to_owned(),
+                max_bytes: None,
+                validate_utf8: true,
+            }
+        );
+        config.validate_utf8 = Some(false);
+        assert_eq!(
+            string_type(&config, &FeatureSet::default()),
+            TypeSpec::String {
+                typestr: "Vec<u8>".to_owned(),
+                max_bytes: None,
+                validate_utf8: false,
+            }
+        );
+    }
+
     #[test]
     fn from_proto() {
         let mut config = Box::new(
@@ -642,37 +1126,73 @@ mod tests {
             config: Cow::Borrowed(&config),
         };
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Float, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Float, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Float
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Double, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Double, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Double
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Bool, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Bool, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Bool
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::String, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::String, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::String {
                 typestr: "string::String<$N>".to_owned(),
-                max_bytes: Some(10)
+                max_bytes: Some(10),
+                validate_utf8: true,
             }
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Bytes, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Bytes, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Bytes {
                 typestr: "vec::Vec<u8, $N>".to_owned(),
                 max_bytes: Some(10)
             }
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Message, ".msg.Message"), &type_conf).unwrap(),
-            TypeSpec::Message(".msg.Message")
+            TypeSpec::from_proto(
+                &field_proto(Type::Message, ".msg.Message"),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
+            TypeSpec::Message(".msg.Message", None)
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Enum, ".Enum"), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Enum, ".Enum"),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Enum(".Enum")
         );
 
@@ -684,14 +1204,25 @@ mod tests {
             config: Cow::Borrowed(&config),
         };
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::String, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::String, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::String {
                 typestr: "string::String".to_owned(),
-                max_bytes: None
+                max_bytes: None,
+                validate_utf8: true,
             }
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Bytes, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Bytes, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Bytes {
                 typestr: "Bytes".to_owned(),
                 max_bytes: None
@@ -699,6 +1230,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_proto_group() {
+        let config = Box::new(Config::new());
+        let type_conf = CurrentConfig {
+            node: None,
+            config: Cow::Borrowed(&config),
+        };
+        let mut group = field_proto(Type::Group, ".msg.Group");
+        group.set_number(3);
+        assert_eq!(
+            TypeSpec::from_proto(&group, &type_conf, &FeatureSet::default()).unwrap(),
+            TypeSpec::Message(".msg.Group", Some(3))
+        );
+
+        let mut delimited = FeatureSet::default();
+        delimited.set_message_encoding(MessageEncoding::Delimited);
+        let mut msg = field_proto(Type::Message, ".msg.Message");
+        msg.set_number(4);
+        assert_eq!(
+            TypeSpec::from_proto(&msg, &type_conf, &delimited).unwrap(),
+            TypeSpec::Message(".msg.Message", Some(4))
+        );
+        delimited.set_message_encoding(MessageEncoding::LengthPrefixed);
+        assert_eq!(
+            TypeSpec::from_proto(&msg, &type_conf, &delimited).unwrap(),
+            TypeSpec::Message(".msg.Message", None)
+        );
+    }
+
     #[test]
     fn from_proto_num() {
         let mut config = Box::new(Config::new());
@@ -707,19 +1267,39 @@ mod tests {
             config: Cow::Borrowed(&config),
         };
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Sint32, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Sint32, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Sint32, IntSize::S32)
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Int64, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Int64, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Int64, IntSize::S64)
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Fixed32, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Fixed32, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Fixed32, IntSize::S32)
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Uint64, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Uint64, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Uint64, IntSize::S64)
         );
 
@@ -729,11 +1309,21 @@ mod tests {
             config: Cow::Borrowed(&config),
         };
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Sint32, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Sint32, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Sint32, IntSize::S8)
         );
         assert_eq!(
-            TypeSpec::from_proto(&field_proto(Type::Uint64, ""), &type_conf).unwrap(),
+            TypeSpec::from_proto(
+                &field_proto(Type::Uint64, ""),
+                &type_conf,
+                &FeatureSet::default()
+            )
+            .unwrap(),
             TypeSpec::Int(PbInt::Uint64, IntSize::S8)
         );
     }
@@ -779,7 +1369,8 @@ mod tests {
         assert_eq!(
             TypeSpec::String {
                 typestr: "Vec".to_owned(),
-                max_bytes: None
+                max_bytes: None,
+                validate_utf8: true,
             }
             .generate_default("abc\n\tddd", &ctx)
             .unwrap()
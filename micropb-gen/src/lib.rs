@@ -430,11 +430,26 @@
 //!     pub const Rust: Self = Self(0);
 //!     pub const C: Self = Self(1);
 //!     pub const Cpp: Self = Self(2);
+//!
+//!     // All known values
+//!     pub const VALUES: &'static [Self] = &[Self::Rust, Self::C, Self::Cpp];
+//!
+//!     // Conversions to and from the Protobuf names, such as "CPP"
+//!     pub fn as_str_name(&self) -> Option<&'static str> { /* .. */ }
+//!     pub fn from_str_name(name: &str) -> Option<Self> { /* .. */ }
 //! }
 //!
 //! impl From<i32> for Language { /* .. */ }
+//! impl FromStr for Language { /* .. */ }
 //! ```
 //!
+//! Closed enums, which are proto2 enums and editions enums with `enum_type = CLOSED`, can instead
+//! be generated as normal Rust enums via
+//! [`closed_enum_as_rust_enum`](Config::closed_enum_as_rust_enum). Open enums can be generated as
+//! Rust enums with an extra `Unknown` variant holding unrecognized values via
+//! [`open_enum_as_rust_enum`](Config::open_enum_as_rust_enum), which allows exhaustive matching
+//! without changing the wire format.
+//!
 //! # Packages and Modules
 //!
 //! `micropb-gen` translates Protobuf package names into Rust modules by appending an underscore.
@@ -496,9 +511,12 @@
 //! - `field_presence` is supported. `EXPLICIT` is treated as an optional field, and `IMPLICIT` is
 //!   treated as a proto3 singular field. `LEGACY_REQUIRED` is treated the same as `EXPLICIT`.
 //! - `repeated_field_encoding` is supported and treated the same as the "packed" option.
-//! - `enum_type` is ignored, since `micropb` always treats enums as open.
-//! - `message_encoding` is not allowed, since `micropb` doesn't support group-delimited encoding.
-//! - `utf8_validation` is ignored, since Rust strings are always utf8-encoded.
+//! - `enum_type` is supported. `CLOSED` enums are generated as open enums unless
+//!   [`closed_enum_as_rust_enum`](Config::closed_enum_as_rust_enum) is set.
+//! - `message_encoding` is supported. `DELIMITED` message fields are encoded as groups, same as
+//!   proto2 group fields.
+//! - `utf8_validation` is supported. `NONE` string fields are stored in bytes containers and
+//!   aren't validated when decoding. See [`validate_utf8`](Config::validate_utf8).
 //!
 //!  Other standard Editions features are not relevant to code generation.
 
@@ -614,6 +632,13 @@ pub struct Generator {
     pub(crate) comments_to_docs: bool,
     pub(crate) encode_cache: bool,
     pub(crate) cache_extern_types: bool,
+    pub(crate) async_encode_decode: bool,
+    pub(crate) unknown_fields: Option<String>,
+    pub(crate) text_format: bool,
+    pub(crate) json: bool,
+    pub(crate) serde: bool,
+    pub(crate) generate_merge: bool,
+    pub(crate) use_micropb_types: bool,
     pub(crate) service_generators: Vec<Box<dyn ServiceGenerator>>,
 }
 
@@ -648,6 +673,13 @@ impl Generator {
             comments_to_docs: true,
             encode_cache: false,
             cache_extern_types: true,
+            async_encode_decode: false,
+            unknown_fields: None,
+            text_format: false,
+            json: false,
+            serde: false,
+            generate_merge: true,
+            use_micropb_types: true,
             service_generators: Vec::new(),
         }
     }
@@ -1171,7 +1203,8 @@ impl Generator {
     /// # Note
     /// It's technically possible to substitute in Rust types that aren't generated by `micropb-gen`.
     /// However, the generated code expects substituted messages to implement `MessageDecode` and
-    /// `MessageEncode`, and substituted enums to have the "open-enum" structure.
+    /// `MessageEncode` (and `MessageMerge` if [`generate_merge`](Self::generate_merge) is
+    /// enabled), and substituted enums to have the "open-enum" structure.
     pub fn extern_type_path<P1: AsRef<str>, P2: AsRef<str>>(
         &mut self,
         proto_path: P1,
@@ -1188,6 +1221,38 @@ impl Generator {
         self
     }
 
+    /// Determines whether to use the pre-generated well-known types from the
+    /// [`micropb-types`](https://docs.rs/micropb-types) crate.
+    ///
+    /// This is on by default. All `.google.protobuf` well-known types, such as `Timestamp`,
+    /// `Duration`, `Any`, `Struct`, and the wrapper types, are mapped to their definitions in
+    /// `micropb_types` as if by [`extern_type_path`](Self::extern_type_path), so the well-known
+    /// `.proto` files don't need to be compiled. They still need to be available to `protoc` as
+    /// imports. `micropb-types` must be added as a dependency of the crate that includes the
+    /// generated code, with the `alloc` feature enabled if the non-numeric types are used.
+    ///
+    /// Well-known types that are compiled along with the other `.proto` files, or that already
+    /// have an extern path, are not mapped.
+    ///
+    /// `micropb-types` doesn't provide text format, JSON, or `serde` impls, so if any of those
+    /// options are enabled, referring to a mapped well-known type returns an error. In that case,
+    /// compile the well-known `.proto` files along with the other files.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let mut generator = micropb_gen::Generator::new();
+    /// // Text format impls are needed, so generate `Timestamp` instead of using `micropb-types`
+    /// generator.text_format(true);
+    /// generator.compile_protos(
+    ///     &["app.proto", "google/protobuf/timestamp.proto"],
+    ///     std::env::var("OUT_DIR").unwrap() + "/output.rs",
+    /// ).unwrap();
+    /// ```
+    pub fn use_micropb_types(&mut self, flag: bool) -> &mut Self {
+        self.use_micropb_types = flag;
+        self
+    }
+
     /// Determines whether to generate code to calculate the `MAX_SIZE` constant on each message.
     ///
     /// By default, `micropb-gen` generates code to calculate the `MAX_SIZE` associated constant
@@ -1288,6 +1353,139 @@ impl Generator {
         self.cache_extern_types = cache_extern_types;
         self
     }
+
+    /// Generate async encoding and decoding logic in addition to the normal logic.
+    ///
+    /// If enabled, messages will also implement `AsyncMessageDecode` and/or `AsyncMessageEncode`
+    /// (depending on [`encode_decode`](Self::encode_decode)), allowing them to be decoded from an
+    /// `AsyncPbRead` and encoded into an `AsyncPbWrite` without buffering the whole message in
+    /// memory. The generated code requires the `async` feature of `micropb`.
+    ///
+    /// Async encoding does not use the encode cache, even if [`encode_cache`](Self::encode_cache)
+    /// is set. For cyclic message types, the futures of nested messages will be boxed, which
+    /// requires `alloc`.
+    ///
+    /// Custom fields and unknown handlers must implement `AsyncFieldDecode` and
+    /// `AsyncFieldEncode`.
+    ///
+    /// This option is also required by the push decoder in `micropb::push_decode`, which decodes
+    /// messages from byte fragments via `AsyncMessageDecode`. The push decoder doesn't need an
+    /// async executor, so blocking firmware should enable this option as well if it uses the push
+    /// decoder.
+    ///
+    /// Disabled by default.
+    pub fn async_encode_decode(&mut self, async_encode_decode: bool) -> &mut Self {
+        self.async_encode_decode = async_encode_decode;
+        self
+    }
+
+    /// Retain unknown fields in every generated message using the given container type.
+    ///
+    /// Equivalent to setting [`unknown_handler`](Config::unknown_handler) on every message, which
+    /// adds a `_unknown` field of type `container_type` to each message struct. Messages that
+    /// already have an `unknown_handler` configured keep their own handler. This is meant to be
+    /// used with `micropb::UnknownFields`, which stores unknown fields as raw bytes and re-emits
+    /// them unchanged when encoding, so messages can be forwarded without losing fields that
+    /// aren't in the local schema.
+    ///
+    /// ```no_run
+    /// let mut generator = micropb_gen::Generator::new();
+    /// // Keep up to 64 bytes of unknown fields per message
+    /// generator.retain_unknown_fields("::micropb::HeaplessUnknownFields<64>");
+    /// // With `alloc`, use a growable container instead
+    /// generator.retain_unknown_fields("::micropb::VecUnknownFields");
+    /// ```
+    pub fn retain_unknown_fields<P: AsRef<str>>(&mut self, container_type: P) -> &mut Self {
+        self.unknown_fields = Some(container_type.as_ref().to_owned());
+        self
+    }
+
+    /// Generate Protobuf text format printing and parsing for messages and enums.
+    ///
+    /// If enabled, messages will implement `micropb::text::TextEncode` and `core::fmt::Display`,
+    /// which print the message in text format, as well as `micropb::text::TextDecode`, which
+    /// parses text format into the message. Enums will implement `micropb::text::TextEnum`, which
+    /// maps values to and from their names in the `.proto` file. The generated code requires the
+    /// `text-format` feature of `micropb`.
+    ///
+    /// Fields with explicit presence are only printed if they are set, while fields with implicit
+    /// presence are only printed if they're not the default value. Parsed values are written into
+    /// the message's own containers, so running out of capacity in a fixed container is reported
+    /// as an error, same as when decoding from the wire. Custom fields and unknown handlers are not
+    /// part of the text format, and are ignored.
+    ///
+    /// Disabled by default.
+    pub fn text_format(&mut self, text_format: bool) -> &mut Self {
+        self.text_format = text_format;
+        self
+    }
+
+    /// Generate canonical proto3 JSON encoding and decoding for messages.
+    ///
+    /// If enabled, messages will implement `micropb::json::JsonEncode`, which writes the message
+    /// as JSON into a `core::fmt::Write` or `PbWrite`, and `micropb::json::JsonDecode`, which
+    /// parses JSON from a byte slice. Enums will implement `micropb::text::TextEnum` to map values
+    /// to and from their names. The generated code requires the `json` feature of `micropb`.
+    ///
+    /// The output follows the [proto3 JSON mapping](https://protobuf.dev/programming-guides/json/),
+    /// with fields named by their `json_name` and 64-bit integers written as strings. Parsing
+    /// accepts both the JSON names and the original field names. Like decoding from the wire,
+    /// parsing doesn't allocate beyond the message's own containers, and running out of capacity
+    /// in a fixed container is reported as an error. Custom fields and unknown handlers are not
+    /// part of the JSON encoding, and are ignored.
+    ///
+    /// Disabled by default.
+    pub fn json(&mut self, json: bool) -> &mut Self {
+        self.json = json;
+        self
+    }
+
+    /// Generate `serde` `Serialize` and `Deserialize` impls for messages and enums.
+    ///
+    /// Unlike deriving the serde traits via [`Config::type_attributes`], the generated impls
+    /// respect field presence and Protobuf naming. Messages are serialized as structs containing
+    /// only the fields that are present, named by their `json_name`. Each oneof is serialized as
+    /// the field that is set, as if it were a field of the message. Enums are serialized by value
+    /// name in human-readable formats. Unknown enum values are serialized as the value number
+    /// written as a string, such as `"-3"`, and read back the same way.
+    ///
+    /// Deserialization accepts both JSON names and the original field names. Values are written
+    /// into the message's own containers without allocating, so this works with `no_std` formats
+    /// such as `serde-json-core`. The generated code requires the `serde` feature of `micropb`.
+    /// Custom fields and unknown handlers are not serialized.
+    ///
+    /// Disabled by default.
+    pub fn serde(&mut self, serde: bool) -> &mut Self {
+        self.serde = serde;
+        self
+    }
+
+    /// Determines whether to generate `micropb::MessageMerge` impls for messages.
+    ///
+    /// `MessageMerge::merge_from` merges another instance of the message into `self` the same way
+    /// decoding does, without going through the wire format. This is useful for layering messages,
+    /// such as applying user settings on top of the defaults. Fields set in the other message
+    /// overwrite those in `self`, sub-messages are merged recursively, repeated fields and unknown
+    /// fields retained via [`retain_unknown_fields`](Self::retain_unknown_fields) are appended,
+    /// and `map` entries are inserted.
+    ///
+    /// Custom fields and custom oneofs are left untouched, since their contents are opaque to the
+    /// generator. The same goes for the `_unknown` field of messages with a custom handler set via
+    /// [`Config::unknown_handler`](Config::unknown_handler), so unknown fields captured by the
+    /// handler in the other message are silently dropped. If a fixed-capacity container can't
+    /// hold the merged elements, `micropb::CapacityExceeded` is returned and `self` is left
+    /// partially merged, so merge into a copy if that's a concern.
+    ///
+    /// Merging relies on the same container operations as decoding, so impls are only generated
+    /// for messages with decoding enabled. Messages substituted via
+    /// [`extern_type_path`](Self::extern_type_path) must implement `MessageMerge` too, so disable
+    /// this if they don't.
+    ///
+    /// Enabled by default.
+    pub fn generate_merge(&mut self, generate_merge: bool) -> &mut Self {
+        self.generate_merge = generate_merge;
+        self
+    }
 }
 
 fn split_pkg_name(name: &str) -> impl Iterator<Item = &str> {
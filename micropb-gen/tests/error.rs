@@ -355,3 +355,33 @@ fn warn_unused_config() {
     generator.configure(".Msg", Config::new().max_len(5));
     compile(generator);
 }
+
+#[test]
+fn micropb_types_text_format() {
+    let mut generator = Generator::with_warning_callback(warn_panic);
+    generator
+        .text_format(true)
+        .add_protoc_arg("-Itests")
+        .add_protoc_arg("-I../micropb-types/proto");
+    let file = NamedTempFile::new().unwrap();
+    let err = generator
+        .compile_protos(&["wkt.proto"], file.path())
+        .unwrap_err()
+        .to_string();
+    dbg!(&err);
+    assert!(err.contains("(.test.Event.Inner.time)"));
+    assert!(err.contains(".google.protobuf.Timestamp is mapped to `micropb-types`"));
+
+    // Compiling the well-known type ourselves disables the mapping
+    let mut generator = Generator::with_warning_callback(warn_panic);
+    generator
+        .text_format(true)
+        .add_protoc_arg("-Itests")
+        .add_protoc_arg("-I../micropb-types/proto");
+    generator
+        .compile_protos(
+            &["wkt.proto", "google/protobuf/timestamp.proto"],
+            file.path(),
+        )
+        .unwrap();
+}
@@ -0,0 +1,219 @@
+use core::fmt;
+
+use crate::Duration;
+
+const NANOS_PER_SECOND: i32 = 1_000_000_000;
+/// Max absolute value of `Duration::seconds`, which is roughly 10,000 years
+const MAX_SECONDS: i64 = 315_576_000_000;
+
+/// Error returned when converting to or from [`Duration`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The [`Duration`] is not valid, according to [`Duration::is_valid`]
+    Invalid,
+    /// The duration is negative, but the target type can only represent non-negative durations
+    Negative,
+    /// The duration is outside the range of the target type
+    OutOfRange,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("invalid Protobuf duration"),
+            Self::Negative => f.write_str("negative duration"),
+            Self::OutOfRange => f.write_str("duration out of range"),
+        }
+    }
+}
+
+impl core::error::Error for DurationError {}
+
+impl Duration {
+    /// Check if the duration is valid according to the Protobuf spec.
+    ///
+    /// `seconds` must be within ±315,576,000,000, and `nanos` must be within ±999,999,999.
+    /// `seconds` and `nanos` can't have different signs.
+    pub fn is_valid(&self) -> bool {
+        (-MAX_SECONDS..=MAX_SECONDS).contains(&self.seconds)
+            && self.nanos.abs() < NANOS_PER_SECOND
+            && !(self.seconds > 0 && self.nanos < 0)
+            && !(self.seconds < 0 && self.nanos > 0)
+    }
+
+    /// Construct a valid duration from a possibly negative total of seconds and nanoseconds,
+    /// checking against the range allowed by the Protobuf spec.
+    fn from_parts(seconds: i64, nanos: i32) -> Result<Self, DurationError> {
+        let dur = Self { seconds, nanos };
+        if dur.is_valid() {
+            Ok(dur)
+        } else {
+            Err(DurationError::OutOfRange)
+        }
+    }
+}
+
+impl TryFrom<core::time::Duration> for Duration {
+    type Error = DurationError;
+
+    fn try_from(dur: core::time::Duration) -> Result<Self, Self::Error> {
+        let seconds = i64::try_from(dur.as_secs()).map_err(|_| DurationError::OutOfRange)?;
+        Self::from_parts(seconds, dur.subsec_nanos() as i32)
+    }
+}
+
+impl TryFrom<Duration> for core::time::Duration {
+    type Error = DurationError;
+
+    fn try_from(dur: Duration) -> Result<Self, Self::Error> {
+        if !dur.is_valid() {
+            return Err(DurationError::Invalid);
+        }
+        if dur.seconds < 0 || dur.nanos < 0 {
+            return Err(DurationError::Negative);
+        }
+        Ok(core::time::Duration::new(
+            dur.seconds as u64,
+            dur.nanos as u32,
+        ))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::TimeDelta> for Duration {
+    type Error = DurationError;
+
+    fn try_from(dur: chrono::TimeDelta) -> Result<Self, Self::Error> {
+        Self::from_parts(dur.num_seconds(), dur.subsec_nanos())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Duration> for chrono::TimeDelta {
+    type Error = DurationError;
+
+    fn try_from(dur: Duration) -> Result<Self, Self::Error> {
+        if !dur.is_valid() {
+            return Err(DurationError::Invalid);
+        }
+        // TimeDelta expects non-negative nanos, so borrow a second from negative durations
+        let (seconds, nanos) = if dur.nanos < 0 {
+            (dur.seconds - 1, dur.nanos + NANOS_PER_SECOND)
+        } else {
+            (dur.seconds, dur.nanos)
+        };
+        chrono::TimeDelta::new(seconds, nanos as u32).ok_or(DurationError::OutOfRange)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::Duration> for Duration {
+    type Error = DurationError;
+
+    fn try_from(dur: time::Duration) -> Result<Self, Self::Error> {
+        Self::from_parts(dur.whole_seconds(), dur.subsec_nanoseconds())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Duration> for time::Duration {
+    type Error = DurationError;
+
+    fn try_from(dur: Duration) -> Result<Self, Self::Error> {
+        if !dur.is_valid() {
+            return Err(DurationError::Invalid);
+        }
+        Ok(time::Duration::new(dur.seconds, dur.nanos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validity() {
+        let valid = |seconds, nanos| Duration { seconds, nanos }.is_valid();
+        assert!(valid(0, 0));
+        assert!(valid(-1, -999_999_999));
+        assert!(valid(0, -1));
+        assert!(valid(MAX_SECONDS, 999_999_999));
+        assert!(!valid(MAX_SECONDS + 1, 0));
+        assert!(!valid(-1, 1));
+        assert!(!valid(1, -1));
+        assert!(!valid(0, NANOS_PER_SECOND));
+    }
+
+    #[test]
+    fn core_duration() {
+        let dur = Duration::try_from(core::time::Duration::new(5, 20)).unwrap();
+        assert_eq!(
+            dur,
+            Duration {
+                seconds: 5,
+                nanos: 20
+            }
+        );
+        assert_eq!(
+            core::time::Duration::try_from(dur),
+            Ok(core::time::Duration::new(5, 20))
+        );
+
+        assert_eq!(
+            Duration::try_from(core::time::Duration::MAX),
+            Err(DurationError::OutOfRange)
+        );
+        assert_eq!(
+            core::time::Duration::try_from(Duration {
+                seconds: 0,
+                nanos: -1
+            }),
+            Err(DurationError::Negative)
+        );
+        assert_eq!(
+            core::time::Duration::try_from(Duration {
+                seconds: 1,
+                nanos: -1
+            }),
+            Err(DurationError::Invalid)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_duration() {
+        let delta = chrono::TimeDelta::try_milliseconds(-1500).unwrap();
+        let dur = Duration::try_from(delta).unwrap();
+        assert_eq!(
+            dur,
+            Duration {
+                seconds: -1,
+                nanos: -500_000_000
+            }
+        );
+        assert_eq!(chrono::TimeDelta::try_from(dur), Ok(delta));
+        assert_eq!(
+            Duration::try_from(chrono::TimeDelta::MAX),
+            Err(DurationError::OutOfRange)
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_duration() {
+        let delta = time::Duration::milliseconds(-1500);
+        let dur = Duration::try_from(delta).unwrap();
+        assert_eq!(
+            dur,
+            Duration {
+                seconds: -1,
+                nanos: -500_000_000
+            }
+        );
+        assert_eq!(time::Duration::try_from(dur), Ok(delta));
+        assert_eq!(
+            Duration::try_from(time::Duration::MAX),
+            Err(DurationError::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Pre-generated Rust definitions of the [Protobuf well-known
+//! types](https://protobuf.dev/reference/protobuf/google.protobuf/), for use with
+//! [`micropb`](https://docs.rs/micropb).
+//!
+//! Instead of compiling `google/protobuf/*.proto` in every project, `micropb-gen` substitutes
+//! all references to `.google.protobuf` well-known types with the definitions from this crate by
+//! default, so only this crate needs to be added as a dependency. Well-known types that are
+//! compiled along with the other `.proto` files are generated as usual. See
+//! `micropb_gen::Generator::use_micropb_types` for details.
+//!
+//! The types are generated with both encoding and decoding support, as well as the
+//! `encode_cache` option, so they work regardless of whether the generated code uses
+//! `encode_cache`. Text format, JSON, and `serde` impls are not provided, so `micropb-gen` returns
+//! an error if any of those options are combined with references to the types from this crate.
+//!
+//! # Feature Flags
+//! - **alloc**: Enables the types that require dynamically-sized fields, which are [`Any`],
+//!   [`FieldMask`], [`Struct`], [`Value`], [`ListValue`], [`NullValue`], [`StringValue`], and
+//!   [`BytesValue`]. These types use containers from `alloc`. Without this feature, only
+//!   [`Timestamp`], [`Duration`], [`Empty`], and the numeric wrapper types are available.
+//! - **std**: Enables conversions between [`Timestamp`] and
+//!   [`SystemTime`](std::time::SystemTime). Implies `alloc`.
+//! - **chrono**: Enables conversions to and from `chrono::DateTime<Utc>` and `chrono::TimeDelta`.
+//! - **time**: Enables conversions to and from `time::OffsetDateTime` and `time::Duration`.
+//!
+//! Conversions between [`Duration`] and [`core::time::Duration`] are always available.
+//!
+//! # Example
+//! ```
+//! use micropb_types::{Duration, Timestamp};
+//!
+//! let dur = Duration::try_from(core::time::Duration::from_millis(1500)).unwrap();
+//! assert_eq!(dur.seconds, 1);
+//! assert_eq!(dur.nanos, 500_000_000);
+//!
+//! let ts = Timestamp {
+//!     seconds: -1,
+//!     nanos: 0,
+//! };
+//! assert!(ts.is_valid());
+//! ```
+
+#![no_std]
+#![warn(missing_docs)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod duration;
+mod timestamp;
+
+mod wkt_core {
+    #![allow(clippy::all, missing_docs, rustdoc::all)]
+    #![allow(nonstandard_style, unused)]
+    include!("wkt_core.rs");
+}
+
+#[cfg(feature = "alloc")]
+mod wkt_alloc {
+    #![allow(clippy::all, missing_docs, rustdoc::all)]
+    #![allow(nonstandard_style, unused)]
+    include!("wkt_alloc.rs");
+}
+
+pub use duration::DurationError;
+pub use timestamp::TimestampError;
+pub use wkt_core::google::protobuf::*;
+
+#[cfg(feature = "alloc")]
+pub use wkt_alloc::google::protobuf::*;
@@ -0,0 +1,244 @@
+use core::fmt;
+
+use crate::Timestamp;
+
+/// Seconds of 0001-01-01T00:00:00Z
+const MIN_SECONDS: i64 = -62_135_596_800;
+/// Seconds of 9999-12-31T23:59:59Z
+const MAX_SECONDS: i64 = 253_402_300_799;
+const MAX_NANOS: i32 = 999_999_999;
+
+/// Error returned when converting to or from [`Timestamp`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The [`Timestamp`] is not valid, according to [`Timestamp::is_valid`]
+    Invalid,
+    /// The timestamp is outside the range of the target type
+    OutOfRange,
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("invalid Protobuf timestamp"),
+            Self::OutOfRange => f.write_str("timestamp out of range"),
+        }
+    }
+}
+
+impl core::error::Error for TimestampError {}
+
+impl Timestamp {
+    /// Check if the timestamp is valid according to the Protobuf spec.
+    ///
+    /// `seconds` must be between 0001-01-01T00:00:00Z and 9999-12-31T23:59:59Z, and `nanos` must
+    /// be between 0 and 999,999,999.
+    pub fn is_valid(&self) -> bool {
+        (MIN_SECONDS..=MAX_SECONDS).contains(&self.seconds) && (0..=MAX_NANOS).contains(&self.nanos)
+    }
+
+    #[cfg(any(feature = "std", feature = "chrono", feature = "time"))]
+    fn from_parts(seconds: i64, nanos: u32) -> Result<Self, TimestampError> {
+        let ts = Self {
+            seconds,
+            nanos: nanos as i32,
+        };
+        if ts.is_valid() {
+            Ok(ts)
+        } else {
+            Err(TimestampError::OutOfRange)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(dur) => {
+                let seconds =
+                    i64::try_from(dur.as_secs()).map_err(|_| TimestampError::OutOfRange)?;
+                Self::from_parts(seconds, dur.subsec_nanos())
+            }
+            Err(err) => {
+                // Times before the epoch still need non-negative nanos, so round the seconds down
+                let dur = err.duration();
+                let seconds =
+                    i64::try_from(dur.as_secs()).map_err(|_| TimestampError::OutOfRange)?;
+                match dur.subsec_nanos() {
+                    0 => Self::from_parts(-seconds, 0),
+                    nanos => Self::from_parts(-seconds - 1, 1_000_000_000 - nanos),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Timestamp> for std::time::SystemTime {
+    type Error = TimestampError;
+
+    fn try_from(ts: Timestamp) -> Result<Self, Self::Error> {
+        if !ts.is_valid() {
+            return Err(TimestampError::Invalid);
+        }
+        let nanos = core::time::Duration::from_nanos(ts.nanos as u64);
+        let time = if ts.seconds >= 0 {
+            std::time::UNIX_EPOCH.checked_add(core::time::Duration::from_secs(ts.seconds as u64))
+        } else {
+            std::time::UNIX_EPOCH
+                .checked_sub(core::time::Duration::from_secs(ts.seconds.unsigned_abs()))
+        };
+        time.and_then(|t| t.checked_add(nanos))
+            .ok_or(TimestampError::OutOfRange)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(time: chrono::DateTime<chrono::Utc>) -> Result<Self, Self::Error> {
+        // Protobuf timestamps don't have leap seconds, so clamp the leap second representation
+        Self::from_parts(
+            time.timestamp(),
+            time.timestamp_subsec_nanos().min(MAX_NANOS as u32),
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Timestamp> for chrono::DateTime<chrono::Utc> {
+    type Error = TimestampError;
+
+    fn try_from(ts: Timestamp) -> Result<Self, Self::Error> {
+        if !ts.is_valid() {
+            return Err(TimestampError::Invalid);
+        }
+        chrono::DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+            .ok_or(TimestampError::OutOfRange)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(time: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        Self::from_parts(time.unix_timestamp(), time.nanosecond())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Timestamp> for time::OffsetDateTime {
+    type Error = TimestampError;
+
+    fn try_from(ts: Timestamp) -> Result<Self, Self::Error> {
+        if !ts.is_valid() {
+            return Err(TimestampError::Invalid);
+        }
+        let nanos = ts.seconds as i128 * 1_000_000_000 + ts.nanos as i128;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map_err(|_| TimestampError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validity() {
+        let valid = |seconds, nanos| Timestamp { seconds, nanos }.is_valid();
+        assert!(valid(0, 0));
+        assert!(valid(MIN_SECONDS, 0));
+        assert!(valid(MAX_SECONDS, MAX_NANOS));
+        assert!(!valid(MIN_SECONDS - 1, 0));
+        assert!(!valid(MAX_SECONDS + 1, 0));
+        assert!(!valid(0, -1));
+        assert!(!valid(0, MAX_NANOS + 1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_time() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH - core::time::Duration::from_millis(1500);
+        let ts = Timestamp::try_from(time).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: -2,
+                nanos: 500_000_000
+            }
+        );
+        assert_eq!(SystemTime::try_from(ts), Ok(time));
+
+        let time = UNIX_EPOCH + core::time::Duration::new(3, 7);
+        let ts = Timestamp::try_from(time).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: 3,
+                nanos: 7
+            }
+        );
+        assert_eq!(SystemTime::try_from(ts), Ok(time));
+
+        assert_eq!(
+            Timestamp::try_from(UNIX_EPOCH + core::time::Duration::from_secs(1 << 40)),
+            Err(TimestampError::OutOfRange)
+        );
+        assert_eq!(
+            SystemTime::try_from(Timestamp {
+                seconds: 0,
+                nanos: -1
+            }),
+            Err(TimestampError::Invalid)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_time() {
+        let time = chrono::DateTime::from_timestamp(-2, 500_000_000).unwrap();
+        let ts = Timestamp::try_from(time).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: -2,
+                nanos: 500_000_000
+            }
+        );
+        assert_eq!(chrono::DateTime::try_from(ts), Ok(time));
+        assert_eq!(
+            Timestamp::try_from(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+            Err(TimestampError::OutOfRange)
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_time() {
+        let time = time::OffsetDateTime::from_unix_timestamp_nanos(-1_500_000_000).unwrap();
+        let ts = Timestamp::try_from(time).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: -2,
+                nanos: 500_000_000
+            }
+        );
+        assert_eq!(time::OffsetDateTime::try_from(ts), Ok(time));
+        assert_eq!(
+            time::OffsetDateTime::try_from(Timestamp {
+                seconds: MAX_SECONDS + 1,
+                nanos: 0
+            }),
+            Err(TimestampError::Invalid)
+        );
+    }
+}
@@ -0,0 +1,1697 @@
+pub mod r#google {
+    pub mod r#protobuf {
+        /// `Any` contains an arbitrary serialized protocol buffer message along with a
+        /// URL that describes the type of the serialized message.
+        ///
+        /// In its binary encoding, an `Any` is an ordinary message; but in other wire
+        /// forms like JSON, it has a special encoding. The format of the type URL is
+        /// described on the `type_url` field.
+        ///
+        /// Protobuf APIs provide utilities to interact with `Any` values:
+        ///
+        /// - A 'pack' operation accepts a message and constructs a generic `Any` wrapper
+        ///   around it.
+        /// - An 'unpack' operation reads the content of an `Any` message, either into an
+        ///   existing message or a new one. Unpack operations must check the type of the
+        ///   value they unpack against the declared `type_url`.
+        /// - An 'is' operation decides whether an `Any` contains a message of the given
+        ///   type, i.e. whether it can 'unpack' that type.
+        ///
+        /// The JSON format representation of an `Any` follows one of these cases:
+        ///
+        /// - For types without special-cased JSON encodings, the JSON format
+        ///   representation of the `Any` is the same as that of the message, with an
+        ///   additional `@type` field which contains the type URL.
+        /// - For types with special-cased JSON encodings (typically called 'well-known'
+        ///   types, listed in https://protobuf.dev/programming-guides/json/#any), the
+        ///   JSON format representation has a key `@type` which contains the type URL
+        ///   and a key `value` which contains the JSON-serialized value.
+        ///
+        /// The text format representation of an `Any` is like a message with one field
+        /// whose name is the type URL in brackets. For example, an `Any` containing a
+        /// `foo.Bar` message may be written `[type.googleapis.com/foo.Bar] { a: 2 }`.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct Any {
+            /// Identifies the type of the serialized Protobuf message with a URI reference
+            /// consisting of a prefix ending in a slash and the fully-qualified type name.
+            ///
+            /// Example: type.googleapis.com/google.protobuf.StringValue
+            ///
+            /// This string must contain at least one `/` character, and the content after
+            /// the last `/` must be the fully-qualified name of the type in canonical
+            /// form, without a leading dot. Do not write a scheme on these URI references
+            /// so that clients do not attempt to contact them.
+            ///
+            /// The prefix is arbitrary and Protobuf implementations are expected to
+            /// simply strip off everything up to and including the last `/` to identify
+            /// the type. `type.googleapis.com/` is a common default prefix that some
+            /// legacy implementations require. This prefix does not indicate the origin of
+            /// the type, and URIs containing it are not expected to respond to any
+            /// requests.
+            ///
+            /// All type URL strings must be legal URI references with the additional
+            /// restriction (for the text format) that the content of the reference
+            /// must consist only of alphanumeric characters, percent-encoded escapes, and
+            /// characters in the following set (not including the outer backticks):
+            /// `/-.~_!$&()*+,;=`. Despite our allowing percent encodings, implementations
+            /// should not unescape them to prevent confusion with existing parsers. For
+            /// example, `type.googleapis.com%2FFoo` should be rejected.
+            ///
+            /// In the original design of `Any`, the possibility of launching a type
+            /// resolution service at these type URLs was considered but Protobuf never
+            /// implemented one and considers contacting these URLs to be problematic and
+            /// a potential security issue. Do not attempt to contact type URLs.
+            pub r#type_url: ::alloc::string::String,
+            /// Holds a Protobuf serialization of the type described by type_url.
+            pub r#value: ::alloc::vec::Vec<u8>,
+        }
+        impl Any {
+            /// Return a reference to `type_url`
+            #[inline]
+            pub fn r#type_url(&self) -> &::alloc::string::String {
+                &self.r#type_url
+            }
+            /// Return a mutable reference to `type_url`
+            #[inline]
+            pub fn mut_type_url(&mut self) -> &mut ::alloc::string::String {
+                &mut self.r#type_url
+            }
+            /// Set the value of `type_url`
+            #[inline]
+            pub fn set_type_url(&mut self, value: ::alloc::string::String) -> &mut Self {
+                self.r#type_url = value.into();
+                self
+            }
+            /// Builder method that sets the value of `type_url`. Useful for initializing the message.
+            #[inline]
+            pub fn init_type_url(mut self, value: ::alloc::string::String) -> Self {
+                self.r#type_url = value.into();
+                self
+            }
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &::alloc::vec::Vec<u8> {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut ::alloc::vec::Vec<u8> {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: ::alloc::vec::Vec<u8>) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: ::alloc::vec::Vec<u8>) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for Any {
+            const FULL_NAME: &'static str = "google.protobuf.Any";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Any";
+        }
+        impl ::micropb::MessageMerge for Any {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#type_url;
+                    if !val_ref.is_empty() {
+                        self.r#type_url = val_ref.clone();
+                    }
+                }
+                {
+                    let val_ref = &other.r#value;
+                    if !val_ref.is_empty() {
+                        self.r#value = val_ref.clone();
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::any::PbAny for Any {
+            type TypeUrl = ::alloc::string::String;
+            type Value = ::alloc::vec::Vec<u8>;
+            fn any_parts(&self) -> (&Self::TypeUrl, &Self::Value) {
+                (&self.r#type_url, &self.r#value)
+            }
+            fn any_parts_mut(&mut self) -> (&mut Self::TypeUrl, &mut Self::Value) {
+                (&mut self.r#type_url, &mut self.r#value)
+            }
+        }
+        impl ::micropb::MessageDecode for Any {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#type_url;
+                            {
+                                decoder
+                                    .decode_string(mut_ref, ::micropb::Presence::Implicit)?;
+                            };
+                        }
+                        2u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                decoder
+                                    .decode_bytes(mut_ref, ::micropb::Presence::Implicit)?;
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Any {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result:: < usize, & 'static str >
+                    ::Err("(.google.protobuf.Any.type_url) unbounded string or bytes"), |
+                    size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                match ::micropb::const_map!(
+                    ::core::result::Result:: < usize, & 'static str >
+                    ::Err("(.google.protobuf.Any.value) unbounded string or bytes"), |
+                    size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Any_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#type_url;
+                    if !val_ref.is_empty() {
+                        encoder.encode_varint32(10u32)?;
+                        encoder.encode_string(val_ref)?;
+                    }
+                }
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        encoder.encode_varint32(18u32)?;
+                        encoder.encode_bytes(val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#type_url;
+                    if !val_ref.is_empty() {
+                        cache._size
+                            += 1usize
+                                + ::micropb::size::sizeof_len_record(val_ref.len());
+                    }
+                }
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        cache._size
+                            += 1usize
+                                + ::micropb::size::sizeof_len_record(val_ref.len());
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Any`
+        pub mod Any_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// `FieldMask` represents a set of symbolic field paths, for example:
+        ///
+        /// ```text
+        /// paths: "f.a"
+        /// paths: "f.b.d"
+        /// ```
+        ///
+        /// Here `f` represents a field in some root message, `a` and `b`
+        /// fields in the message found in `f`, and `d` a field found in the
+        /// message in `f.b`.
+        ///
+        /// Field masks are used to specify a subset of fields that should be
+        /// returned by a get operation or modified by an update operation.
+        /// Field masks also have a custom JSON encoding (see below).
+        ///
+        /// # Field Masks in Projections
+        ///
+        /// When used in the context of a projection, a response message or
+        /// sub-message is filtered by the API to only contain those fields as
+        /// specified in the mask. For example, if the mask in the previous
+        /// example is applied to a response message as follows:
+        ///
+        /// ```text
+        /// f {
+        ///   a : 22
+        ///   b {
+        ///     d : 1
+        ///     x : 2
+        ///   }
+        ///   y : 13
+        /// }
+        /// z: 8
+        /// ```
+        ///
+        /// The result will not contain specific values for fields x,y and z
+        /// (their value will be set to the default, and omitted in proto text
+        /// output):
+        ///
+        ///
+        /// ```text
+        /// f {
+        ///   a : 22
+        ///   b {
+        ///     d : 1
+        ///   }
+        /// }
+        /// ```
+        ///
+        /// A repeated field is not allowed except at the last position of a
+        /// paths string.
+        ///
+        /// If a FieldMask object is not present in a get operation, the
+        /// operation applies to all fields (as if a FieldMask of all fields
+        /// had been specified).
+        ///
+        /// Note that a field mask does not necessarily apply to the
+        /// top-level response message. In case of a REST get operation, the
+        /// field mask applies directly to the response, but in case of a REST
+        /// list operation, the mask instead applies to each individual message
+        /// in the returned resource list. In case of a REST custom method,
+        /// other definitions may be used. Where the mask applies will be
+        /// clearly documented together with its declaration in the API.  In
+        /// any case, the effect on the returned resource/resources is required
+        /// behavior for APIs.
+        ///
+        /// # Field Masks in Update Operations
+        ///
+        /// A field mask in update operations specifies which fields of the
+        /// targeted resource are going to be updated. The API is required
+        /// to only change the values of the fields as specified in the mask
+        /// and leave the others untouched. If a resource is passed in to
+        /// describe the updated values, the API ignores the values of all
+        /// fields not covered by the mask.
+        ///
+        /// If a repeated field is specified for an update operation, new values will
+        /// be appended to the existing repeated field in the target resource. Note that
+        /// a repeated field is only allowed in the last position of a `paths` string.
+        ///
+        /// If a sub-message is specified in the last position of the field mask for an
+        /// update operation, then new value will be merged into the existing sub-message
+        /// in the target resource.
+        ///
+        /// For example, given the target message:
+        ///
+        /// ```text
+        /// f {
+        ///   b {
+        ///     d: 1
+        ///     x: 2
+        ///   }
+        ///   c: [1]
+        /// }
+        /// ```
+        ///
+        /// And an update message:
+        ///
+        /// ```text
+        /// f {
+        ///   b {
+        ///     d: 10
+        ///   }
+        ///   c: [2]
+        /// }
+        /// ```
+        ///
+        /// then if the field mask is:
+        ///
+        ///  paths: ["f.b", "f.c"]
+        ///
+        /// then the result will be:
+        ///
+        /// ```text
+        /// f {
+        ///   b {
+        ///     d: 10
+        ///     x: 2
+        ///   }
+        ///   c: [1, 2]
+        /// }
+        /// ```
+        ///
+        /// An implementation may provide options to override this default behavior for
+        /// repeated and message fields.
+        ///
+        /// Note that libraries which implement FieldMask resolution have various
+        /// different behaviors in the face of empty masks or the special "*" mask.
+        /// When implementing a service you should confirm these cases have the
+        /// appropriate behavior in the underlying FieldMask library that you desire,
+        /// and you may need to special case those cases in your application code if
+        /// the underlying field mask library behavior differs from your intended
+        /// service semantics.
+        ///
+        /// Update methods implementing https://google.aip.dev/134
+        /// - MUST support the special value * meaning "full replace"
+        /// - MUST treat an omitted field mask as "replace fields which are present".
+        ///
+        /// Other methods implementing https://google.aip.dev/157
+        /// - SHOULD support the special value "*" to mean "get all".
+        /// - MUST treat an omitted field mask to mean "get all", unless otherwise
+        /// documented.
+        ///
+        /// ## Considerations for HTTP REST
+        ///
+        /// The HTTP kind of an update operation which uses a field mask must
+        /// be set to PATCH instead of PUT in order to satisfy HTTP semantics
+        /// (PUT must only be used for full updates).
+        ///
+        /// # JSON Encoding of Field Masks
+        ///
+        /// In JSON, a field mask is encoded as a single string where paths are
+        /// separated by a comma. Fields name in each path are converted
+        /// to/from lower-camel naming conventions.
+        ///
+        /// As an example, consider the following message declarations:
+        ///
+        /// ```text
+        /// message Profile {
+        ///   User user = 1;
+        ///   Photo photo = 2;
+        /// }
+        /// message User {
+        ///   string display_name = 1;
+        ///   string address = 2;
+        /// }
+        /// ```
+        ///
+        /// In proto a field mask for `Profile` may look as such:
+        ///
+        /// ```text
+        /// mask {
+        ///   paths: "user.display_name"
+        ///   paths: "photo"
+        /// }
+        /// ```
+        ///
+        /// In JSON, the same mask is represented as below:
+        ///
+        /// ```text
+        /// {
+        ///   mask: "user.displayName,photo"
+        /// }
+        /// ```
+        ///
+        /// # Field Masks and Oneof Fields
+        ///
+        /// Field masks treat fields in oneofs just as regular fields. Consider the
+        /// following message:
+        ///
+        /// ```text
+        /// message SampleMessage {
+        ///   oneof test_oneof {
+        ///     string name = 4;
+        ///     SubMessage sub_message = 9;
+        ///   }
+        /// }
+        /// ```
+        ///
+        /// The field mask can be:
+        ///
+        /// ```text
+        /// mask {
+        ///   paths: "name"
+        /// }
+        /// ```
+        ///
+        /// Or:
+        ///
+        /// ```text
+        /// mask {
+        ///   paths: "sub_message"
+        /// }
+        /// ```
+        ///
+        /// Note that oneof type names ("test_oneof" in this case) cannot be used in
+        /// paths.
+        ///
+        /// ## Field Mask Verification
+        ///
+        /// The implementation of any API method which has a FieldMask type field in the
+        /// request should verify the included field paths, and return an
+        /// `INVALID_ARGUMENT` error if any path is unmappable.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct FieldMask {
+            /// The set of field mask paths.
+            pub r#paths: ::alloc::vec::Vec<::alloc::string::String>,
+        }
+        impl FieldMask {}
+        impl ::micropb::MessageName for FieldMask {
+            const FULL_NAME: &'static str = "google.protobuf.FieldMask";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FieldMask";
+        }
+        impl ::micropb::MessageMerge for FieldMask {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#paths.pb_reserve(other.r#paths.len());
+                for val_ref in other.r#paths.iter() {
+                    self.r#paths
+                        .pb_push(val_ref.clone())
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for FieldMask {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut val: ::alloc::string::String = ::core::default::Default::default();
+                            let mut_ref = &mut val;
+                            {
+                                decoder
+                                    .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
+                            };
+                            decoder.spend_elem_budget::<::alloc::string::String>()?;
+                            if let (Err(_), false) = (
+                                self.r#paths.pb_push(val),
+                                decoder.ignore_repeated_cap_err,
+                            ) {
+                                return Err(::micropb::DecodeError::Capacity);
+                            }
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for FieldMask {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::core::result::Result::<
+                    usize,
+                    &'static str,
+                >::Err("(.google.protobuf.FieldMask.paths) unbounded vec") {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = FieldMask_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    for (i, val_ref) in self.r#paths.iter().enumerate() {
+                        encoder.encode_varint32(10u32)?;
+                        encoder.encode_string(val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    for (i, val_ref) in self.r#paths.iter().enumerate() {
+                        cache._size
+                            += 1usize
+                                + ::micropb::size::sizeof_len_record(val_ref.len());
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `FieldMask`
+        pub mod FieldMask_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Represents a JSON object.
+        ///
+        /// An unordered key-value map, intending to perfectly capture the semantics of a
+        /// JSON object. This enables parsing any arbitrary JSON payload as a message
+        /// field in ProtoJSON format.
+        ///
+        /// This follows RFC 8259 guidelines for interoperable JSON: notably this type
+        /// cannot represent large Int64 values or `NaN`/`Infinity` numbers,
+        /// since the JSON format generally does not support those values in its number
+        /// type.
+        ///
+        /// If you do not intend to parse arbitrary JSON into your message, a custom
+        /// typed message should be preferred instead of using this type.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct Struct {
+            /// Unordered map of dynamically typed values.
+            pub r#fields: ::alloc::collections::BTreeMap<::alloc::string::String, Value>,
+        }
+        impl Struct {}
+        impl ::micropb::MessageName for Struct {
+            const FULL_NAME: &'static str = "google.protobuf.Struct";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Struct";
+        }
+        impl ::micropb::MessageMerge for Struct {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                for (key_ref, val_ref) in &other.r#fields {
+                    self.r#fields
+                        .pb_insert(
+                            key_ref.clone(),
+                            {
+                                let mut msg = ::core::default::Default::default();
+                                ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                                msg
+                            },
+                        )
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Struct {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            if let Some((k, v)) = decoder
+                                .decode_map_elem(
+                                    |mut_ref: &mut ::alloc::string::String, decoder| {
+                                        decoder
+                                            .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
+                                        Ok(())
+                                    },
+                                    |mut_ref: &mut Value, decoder| {
+                                        decoder.decode_nested_message(mut_ref)?;
+                                        Ok(())
+                                    },
+                                )?
+                            {
+                                decoder
+                                    .spend_elem_budget::<(::alloc::string::String, Value)>()?;
+                                if let (Err(_), false) = (
+                                    self.r#fields.pb_insert(k, v),
+                                    decoder.ignore_repeated_cap_err,
+                                ) {
+                                    return Err(::micropb::DecodeError::Capacity);
+                                }
+                            }
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Struct {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::core::result::Result::<
+                    usize,
+                    &'static str,
+                >::Err("(.google.protobuf.Struct.fields) unbounded map") {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Struct_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    for (i, (k, v)) in (&self.r#fields).into_iter().enumerate() {
+                        let len = ::micropb::size::sizeof_map_elem(
+                            k,
+                            v,
+                            |val_ref| {
+                                ::micropb::size::sizeof_len_record(val_ref.len())
+                            },
+                            |val_ref| {
+                                ::micropb::size::sizeof_len_record(cache.r#fields[i]._size)
+                            },
+                        );
+                        encoder.encode_varint32(10u32)?;
+                        encoder
+                            .encode_map_elem(
+                                len,
+                                k,
+                                2u8,
+                                v,
+                                2u8,
+                                |encoder, val_ref| { encoder.encode_string(val_ref) },
+                                |encoder, val_ref| {
+                                    val_ref
+                                        .encode_len_delimited_cached(encoder, &cache.r#fields[i])
+                                },
+                            )?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    for (i, (k, v)) in (&self.r#fields).into_iter().enumerate() {
+                        let len = ::micropb::size::sizeof_map_elem(
+                            k,
+                            v,
+                            |val_ref| {
+                                ::micropb::size::sizeof_len_record(val_ref.len())
+                            },
+                            |val_ref| {
+                                let elem = val_ref.populate_cache();
+                                let sz = elem._size;
+                                cache
+                                    .r#fields
+                                    .pb_push(elem)
+                                    .expect("vec overflow while caching");
+                                ::micropb::size::sizeof_len_record(sz)
+                            },
+                        );
+                        cache._size += ::micropb::size::sizeof_len_record(len) + 1usize;
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Struct`
+        pub mod Struct_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+                pub r#fields: ::alloc::vec::Vec<super::Value_::_Cache>,
+            }
+        }
+        /// Represents a JSON value.
+        ///
+        /// `Value` represents a dynamically typed value which can be either
+        /// null, a number, a string, a boolean, a recursive struct value, or a
+        /// list of values. A producer of value is expected to set one of these
+        /// variants. Absence of any variant is an invalid state.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct Value {
+            /// The kind of value.
+            pub r#kind: ::core::option::Option<Value_::Kind>,
+        }
+        impl Value {}
+        impl ::micropb::MessageName for Value {
+            const FULL_NAME: &'static str = "google.protobuf.Value";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Value";
+        }
+        impl ::micropb::MessageMerge for Value {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                if let Some(oneof) = &other.r#kind {
+                    match &*oneof {
+                        Value_::Kind::NullValue(val_ref) => {
+                            self.r#kind = ::core::option::Option::Some(
+                                Value_::Kind::NullValue(*val_ref),
+                            );
+                        }
+                        Value_::Kind::NumberValue(val_ref) => {
+                            self.r#kind = ::core::option::Option::Some(
+                                Value_::Kind::NumberValue(*val_ref),
+                            );
+                        }
+                        Value_::Kind::StringValue(val_ref) => {
+                            self.r#kind = ::core::option::Option::Some(
+                                Value_::Kind::StringValue(val_ref.clone()),
+                            );
+                        }
+                        Value_::Kind::BoolValue(val_ref) => {
+                            self.r#kind = ::core::option::Option::Some(
+                                Value_::Kind::BoolValue(*val_ref),
+                            );
+                        }
+                        Value_::Kind::StructValue(val_ref) => {
+                            if let ::core::option::Option::Some(
+                                Value_::Kind::StructValue(dest),
+                            ) = self.r#kind.as_mut()
+                            {
+                                ::micropb::MessageMerge::merge_from(&mut *dest, val_ref)?;
+                            } else {
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::StructValue({
+                                        let mut msg = ::core::default::Default::default();
+                                        ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                                        msg
+                                    }),
+                                );
+                            }
+                        }
+                        Value_::Kind::ListValue(val_ref) => {
+                            if let ::core::option::Option::Some(
+                                Value_::Kind::ListValue(dest),
+                            ) = self.r#kind.as_mut()
+                            {
+                                ::micropb::MessageMerge::merge_from(&mut *dest, val_ref)?;
+                            } else {
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::ListValue({
+                                        let mut msg = ::core::default::Default::default();
+                                        ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                                        msg
+                                    }),
+                                );
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Value {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::NullValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::NullValue(::core::default::Default::default()),
+                                );
+                            };
+                            let val = decoder.decode_int32().map(|n| NullValue(n as _))?;
+                            *mut_ref = val as _;
+                        }
+                        2u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::NumberValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::NumberValue(
+                                        ::core::default::Default::default(),
+                                    ),
+                                );
+                            };
+                            let val = decoder.decode_double()?;
+                            *mut_ref = val as _;
+                        }
+                        3u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::StringValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::StringValue(
+                                        ::core::default::Default::default(),
+                                    ),
+                                );
+                            };
+                            decoder
+                                .decode_string(mut_ref, ::micropb::Presence::Explicit)?;
+                        }
+                        4u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::BoolValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::BoolValue(::core::default::Default::default()),
+                                );
+                            };
+                            let val = decoder.decode_bool()?;
+                            *mut_ref = val as _;
+                        }
+                        5u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::StructValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::StructValue(
+                                        ::core::default::Default::default(),
+                                    ),
+                                );
+                            };
+                            decoder.decode_nested_message(mut_ref)?;
+                        }
+                        6u32 => {
+                            let mut_ref = loop {
+                                if let ::core::option::Option::Some(variant) = &mut self
+                                    .r#kind
+                                {
+                                    if let Value_::Kind::ListValue(variant) = &mut *variant {
+                                        break &mut *variant;
+                                    }
+                                }
+                                self.r#kind = ::core::option::Option::Some(
+                                    Value_::Kind::ListValue(::core::default::Default::default()),
+                                );
+                            };
+                            decoder.decode_nested_message(mut_ref)?;
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Value {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match 'oneof: {
+                    let mut max_size = 0;
+                    match ::micropb::const_map!(
+                        ::core::result::Result::Ok(NullValue::_MAX_SIZE), | size | size +
+                        1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    match ::micropb::const_map!(
+                        ::core::result::Result::Ok(8usize), | size | size + 1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    match ::micropb::const_map!(
+                        ::core::result::Result:: < usize, & 'static str >
+                        ::Err("(.google.protobuf.Value.string_value) unbounded string or bytes"),
+                        | size | size + 1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    match ::micropb::const_map!(
+                        ::core::result::Result::Ok(1usize), | size | size + 1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    match ::micropb::const_map!(
+                        ::micropb::const_map!(< Struct as ::micropb::MessageEncode >
+                        ::MAX_SIZE, | size | ::micropb::size::sizeof_len_record(size)), |
+                        size | size + 1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    match ::micropb::const_map!(
+                        ::micropb::const_map!(< ListValue as ::micropb::MessageEncode >
+                        ::MAX_SIZE, | size | ::micropb::size::sizeof_len_record(size)), |
+                        size | size + 1usize
+                    ) {
+                        ::core::result::Result::Ok(size) => {
+                            if size > max_size {
+                                max_size = size;
+                            }
+                        }
+                        ::core::result::Result::Err(err) => {
+                            break 'oneof (::core::result::Result::<usize, _>::Err(err));
+                        }
+                    }
+                    ::core::result::Result::Ok(max_size)
+                } {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Value_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                if let Some(oneof) = &self.r#kind {
+                    match &*oneof {
+                        Value_::Kind::NullValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(8u32)?;
+                            encoder.encode_int32(val_ref.0 as _)?;
+                        }
+                        Value_::Kind::NumberValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(17u32)?;
+                            encoder.encode_double(*val_ref)?;
+                        }
+                        Value_::Kind::StringValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(26u32)?;
+                            encoder.encode_string(val_ref)?;
+                        }
+                        Value_::Kind::BoolValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(32u32)?;
+                            encoder.encode_bool(*val_ref)?;
+                        }
+                        Value_::Kind::StructValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(42u32)?;
+                            if let Value_::_KindCache::StructValue(subcache) = &cache
+                                .r#kind
+                            {
+                                val_ref.encode_len_delimited_cached(encoder, &subcache)
+                            } else {
+                                core::unreachable!("unexpected cache variant")
+                            }?;
+                        }
+                        Value_::Kind::ListValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            encoder.encode_varint32(50u32)?;
+                            if let Value_::_KindCache::ListValue(subcache) = &cache
+                                .r#kind
+                            {
+                                val_ref.encode_len_delimited_cached(encoder, &subcache)
+                            } else {
+                                core::unreachable!("unexpected cache variant")
+                            }?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                if let Some(oneof) = &self.r#kind {
+                    match &*oneof {
+                        Value_::Kind::NullValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            cache._size
+                                += 1usize + ::micropb::size::sizeof_int32(val_ref.0 as _);
+                        }
+                        Value_::Kind::NumberValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            cache._size += 1usize + 8;
+                        }
+                        Value_::Kind::StringValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            cache._size
+                                += 1usize
+                                    + ::micropb::size::sizeof_len_record(val_ref.len());
+                        }
+                        Value_::Kind::BoolValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            cache._size += 1usize + 1;
+                        }
+                        Value_::Kind::StructValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            let subcache = val_ref.populate_cache();
+                            cache._size
+                                += 1usize
+                                    + ::micropb::size::sizeof_len_record(subcache._size);
+                            cache.r#kind = Value_::_KindCache::StructValue(subcache);
+                        }
+                        Value_::Kind::ListValue(val_ref) => {
+                            let val_ref = &*val_ref;
+                            let subcache = val_ref.populate_cache();
+                            cache._size
+                                += 1usize
+                                    + ::micropb::size::sizeof_len_record(subcache._size);
+                            cache.r#kind = Value_::_KindCache::ListValue(subcache);
+                        }
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Value`
+        pub mod Value_ {
+            /// The kind of value.
+            #[derive(Debug, PartialEq, Clone)]
+            pub enum Kind {
+                /// Represents a JSON `null`.
+                NullValue(super::NullValue),
+                /// Represents a JSON number. Must not be `NaN`, `Infinity` or
+                /// `-Infinity`, since those are not supported in JSON. This also cannot
+                /// represent large Int64 values, since JSON format generally does not
+                /// support them in its number type.
+                NumberValue(f64),
+                /// Represents a JSON string.
+                StringValue(::alloc::string::String),
+                /// Represents a JSON boolean (`true` or `false` literal in JSON).
+                BoolValue(bool),
+                /// Represents a JSON object.
+                StructValue(super::Struct),
+                /// Represents a JSON array.
+                ListValue(super::ListValue),
+            }
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+                pub r#kind: _KindCache,
+            }
+            #[derive(Default)]
+            pub enum _KindCache {
+                StructValue(super::Struct_::_Cache),
+                ListValue(super::ListValue_::_Cache),
+                #[default]
+                None,
+            }
+        }
+        /// Represents a JSON array.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct ListValue {
+            /// Repeated field of dynamically typed values.
+            pub r#values: ::alloc::vec::Vec<Value>,
+        }
+        impl ListValue {}
+        impl ::micropb::MessageName for ListValue {
+            const FULL_NAME: &'static str = "google.protobuf.ListValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.ListValue";
+        }
+        impl ::micropb::MessageMerge for ListValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                self.r#values.pb_reserve(other.r#values.len());
+                for val_ref in other.r#values.iter() {
+                    self.r#values
+                        .pb_push({
+                            let mut msg = ::core::default::Default::default();
+                            ::micropb::MessageMerge::merge_from(&mut msg, val_ref)?;
+                            msg
+                        })
+                        .map_err(|_| ::micropb::CapacityExceeded)?;
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for ListValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut val: Value = ::core::default::Default::default();
+                            let mut_ref = &mut val;
+                            {
+                                decoder.decode_nested_message(mut_ref)?;
+                            };
+                            decoder.spend_elem_budget::<Value>()?;
+                            if let (Err(_), false) = (
+                                self.r#values.pb_push(val),
+                                decoder.ignore_repeated_cap_err,
+                            ) {
+                                return Err(::micropb::DecodeError::Capacity);
+                            }
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for ListValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::core::result::Result::<
+                    usize,
+                    &'static str,
+                >::Err("(.google.protobuf.ListValue.values) unbounded vec") {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = ListValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    for (i, val_ref) in self.r#values.iter().enumerate() {
+                        encoder.encode_varint32(10u32)?;
+                        val_ref
+                            .encode_len_delimited_cached(encoder, &cache.r#values[i])?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    for (i, val_ref) in self.r#values.iter().enumerate() {
+                        let elem = val_ref.populate_cache();
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_len_record(elem._size);
+                        cache
+                            .r#values
+                            .pb_push(elem)
+                            .expect("vec overflow while caching");
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `ListValue`
+        pub mod ListValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+                pub r#values: ::alloc::vec::Vec<super::Value_::_Cache>,
+            }
+        }
+        /// Represents a JSON `null`.
+        ///
+        /// `NullValue` is a sentinel, using an enum with only one value to represent
+        /// the null value for the `Value` type union.
+        ///
+        /// A field of type `NullValue` with any value other than `0` is considered
+        /// invalid. Most ProtoJSON serializers will emit a `Value` with a `null_value`
+        /// set as a JSON `null` regardless of the integer value, and so will round trip
+        /// to a `0` value.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct NullValue(pub i32);
+        impl NullValue {
+            /// Maximum encoded size of the enum
+            pub const _MAX_SIZE: usize = 10usize;
+            /// Null value.
+            pub const NullValue: Self = Self(0);
+            /// All known values of the enum, in declaration order, excluding aliases
+            pub const VALUES: &'static [Self] = &[Self::NullValue];
+            /// Protobuf name of the enum value, or `None` if the value is unrecognized
+            pub fn as_str_name(&self) -> ::core::option::Option<&'static str> {
+                match *self {
+                    NullValue::NullValue => ::core::option::Option::Some("NULL_VALUE"),
+                    _ => ::core::option::Option::None,
+                }
+            }
+            /// Get the enum value from its Protobuf name
+            pub fn from_str_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    "NULL_VALUE" => ::core::option::Option::Some(Self::NullValue),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+        impl ::core::str::FromStr for NullValue {
+            type Err = ::micropb::UnknownEnumName;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                Self::from_str_name(s).ok_or(::micropb::UnknownEnumName)
+            }
+        }
+        impl core::default::Default for NullValue {
+            fn default() -> Self {
+                Self(0)
+            }
+        }
+        impl core::convert::From<i32> for NullValue {
+            fn from(val: i32) -> Self {
+                Self(val)
+            }
+        }
+        impl core::fmt::Debug for NullValue {
+            fn fmt(
+                &self,
+                formatter: &mut core::fmt::Formatter<'_>,
+            ) -> core::fmt::Result {
+                match *self {
+                    NullValue::NullValue => formatter.write_str("NullValue"),
+                    Self(n) => formatter.debug_tuple("_Unknown").field(&n).finish(),
+                }
+            }
+        }
+        /// Wrapper message for `string`.
+        ///
+        /// The JSON representation for `StringValue` is JSON string.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct StringValue {
+            /// The string value.
+            pub r#value: ::alloc::string::String,
+        }
+        impl StringValue {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &::alloc::string::String {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut ::alloc::string::String {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: ::alloc::string::String) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: ::alloc::string::String) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for StringValue {
+            const FULL_NAME: &'static str = "google.protobuf.StringValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.StringValue";
+        }
+        impl ::micropb::MessageMerge for StringValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if !val_ref.is_empty() {
+                        self.r#value = val_ref.clone();
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for StringValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                decoder
+                                    .decode_string(mut_ref, ::micropb::Presence::Implicit)?;
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for StringValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result:: < usize, & 'static str >
+                    ::Err("(.google.protobuf.StringValue.value) unbounded string or bytes"),
+                    | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = StringValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        encoder.encode_varint32(10u32)?;
+                        encoder.encode_string(val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        cache._size
+                            += 1usize
+                                + ::micropb::size::sizeof_len_record(val_ref.len());
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `StringValue`
+        pub mod StringValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `bytes`.
+        ///
+        /// The JSON representation for `BytesValue` is JSON string.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone)]
+        pub struct BytesValue {
+            /// The bytes value.
+            pub r#value: ::alloc::vec::Vec<u8>,
+        }
+        impl BytesValue {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &::alloc::vec::Vec<u8> {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut ::alloc::vec::Vec<u8> {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: ::alloc::vec::Vec<u8>) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: ::alloc::vec::Vec<u8>) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for BytesValue {
+            const FULL_NAME: &'static str = "google.protobuf.BytesValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.BytesValue";
+        }
+        impl ::micropb::MessageMerge for BytesValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if !val_ref.is_empty() {
+                        self.r#value = val_ref.clone();
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for BytesValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                decoder
+                                    .decode_bytes(mut_ref, ::micropb::Presence::Implicit)?;
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for BytesValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result:: < usize, & 'static str >
+                    ::Err("(.google.protobuf.BytesValue.value) unbounded string or bytes"),
+                    | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = BytesValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        encoder.encode_varint32(10u32)?;
+                        encoder.encode_bytes(val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if !val_ref.is_empty() {
+                        cache._size
+                            += 1usize
+                                + ::micropb::size::sizeof_len_record(val_ref.len());
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `BytesValue`
+        pub mod BytesValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+    }
+}
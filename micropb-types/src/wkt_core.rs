@@ -0,0 +1,1645 @@
+pub mod r#google {
+    pub mod r#protobuf {
+        /// A Duration represents a signed, fixed-length span of time represented
+        /// as a count of seconds and fractions of seconds at nanosecond
+        /// resolution. It is independent of any calendar and concepts like "day"
+        /// or "month". It is related to Timestamp in that the difference between
+        /// two Timestamp values is a Duration and it can be added or subtracted
+        /// from a Timestamp. Range is approximately +-10,000 years.
+        ///
+        /// # Examples
+        ///
+        /// Example 1: Compute Duration from two Timestamps in pseudo code.
+        ///
+        /// ```text
+        /// Timestamp start = ...;
+        /// Timestamp end = ...;
+        /// Duration duration = ...;
+        ///
+        /// duration.seconds = end.seconds - start.seconds;
+        /// duration.nanos = end.nanos - start.nanos;
+        ///
+        /// if (duration.seconds < 0 && duration.nanos > 0) {
+        ///   duration.seconds += 1;
+        ///   duration.nanos -= 1000000000;
+        /// } else if (duration.seconds > 0 && duration.nanos < 0) {
+        ///   duration.seconds -= 1;
+        ///   duration.nanos += 1000000000;
+        /// }
+        /// ```
+        ///
+        /// Example 2: Compute Timestamp from Timestamp + Duration in pseudo code.
+        ///
+        /// ```text
+        /// Timestamp start = ...;
+        /// Duration duration = ...;
+        /// Timestamp end = ...;
+        ///
+        /// end.seconds = start.seconds + duration.seconds;
+        /// end.nanos = start.nanos + duration.nanos;
+        ///
+        /// if (end.nanos < 0) {
+        ///   end.seconds -= 1;
+        ///   end.nanos += 1000000000;
+        /// } else if (end.nanos >= 1000000000) {
+        ///   end.seconds += 1;
+        ///   end.nanos -= 1000000000;
+        /// }
+        /// ```
+        ///
+        /// Example 3: Compute Duration from datetime.timedelta in Python.
+        ///
+        /// ```text
+        /// td = datetime.timedelta(days=3, minutes=10)
+        /// duration = Duration()
+        /// duration.FromTimedelta(td)
+        /// ```
+        ///
+        /// # JSON Mapping
+        ///
+        /// In JSON format, the Duration type is encoded as a string rather than an
+        /// object, where the string ends in the suffix "s" (indicating seconds) and
+        /// is preceded by the number of seconds, with nanoseconds expressed as
+        /// fractional seconds. For example, 3 seconds with 0 nanoseconds should be
+        /// encoded in JSON format as "3s", while 3 seconds and 1 nanosecond should
+        /// be expressed in JSON format as "3.000000001s", and 3 seconds and 1
+        /// microsecond should be expressed in JSON format as "3.000001s".
+        ///
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct Duration {
+            /// Signed seconds of the span of time. Must be from -315,576,000,000
+            /// to +315,576,000,000 inclusive. Note: these bounds are computed from:
+            /// 60 sec/min * 60 min/hr * 24 hr/day * 365.25 days/year * 10000 years
+            pub r#seconds: i64,
+            /// Signed fractions of a second at nanosecond resolution of the span
+            /// of time. Durations less than one second are represented with a 0
+            /// `seconds` field and a positive or negative `nanos` field. For durations
+            /// of one second or more, a non-zero value for the `nanos` field must be
+            /// of the same sign as the `seconds` field. Must be from -999,999,999
+            /// to +999,999,999 inclusive.
+            pub r#nanos: i32,
+        }
+        impl Duration {
+            /// Return a reference to `seconds`
+            #[inline]
+            pub fn r#seconds(&self) -> &i64 {
+                &self.r#seconds
+            }
+            /// Return a mutable reference to `seconds`
+            #[inline]
+            pub fn mut_seconds(&mut self) -> &mut i64 {
+                &mut self.r#seconds
+            }
+            /// Set the value of `seconds`
+            #[inline]
+            pub fn set_seconds(&mut self, value: i64) -> &mut Self {
+                self.r#seconds = value.into();
+                self
+            }
+            /// Builder method that sets the value of `seconds`. Useful for initializing the message.
+            #[inline]
+            pub fn init_seconds(mut self, value: i64) -> Self {
+                self.r#seconds = value.into();
+                self
+            }
+            /// Return a reference to `nanos`
+            #[inline]
+            pub fn r#nanos(&self) -> &i32 {
+                &self.r#nanos
+            }
+            /// Return a mutable reference to `nanos`
+            #[inline]
+            pub fn mut_nanos(&mut self) -> &mut i32 {
+                &mut self.r#nanos
+            }
+            /// Set the value of `nanos`
+            #[inline]
+            pub fn set_nanos(&mut self, value: i32) -> &mut Self {
+                self.r#nanos = value.into();
+                self
+            }
+            /// Builder method that sets the value of `nanos`. Useful for initializing the message.
+            #[inline]
+            pub fn init_nanos(mut self, value: i32) -> Self {
+                self.r#nanos = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for Duration {
+            const FULL_NAME: &'static str = "google.protobuf.Duration";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Duration";
+        }
+        impl ::micropb::MessageMerge for Duration {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#seconds;
+                    if *val_ref != 0 {
+                        self.r#seconds = *val_ref;
+                    }
+                }
+                {
+                    let val_ref = &other.r#nanos;
+                    if *val_ref != 0 {
+                        self.r#nanos = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Duration {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#seconds;
+                            {
+                                let val = decoder.decode_int64()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        2u32 => {
+                            let mut_ref = &mut self.r#nanos;
+                            {
+                                let val = decoder.decode_int32()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Duration {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Duration_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#seconds;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_int64(*val_ref as _)?;
+                    }
+                }
+                {
+                    let val_ref = &self.r#nanos;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(16u32)?;
+                        encoder.encode_int32(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#seconds;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int64(*val_ref as _);
+                    }
+                }
+                {
+                    let val_ref = &self.r#nanos;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int32(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Duration`
+        pub mod Duration_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// A generic empty message that you can re-use to avoid defining duplicated
+        /// empty messages in your APIs. A typical example is to use it as the request
+        /// or the response type of an API method. For instance:
+        ///
+        /// ```text
+        /// service Foo {
+        ///   rpc Bar(google.protobuf.Empty) returns (google.protobuf.Empty);
+        /// }
+        /// ```
+        ///
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct Empty {}
+        impl Empty {}
+        impl ::micropb::MessageName for Empty {
+            const FULL_NAME: &'static str = "google.protobuf.Empty";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Empty";
+        }
+        impl ::micropb::MessageMerge for Empty {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Empty {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Empty {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Empty_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Empty`
+        pub mod Empty_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// A Timestamp represents a point in time independent of any time zone or local
+        /// calendar, encoded as a count of seconds and fractions of seconds at
+        /// nanosecond resolution. The count is relative to an epoch at UTC midnight on
+        /// January 1, 1970, in the proleptic Gregorian calendar which extends the
+        /// Gregorian calendar backwards to year one.
+        ///
+        /// All minutes are 60 seconds long. Leap seconds are "smeared" so that no leap
+        /// second table is needed for interpretation, using a [24-hour linear
+        /// smear](https://developers.google.com/time/smear).
+        ///
+        /// The range is from 0001-01-01T00:00:00Z to 9999-12-31T23:59:59.999999999Z. By
+        /// restricting to that range, we ensure that we can convert to and from [RFC
+        /// 3339](https://www.ietf.org/rfc/rfc3339.txt) date strings.
+        ///
+        /// # Examples
+        ///
+        /// Example 1: Compute Timestamp from POSIX `time()`.
+        ///
+        /// ```text
+        /// Timestamp timestamp;
+        /// timestamp.set_seconds(time(NULL));
+        /// timestamp.set_nanos(0);
+        /// ```
+        ///
+        /// Example 2: Compute Timestamp from POSIX `gettimeofday()`.
+        ///
+        /// ```text
+        /// struct timeval tv;
+        /// gettimeofday(&tv, NULL);
+        ///
+        /// Timestamp timestamp;
+        /// timestamp.set_seconds(tv.tv_sec);
+        /// timestamp.set_nanos(tv.tv_usec * 1000);
+        /// ```
+        ///
+        /// Example 3: Compute Timestamp from Win32 `GetSystemTimeAsFileTime()`.
+        ///
+        /// ```text
+        /// FILETIME ft;
+        /// GetSystemTimeAsFileTime(&ft);
+        /// UINT64 ticks = (((UINT64)ft.dwHighDateTime) << 32) | ft.dwLowDateTime;
+        ///
+        /// // A Windows tick is 100 nanoseconds. Windows epoch 1601-01-01T00:00:00Z
+        /// // is 11644473600 seconds before Unix epoch 1970-01-01T00:00:00Z.
+        /// Timestamp timestamp;
+        /// timestamp.set_seconds((INT64) ((ticks / 10000000) - 11644473600LL));
+        /// timestamp.set_nanos((INT32) ((ticks % 10000000) * 100));
+        /// ```
+        ///
+        /// Example 4: Compute Timestamp from Java `System.currentTimeMillis()`.
+        ///
+        /// ```text
+        /// long millis = System.currentTimeMillis();
+        ///
+        /// Timestamp timestamp = Timestamp.newBuilder().setSeconds(millis / 1000)
+        ///     .setNanos((int) ((millis % 1000) * 1000000)).build();
+        /// ```
+        ///
+        /// Example 5: Compute Timestamp from Java `Instant.now()`.
+        ///
+        /// ```text
+        /// Instant now = Instant.now();
+        ///
+        /// Timestamp timestamp =
+        ///     Timestamp.newBuilder().setSeconds(now.getEpochSecond())
+        ///         .setNanos(now.getNano()).build();
+        /// ```
+        ///
+        /// Example 6: Compute Timestamp from current time in Python.
+        ///
+        /// ```text
+        /// timestamp = Timestamp()
+        /// timestamp.GetCurrentTime()
+        /// ```
+        ///
+        /// # JSON Mapping
+        ///
+        /// In JSON format, the Timestamp type is encoded as a string in the
+        /// [RFC 3339](https://www.ietf.org/rfc/rfc3339.txt) format. That is, the
+        /// format is "{year}-{month}-{day}T{hour}:{min}:{sec}[.{frac_sec}]Z"
+        /// where {year} is always expressed using four digits while {month}, {day},
+        /// {hour}, {min}, and {sec} are zero-padded to two digits each. The fractional
+        /// seconds, which can go up to 9 digits (i.e. up to 1 nanosecond resolution),
+        /// are optional. The "Z" suffix indicates the timezone ("UTC"); the timezone
+        /// is required. A ProtoJSON serializer should always use UTC (as indicated by
+        /// "Z") when printing the Timestamp type and a ProtoJSON parser should be
+        /// able to accept both UTC and other timezones (as indicated by an offset).
+        ///
+        /// For example, "2017-01-15T01:30:15.01Z" encodes 15.01 seconds past
+        /// 01:30 UTC on January 15, 2017.
+        ///
+        /// In JavaScript, one can convert a Date object to this format using the
+        /// standard
+        /// [toISOString()](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/toISOString)
+        /// method. In Python, a standard `datetime.datetime` object can be converted
+        /// to this format using
+        /// [`strftime`](https://docs.python.org/2/library/time.html#time.strftime) with
+        /// the time format spec '%Y-%m-%dT%H:%M:%S.%fZ'. Likewise, in Java, one can use
+        /// the Joda Time's [`ISODateTimeFormat.dateTime()`](
+        /// http://joda-time.sourceforge.net/apidocs/org/joda/time/format/ISODateTimeFormat.html#dateTime()
+        /// ) to obtain a formatter capable of generating timestamps in this format.
+        ///
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct Timestamp {
+            /// Represents seconds of UTC time since Unix epoch 1970-01-01T00:00:00Z. Must
+            /// be between -62135596800 and 253402300799 inclusive (which corresponds to
+            /// 0001-01-01T00:00:00Z to 9999-12-31T23:59:59Z).
+            pub r#seconds: i64,
+            /// Non-negative fractions of a second at nanosecond resolution. This field is
+            /// the nanosecond portion of the duration, not an alternative to seconds.
+            /// Negative second values with fractions must still have non-negative nanos
+            /// values that count forward in time. Must be between 0 and 999,999,999
+            /// inclusive.
+            pub r#nanos: i32,
+        }
+        impl Timestamp {
+            /// Return a reference to `seconds`
+            #[inline]
+            pub fn r#seconds(&self) -> &i64 {
+                &self.r#seconds
+            }
+            /// Return a mutable reference to `seconds`
+            #[inline]
+            pub fn mut_seconds(&mut self) -> &mut i64 {
+                &mut self.r#seconds
+            }
+            /// Set the value of `seconds`
+            #[inline]
+            pub fn set_seconds(&mut self, value: i64) -> &mut Self {
+                self.r#seconds = value.into();
+                self
+            }
+            /// Builder method that sets the value of `seconds`. Useful for initializing the message.
+            #[inline]
+            pub fn init_seconds(mut self, value: i64) -> Self {
+                self.r#seconds = value.into();
+                self
+            }
+            /// Return a reference to `nanos`
+            #[inline]
+            pub fn r#nanos(&self) -> &i32 {
+                &self.r#nanos
+            }
+            /// Return a mutable reference to `nanos`
+            #[inline]
+            pub fn mut_nanos(&mut self) -> &mut i32 {
+                &mut self.r#nanos
+            }
+            /// Set the value of `nanos`
+            #[inline]
+            pub fn set_nanos(&mut self, value: i32) -> &mut Self {
+                self.r#nanos = value.into();
+                self
+            }
+            /// Builder method that sets the value of `nanos`. Useful for initializing the message.
+            #[inline]
+            pub fn init_nanos(mut self, value: i32) -> Self {
+                self.r#nanos = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for Timestamp {
+            const FULL_NAME: &'static str = "google.protobuf.Timestamp";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Timestamp";
+        }
+        impl ::micropb::MessageMerge for Timestamp {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#seconds;
+                    if *val_ref != 0 {
+                        self.r#seconds = *val_ref;
+                    }
+                }
+                {
+                    let val_ref = &other.r#nanos;
+                    if *val_ref != 0 {
+                        self.r#nanos = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Timestamp {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#seconds;
+                            {
+                                let val = decoder.decode_int64()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        2u32 => {
+                            let mut_ref = &mut self.r#nanos;
+                            {
+                                let val = decoder.decode_int32()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Timestamp {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Timestamp_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#seconds;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_int64(*val_ref as _)?;
+                    }
+                }
+                {
+                    let val_ref = &self.r#nanos;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(16u32)?;
+                        encoder.encode_int32(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#seconds;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int64(*val_ref as _);
+                    }
+                }
+                {
+                    let val_ref = &self.r#nanos;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int32(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Timestamp`
+        pub mod Timestamp_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `double`.
+        ///
+        /// The JSON representation for `DoubleValue` is JSON number.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct DoubleValue {
+            /// The double value.
+            pub r#value: f64,
+        }
+        impl DoubleValue {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &f64 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut f64 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: f64) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: f64) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for DoubleValue {
+            const FULL_NAME: &'static str = "google.protobuf.DoubleValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.DoubleValue";
+        }
+        impl ::micropb::MessageMerge for DoubleValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0.0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for DoubleValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_double()?;
+                                let val_ref = &val;
+                                if *val_ref != 0.0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for DoubleValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(8usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = DoubleValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0.0 {
+                        encoder.encode_varint32(9u32)?;
+                        encoder.encode_double(*val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0.0 {
+                        cache._size += 1usize + 8;
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `DoubleValue`
+        pub mod DoubleValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `float`.
+        ///
+        /// The JSON representation for `FloatValue` is JSON number.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct FloatValue {
+            /// The float value.
+            pub r#value: f32,
+        }
+        impl FloatValue {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &f32 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut f32 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: f32) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: f32) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for FloatValue {
+            const FULL_NAME: &'static str = "google.protobuf.FloatValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.FloatValue";
+        }
+        impl ::micropb::MessageMerge for FloatValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0.0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for FloatValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_float()?;
+                                let val_ref = &val;
+                                if *val_ref != 0.0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for FloatValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(4usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = FloatValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0.0 {
+                        encoder.encode_varint32(13u32)?;
+                        encoder.encode_float(*val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0.0 {
+                        cache._size += 1usize + 4;
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `FloatValue`
+        pub mod FloatValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `int64`.
+        ///
+        /// The JSON representation for `Int64Value` is JSON string.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct Int64Value {
+            /// The int64 value.
+            pub r#value: i64,
+        }
+        impl Int64Value {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &i64 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut i64 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: i64) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: i64) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for Int64Value {
+            const FULL_NAME: &'static str = "google.protobuf.Int64Value";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Int64Value";
+        }
+        impl ::micropb::MessageMerge for Int64Value {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Int64Value {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_int64()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Int64Value {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Int64Value_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_int64(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int64(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Int64Value`
+        pub mod Int64Value_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `uint64`.
+        ///
+        /// The JSON representation for `UInt64Value` is JSON string.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct UInt64Value {
+            /// The uint64 value.
+            pub r#value: u64,
+        }
+        impl UInt64Value {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &u64 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut u64 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: u64) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: u64) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for UInt64Value {
+            const FULL_NAME: &'static str = "google.protobuf.UInt64Value";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.UInt64Value";
+        }
+        impl ::micropb::MessageMerge for UInt64Value {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for UInt64Value {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_varint64()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for UInt64Value {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = UInt64Value_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_varint64(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_varint64(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `UInt64Value`
+        pub mod UInt64Value_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `int32`.
+        ///
+        /// The JSON representation for `Int32Value` is JSON number.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct Int32Value {
+            /// The int32 value.
+            pub r#value: i32,
+        }
+        impl Int32Value {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &i32 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut i32 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: i32) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: i32) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for Int32Value {
+            const FULL_NAME: &'static str = "google.protobuf.Int32Value";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.Int32Value";
+        }
+        impl ::micropb::MessageMerge for Int32Value {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for Int32Value {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_int32()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for Int32Value {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(10usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = Int32Value_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_int32(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_int32(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `Int32Value`
+        pub mod Int32Value_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `uint32`.
+        ///
+        /// The JSON representation for `UInt32Value` is JSON number.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct UInt32Value {
+            /// The uint32 value.
+            pub r#value: u32,
+        }
+        impl UInt32Value {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &u32 {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut u32 {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: u32) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: u32) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for UInt32Value {
+            const FULL_NAME: &'static str = "google.protobuf.UInt32Value";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.UInt32Value";
+        }
+        impl ::micropb::MessageMerge for UInt32Value {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref != 0 {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for UInt32Value {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_varint32()?;
+                                let val_ref = &val;
+                                if *val_ref != 0 {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for UInt32Value {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(5usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = UInt32Value_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_varint32(*val_ref as _)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref != 0 {
+                        cache._size
+                            += 1usize + ::micropb::size::sizeof_varint32(*val_ref as _);
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `UInt32Value`
+        pub mod UInt32Value_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+        /// Wrapper message for `bool`.
+        ///
+        /// The JSON representation for `BoolValue` is JSON `true` and `false`.
+        ///
+        /// Not recommended for use in new APIs, but still useful for legacy APIs and
+        /// has no plan to be removed.
+        #[derive(Debug, Default, PartialEq, Clone, Copy)]
+        pub struct BoolValue {
+            /// The bool value.
+            pub r#value: bool,
+        }
+        impl BoolValue {
+            /// Return a reference to `value`
+            #[inline]
+            pub fn r#value(&self) -> &bool {
+                &self.r#value
+            }
+            /// Return a mutable reference to `value`
+            #[inline]
+            pub fn mut_value(&mut self) -> &mut bool {
+                &mut self.r#value
+            }
+            /// Set the value of `value`
+            #[inline]
+            pub fn set_value(&mut self, value: bool) -> &mut Self {
+                self.r#value = value.into();
+                self
+            }
+            /// Builder method that sets the value of `value`. Useful for initializing the message.
+            #[inline]
+            pub fn init_value(mut self, value: bool) -> Self {
+                self.r#value = value.into();
+                self
+            }
+        }
+        impl ::micropb::MessageName for BoolValue {
+            const FULL_NAME: &'static str = "google.protobuf.BoolValue";
+            const TYPE_URL: &'static str = "type.googleapis.com/google.protobuf.BoolValue";
+        }
+        impl ::micropb::MessageMerge for BoolValue {
+            fn merge_from(
+                &mut self,
+                other: &Self,
+            ) -> Result<(), ::micropb::CapacityExceeded> {
+                use ::micropb::{PbVec, PbMap};
+                {
+                    let val_ref = &other.r#value;
+                    if *val_ref {
+                        self.r#value = *val_ref;
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageDecode for BoolValue {
+            fn decode<IMPL_MICROPB_READ: ::micropb::PbRead>(
+                &mut self,
+                decoder: &mut ::micropb::PbDecoder<IMPL_MICROPB_READ>,
+                len: usize,
+            ) -> Result<(), ::micropb::DecodeError<IMPL_MICROPB_READ::Error>> {
+                use ::micropb::{PbBytes, PbString, PbVec, PbMap, FieldDecode};
+                let before = decoder.bytes_read();
+                while decoder.bytes_read() - before < len {
+                    let tag = decoder.decode_tag()?;
+                    if tag.wire_type() == ::micropb::WIRE_TYPE_EGROUP {
+                        return decoder.end_group(tag);
+                    }
+                    match tag.field_num() {
+                        0 => return Err(::micropb::DecodeError::ZeroField),
+                        1u32 => {
+                            let mut_ref = &mut self.r#value;
+                            {
+                                let val = decoder.decode_bool()?;
+                                let val_ref = &val;
+                                if *val_ref {
+                                    *mut_ref = val as _;
+                                }
+                            };
+                        }
+                        _ => {
+                            decoder.skip_wire_value(tag.wire_type())?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl ::micropb::MessageEncodeCached for BoolValue {
+            const MAX_SIZE: ::core::result::Result<usize, &'static str> = 'msg: {
+                let mut max_size = 0;
+                match ::micropb::const_map!(
+                    ::core::result::Result::Ok(1usize), | size | size + 1usize
+                ) {
+                    ::core::result::Result::Ok(size) => {
+                        max_size += size;
+                    }
+                    ::core::result::Result::Err(err) => {
+                        break 'msg (::core::result::Result::<usize, _>::Err(err));
+                    }
+                }
+                ::core::result::Result::Ok(max_size)
+            };
+            type Cache = BoolValue_::_Cache;
+            fn encode_cached<IMPL_MICROPB_WRITE: ::micropb::PbWrite>(
+                &self,
+                encoder: &mut ::micropb::PbEncoder<IMPL_MICROPB_WRITE>,
+                cache: &Self::Cache,
+            ) -> Result<(), IMPL_MICROPB_WRITE::Error> {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref {
+                        encoder.encode_varint32(8u32)?;
+                        encoder.encode_bool(*val_ref)?;
+                    }
+                }
+                Ok(())
+            }
+            fn populate_cache(&self) -> Self::Cache {
+                use ::micropb::{PbMap, PbVec, FieldEncode, MessageEncode};
+                let mut cache = Self::Cache::default();
+                {
+                    let val_ref = &self.r#value;
+                    if *val_ref {
+                        cache._size += 1usize + 1;
+                    }
+                }
+                cache
+            }
+            fn compute_size_cached(&self, cache: &Self::Cache) -> usize {
+                cache._size
+            }
+        }
+        /// Inner types for `BoolValue`
+        pub mod BoolValue_ {
+            #[derive(Default)]
+            pub struct _Cache {
+                pub _size: usize,
+            }
+        }
+    }
+}
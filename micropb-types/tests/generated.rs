@@ -0,0 +1,79 @@
+//! Checks that the pre-generated well-known types are in sync with `micropb-gen`.
+//!
+//! Run with `MICROPB_TYPES_BLESS=1` to regenerate the checked-in files.
+
+use std::path::Path;
+
+use micropb_gen::{Config, Generator};
+
+const CORE_PROTOS: &[&str] = &[
+    "google/protobuf/duration.proto",
+    "google/protobuf/empty.proto",
+    "google/protobuf/timestamp.proto",
+    "google/protobuf/wrappers.proto",
+];
+
+const ALLOC_PROTOS: &[&str] = &[
+    "google/protobuf/any.proto",
+    "google/protobuf/field_mask.proto",
+    "google/protobuf/struct.proto",
+    "google/protobuf/wrappers.proto",
+];
+
+// Wrappers that contain strings or bytes can only be generated with `alloc`
+const ALLOC_WRAPPERS: &[&str] = &[
+    ".google.protobuf.StringValue",
+    ".google.protobuf.BytesValue",
+];
+
+fn generator() -> Generator {
+    let mut gen = Generator::with_warning_callback(|msg| panic!("{msg}"));
+    gen.suffixed_package_names(false)
+        .encode_cache(true)
+        .add_protoc_arg(concat!("-I", env!("CARGO_MANIFEST_DIR"), "/proto"));
+    gen
+}
+
+fn check(gen: Generator, protos: &[&str], file: &str) {
+    let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join(file);
+    let actual = Path::new(env!("CARGO_TARGET_TMPDIR")).join(file);
+    gen.compile_protos(protos, &actual).unwrap();
+
+    let actual = std::fs::read_to_string(actual).unwrap();
+    if std::env::var_os("MICROPB_TYPES_BLESS").is_some() {
+        std::fs::write(&expected, actual).unwrap();
+    } else {
+        let expected = std::fs::read_to_string(&expected).unwrap_or_default();
+        assert!(
+            expected == actual,
+            "{file} is out of date, rerun with MICROPB_TYPES_BLESS=1 to regenerate it"
+        );
+    }
+}
+
+#[test]
+fn core_types() {
+    let mut gen = generator();
+    gen.configure_many(ALLOC_WRAPPERS, Config::new().skip(true));
+    check(gen, CORE_PROTOS, "wkt_core.rs");
+}
+
+#[test]
+fn alloc_types() {
+    let mut gen = generator();
+    gen.use_container_alloc()
+        .configure(".google.protobuf", Config::new().skip(true))
+        .configure_many(ALLOC_WRAPPERS, Config::new().skip(false))
+        .configure_many(
+            &[
+                ".google.protobuf.Any",
+                ".google.protobuf.FieldMask",
+                ".google.protobuf.Struct",
+                ".google.protobuf.Value",
+                ".google.protobuf.ListValue",
+                ".google.protobuf.NullValue",
+            ],
+            Config::new().skip(false),
+        );
+    check(gen, ALLOC_PROTOS, "wkt_alloc.rs");
+}
@@ -0,0 +1,241 @@
+//! Packing and unpacking messages into `google.protobuf.Any`.
+//!
+//! `micropb-gen` implements [`PbAny`] for the generated `google.protobuf.Any` message, regardless
+//! of the containers used for its `type_url` and `value` fields. Any message implementing
+//! [`MessageName`] can then be packed into and unpacked from it. `micropb-gen` implements
+//! [`MessageName`] for all generated messages.
+//!
+//! ```no_run
+//! use micropb::any::PbAny;
+//! # use micropb::{MessageDecode, MessageEncode, MessageName, PbDecoder, PbEncoder, PbRead, PbWrite, DecodeError, PbString};
+//! # #[derive(Default)]
+//! # struct Any { type_url: String, value: Vec<u8> }
+//! # impl PbAny for Any {
+//! #   type TypeUrl = String;
+//! #   type Value = Vec<u8>;
+//! #   fn any_parts(&self) -> (&String, &Vec<u8>) { (&self.type_url, &self.value) }
+//! #   fn any_parts_mut(&mut self) -> (&mut String, &mut Vec<u8>) { (&mut self.type_url, &mut self.value) }
+//! # }
+//! # #[derive(Default)]
+//! # struct Command;
+//! # impl MessageName for Command {
+//! #   const FULL_NAME: &str = "app.Command";
+//! #   const TYPE_URL: &str = "type.googleapis.com/app.Command";
+//! # }
+//! # impl MessageEncode for Command {
+//! #   const MAX_SIZE: Result<usize, &str> = Ok(0);
+//! #   fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> { todo!() }
+//! #   fn compute_size(&self) -> usize { 0 }
+//! # }
+//! # impl MessageDecode for Command {
+//! #   fn decode<R: PbRead>(&mut self, decoder: &mut PbDecoder<R>, len: usize) -> Result<(), DecodeError<R::Error>> { todo!() }
+//! # }
+//! let any = Any::pack(&Command::default())?;
+//! assert_eq!(any.type_url, "type.googleapis.com/app.Command");
+//! assert!(any.is::<Command>());
+//! let cmd: Option<Command> = any.unpack()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "encode")]
+use crate::{misc::maybe_uninit_zero_bytes, BufferTooSmall, MessageEncode};
+#[cfg(feature = "decode")]
+use crate::{DecodeError, MessageDecode};
+use crate::{MessageName, PbBytes, PbString};
+
+/// Protobuf `google.protobuf.Any` message, which holds an encoded message of any type along with
+/// its type URL.
+///
+/// Implementations are auto-generated by `micropb-gen` for `google.protobuf.Any`.
+pub trait PbAny {
+    /// Container of the `type_url` field
+    type TypeUrl: PbString + AsRef<str>;
+    /// Container of the `value` field
+    type Value: PbBytes + AsRef<[u8]>;
+
+    /// Reference the `type_url` and `value` fields
+    fn any_parts(&self) -> (&Self::TypeUrl, &Self::Value);
+
+    /// Mutably reference the `type_url` and `value` fields
+    fn any_parts_mut(&mut self) -> (&mut Self::TypeUrl, &mut Self::Value);
+
+    /// Fully-qualified name of the packed message type, which is the part of the type URL after
+    /// the last `/`.
+    fn type_name(&self) -> &str {
+        let type_url = self.any_parts().0.as_ref();
+        match type_url.rfind('/') {
+            Some(i) => &type_url[i + 1..],
+            None => type_url,
+        }
+    }
+
+    /// Check if the packed message is of type `M`
+    fn is<M: MessageName>(&self) -> bool {
+        self.type_name() == M::FULL_NAME
+    }
+
+    /// Pack a message into a new `Any`.
+    ///
+    /// Returns an error if the type URL or the encoded message don't fit in the containers.
+    #[cfg(feature = "encode")]
+    fn pack<M: MessageEncode + MessageName>(msg: &M) -> Result<Self, BufferTooSmall>
+    where
+        Self: Default,
+    {
+        let mut any = Self::default();
+        any.pack_from(msg)?;
+        Ok(any)
+    }
+
+    /// Pack a message into `self`, replacing the existing contents.
+    ///
+    /// Returns an error if the type URL or the encoded message don't fit in the containers, in
+    /// which case the contents of `self` are unspecified.
+    #[cfg(feature = "encode")]
+    fn pack_from<M: MessageEncode + MessageName>(&mut self, msg: &M) -> Result<(), BufferTooSmall> {
+        let (type_url, value) = self.any_parts_mut();
+        fill_container(type_url, M::TYPE_URL.len(), |buf| {
+            buf.copy_from_slice(M::TYPE_URL.as_bytes());
+            Ok(())
+        })?;
+        fill_container(value, msg.compute_size(), |buf| {
+            msg.encode_to_slice(buf).map(|_| ())
+        })
+    }
+
+    /// Unpack the message if it's of type `M`, otherwise return `None`.
+    #[cfg(feature = "decode")]
+    fn unpack<M: MessageDecode + MessageName + Default>(
+        &self,
+    ) -> Result<Option<M>, DecodeError<core::convert::Infallible>> {
+        let mut msg = M::default();
+        Ok(self.unpack_into(&mut msg)?.then_some(msg))
+    }
+
+    /// Decode the message and merge it into `msg` if it's of type `M`. Returns whether the types
+    /// matched.
+    #[cfg(feature = "decode")]
+    fn unpack_into<M: MessageDecode + MessageName>(
+        &self,
+        msg: &mut M,
+    ) -> Result<bool, DecodeError<core::convert::Infallible>> {
+        if !self.is::<M>() {
+            return Ok(false);
+        }
+        msg.decode_from_bytes(self.any_parts().1.as_ref())?;
+        Ok(true)
+    }
+}
+
+/// Replace the contents of a string or bytes container with `len` bytes written by `write`
+#[cfg(feature = "encode")]
+fn fill_container<S: PbString>(
+    container: &mut S,
+    len: usize,
+    write: impl FnOnce(&mut [u8]) -> Result<(), BufferTooSmall>,
+) -> Result<(), BufferTooSmall> {
+    container.pb_clear();
+    container.pb_reserve(len);
+    let spare_cap = container
+        .pb_spare_cap()
+        .get_mut(..len)
+        .ok_or(BufferTooSmall)?;
+    write(maybe_uninit_zero_bytes(spare_cap))?;
+    // SAFETY: the first `len` bytes of the spare capacity have been initialized. For strings, the
+    // only bytes written are from a `str`, so they're valid UTF-8.
+    unsafe { container.pb_set_len(len) };
+    Ok(())
+}
+
+#[cfg(all(test, feature = "encode", feature = "decode"))]
+mod tests {
+    use arrayvec::{ArrayString, ArrayVec};
+
+    use super::*;
+    use crate::{PbDecoder, PbEncoder, PbRead, PbWrite};
+
+    #[derive(Default)]
+    struct Any<S, B> {
+        type_url: S,
+        value: B,
+    }
+
+    impl<S: PbString + AsRef<str>, B: PbBytes + AsRef<[u8]>> PbAny for Any<S, B> {
+        type TypeUrl = S;
+        type Value = B;
+
+        fn any_parts(&self) -> (&S, &B) {
+            (&self.type_url, &self.value)
+        }
+
+        fn any_parts_mut(&mut self) -> (&mut S, &mut B) {
+            (&mut self.type_url, &mut self.value)
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Num(u32);
+
+    impl MessageName for Num {
+        const FULL_NAME: &'static str = "test.Num";
+        const TYPE_URL: &'static str = "type.googleapis.com/test.Num";
+    }
+
+    impl MessageEncode for Num {
+        const MAX_SIZE: Result<usize, &'static str> = Ok(6);
+
+        fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> {
+            encoder.encode_varint32(8)?;
+            encoder.encode_varint32(self.0)
+        }
+
+        fn compute_size(&self) -> usize {
+            1 + crate::size::sizeof_varint32(self.0)
+        }
+    }
+
+    impl MessageDecode for Num {
+        fn decode<R: PbRead>(
+            &mut self,
+            decoder: &mut PbDecoder<R>,
+            len: usize,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let before = decoder.bytes_read();
+            while decoder.bytes_read() - before < len {
+                decoder.decode_tag()?;
+                self.0 = decoder.decode_varint32()?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pack_unpack() {
+        let any: Any<ArrayString<32>, ArrayVec<u8, 4>> = Any::pack(&Num(150)).unwrap();
+        assert_eq!(any.type_url.as_str(), Num::TYPE_URL);
+        assert_eq!(any.value.as_slice(), &[0x08, 0x96, 0x01]);
+        assert_eq!(any.type_name(), "test.Num");
+        assert!(any.is::<Num>());
+        assert_eq!(any.unpack(), Ok(Some(Num(150))));
+
+        // Type URL prefixes are ignored when unpacking
+        let mut any = Any::<String, Vec<u8>>::pack(&Num(1)).unwrap();
+        any.type_url = "example.com/types/test.Num".into();
+        assert_eq!(any.unpack(), Ok(Some(Num(1))));
+        any.type_url = "test.Num2".into();
+        assert!(!any.is::<Num>());
+        assert_eq!(any.unpack::<Num>(), Ok(None));
+    }
+
+    #[test]
+    fn capacity() {
+        assert_eq!(
+            Any::<ArrayString<16>, ArrayVec<u8, 4>>::pack(&Num(1)).err(),
+            Some(BufferTooSmall)
+        );
+        assert_eq!(
+            Any::<ArrayString<32>, ArrayVec<u8, 2>>::pack(&Num(150)).err(),
+            Some(BufferTooSmall)
+        );
+    }
+}
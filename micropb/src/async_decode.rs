@@ -0,0 +1,562 @@
+use core::{convert::Infallible, mem::MaybeUninit, ops::Deref, str::from_utf8};
+
+#[cfg(feature = "detailed-errors")]
+use crate::error_context::{DetailedDecodeError, ErrorTrace};
+use crate::{
+    container::{PbBytes, PbString, PbVec},
+    decode::{decoder_methods, MapEntryReader},
+    field::AsyncFieldDecode,
+    misc::{
+        maybe_uninit_slice_assume_init_ref, maybe_uninit_write_slice,
+        maybe_ununit_array_assume_init, varint32_bytes, varint32_from_bytes, varint64_bytes,
+    },
+    AsyncMessageDecode, DecodeError, FixedWidth, MapKey, Presence, Tag, WIRE_TYPE_EGROUP,
+    WIRE_TYPE_I32, WIRE_TYPE_I64, WIRE_TYPE_LEN, WIRE_TYPE_SGROUP, WIRE_TYPE_VARINT,
+};
+
+/// Async version of [`PbRead`](crate::PbRead).
+///
+/// Like [`PbRead`](crate::PbRead), this trait assumes that the reader uses an underlying buffer.
+/// The difference is that filling the buffer is asynchronous, so [`AsyncPbDecoder`] can yield to
+/// the executor while waiting for more bytes to arrive, such as from a UART or USB endpoint.
+#[allow(async_fn_in_trait)]
+pub trait AsyncPbRead {
+    /// I/O error returned on read failure.
+    type Error;
+
+    /// Returns the internal buffer, waiting for it to be filled with more data if necessary.
+    ///
+    /// This call does not consume the underlying buffer, so calling it consecutively may yield the
+    /// same contents. As such, this call must be followed by a [`pb_advance`](Self::pb_advance)
+    /// call with the number of bytes that are "consumed" from the returned buffer.
+    ///
+    /// Empty buffer is returned if and only if the underlying reader has reached EOF.
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error>;
+
+    /// Consumes `bytes` from the underlying buffer.
+    ///
+    /// Same as [`PbRead::pb_advance`](crate::PbRead::pb_advance). This function doesn't perform
+    /// I/O, so it's synchronous and infallible.
+    fn pb_advance(&mut self, bytes: usize);
+
+    /// Try to read exactly the number of bytes needed to fill `buf`.
+    ///
+    /// Returns the number of bytes read, which will be at most the size of `buf`. If the return is
+    /// less than `buf`, then the reader reached EOF before filling `buf`. This function will
+    /// advance the reader by the amount of bytes read, so no need to call
+    /// [`pb_advance`](Self::pb_advance).
+    async fn pb_read_exact(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<usize, Self::Error> {
+        let mut pos = 0;
+        loop {
+            let remaining = buf.get_mut(pos..).unwrap_or(&mut []);
+            if remaining.is_empty() {
+                break;
+            }
+            let chunk = &self.pb_read_chunk().await?;
+            if chunk.is_empty() {
+                return Ok(pos);
+            }
+            let n = maybe_uninit_write_slice(remaining, chunk);
+            self.pb_advance(n);
+            pos += n;
+        }
+
+        debug_assert_eq!(pos, buf.len());
+        Ok(pos)
+    }
+}
+
+impl<T: AsyncPbRead> AsyncPbRead for &mut T {
+    type Error = T::Error;
+
+    #[inline]
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        (*self).pb_read_chunk().await
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        (*self).pb_advance(bytes)
+    }
+
+    #[inline]
+    async fn pb_read_exact(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<usize, Self::Error> {
+        (*self).pb_read_exact(buf).await
+    }
+}
+
+impl AsyncPbRead for &[u8] {
+    type Error = Infallible;
+
+    #[inline]
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(*self)
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        *self = self.get(bytes..).unwrap_or(&[])
+    }
+
+    #[inline]
+    async fn pb_read_exact(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<usize, Self::Error> {
+        let n = maybe_uninit_write_slice(buf, self);
+        self.pb_advance(n);
+        Ok(n)
+    }
+}
+
+impl AsyncPbRead for MapEntryReader<'_> {
+    type Error = Infallible;
+
+    #[inline]
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.chunk())
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        self.advance(bytes)
+    }
+}
+
+#[cfg(feature = "embedded-io-async-0-6")]
+impl<R: embedded_io_async_0_6::BufRead> AsyncPbRead for crate::EmbeddedIoReader<R> {
+    type Error = R::Error;
+
+    #[inline]
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        self.0.fill_buf().await
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        self.0.consume(bytes)
+    }
+}
+
+#[derive(Debug)]
+/// Async version of [`PbDecoder`](crate::PbDecoder).
+///
+/// Reads bytes from an underlying [`AsyncPbRead`] instance, awaiting the reader whenever it runs
+/// out of buffered bytes. This allows a message to be decoded while its bytes are still arriving,
+/// without first buffering the whole message.
+///
+/// The decoding methods have the same semantics as those of [`PbDecoder`](crate::PbDecoder),
+/// except that callbacks, such as the element decoder of
+/// [`decode_packed`](Self::decode_packed), are async closures.
+///
+/// # Example
+///
+/// Decoding a Protobuf message:
+/// ```no_run
+/// use micropb::{AsyncPbRead, AsyncPbDecoder, AsyncMessageDecode, DecodeError};
+///
+/// # #[derive(Default)]
+/// # struct ProtoMessage;
+/// # impl micropb::AsyncMessageDecode for ProtoMessage {
+/// #   async fn decode_async<R: AsyncPbRead>(&mut self, decoder: &mut AsyncPbDecoder<R>, len: usize) -> Result<(), micropb::DecodeError<R::Error>> { todo!() }
+/// # }
+///
+/// # async fn example() -> Result<(), DecodeError<core::convert::Infallible>> {
+/// let data = [0x08, 0x96, 0x01];
+/// // Slices implement `AsyncPbRead` out of the box
+/// let mut decoder = AsyncPbDecoder::new(data.as_slice());
+///
+/// let mut message = ProtoMessage::default();
+/// message.decode_async(&mut decoder, data.len()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncPbDecoder<R: AsyncPbRead> {
+    reader: R,
+    idx: usize,
+    /// Same as [`PbDecoder::ignore_repeated_cap_err`](crate::PbDecoder::ignore_repeated_cap_err).
+    pub ignore_repeated_cap_err: bool,
+    /// Same as [`PbDecoder::ignore_wrong_len`](crate::PbDecoder::ignore_wrong_len).
+    pub ignore_wrong_len: bool,
+    /// Same as [`PbDecoder::max_depth`](crate::PbDecoder::max_depth).
+    pub max_depth: usize,
+    /// Same as [`PbDecoder::max_field_len`](crate::PbDecoder::max_field_len).
+    pub max_field_len: Option<usize>,
+    /// Same as [`PbDecoder::alloc_budget`](crate::PbDecoder::alloc_budget).
+    pub alloc_budget: Option<usize>,
+    depth: usize,
+    group: Option<u32>,
+    clean_eof: bool,
+    #[cfg(feature = "detailed-errors")]
+    trace: ErrorTrace,
+}
+
+impl<R: AsyncPbRead> AsyncPbDecoder<R> {
+    #[inline]
+    /// Construct a new decoder from an [`AsyncPbRead`].
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            idx: 0,
+            ignore_repeated_cap_err: false,
+            ignore_wrong_len: false,
+            max_depth: crate::DEFAULT_MAX_DEPTH,
+            max_field_len: None,
+            alloc_budget: None,
+            depth: 0,
+            group: None,
+            clean_eof: false,
+            #[cfg(feature = "detailed-errors")]
+            trace: ErrorTrace::default(),
+        }
+    }
+
+    decoder_methods!(
+        AsyncPbDecoder,
+        [async],
+        [await],
+        AsyncFn,
+        AsyncFnMut,
+        AsyncFnOnce,
+        AsyncFieldDecode::decode_field_async,
+        AsyncMessageDecode::{decode_async, decode_len_delimited_async},
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use arrayvec::{ArrayString, ArrayVec};
+
+    use super::*;
+
+    /// Reader that returns `Pending` before every chunk, emulating bytes arriving over time.
+    /// Each chunk is at most 2 bytes.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        ready: bool,
+    }
+
+    impl<'a> Trickle<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Self { data, ready: false }
+        }
+    }
+
+    impl AsyncPbRead for Trickle<'_> {
+        type Error = Infallible;
+
+        async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+            core::future::poll_fn(|cx| {
+                if self.ready {
+                    Poll::Ready(())
+                } else {
+                    self.ready = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+            Ok(&self.data[..self.data.len().min(2)])
+        }
+
+        fn pb_advance(&mut self, bytes: usize) {
+            self.ready = false;
+            self.data.pb_advance(bytes);
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    macro_rules! assert_decode {
+        (@testcase $expected:expr, $reader:expr, $($op:tt)+) => {
+            let mut decoder = AsyncPbDecoder::new($reader);
+            let res = block_on(decoder.$($op)+);
+            assert_eq!($expected, res);
+        };
+
+        ($expected:expr, $arr:expr, $($op:tt)+) => {
+            assert_decode!(@testcase $expected, $arr.as_slice(), $($op)+);
+            assert_decode!(@testcase $expected, Trickle::new($arr.as_slice()), $($op)+);
+        };
+    }
+
+    #[test]
+    fn varint() {
+        assert_decode!(Ok(150), [0x96, 0x01], decode_varint32());
+        assert_decode!(
+            Ok(u32::MAX),
+            [0xFF, 0xFF, 0xFF, 0xFF, 0x0F],
+            decode_varint32()
+        );
+        assert_decode!(Err(DecodeError::UnexpectedEof), [0x80], decode_varint32());
+        assert_decode!(
+            Ok(u64::MAX),
+            [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+            decode_varint64()
+        );
+        assert_decode!(
+            Err(DecodeError::VarIntLimit),
+            [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01],
+            decode_varint64()
+        );
+        assert_decode!(Ok(-1), [1], decode_sint32());
+        assert_decode!(Ok(true), [0x01], decode_bool());
+    }
+
+    #[test]
+    fn fixed() {
+        assert_decode!(Ok(0x01020304), [0x04, 0x03, 0x02, 0x01], decode_fixed32());
+        assert_decode!(
+            Ok(-2),
+            [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            decode_sfixed64()
+        );
+        assert_decode!(
+            Ok(-2),
+            [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            decode_sfixed64_as_32()
+        );
+        assert_decode!(Ok(1.0), [0x00, 0x00, 0x80, 0x3F], decode_float());
+        assert_decode!(
+            Err(DecodeError::UnexpectedEof),
+            [0x00, 0x00, 0x80],
+            decode_float()
+        );
+    }
+
+    #[test]
+    fn string_bytes() {
+        let mut string = ArrayString::<5>::new();
+        assert_decode!(
+            Ok(()),
+            [0x03, b'a', b'b', b'c'],
+            decode_string(&mut string, Presence::Explicit)
+        );
+        assert_eq!(string.as_str(), "abc");
+        assert_decode!(
+            Err(DecodeError::Capacity),
+            [0x06, b'a', b'b', b'c', b'd', b'e', b'f'],
+            decode_string(&mut string, Presence::Explicit)
+        );
+        assert_decode!(
+            Err(DecodeError::Utf8),
+            [0x01, 0xFF],
+            decode_string(&mut string, Presence::Explicit)
+        );
+
+        let mut bytes = ArrayVec::<u8, 5>::new();
+        assert_decode!(
+            Ok(()),
+            [0x02, 0x01, 0x02],
+            decode_bytes(&mut bytes, Presence::Explicit)
+        );
+        assert_eq!(bytes.as_slice(), &[0x01, 0x02]);
+        assert_decode!(
+            Err(DecodeError::UnexpectedEof),
+            [0x03, 0x01, 0x02],
+            decode_bytes(&mut bytes, Presence::Explicit)
+        );
+    }
+
+    #[test]
+    fn packed() {
+        let mut vec = ArrayVec::<u32, 4>::new();
+        // Each assertion decodes twice, once per reader
+        assert_decode!(
+            Ok(()),
+            [0x03, 0x01, 0x96, 0x01],
+            decode_packed(&mut vec, async |d| d.decode_varint32().await)
+        );
+        assert_eq!(vec.as_slice(), &[1, 150, 1, 150]);
+        assert_decode!(
+            Err(DecodeError::Capacity),
+            [0x02, 0x01, 0x02],
+            decode_packed(&mut vec, async |d| d.decode_varint32().await)
+        );
+        assert_decode!(
+            Err(DecodeError::WrongLen),
+            [0x01, 0x96, 0x01],
+            decode_packed(&mut ArrayVec::<u32, 3>::new(), async |d| d
+                .decode_varint32()
+                .await)
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let mut decoder = AsyncPbDecoder::new([0x03, b'a', b'b', b'c'].as_slice());
+        decoder.max_field_len = Some(2);
+        let mut string = ArrayString::<5>::new();
+        assert_eq!(
+            block_on(decoder.decode_string(&mut string, Presence::Explicit)),
+            Err(DecodeError::BudgetExceeded)
+        );
+
+        let mut decoder = AsyncPbDecoder::new([0x02, 0x01, 0x02, 0x02, 0x03, 0x04].as_slice());
+        decoder.alloc_budget = Some(3);
+        let mut vec = ArrayVec::<u32, 4>::new();
+        block_on(decoder.decode_packed(&mut vec, async |d| d.decode_varint32().await)).unwrap();
+        assert_eq!(decoder.alloc_budget, Some(1));
+        let mut bytes = ArrayVec::<u8, 4>::new();
+        assert_eq!(
+            block_on(decoder.decode_bytes(&mut bytes, Presence::Explicit)),
+            Err(DecodeError::BudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn packed_fixed() {
+        let mut vec = ArrayVec::<u32, 4>::new();
+        assert_decode!(
+            Ok(()),
+            [0x04, 0x01, 0x02, 0x03, 0x04],
+            decode_packed_fixed(&mut vec)
+        );
+        assert_eq!(vec.as_slice(), &[0x04030201, 0x04030201]);
+        // Falls back to pushing one element at a time when there's not enough spare capacity
+        assert_decode!(
+            Err(DecodeError::Capacity),
+            [0x0C, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00],
+            decode_packed_fixed(&mut vec)
+        );
+        assert_eq!(vec.as_slice(), &[0x04030201, 0x04030201, 1, 2]);
+        assert_decode!(
+            Err(DecodeError::WrongLen),
+            [0x03, 0x01, 0x02, 0x03],
+            decode_packed_fixed(&mut ArrayVec::<u32, 3>::new())
+        );
+
+        let mut vec = ArrayVec::<f32, 4>::new();
+        assert_decode!(
+            Ok(()),
+            [0x04, 0x00, 0x00, 0xC0, 0x3F],
+            decode_packed_fixed(&mut vec)
+        );
+        assert_eq!(vec.as_slice(), &[1.5, 1.5]);
+    }
+
+    #[test]
+    fn map_elem() {
+        assert_decode!(
+            Ok(Some((3, 150))),
+            [0x05, 0x10, 0x96, 0x01, 0x08, 0x03],
+            decode_map_elem(
+                async |k: &mut u32, d| {
+                    *k = d.decode_varint32().await?;
+                    Ok(())
+                },
+                async |v: &mut u32, d| {
+                    *v = d.decode_varint32().await?;
+                    Ok(())
+                },
+            )
+        );
+        assert_decode!(
+            Ok(None),
+            [0x02, 0x08, 0x03],
+            decode_map_elem(
+                async |k: &mut u32, d| {
+                    *k = d.decode_varint32().await?;
+                    Ok(())
+                },
+                async |v: &mut u32, d| {
+                    *v = d.decode_varint32().await?;
+                    Ok(())
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn skip() {
+        assert_decode!(Ok(()), [0x96, 0x01], skip_wire_value(WIRE_TYPE_VARINT));
+        assert_decode!(Ok(()), [0x02, 0x01, 0x02], skip_wire_value(WIRE_TYPE_LEN));
+        assert_decode!(
+            Ok(()),
+            [0x08, 0x01, 0x13, 0x12, 0x01, 0xAA, 0x14, 0x0C],
+            skip_wire_value(WIRE_TYPE_SGROUP)
+        );
+        assert_decode!(
+            Err(DecodeError::UnexpectedEndGroup),
+            [],
+            skip_wire_value(WIRE_TYPE_EGROUP)
+        );
+        assert_decode!(
+            Err(DecodeError::UnexpectedEof),
+            [0x01, 0x02, 0x03],
+            skip_wire_value(WIRE_TYPE_I32)
+        );
+    }
+
+    /// Message that collects the values of all its varint fields
+    #[derive(Default)]
+    struct Varints(ArrayVec<u32, 4>);
+
+    impl AsyncMessageDecode for Varints {
+        async fn decode_async<R: AsyncPbRead>(
+            &mut self,
+            decoder: &mut AsyncPbDecoder<R>,
+            len: usize,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let before = decoder.bytes_read();
+            while decoder.bytes_read() - before < len {
+                decoder.decode_tag().await?;
+                self.0.push(decoder.decode_varint32().await?);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn until_eof() {
+        let data = [0x08, 0x01, 0x10, 0x96, 0x01];
+        let mut msg = Varints::default();
+        let mut decoder = AsyncPbDecoder::new(Trickle::new(data.as_slice()));
+        block_on(decoder.decode_until_eof(&mut msg)).unwrap();
+        assert_eq!(msg.0.as_slice(), &[1, 150]);
+        assert!(block_on(decoder.is_eof()).unwrap());
+
+        // Input ends in the middle of a field
+        let mut decoder = AsyncPbDecoder::new(Trickle::new(&data[..4]));
+        assert_eq!(
+            block_on(decoder.decode_until_eof(&mut Varints::default())),
+            Err(DecodeError::UnexpectedEof)
+        );
+        let mut decoder = AsyncPbDecoder::new(Trickle::new(&data[..3]));
+        assert_eq!(
+            block_on(decoder.decode_until_eof(&mut Varints::default())),
+            Err(DecodeError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn is_eof() {
+        let mut decoder = AsyncPbDecoder::new(Trickle::new(&[0x96, 0x01]));
+        assert!(!block_on(decoder.is_eof()).unwrap());
+        block_on(decoder.decode_varint32()).unwrap();
+        assert!(block_on(decoder.is_eof()).unwrap());
+    }
+
+    #[cfg(feature = "embedded-io-async-0-6")]
+    #[test]
+    fn embedded_io_reader() {
+        let data = [0x96, 0x01, 0x02, b'a', b'b'];
+        let mut decoder = AsyncPbDecoder::new(crate::EmbeddedIoReader(data.as_slice()));
+        assert_eq!(block_on(decoder.decode_varint32()), Ok(150));
+        let mut string = ArrayString::<4>::new();
+        block_on(decoder.decode_string(&mut string, Presence::Implicit)).unwrap();
+        assert_eq!(string.as_str(), "ab");
+        assert_eq!(decoder.bytes_read(), data.len());
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    encode::{encoder_methods, write_varint, VarInt},
+    misc::fixed_width_as_bytes,
+    AsyncMessageEncode, FixedWidth, PbWrite, Tag,
+};
+
+/// Async version of [`PbWrite`].
+///
+/// [`AsyncPbEncoder`] uses this trait as the interface for writing encoded Protobuf messages to
+/// asynchronous outputs, such as a UART or USB endpoint.
+///
+/// This trait is implemented for the same in-memory byte vectors as [`PbWrite`], since writing to
+/// them never needs to wait.
+#[allow(async_fn_in_trait)]
+pub trait AsyncPbWrite {
+    /// I/O error returned on write failure.
+    type Error;
+
+    /// Writes all bytes in `data`.
+    async fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<W: AsyncPbWrite> AsyncPbWrite for &mut W {
+    type Error = W::Error;
+
+    #[inline]
+    async fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        (*self).pb_write(data).await
+    }
+}
+
+macro_rules! impl_async_write_for_sync {
+    ($(#[$attr:meta])* impl$(<const $n:ident: usize>)? for $ty:ty) => {
+        $(#[$attr])*
+        impl$(<const $n: usize>)? AsyncPbWrite for $ty {
+            type Error = <Self as PbWrite>::Error;
+
+            #[inline]
+            async fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+                PbWrite::pb_write(self, data)
+            }
+        }
+    };
+}
+
+impl_async_write_for_sync!(impl for &mut [u8]);
+impl_async_write_for_sync!(
+    #[cfg(feature = "container-arrayvec-0-7")]
+    impl<const N: usize> for arrayvec::ArrayVec<u8, N>
+);
+impl_async_write_for_sync!(
+    #[cfg(feature = "container-heapless-0-8")]
+    impl<const N: usize> for heapless_0_8::Vec<u8, N>
+);
+impl_async_write_for_sync!(
+    #[cfg(feature = "container-heapless-0-9")]
+    impl<const N: usize> for heapless_0_9::Vec<u8, N>
+);
+impl_async_write_for_sync!(
+    #[cfg(feature = "alloc")]
+    impl for alloc::vec::Vec<u8>
+);
+
+#[cfg(feature = "embedded-io-async-0-6")]
+impl<W: embedded_io_async_0_6::Write> AsyncPbWrite for crate::EmbeddedIoWriter<W> {
+    type Error = W::Error;
+
+    #[inline]
+    async fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(data).await
+    }
+}
+
+#[derive(Debug)]
+/// Async version of [`PbEncoder`](crate::PbEncoder).
+///
+/// Writes bytes to an underlying [`AsyncPbWrite`] instance. Each encoded value is written out in a
+/// single call to [`AsyncPbWrite::pb_write`], so the writer doesn't need to buffer the whole
+/// message.
+///
+/// # Example
+///
+/// Encoding a Protobuf message:
+/// ``` no_run
+/// use micropb::{AsyncPbEncoder, AsyncPbWrite, AsyncMessageEncode};
+/// # use micropb::{MessageEncode, PbEncoder, PbWrite};
+/// # use heapless_0_9 as heapless;
+///
+/// # #[derive(Default)]
+/// # struct ProtoMessage(u32);
+/// # impl micropb::MessageEncode for ProtoMessage {
+/// #   const MAX_SIZE: Result<usize, &str> = Ok(0);
+/// #   fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> { todo!() }
+/// #   fn compute_size(&self) -> usize { 0 }
+/// # }
+/// # impl micropb::AsyncMessageEncode for ProtoMessage {
+/// #   async fn encode_async<W: AsyncPbWrite>(&self, encoder: &mut AsyncPbEncoder<W>) -> Result<(), W::Error> { todo!() }
+/// # }
+///
+/// # async fn example() -> Result<(), ()> {
+/// let mut message = ProtoMessage::default();
+/// message.0 = 12;
+///
+/// let mut encoder = AsyncPbEncoder::new(heapless::Vec::<u8, 10>::new());
+/// message.encode_async(&mut encoder).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncPbEncoder<W: AsyncPbWrite> {
+    writer: W,
+}
+
+impl<W: AsyncPbWrite> AsyncPbEncoder<W> {
+    #[inline]
+    /// Construct a new encoder from an [`AsyncPbWrite`].
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    #[inline]
+    /// Transform the encoder into the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    #[inline]
+    /// Get reference to underlying writer.
+    pub fn as_writer(&self) -> &W {
+        &self.writer
+    }
+
+    #[inline]
+    pub(crate) async fn write(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
+        self.writer.pb_write(bytes).await
+    }
+
+    encoder_methods!(
+        [async],
+        [await],
+        AsyncFnMut,
+        AsyncMessageEncode::encode_async,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use arrayvec::ArrayVec;
+
+    use crate::{size::*, PbEncoder, WIRE_TYPE_LEN, WIRE_TYPE_VARINT};
+
+    use super::*;
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    // Compare the async encoder output against the blocking encoder
+    macro_rules! assert_encode {
+        ($encode:ident( $($arg:expr),+ )) => {
+            let mut encoder = AsyncPbEncoder::new(ArrayVec::<u8, 20>::new());
+            block_on(encoder.$encode($($arg),+)).unwrap();
+            let mut expected = PbEncoder::new(ArrayVec::<u8, 20>::new());
+            expected.$encode($($arg),+).unwrap();
+            assert_eq!(expected.as_writer().as_slice(), encoder.writer.as_slice());
+        }
+    }
+
+    #[test]
+    fn varint() {
+        assert_encode!(encode_varint32(0));
+        assert_encode!(encode_varint32(150));
+        assert_encode!(encode_varint32(u32::MAX));
+        assert_encode!(encode_varint64(u64::MAX));
+        assert_encode!(encode_int32(-1));
+        assert_encode!(encode_int32(i32::MIN));
+        assert_encode!(encode_int64(-2));
+        assert_encode!(encode_sint32(-100));
+        assert_encode!(encode_sint64(i64::MIN));
+        assert_encode!(encode_bool(true));
+    }
+
+    #[test]
+    fn fixed() {
+        assert_encode!(encode_fixed32(0x01020304));
+        assert_encode!(encode_sfixed64(-2));
+        assert_encode!(encode_fixed64_as_32(5));
+        assert_encode!(encode_sfixed64_as_32(-5));
+        assert_encode!(encode_float(-1.5));
+        assert_encode!(encode_double(1.5));
+    }
+
+    #[test]
+    fn bytes_string() {
+        assert_encode!(encode_bytes(&[0x01, 0x02, 0x03]));
+        assert_encode!(encode_string("abc"));
+    }
+
+    #[test]
+    fn packed_fixed() {
+        assert_encode!(encode_packed_fixed(&[0u32; 0]));
+        assert_encode!(encode_packed_fixed(&[1u32, 6]));
+        assert_encode!(encode_packed_fixed(&[-1.5f64, 2.0]));
+    }
+
+    #[test]
+    fn packed_and_map() {
+        let mut encoder = AsyncPbEncoder::new(ArrayVec::<u8, 20>::new());
+        let elems = [1u32, 150, 3];
+        let len = sizeof_packed(&elems, |&v| sizeof_varint32(v));
+        block_on(encoder.encode_packed(len, &elems, async |e, v| e.encode_varint32(v).await))
+            .unwrap();
+        assert_eq!(encoder.writer.as_slice(), &[0x04, 0x01, 0x96, 0x01, 0x03]);
+
+        let mut encoder = AsyncPbEncoder::new(ArrayVec::<u8, 20>::new());
+        block_on(encoder.encode_map_elem(
+            6,
+            "a",
+            WIRE_TYPE_LEN,
+            &150u32,
+            WIRE_TYPE_VARINT,
+            async |e, k| e.encode_string(k).await,
+            async |e, v| e.encode_varint32(*v).await,
+        ))
+        .unwrap();
+        assert_eq!(
+            encoder.writer.as_slice(),
+            &[0x06, 0x0A, 0x01, b'a', 0x10, 0x96, 0x01]
+        );
+    }
+
+    #[test]
+    fn write_error() {
+        let mut buf = [0u8; 1];
+        let mut encoder = AsyncPbEncoder::new(buf.as_mut_slice());
+        assert_eq!(block_on(encoder.encode_varint32(150)), Err(()));
+    }
+
+    #[cfg(feature = "embedded-io-async-0-6")]
+    #[test]
+    fn embedded_io_writer() {
+        let mut buf = [0u8; 2];
+        let mut encoder = AsyncPbEncoder::new(crate::EmbeddedIoWriter(buf.as_mut_slice()));
+        block_on(encoder.encode_varint32(150)).unwrap();
+        assert_eq!(buf, [0x96, 0x01]);
+
+        let mut encoder = AsyncPbEncoder::new(crate::EmbeddedIoWriter(buf.as_mut_slice()));
+        assert_eq!(
+            block_on(encoder.encode_fixed32(1)),
+            Err(embedded_io_0_6::SliceWriteError::Full)
+        );
+    }
+}
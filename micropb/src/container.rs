@@ -87,12 +87,48 @@ pub trait PbVec<T> {
     ///
     /// Returns error if the fixed capacity is already full.
     fn pb_push(&mut self, elem: T) -> Result<(), ()>;
+
+    /// Reserve capacity for at least `additional` more elements.
+    ///
+    /// Does nothing by default.
+    fn pb_reserve(&mut self, _additional: usize) {}
+
+    /// Returns the remaining spare capacity of the vector as a slice of `MaybeUninit<T>`.
+    ///
+    /// Used for bulk-copying packed fixed-size fields directly into the vector. The default
+    /// implementation returns an empty slice, in which case elements are inserted one at a time
+    /// with [`pb_push`](PbVec::pb_push) instead.
+    fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut []
+    }
+
+    /// Increase the length of the vector by `additional`.
+    ///
+    /// Must be implemented if [`pb_spare_cap`](PbVec::pb_spare_cap) is implemented.
+    ///
+    /// # Safety
+    ///
+    /// The first `additional` elements returned by [`pb_spare_cap`](PbVec::pb_spare_cap) must
+    /// have been initialized.
+    unsafe fn pb_extend_len(&mut self, _additional: usize) {}
 }
 
 impl<T, V: PbVec<T>> PbVec<T> for &mut V {
     fn pb_push(&mut self, elem: T) -> Result<(), ()> {
         (*self).pb_push(elem)
     }
+
+    fn pb_reserve(&mut self, additional: usize) {
+        (*self).pb_reserve(additional)
+    }
+
+    fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+        (*self).pb_spare_cap()
+    }
+
+    unsafe fn pb_extend_len(&mut self, additional: usize) {
+        (*self).pb_extend_len(additional)
+    }
 }
 
 /// Map that stores key-value pairs. Represents Protobuf `map` field.
@@ -273,6 +309,24 @@ mod impl_arrayvec {
         fn pb_push(&mut self, elem: T) -> Result<(), ()> {
             self.try_push(elem).map_err(drop)
         }
+
+        #[inline]
+        fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+            let len = self.len();
+            // SAFETY: Underlying storage is array of N elements, and everything past `len` is
+            // spare capacity
+            unsafe {
+                core::slice::from_raw_parts_mut(
+                    self.as_mut_ptr().add(len) as *mut MaybeUninit<T>,
+                    N - len,
+                )
+            }
+        }
+
+        #[inline]
+        unsafe fn pb_extend_len(&mut self, additional: usize) {
+            self.set_len(self.len() + additional)
+        }
     }
 }
 
@@ -340,6 +394,22 @@ macro_rules! impl_heapless {
                 fn pb_push(&mut self, elem: T) -> Result<(), ()> {
                     self.push(elem).map_err(drop)
                 }
+
+                #[inline]
+                fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+                    let len = self.len();
+                    // SAFETY: Underlying storage is static array of size N, so it's safe to create a slice
+                    // of N values
+                    let slice = unsafe {
+                        core::slice::from_raw_parts_mut(self.as_mut_ptr() as *mut MaybeUninit<T>, N)
+                    };
+                    slice.get_mut(len..).unwrap_or(&mut [])
+                }
+
+                #[inline]
+                unsafe fn pb_extend_len(&mut self, additional: usize) {
+                    self.set_len(self.len() + additional)
+                }
             }
 
             impl<K: Eq + Hash, V, S: BuildHasher, const N: usize> PbMap<K, V> for IndexMap<K, V, S, N> {
@@ -462,6 +532,21 @@ mod impl_alloc {
             self.push(elem);
             Ok(())
         }
+
+        #[inline]
+        fn pb_reserve(&mut self, additional: usize) {
+            self.reserve(additional)
+        }
+
+        #[inline]
+        fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+            self.spare_capacity_mut()
+        }
+
+        #[inline]
+        unsafe fn pb_extend_len(&mut self, additional: usize) {
+            self.set_len(self.len() + additional)
+        }
     }
 
     impl<T> PbVec<T> for Cow<'_, [T]>
@@ -472,6 +557,19 @@ mod impl_alloc {
             self.to_mut().push(elem);
             Ok(())
         }
+
+        fn pb_reserve(&mut self, additional: usize) {
+            self.to_mut().reserve(additional)
+        }
+
+        fn pb_spare_cap(&mut self) -> &mut [MaybeUninit<T>] {
+            self.to_mut().spare_capacity_mut()
+        }
+
+        unsafe fn pb_extend_len(&mut self, additional: usize) {
+            let vec = self.to_mut();
+            vec.set_len(vec.len() + additional)
+        }
     }
 
     impl<K: Ord, V> PbMap<K, V> for BTreeMap<K, V> {
@@ -1,18 +1,29 @@
 use core::{
     convert::Infallible,
+    marker::PhantomData,
     mem::MaybeUninit,
+    ops::Deref,
     str::{from_utf8, Utf8Error},
 };
 
+#[cfg(feature = "detailed-errors")]
+use crate::error_context::{DetailedDecodeError, ErrorTrace};
 use crate::{
     container::{PbBytes, PbString, PbVec},
+    field::FieldDecode,
+    framing::{Crc, CrcReader},
     misc::{
         maybe_uninit_slice_assume_init_ref, maybe_uninit_write_slice,
-        maybe_ununit_array_assume_init,
+        maybe_ununit_array_assume_init, varint32_bytes, varint32_from_bytes, varint64_bytes,
     },
-    MessageDecode, Presence, Tag, WIRE_TYPE_I32, WIRE_TYPE_I64, WIRE_TYPE_LEN, WIRE_TYPE_VARINT,
+    FixedWidth, MessageDecode, Presence, Tag, WIRE_TYPE_EGROUP, WIRE_TYPE_I32, WIRE_TYPE_I64,
+    WIRE_TYPE_LEN, WIRE_TYPE_SGROUP, WIRE_TYPE_VARINT,
 };
 
+/// Default value of [`PbDecoder::max_depth`], same as the default recursion limit of the official
+/// Protobuf implementations.
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Eq)]
 /// Protobuf [decoder](PbDecoder) error.
@@ -25,7 +36,10 @@ pub enum DecodeError<E> {
     /// Reader encountered EOF in the middle of decoding
     UnexpectedEof,
     /// Encountered deprecated wire type
+    #[deprecated(note = "group wire types are now decoded, so this error is never returned")]
     Deprecation,
+    /// Encountered end-group tag that doesn't close the current group
+    UnexpectedEndGroup,
     /// Unknown Protobuf wire type encountered
     UnknownWireType,
     /// Field number of 0, which is not allowed
@@ -38,10 +52,71 @@ pub enum DecodeError<E> {
     Capacity,
     /// Actual length of length-delimited record differs from value of length prefix
     WrongLen,
+    /// Nesting depth of messages exceeded the decoder's `max_depth`
+    MaxDepth,
+    /// Length of a `string`, `bytes`, or packed field exceeded the decoder's `max_field_len`, or
+    /// the memory taken by decoded fields exceeded its `alloc_budget`
+    BudgetExceeded,
+    /// Checksum trailer of a framed message doesn't match its contents
+    ChecksumMismatch,
     /// Error returned from reader
     Reader(E),
 }
 
+impl<E: core::fmt::Display> core::fmt::Display for DecodeError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::VarIntLimit => f.write_str("varint exceeded 10 bytes"),
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            #[allow(deprecated)]
+            Self::Deprecation => f.write_str("deprecated wire type"),
+            Self::UnexpectedEndGroup => f.write_str("unexpected end-group tag"),
+            Self::UnknownWireType => f.write_str("unknown wire type"),
+            Self::ZeroField => f.write_str("field number 0"),
+            Self::CustomField => f.write_str("custom field rejected a known field number"),
+            Self::Utf8 => f.write_str("string is not valid UTF-8"),
+            Self::Capacity => f.write_str("capacity of fixed container exceeded"),
+            Self::WrongLen => f.write_str("record length does not match length prefix"),
+            Self::MaxDepth => f.write_str("max nesting depth exceeded"),
+            Self::BudgetExceeded => f.write_str("field length exceeded decoder budget"),
+            Self::ChecksumMismatch => f.write_str("checksum mismatch"),
+            Self::Reader(e) => write!(f, "reader error: {e}"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for DecodeError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Reader(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl DecodeError<Infallible> {
+    /// Convert to an error of any reader type, since an infallible reader never fails
+    pub(crate) fn widen<E>(self) -> DecodeError<E> {
+        match self {
+            Self::VarIntLimit => DecodeError::VarIntLimit,
+            Self::UnexpectedEof => DecodeError::UnexpectedEof,
+            #[allow(deprecated)]
+            Self::Deprecation => DecodeError::Deprecation,
+            Self::UnexpectedEndGroup => DecodeError::UnexpectedEndGroup,
+            Self::UnknownWireType => DecodeError::UnknownWireType,
+            Self::ZeroField => DecodeError::ZeroField,
+            Self::CustomField => DecodeError::CustomField,
+            Self::Utf8 => DecodeError::Utf8,
+            Self::Capacity => DecodeError::Capacity,
+            Self::WrongLen => DecodeError::WrongLen,
+            Self::MaxDepth => DecodeError::MaxDepth,
+            Self::BudgetExceeded => DecodeError::BudgetExceeded,
+            Self::ChecksumMismatch => DecodeError::ChecksumMismatch,
+            Self::Reader(e) => match e {},
+        }
+    }
+}
+
 impl<E> From<Utf8Error> for DecodeError<E> {
     fn from(_: Utf8Error) -> Self {
         Self::Utf8
@@ -144,6 +219,111 @@ impl PbRead for &[u8] {
     }
 }
 
+/// Wire value of a map key, used to re-encode map entries for
+/// [`PbDecoder::decode_unknown_map_entry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapKey<'a> {
+    /// Varint key, which is already zigzag-encoded for `sint` keys
+    Varint(u64),
+    /// 4-byte key
+    I32(u32),
+    /// 8-byte key
+    I64(u64),
+    /// `string` key
+    Len(&'a [u8]),
+}
+
+/// Reader over a length-delimited map entry, re-encoded from its key and varint value
+pub(crate) struct MapEntryReader<'a> {
+    /// Entry length, key tag, and key bytes (or the length of a `string` key)
+    head: [u8; 16],
+    head_len: usize,
+    /// Contents of a `string` key
+    key: &'a [u8],
+    /// Value tag and value bytes
+    tail: [u8; 11],
+    tail_len: usize,
+    pos: usize,
+}
+
+impl<'a> MapEntryReader<'a> {
+    pub(crate) fn new(key: MapKey<'a>, value: u64) -> Self {
+        let mut key_bytes = [0; 10];
+        let (wire_type, key_len, contents) = match key {
+            MapKey::Varint(v) => {
+                let (bytes, len) = varint64_bytes(v);
+                key_bytes = bytes;
+                (WIRE_TYPE_VARINT, len, &[][..])
+            }
+            MapKey::I32(v) => {
+                key_bytes[..4].copy_from_slice(&v.to_le_bytes());
+                (WIRE_TYPE_I32, 4, &[][..])
+            }
+            MapKey::I64(v) => {
+                key_bytes[..8].copy_from_slice(&v.to_le_bytes());
+                (WIRE_TYPE_I64, 8, &[][..])
+            }
+            MapKey::Len(s) => {
+                let (bytes, len) = varint64_bytes(s.len() as u64);
+                key_bytes = bytes;
+                (WIRE_TYPE_LEN, len, s)
+            }
+        };
+        let (val_bytes, val_len) = varint64_bytes(value);
+        let entry_len = 1 + key_len + contents.len() + 1 + val_len;
+        let (len_bytes, len_len) = varint64_bytes(entry_len as u64);
+
+        let mut head = [0; 16];
+        head[..len_len].copy_from_slice(&len_bytes[..len_len]);
+        head[len_len] = Tag::from_parts(1, wire_type).varint() as u8;
+        head[len_len + 1..][..key_len].copy_from_slice(&key_bytes[..key_len]);
+        let mut tail = [0; 11];
+        tail[0] = Tag::from_parts(2, WIRE_TYPE_VARINT).varint() as u8;
+        tail[1..][..val_len].copy_from_slice(&val_bytes[..val_len]);
+        Self {
+            head,
+            head_len: len_len + 1 + key_len,
+            key: contents,
+            tail,
+            tail_len: 1 + val_len,
+            pos: 0,
+        }
+    }
+
+    pub(crate) fn chunk(&self) -> &[u8] {
+        let mut pos = self.pos;
+        for part in [
+            &self.head[..self.head_len],
+            self.key,
+            &self.tail[..self.tail_len],
+        ] {
+            if pos < part.len() {
+                return &part[pos..];
+            }
+            pos -= part.len();
+        }
+        &[]
+    }
+
+    pub(crate) fn advance(&mut self, bytes: usize) {
+        self.pos += bytes;
+    }
+}
+
+impl PbRead for MapEntryReader<'_> {
+    type Error = Infallible;
+
+    #[inline]
+    fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.chunk())
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        self.advance(bytes)
+    }
+}
+
 #[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 /// Adapter that implements [`PbRead`] for all implementers of [`std::io::BufRead`], allowing the
@@ -165,6 +345,31 @@ impl<R: std::io::BufRead> PbRead for StdReader<R> {
     }
 }
 
+#[cfg(feature = "embedded-io-0-6")]
+#[derive(Debug, Clone)]
+/// Adapter that implements [`PbRead`] for all implementers of [`embedded_io::BufRead`](embedded_io_0_6::BufRead)
+/// from `embedded-io` v0.6, allowing the decoder to read from HAL peripherals and other
+/// `embedded-io` readers.
+///
+/// With the `embedded-io-async-0-6` feature, this also implements
+/// [`AsyncPbRead`](crate::AsyncPbRead) for implementers of `embedded_io_async::BufRead`.
+pub struct EmbeddedIoReader<R>(pub R);
+
+#[cfg(feature = "embedded-io-0-6")]
+impl<R: embedded_io_0_6::BufRead> PbRead for EmbeddedIoReader<R> {
+    type Error = R::Error;
+
+    #[inline]
+    fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        self.0.fill_buf()
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        self.0.consume(bytes)
+    }
+}
+
 #[derive(Debug)]
 /// Decoder that reads Protobuf bytes and decodes them into Rust types.
 ///
@@ -212,493 +417,1139 @@ pub struct PbDecoder<R: PbRead> {
     /// length-delimited repeated or map field's actual length does not match its length prefix.
     /// Note that other errors can still happen down the line if the length is wrong.
     pub ignore_wrong_len: bool,
+    /// Maximum nesting depth of messages. When decoding a message field would nest deeper than
+    /// this, the decoder will report [`DecodeError::MaxDepth`]. The top-level message is at depth
+    /// 0, so a value of 0 rejects all message fields.
+    ///
+    /// Since generated message decoding recurses once per nesting level, this bounds the stack
+    /// usage of the decoder when decoding untrusted input. Defaults to
+    /// [`DEFAULT_MAX_DEPTH`].
+    pub max_depth: usize,
+    /// Maximum length in bytes of a single `string`, `bytes`, or packed field. Longer fields are
+    /// rejected with [`DecodeError::BudgetExceeded`] before any memory is reserved for them. No
+    /// limit by default.
+    pub max_field_len: Option<usize>,
+    /// Remaining number of bytes that may be reserved for `string`, `bytes`, packed, repeated,
+    /// and map fields. The length of each `string`, `bytes`, or packed field is deducted from the
+    /// budget before memory is reserved for it. Non-packed repeated fields and map fields are
+    /// charged the size of each element or entry before it's added to the container. Fields that
+    /// don't fit in the remaining budget are rejected with [`DecodeError::BudgetExceeded`]. No
+    /// limit by default.
+    ///
+    /// Together with [`max_field_len`](Self::max_field_len), this prevents malicious length
+    /// prefixes from causing huge allocations when decoding into heap-allocated containers.
+    pub alloc_budget: Option<usize>,
+    depth: usize,
+    /// Field number of the group that the innermost message is being decoded from, if any
+    group: Option<u32>,
+    /// Set when the reader hits EOF right at the start of a top-level tag
+    clean_eof: bool,
+    #[cfg(feature = "detailed-errors")]
+    trace: ErrorTrace,
 }
 
-impl<R: PbRead> PbDecoder<R> {
-    #[inline]
-    /// Construct a new decoder from a [`PbRead`].
-    pub fn new(reader: R) -> Self {
-        Self {
-            reader,
-            idx: 0,
-            ignore_repeated_cap_err: false,
-            ignore_wrong_len: false,
+/// Generate the decoding methods shared by `PbDecoder` and `AsyncPbDecoder`.
+///
+/// Both decoders run the same logic, except that the async one awaits every read, takes async
+/// closures as callbacks, and decodes messages and unknown fields through the async traits. The
+/// `async` and `await` arguments are left empty for the blocking decoder.
+macro_rules! decoder_methods {
+    (
+        $Decoder:ident,
+        [$($async:tt)*],
+        [$($await:tt)*],
+        $Fn:ident,
+        $FnMut:ident,
+        $FnOnce:ident,
+        $FieldDecode:ident::$decode_field:ident,
+        $MessageDecode:ident::{$decode:ident, $decode_len_delimited:ident} $(,)?
+    ) => {
+        #[inline(always)]
+        /// Decode a Protobuf tag.
+        pub $($async)* fn decode_tag(&mut self) -> Result<Tag, DecodeError<R::Error>> {
+            let start = self.idx;
+            let tag = match self.decode_varint32()$(.$await)* {
+                Ok(v) => Tag(v),
+                Err(e) => {
+                    self.clean_eof = matches!(e, DecodeError::UnexpectedEof)
+                        && self.idx == start
+                        && self.depth == 0;
+                    return Err(e);
+                }
+            };
+            #[cfg(feature = "detailed-errors")]
+            self.trace.on_tag(tag.field_num());
+            Ok(tag)
         }
-    }
 
-    #[inline]
-    /// Transform the decoder into the underlying reader.
-    pub fn into_reader(self) -> R {
-        self.reader
-    }
+        pub(crate) $($async)* fn decode_len_record<
+            T,
+            F: $FnOnce(usize, usize, &mut Self) -> Result<T, DecodeError<R::Error>>,
+        >(
+            &mut self,
+            decoder: F,
+        ) -> Result<T, DecodeError<R::Error>> {
+            let len = self.decode_varint32()$(.$await)*? as usize;
+            let before = self.bytes_read();
+            let val = decoder(len, before, self)$(.$await)*.inspect_err(|_| {
+                // EOF inside a record always means the input is truncated
+                self.clean_eof = false;
+            })?;
+            let actual_len = self.bytes_read() - before;
+            if actual_len != len && !self.ignore_wrong_len {
+                Err(DecodeError::WrongLen)
+            } else {
+                Ok(val)
+            }
+        }
 
-    #[inline]
-    /// Get reference to underlying reader.
-    pub fn as_reader(&self) -> &R {
-        &self.reader
-    }
+        /// Decode a message that spans the rest of the input, merging it into `msg`.
+        ///
+        /// Unlike [`decode_message`](Self::decode_message), the length of the message doesn't need
+        /// to be known beforehand. Instead, the message ends when the reader reaches EOF between
+        /// two fields. If the input ends in the middle of a field, [`DecodeError::UnexpectedEof`]
+        /// is returned.
+        ///
+        /// This is useful for readers that signal the end of each message with EOF, such as the
+        /// frame readers in [`framing`](crate::framing).
+        pub $($async)* fn decode_until_eof<M: $MessageDecode>(
+            &mut self,
+            msg: &mut M,
+        ) -> Result<(), DecodeError<R::Error>> {
+            self.clean_eof = false;
+            match msg.$decode(self, usize::MAX)$(.$await)* {
+                Err(DecodeError::UnexpectedEof) if self.clean_eof => Ok(()),
+                res => res,
+            }
+        }
 
-    #[inline]
-    /// Get the number of bytes that the decoder has consumed from the reader.
-    pub fn bytes_read(&self) -> usize {
-        self.idx
-    }
+        /// Returns `true` if the reader has no more bytes to decode.
+        pub $($async)* fn is_eof(&mut self) -> Result<bool, DecodeError<R::Error>> {
+            let chunk = self.reader.pb_read_chunk()$(.$await)*.map_err(DecodeError::Reader)?;
+            Ok(chunk.is_empty())
+        }
 
-    #[inline]
-    fn advance(&mut self, bytes: usize) {
-        self.reader.pb_advance(bytes);
-        self.idx += bytes;
-    }
+        #[inline]
+        /// Transform the decoder into the underlying reader.
+        pub fn into_reader(self) -> R {
+            self.reader
+        }
 
-    #[inline]
-    fn get_byte(&mut self) -> Result<u8, DecodeError<R::Error>> {
-        let chunk = self.reader.pb_read_chunk().map_err(DecodeError::Reader)?;
-        let b = chunk.first().copied().ok_or(DecodeError::UnexpectedEof)?;
-        self.advance(1);
-        Ok(b)
-    }
+        #[inline]
+        /// Get reference to underlying reader.
+        pub fn as_reader(&self) -> &R {
+            &self.reader
+        }
 
-    /// Decode an `uint32`.
-    pub fn decode_varint32(&mut self) -> Result<u32, DecodeError<R::Error>> {
-        let b = self.get_byte()?;
-        // Single byte case
-        if b & 0x80 == 0 {
-            return Ok(b as u32);
-        }
-
-        let mut varint: u32 = b as u32 & !0x80;
-        let mut bitpos = 7;
-        for i in 1..10 {
-            let b = self.get_byte()?;
-            // Take the first 5 bytes into account, but ignore the later 5 bytes since they're
-            // going to be truncated anyways
-            if i < 5 {
-                let u = b & !0x80;
-                varint |= (u as u32) << bitpos;
-                bitpos += 7;
-            }
+        #[inline]
+        /// Get the number of bytes that the decoder has consumed from the reader.
+        pub fn bytes_read(&self) -> usize {
+            self.idx
+        }
+
+        #[inline]
+        fn advance(&mut self, bytes: usize) {
+            self.reader.pb_advance(bytes);
+            self.idx += bytes;
+        }
+
+        #[inline]
+        $($async)* fn get_byte(&mut self) -> Result<u8, DecodeError<R::Error>> {
+            let chunk = self.reader.pb_read_chunk()$(.$await)*.map_err(DecodeError::Reader)?;
+            let b = chunk.first().copied().ok_or(DecodeError::UnexpectedEof)?;
+            self.advance(1);
+            Ok(b)
+        }
+
+        /// Decode an `uint32`.
+        pub $($async)* fn decode_varint32(&mut self) -> Result<u32, DecodeError<R::Error>> {
+            let b = self.get_byte()$(.$await)*?;
+            // Single byte case
             if b & 0x80 == 0 {
-                return Ok(varint);
+                return Ok(b as u32);
+            }
+
+            let mut varint: u32 = b as u32 & !0x80;
+            let mut bitpos = 7;
+            for i in 1..10 {
+                let b = self.get_byte()$(.$await)*?;
+                // Take the first 5 bytes into account, but ignore the later 5 bytes since they're
+                // going to be truncated anyways
+                if i < 5 {
+                    let u = b & !0x80;
+                    varint |= (u as u32) << bitpos;
+                    bitpos += 7;
+                }
+                if b & 0x80 == 0 {
+                    return Ok(varint);
+                }
             }
+            Err(DecodeError::VarIntLimit)
         }
-        Err(DecodeError::VarIntLimit)
-    }
 
-    #[cfg(feature = "enable-64bit")]
-    /// Decode an `uint64`.
-    pub fn decode_varint64(&mut self) -> Result<u64, DecodeError<R::Error>> {
-        let b = self.get_byte()?;
-        // Single byte case
-        if b & 0x80 == 0 {
-            return Ok(b as u64);
-        }
-
-        let mut varint: u64 = b as u64 & !0x80;
-        let mut bitpos = 7;
-        for _ in 1..10 {
-            let b = self.get_byte()?;
-            let u = b & !0x80;
-            varint |= (u as u64) << bitpos;
-            bitpos += 7;
+        #[cfg(feature = "enable-64bit")]
+        /// Decode an `uint64`.
+        pub $($async)* fn decode_varint64(&mut self) -> Result<u64, DecodeError<R::Error>> {
+            let b = self.get_byte()$(.$await)*?;
+            // Single byte case
             if b & 0x80 == 0 {
-                return Ok(varint);
+                return Ok(b as u64);
             }
+
+            let mut varint: u64 = b as u64 & !0x80;
+            let mut bitpos = 7;
+            for _ in 1..10 {
+                let b = self.get_byte()$(.$await)*?;
+                let u = b & !0x80;
+                varint |= (u as u64) << bitpos;
+                bitpos += 7;
+                if b & 0x80 == 0 {
+                    return Ok(varint);
+                }
+            }
+            Err(DecodeError::VarIntLimit)
         }
-        Err(DecodeError::VarIntLimit)
-    }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Decode an `int64`.
-    pub fn decode_int64(&mut self) -> Result<i64, DecodeError<R::Error>> {
-        self.decode_varint64().map(|u| u as i64)
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Decode an `int64`.
+        pub $($async)* fn decode_int64(&mut self) -> Result<i64, DecodeError<R::Error>> {
+            self.decode_varint64()$(.$await)*.map(|u| u as i64)
+        }
 
-    #[inline]
-    /// Decode an `int32`.
-    pub fn decode_int32(&mut self) -> Result<i32, DecodeError<R::Error>> {
-        self.decode_varint32().map(|u| u as i32)
-    }
+        #[inline]
+        /// Decode an `int32`.
+        pub $($async)* fn decode_int32(&mut self) -> Result<i32, DecodeError<R::Error>> {
+            self.decode_varint32()$(.$await)*.map(|u| u as i32)
+        }
 
-    #[inline]
-    /// Decode an `sint32`.
-    pub fn decode_sint32(&mut self) -> Result<i32, DecodeError<R::Error>> {
-        self.decode_varint32()
-            .map(|u| ((u >> 1) as i32) ^ -((u & 1) as i32))
-    }
+        #[inline]
+        /// Decode an `sint32`.
+        pub $($async)* fn decode_sint32(&mut self) -> Result<i32, DecodeError<R::Error>> {
+            self.decode_varint32()$(.$await)*
+                .map(|u| ((u >> 1) as i32) ^ -((u & 1) as i32))
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Decode an `sint64`.
-    pub fn decode_sint64(&mut self) -> Result<i64, DecodeError<R::Error>> {
-        self.decode_varint64()
-            .map(|u| ((u >> 1) as i64) ^ -((u & 1) as i64))
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Decode an `sint64`.
+        pub $($async)* fn decode_sint64(&mut self) -> Result<i64, DecodeError<R::Error>> {
+            self.decode_varint64()$(.$await)*
+                .map(|u| ((u >> 1) as i64) ^ -((u & 1) as i64))
+        }
 
-    #[inline]
-    /// Decode a `bool`.
-    pub fn decode_bool(&mut self) -> Result<bool, DecodeError<R::Error>> {
-        Ok(self.decode_varint32()? != 0)
-    }
+        #[inline]
+        /// Decode a `bool`.
+        pub $($async)* fn decode_bool(&mut self) -> Result<bool, DecodeError<R::Error>> {
+            Ok(self.decode_varint32()$(.$await)*? != 0)
+        }
 
-    fn read_exact(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<(), DecodeError<R::Error>> {
-        let bytes_read = self
-            .reader
-            .pb_read_exact(buf)
-            .map_err(DecodeError::Reader)?;
-        self.idx += bytes_read;
+        $($async)* fn read_exact(
+            &mut self,
+            buf: &mut [MaybeUninit<u8>],
+        ) -> Result<(), DecodeError<R::Error>> {
+            let bytes_read = self
+                .reader
+                .pb_read_exact(buf)$(.$await)*
+                .map_err(DecodeError::Reader)?;
+            self.idx += bytes_read;
+
+            if bytes_read < buf.len() {
+                return Err(DecodeError::UnexpectedEof);
+            }
+            Ok(())
+        }
 
-        if bytes_read < buf.len() {
-            return Err(DecodeError::UnexpectedEof);
+        $($async)* fn read_array<const N: usize>(
+            &mut self,
+        ) -> Result<[u8; N], DecodeError<R::Error>> {
+            let mut data = [MaybeUninit::uninit(); N];
+            self.read_exact(&mut data)$(.$await)*?;
+            // SAFETY: read_exact is guaranteed to write to the whole buffer
+            Ok(unsafe { maybe_ununit_array_assume_init(data) })
         }
-        Ok(())
-    }
 
-    #[inline]
-    /// Decode a `fixed32`.
-    pub fn decode_fixed32(&mut self) -> Result<u32, DecodeError<R::Error>> {
-        let mut data = [MaybeUninit::uninit(); 4];
-        self.read_exact(&mut data)?;
-        // SAFETY: read_exact is guaranteed to write to the whole buffer
-        let data = unsafe { maybe_ununit_array_assume_init(data) };
-        Ok(u32::from_le_bytes(data))
-    }
+        /// Check `len` against the field length limit and deduct it from the allocation budget
+        fn spend_budget(&mut self, len: usize) -> Result<(), DecodeError<R::Error>> {
+            if self.max_field_len.is_some_and(|max| len > max) {
+                return Err(DecodeError::BudgetExceeded);
+            }
+            if let Some(budget) = &mut self.alloc_budget {
+                *budget = budget.checked_sub(len).ok_or(DecodeError::BudgetExceeded)?;
+            }
+            Ok(())
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Decode a `fixed64`.
-    pub fn decode_fixed64(&mut self) -> Result<u64, DecodeError<R::Error>> {
-        let mut data = [MaybeUninit::uninit(); 8];
-        self.read_exact(&mut data)?;
-        // SAFETY: read_exact is guaranteed to write to the whole buffer
-        let data = unsafe { maybe_ununit_array_assume_init(data) };
-        Ok(u64::from_le_bytes(data))
-    }
+        #[inline]
+        /// Deduct the size of an element of type `T` from the allocation budget.
+        ///
+        /// Used by generated code before adding an element to a non-packed repeated field or an
+        /// entry to a map field, since those containers grow one element at a time instead of
+        /// reserving memory for the whole field up front. If the element doesn't fit in the
+        /// remaining budget, return [`DecodeError::BudgetExceeded`].
+        pub fn spend_elem_budget<T>(&mut self) -> Result<(), DecodeError<R::Error>> {
+            if let Some(budget) = &mut self.alloc_budget {
+                *budget = budget
+                    .checked_sub(core::mem::size_of::<T>())
+                    .ok_or(DecodeError::BudgetExceeded)?;
+            }
+            Ok(())
+        }
 
-    #[inline]
-    /// Decode a `fixed64` but keep only the lower 32 bits.
-    ///
-    /// Avoids 64-bit operations for `fixed64` if only the lower bits are needed. This can have
-    /// performance benefits on 32-bit architectures.
-    pub fn decode_fixed64_as_32(&mut self) -> Result<u32, DecodeError<R::Error>> {
-        let n = self.decode_fixed32()?;
-        self.skip_bytes(4)?;
-        Ok(n)
-    }
+        #[inline]
+        /// Decode a `fixed32`.
+        pub $($async)* fn decode_fixed32(&mut self) -> Result<u32, DecodeError<R::Error>> {
+            self.read_array()$(.$await)*.map(u32::from_le_bytes)
+        }
 
-    #[inline]
-    /// Decode a `sfixed32`.
-    pub fn decode_sfixed32(&mut self) -> Result<i32, DecodeError<R::Error>> {
-        self.decode_fixed32().map(|u| u as i32)
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Decode a `fixed64`.
+        pub $($async)* fn decode_fixed64(&mut self) -> Result<u64, DecodeError<R::Error>> {
+            self.read_array()$(.$await)*.map(u64::from_le_bytes)
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Decode a `sfixed64`.
-    pub fn decode_sfixed64(&mut self) -> Result<i64, DecodeError<R::Error>> {
-        self.decode_fixed64().map(|u| u as i64)
-    }
+        #[inline]
+        /// Decode a `fixed64` but keep only the lower 32 bits.
+        ///
+        /// Avoids 64-bit operations for `fixed64` if only the lower bits are needed. This can have
+        /// performance benefits on 32-bit architectures.
+        pub $($async)* fn decode_fixed64_as_32(&mut self) -> Result<u32, DecodeError<R::Error>> {
+            let n = self.decode_fixed32()$(.$await)*?;
+            self.skip_bytes(4)$(.$await)*?;
+            Ok(n)
+        }
 
-    #[inline]
-    /// Decode a `sfixed64` but keep only the lower 32 bits.
-    ///
-    /// Avoids 64-bit operations for `sfixed64` if only the lower bits are needed. This can have
-    /// performance benefits on 32-bit architectures.
-    pub fn decode_sfixed64_as_32(&mut self) -> Result<i32, DecodeError<R::Error>> {
-        let n = self.decode_sfixed32()?;
-        self.skip_bytes(4)?;
-        Ok(n)
-    }
+        #[inline]
+        /// Decode a `sfixed32`.
+        pub $($async)* fn decode_sfixed32(&mut self) -> Result<i32, DecodeError<R::Error>> {
+            self.decode_fixed32()$(.$await)*.map(|u| u as i32)
+        }
 
-    #[inline]
-    /// Decode a `float`.
-    pub fn decode_float(&mut self) -> Result<f32, DecodeError<R::Error>> {
-        self.decode_fixed32().map(f32::from_bits)
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Decode a `sfixed64`.
+        pub $($async)* fn decode_sfixed64(&mut self) -> Result<i64, DecodeError<R::Error>> {
+            self.decode_fixed64()$(.$await)*.map(|u| u as i64)
+        }
 
-    #[inline]
-    /// Decode a `double`.
-    pub fn decode_double(&mut self) -> Result<f64, DecodeError<R::Error>> {
-        let mut data = [MaybeUninit::uninit(); 8];
-        self.read_exact(&mut data)?;
-        // SAFETY: read_exact is guaranteed to write to the whole buffer
-        let data = unsafe { maybe_ununit_array_assume_init(data) };
-        Ok(f64::from_le_bytes(data))
-    }
+        #[inline]
+        /// Decode a `sfixed64` but keep only the lower 32 bits.
+        ///
+        /// Avoids 64-bit operations for `sfixed64` if only the lower bits are needed. This can have
+        /// performance benefits on 32-bit architectures.
+        pub $($async)* fn decode_sfixed64_as_32(&mut self) -> Result<i32, DecodeError<R::Error>> {
+            let n = self.decode_sfixed32()$(.$await)*?;
+            self.skip_bytes(4)$(.$await)*?;
+            Ok(n)
+        }
 
-    #[inline(always)]
-    /// Decode a Protobuf tag.
-    pub fn decode_tag(&mut self) -> Result<Tag, DecodeError<R::Error>> {
-        self.decode_varint32().map(Tag)
-    }
+        #[inline]
+        /// Decode a `float`.
+        pub $($async)* fn decode_float(&mut self) -> Result<f32, DecodeError<R::Error>> {
+            self.decode_fixed32()$(.$await)*.map(f32::from_bits)
+        }
 
-    #[inline]
-    fn read_into_buf<'a>(
-        &mut self,
-        buf: &'a mut [MaybeUninit<u8>],
-        len: usize,
-    ) -> Result<&'a [u8], DecodeError<R::Error>> {
-        if buf.len() < len {
-            return Err(DecodeError::Capacity);
-        }
-        let target = &mut buf[..len];
-        self.read_exact(target)?;
-        // SAFETY: read_exact guarantees that all bytes of target have been initialized
-        Ok(unsafe { maybe_uninit_slice_assume_init_ref(target) })
-    }
+        #[inline]
+        /// Decode a `double`.
+        pub $($async)* fn decode_double(&mut self) -> Result<f64, DecodeError<R::Error>> {
+            self.read_array()$(.$await)*.map(f64::from_le_bytes)
+        }
 
-    /// Decode a `string` into a [`PbString`] container.
-    ///
-    /// The string container's existing contents will be replaced by the string decoded from the
-    /// wire. However, if `presence` is implicit and the new string is empty, the existing string
-    /// will remain unchanged.
-    ///
-    /// # Errors
-    ///
-    /// If the length of the string on the wire exceeds the fixed capacity of the string container,
-    /// return [`DecodeError::Capacity`]. If the string on the wire if not UTF-8, return
-    /// [`DecodeError::Utf8`].
-    pub fn decode_string<S: PbString>(
-        &mut self,
-        string: &mut S,
-        presence: Presence,
-    ) -> Result<(), DecodeError<R::Error>> {
-        let len = self.decode_varint32()? as usize;
-        // With implicit presence, ignore empty strings
-        if len == 0 && presence == Presence::Implicit {
-            return Ok(());
+        #[inline]
+        $($async)* fn read_into_buf<'a>(
+            &mut self,
+            buf: &'a mut [MaybeUninit<u8>],
+            len: usize,
+        ) -> Result<&'a [u8], DecodeError<R::Error>> {
+            if buf.len() < len {
+                return Err(DecodeError::Capacity);
+            }
+            let target = &mut buf[..len];
+            self.read_exact(target)$(.$await)*?;
+            // SAFETY: read_exact guarantees that all bytes of target have been initialized
+            Ok(unsafe { maybe_uninit_slice_assume_init_ref(target) })
         }
 
-        string.pb_clear();
-        string.pb_reserve(len);
-        let spare_cap = string.pb_spare_cap();
-        let written = match self.read_into_buf(spare_cap, len) {
-            Ok(w) => w,
-            Err(e) => {
+        /// Decode a `string` into a [`PbString`] container.
+        ///
+        /// The string container's existing contents will be replaced by the string decoded from the
+        /// wire. However, if `presence` is implicit and the new string is empty, the existing
+        /// string will remain unchanged.
+        ///
+        /// # Errors
+        ///
+        /// If the length of the string on the wire exceeds the fixed capacity of the string
+        /// container, return [`DecodeError::Capacity`]. If the string on the wire if not UTF-8,
+        /// return [`DecodeError::Utf8`].
+        pub $($async)* fn decode_string<S: PbString>(
+            &mut self,
+            string: &mut S,
+            presence: Presence,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let len = self.decode_varint32()$(.$await)*? as usize;
+            // With implicit presence, ignore empty strings
+            if len == 0 && presence == Presence::Implicit {
+                return Ok(());
+            }
+
+            self.spend_budget(len)?;
+            string.pb_clear();
+            string.pb_reserve(len);
+            let spare_cap = string.pb_spare_cap();
+            let written = match self.read_into_buf(spare_cap, len)$(.$await)* {
+                Ok(w) => w,
+                Err(e) => {
+                    // Clear UTF8 errors for fixed-len String
+                    string.pb_clear();
+                    return Err(e);
+                }
+            };
+
+            // Check UTF8 validity
+            if let Err(e) = from_utf8(written) {
                 // Clear UTF8 errors for fixed-len String
                 string.pb_clear();
-                return Err(e);
+                return Err(e.into());
             }
-        };
+            // SAFETY: read_into_buf guarantees that `len` bytes have been written into the string.
+            // Also, we just checked the UTF-8 validity of the written bytes, so the string is
+            // valid.
+            unsafe { string.pb_set_len(len) };
+            Ok(())
+        }
 
-        // Check UTF8 validity
-        if let Err(e) = from_utf8(written) {
-            // Clear UTF8 errors for fixed-len String
-            string.pb_clear();
-            return Err(e.into());
+        /// Decode a `bytes` into a [`PbVec<u8>`](crate::PbVec<u8>) container.
+        ///
+        /// The byte container's existing contents will be replaced by the bytes decoded from the
+        /// wire. However, if `presence` is implicit and the new bytes is empty, the existing
+        /// container will remain unchanged.
+        ///
+        /// # Errors
+        ///
+        /// If the length of the bytes on the wire exceeds the fixed capacity of the byte container,
+        /// return [`DecodeError::Capacity`].
+        pub $($async)* fn decode_bytes<S: PbBytes>(
+            &mut self,
+            bytes: &mut S,
+            presence: Presence,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let len = self.decode_varint32()$(.$await)*? as usize;
+            // With implicit presence, ignore empty strings
+            if len == 0 && presence == Presence::Implicit {
+                return Ok(());
+            }
+
+            self.spend_budget(len)?;
+            bytes.pb_clear();
+            bytes.pb_reserve(len);
+            let spare_cap = bytes.pb_spare_cap();
+            self.read_into_buf(spare_cap, len)$(.$await)*?;
+            // SAFETY: read_into_buf guarantees that `len` bytes have been written into the buffer
+            unsafe { bytes.pb_set_len(len) };
+            Ok(())
         }
-        // SAFETY: read_into_buf guarantees that `len` bytes have been written into the string.
-        // Also, we just checked the UTF-8 validity of the written bytes, so the string is valid.
-        unsafe { string.pb_set_len(len) };
-        Ok(())
-    }
 
-    /// Decode a `bytes` into a [`PbVec<u8>`](crate::PbVec<u8>) container.
-    ///
-    /// The byte container's existing contents will be replaced by the bytes decoded from the
-    /// wire. However, if `presence` is implicit and the new bytes is empty, the existing container
-    /// will remain unchanged.
-    ///
-    /// # Errors
-    ///
-    /// If the length of the bytes on the wire exceeds the fixed capacity of the byte container,
-    /// return [`DecodeError::Capacity`].
-    pub fn decode_bytes<S: PbBytes>(
-        &mut self,
-        bytes: &mut S,
-        presence: Presence,
-    ) -> Result<(), DecodeError<R::Error>> {
-        let len = self.decode_varint32()? as usize;
-        // With implicit presence, ignore empty strings
-        if len == 0 && presence == Presence::Implicit {
-            return Ok(());
-        }
-
-        bytes.pb_clear();
-        bytes.pb_reserve(len);
-        let spare_cap = bytes.pb_spare_cap();
-        self.read_into_buf(spare_cap, len)?;
-        // SAFETY: read_into_buf guarantees that `len` bytes have been written into the buffer
-        unsafe { bytes.pb_set_len(len) };
-        Ok(())
-    }
+        /// Decode a repeated packed field and append the elements to a [`PbVec`] container.
+        ///
+        /// The `decoder` callback determines how each element is decoded from the wire. If the
+        /// number of elements on the wire exceeds the remaining fixed capacity of the container and
+        /// the `ignore_repeated_cap_err` flag is not set, return [`DecodeError::Capacity`].
+        pub $($async)* fn decode_packed<
+            T: Copy,
+            S: PbVec<T>,
+            F: $Fn(&mut Self) -> Result<T, DecodeError<R::Error>>,
+        >(
+            &mut self,
+            vec: &mut S,
+            decoder: F,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let ignore_repeated_cap_err = self.ignore_repeated_cap_err;
+            self.decode_len_record($($async)* |len, before, this: &mut Self| {
+                this.spend_budget(len)?;
+                while this.bytes_read() - before < len {
+                    let val = decoder(this)$(.$await)*?;
+                    if let (Err(_), false) = (vec.pb_push(val), ignore_repeated_cap_err) {
+                        return Err(DecodeError::Capacity);
+                    }
+                }
+                Ok(())
+            })$(.$await)*
+        }
 
-    pub(crate) fn decode_len_record<
-        T,
-        F: FnOnce(usize, usize, &mut Self) -> Result<T, DecodeError<R::Error>>,
-    >(
-        &mut self,
-        decoder: F,
-    ) -> Result<T, DecodeError<R::Error>> {
-        let len = self.decode_varint32()? as usize;
-        let before = self.bytes_read();
-        let val = decoder(len, before, self)?;
-        let actual_len = self.bytes_read() - before;
-        if actual_len != len && !self.ignore_wrong_len {
-            Err(DecodeError::WrongLen)
-        } else {
-            Ok(val)
+        /// Decode a repeated packed field and append some of the elements to a [`PbVec`] container.
+        ///
+        /// Same as [`decode_packed`](Self::decode_packed), except that elements for which the
+        /// `decoder` callback returns `None` are left out of the container. Generated code uses
+        /// this for packed closed enums, whose unrecognized values don't belong in the field.
+        pub $($async)* fn decode_packed_filtered<
+            T: Copy,
+            S: PbVec<T>,
+            F: $FnMut(&mut Self) -> Result<Option<T>, DecodeError<R::Error>>,
+        >(
+            &mut self,
+            vec: &mut S,
+            mut decoder: F,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let ignore_repeated_cap_err = self.ignore_repeated_cap_err;
+            self.decode_len_record($($async)* |len, before, this: &mut Self| {
+                this.spend_budget(len)?;
+                while this.bytes_read() - before < len {
+                    let Some(val) = decoder(this)$(.$await)*? else {
+                        continue;
+                    };
+                    if let (Err(_), false) = (vec.pb_push(val), ignore_repeated_cap_err) {
+                        return Err(DecodeError::Capacity);
+                    }
+                }
+                Ok(())
+            })$(.$await)*
         }
-    }
 
-    /// Decode a repeated packed field and append the elements to a [`PbVec`] container.
-    ///
-    /// The `decoder` callback determines how each element is decoded from the wire. If the number
-    /// of elements on the wire exceeds the remaining fixed capacity of the container and the
-    /// `ignore_repeated_cap_err` flag is not set, return [`DecodeError::Capacity`].
-    pub fn decode_packed<
-        T: Copy,
-        S: PbVec<T>,
-        F: Fn(&mut Self) -> Result<T, DecodeError<R::Error>>,
-    >(
-        &mut self,
-        vec: &mut S,
-        decoder: F,
-    ) -> Result<(), DecodeError<R::Error>> {
-        let ignore_repeated_cap_err = self.ignore_repeated_cap_err;
-        self.decode_len_record(|len, before, this| {
-            while this.bytes_read() - before < len {
-                let val = decoder(this)?;
-                if let (Err(_), false) = (vec.pb_push(val), ignore_repeated_cap_err) {
-                    return Err(DecodeError::Capacity);
+        /// Pass a varint field that has already been decoded to a handler of unknown fields.
+        ///
+        /// `value` is re-encoded and handed to `handler` as a varint field numbered `field_num`, as
+        /// if it was read straight from the wire. Generated code uses this for closed enum values
+        /// that don't match any variant, since Protobuf treats them as unknown fields. If `handler`
+        /// doesn't accept the field, the value is dropped.
+        pub $($async)* fn decode_unknown_varint<H: $FieldDecode>(
+            &mut self,
+            handler: &mut H,
+            field_num: u32,
+            value: u64,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let (bytes, len) = varint64_bytes(value);
+            let mut decoder = $Decoder::new(&bytes[..len]);
+            decoder.ignore_repeated_cap_err = self.ignore_repeated_cap_err;
+            handler
+                .$decode_field(
+                    Tag::from_parts(field_num, WIRE_TYPE_VARINT),
+                    &mut decoder,
+                )$(.$await)*
+                .map(drop)
+                .map_err(DecodeError::widen)
+        }
+
+        /// Pass a map entry that has already been decoded to a handler of unknown fields.
+        ///
+        /// The entry is re-encoded from `key` and the varint `value`, and handed to `handler` as a
+        /// length-delimited field numbered `field_num`. Generated code uses this for map entries
+        /// with closed enum values that don't match any variant, since Protobuf treats the whole
+        /// entry as an unknown field. If `handler` doesn't accept the field, the entry is dropped.
+        pub $($async)* fn decode_unknown_map_entry<H: $FieldDecode>(
+            &mut self,
+            handler: &mut H,
+            field_num: u32,
+            key: MapKey<'_>,
+            value: u64,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let mut decoder = $Decoder::new(MapEntryReader::new(key, value));
+            decoder.ignore_repeated_cap_err = self.ignore_repeated_cap_err;
+            handler
+                .$decode_field(Tag::from_parts(field_num, WIRE_TYPE_LEN), &mut decoder)$(.$await)*
+                .map(drop)
+                .map_err(DecodeError::widen)
+        }
+
+        /// Decode a repeated packed field of fixed-size elements and append them to a [`PbVec`]
+        /// container.
+        ///
+        /// Equivalent to [`decode_packed`](Self::decode_packed) with
+        /// [`decode_fixed32`](Self::decode_fixed32) or similar as the callback, but much faster for
+        /// large fields. If the container has enough spare capacity for all the elements, the
+        /// elements are copied from the reader into the container in bulk, rather than decoded one
+        /// at a time.
+        ///
+        /// If the length of the record is not a multiple of the element size and the
+        /// `ignore_wrong_len` flag is not set, return [`DecodeError::WrongLen`]. Capacity errors
+        /// are handled the same way as [`decode_packed`](Self::decode_packed).
+        pub $($async)* fn decode_packed_fixed<T: FixedWidth, S: PbVec<T>>(
+            &mut self,
+            vec: &mut S,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let len = self.decode_varint32()$(.$await)*? as usize;
+            let elem_size = core::mem::size_of::<T>();
+            let remainder = len % elem_size;
+            if remainder != 0 && !self.ignore_wrong_len {
+                return Err(DecodeError::WrongLen);
+            }
+            let elem_num = len / elem_size;
+
+            self.spend_budget(len)?;
+            vec.pb_reserve(elem_num);
+            if let Some(spare_cap) = vec.pb_spare_cap().get_mut(..elem_num) {
+                // SAFETY: `spare_cap` has exactly `elem_num` elements, so its size in bytes is
+                // `elem_num * size_of::<T>()`. Any byte pattern is a valid `T`, since `T` is a
+                // primitive number type.
+                let spare_bytes = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        spare_cap.as_mut_ptr() as *mut MaybeUninit<u8>,
+                        elem_num * elem_size,
+                    )
+                };
+                self.read_exact(spare_bytes)$(.$await)*?;
+                #[cfg(target_endian = "big")]
+                for elem in spare_cap.iter_mut() {
+                    // SAFETY: read_exact initialized all the elements
+                    elem.write(unsafe { elem.assume_init() }.swap_le());
+                }
+                // SAFETY: We just wrote `elem_num` elements into the spare capacity
+                unsafe { vec.pb_extend_len(elem_num) };
+            } else {
+                // Not enough spare capacity, so push the elements one by one
+                for _ in 0..elem_num {
+                    let mut elem = MaybeUninit::<T>::uninit();
+                    // SAFETY: The byte slice covers exactly the memory of `elem`
+                    let elem_bytes = unsafe {
+                        core::slice::from_raw_parts_mut(
+                            elem.as_mut_ptr() as *mut MaybeUninit<u8>,
+                            elem_size,
+                        )
+                    };
+                    self.read_exact(elem_bytes)$(.$await)*?;
+                    // SAFETY: read_exact initialized all bytes of `elem`
+                    let elem = unsafe { elem.assume_init() }.swap_le();
+                    if let (Err(_), false) = (vec.pb_push(elem), self.ignore_repeated_cap_err) {
+                        return Err(DecodeError::Capacity);
+                    }
                 }
             }
+            self.skip_bytes(remainder)$(.$await)*
+        }
+
+        /// Decode a Protobuf map key-value pair from the decoder.
+        ///
+        /// According the the Protobuf spec, the key-value pair is formatted as a Protobuf message
+        /// with the key in field 1 and the value in field 2. Other field numbers are ignored.
+        ///
+        /// The `key_update` and `val_update` callbacks are expected to decode the key and value
+        /// respectively. If either key or value field is not found, return `None`.
+        pub $($async)* fn decode_map_elem<
+            K: Default,
+            V: Default,
+            UK: $Fn(&mut K, &mut Self) -> Result<(), DecodeError<R::Error>>,
+            UV: $Fn(&mut V, &mut Self) -> Result<(), DecodeError<R::Error>>,
+        >(
+            &mut self,
+            key_update: UK,
+            val_update: UV,
+        ) -> Result<Option<(K, V)>, DecodeError<R::Error>> {
+            let mut key = None;
+            let mut val = None;
+            // Map entries are a nested level in the field path
+            #[cfg(feature = "detailed-errors")]
+            self.trace.enter();
+            let res = self.decode_len_record($($async)* |len, before, this: &mut Self| {
+                while this.bytes_read() - before < len {
+                    let tag = this.decode_tag()$(.$await)*?;
+                    match tag.field_num() {
+                        1 => key_update(key.get_or_insert_with(K::default), this)$(.$await)*?,
+                        2 => val_update(val.get_or_insert_with(V::default), this)$(.$await)*?,
+                        _ => this.skip_wire_value(tag.wire_type())$(.$await)*?,
+                    }
+                }
+                Ok(())
+            })$(.$await)*;
+            #[cfg(feature = "detailed-errors")]
+            self.trace.exit(&res, self.idx);
+            res?;
+
+            if let (Some(key), Some(val)) = (key, val) {
+                Ok(Some((key, val)))
+            } else {
+                Ok(None)
+            }
+        }
+
+        $($async)* fn skip_varint(&mut self) -> Result<(), DecodeError<R::Error>> {
+            for _ in 0..10 {
+                let b = self.get_byte()$(.$await)*?;
+                if b & 0x80 == 0 {
+                    return Ok(());
+                }
+            }
+            Err(DecodeError::VarIntLimit)
+        }
+
+        /// Consume some bytes from the reader.
+        ///
+        /// If reader reached EOF before the specified number of bytes are skipped, return
+        /// [`DecodeError::UnexpectedEof`].
+        pub $($async)* fn skip_bytes(&mut self, bytes: usize) -> Result<(), DecodeError<R::Error>> {
+            let mut total = 0;
+            while total < bytes {
+                let chunk = self.reader.pb_read_chunk()$(.$await)*.map_err(DecodeError::Reader)?;
+                if chunk.is_empty() {
+                    return Err(DecodeError::UnexpectedEof);
+                }
+                let n = chunk.len().min(bytes - total);
+                self.advance(n);
+                total += n;
+            }
+            debug_assert_eq!(total, bytes);
             Ok(())
-        })
-    }
+        }
 
-    //#[cfg(target_endian = "little")]
-    //pub fn decode_packed_fixed<T: DecodeFixedSize, S: PbVec<T>>(
-    //&mut self,
-    //vec: &mut S,
-    //) -> Result<(), DecodeError<R::Error>> {
-    //let len = self.decode_varint32()? as usize;
-    //let elem_size = core::mem::size_of::<T>();
-    //let modulo = len % elem_size;
-    //// Length must be a multiple of elem_size
-    //if modulo > 0 {
-    //return Err(DecodeError::WrongLen {
-    //expected: len,
-    //// Previous multiple of elem_size
-    //actual: len - modulo,
-    //});
-    //}
-    //let elem_num = len / elem_size;
-    //vec.pb_reserve(elem_num);
-    //let spare_cap = vec.pb_spare_cap();
-    //if spare_cap.len() < elem_num {
-    //return Err(DecodeError::Capacity);
-    //}
-    //// SAFETY: Converting slice into uninitialized bytes is always valid. Moreover, we know
-    //// that `spare_cap` has equal or more than `elem_num` values, so its size in bytes can't
-    //// be less than `len`, because `len` is equal to `elem_num * size_of<T>()`.
-    //let spare_bytes = unsafe {
-    //core::slice::from_raw_parts_mut(spare_cap.as_mut_ptr() as *mut MaybeUninit<u8>, len)
-    //};
-    //self.read_exact(spare_bytes)?;
-    //// SAFETY: We just wrote elem_num of elements into the spare space, so we can
-    //// increase the length by that much
-    //unsafe { vec.pb_set_len(vec.len() + elem_num) };
-    //Ok(())
-    //}
-
-    /// Decode a Protobuf map key-value pair from the decoder.
-    ///
-    /// According the the Protobuf spec, the key-value pair is formatted as a Protobuf message with
-    /// the key in field 1 and the value in field 2. Other field numbers are ignored.
-    ///
-    /// The `key_update` and `val_update` callbacks are expected to decode the key and value
-    /// respectively. If either key or value field is not found, return `None`.
-    pub fn decode_map_elem<
-        K: Default,
-        V: Default,
-        UK: Fn(&mut K, &mut Self) -> Result<(), DecodeError<R::Error>>,
-        UV: Fn(&mut V, &mut Self) -> Result<(), DecodeError<R::Error>>,
-    >(
-        &mut self,
-        key_update: UK,
-        val_update: UV,
-    ) -> Result<Option<(K, V)>, DecodeError<R::Error>> {
-        let mut key = None;
-        let mut val = None;
-        self.decode_len_record(|len, before, this| {
-            while this.bytes_read() - before < len {
-                let tag = this.decode_tag()?;
-                match tag.field_num() {
-                    1 => key_update(key.get_or_insert_with(K::default), this)?,
-                    2 => val_update(val.get_or_insert_with(V::default), this)?,
-                    _ => this.skip_wire_value(tag.wire_type())?,
+        /// Skip the next Protobuf value/payload on the wire.
+        ///
+        /// The type of the Protobuf payload is determined by `wire_type`, which must be a valid
+        /// Protobuf wire type. This is mainly used to skip unknown fields.
+        ///
+        /// For [`WIRE_TYPE_SGROUP`], everything up to and including the end-group tag that closes
+        /// the group is skipped. The field numbers of the end-group tags aren't checked. A lone
+        /// [`WIRE_TYPE_EGROUP`] returns [`DecodeError::UnexpectedEndGroup`].
+        pub $($async)* fn skip_wire_value(
+            &mut self,
+            wire_type: u8,
+        ) -> Result<(), DecodeError<R::Error>> {
+            match wire_type {
+                WIRE_TYPE_SGROUP => self.skip_group()$(.$await)*,
+                WIRE_TYPE_EGROUP => Err(DecodeError::UnexpectedEndGroup),
+                _ => self.skip_single_value(wire_type)$(.$await)*,
+            }
+        }
+
+        /// Skip a value of any wire type other than the group ones
+        $($async)* fn skip_single_value(
+            &mut self,
+            wire_type: u8,
+        ) -> Result<(), DecodeError<R::Error>> {
+            match wire_type {
+                WIRE_TYPE_VARINT => self.skip_varint()$(.$await)*?,
+                WIRE_TYPE_I64 => self.skip_bytes(8)$(.$await)*?,
+                WIRE_TYPE_LEN => {
+                    let len = self.decode_varint32()$(.$await)*? as usize;
+                    self.skip_bytes(len)$(.$await)*?;
                 }
+                WIRE_TYPE_I32 => self.skip_bytes(4)$(.$await)*?,
+                _ => return Err(DecodeError::UnknownWireType),
             }
             Ok(())
-        })?;
+        }
 
-        if let (Some(key), Some(val)) = (key, val) {
-            Ok(Some((key, val)))
-        } else {
-            Ok(None)
+        /// Skip the rest of a group whose start tag has already been decoded
+        $($async)* fn skip_group(&mut self) -> Result<(), DecodeError<R::Error>> {
+            // Track nested groups with a counter rather than recursion, so that deeply nested input
+            // can't overflow the stack
+            let mut nested = 0usize;
+            loop {
+                match self.decode_varint32()$(.$await)*? as u8 & 0b111 {
+                    WIRE_TYPE_SGROUP => nested += 1,
+                    WIRE_TYPE_EGROUP if nested == 0 => return Ok(()),
+                    WIRE_TYPE_EGROUP => nested -= 1,
+                    wire_type => self.skip_single_value(wire_type)$(.$await)*?,
+                }
+            }
         }
-    }
 
-    fn skip_varint(&mut self) -> Result<(), DecodeError<R::Error>> {
-        for _ in 0..10 {
-            let b = self.get_byte()?;
-            if b & 0x80 == 0 {
+        /// Read the raw bytes of a varint into `buf`, returning the number of bytes read
+        $($async)* fn read_raw_varint(
+            &mut self,
+            buf: &mut [u8; 10],
+        ) -> Result<usize, DecodeError<R::Error>> {
+            for (i, slot) in buf.iter_mut().enumerate() {
+                let b = self.get_byte()$(.$await)*?;
+                *slot = b;
+                if b & 0x80 == 0 {
+                    return Ok(i + 1);
+                }
+            }
+            Err(DecodeError::VarIntLimit)
+        }
+
+        /// Read the part of a field that comes before its payload, which is the varint itself for
+        /// `VARINT` and the length prefix for `LEN`. Returns the head and the length of the
+        /// payload.
+        ///
+        /// Returns `None` without consuming anything for group wire types.
+        $($async)* fn read_raw_head(
+            &mut self,
+            wire_type: u8,
+            head: &mut [u8; 10],
+        ) -> Result<Option<(usize, usize)>, DecodeError<R::Error>> {
+            let res = match wire_type {
+                WIRE_TYPE_VARINT => (self.read_raw_varint(head)$(.$await)*?, 0),
+                WIRE_TYPE_I64 => (0, 8),
+                WIRE_TYPE_LEN => {
+                    let n = self.read_raw_varint(head)$(.$await)*?;
+                    let len = varint32_from_bytes(&head[..n]) as usize;
+                    self.spend_budget(len)?;
+                    (n, len)
+                }
+                WIRE_TYPE_I32 => (0, 4),
+                WIRE_TYPE_SGROUP | WIRE_TYPE_EGROUP => return Ok(None),
+                _ => return Err(DecodeError::UnknownWireType),
+            };
+            Ok(Some(res))
+        }
+
+        /// Append `tag`, `head`, and the next `payload_len` bytes to `buf`.
+        ///
+        /// Returns `false` without consuming anything if `buf` doesn't have enough capacity.
+        $($async)* fn append_raw<B: PbBytes + Deref<Target = [u8]>>(
+            &mut self,
+            buf: &mut B,
+            tag: Tag,
+            head: &[u8],
+            payload_len: usize,
+        ) -> Result<bool, DecodeError<R::Error>> {
+            let (tag_bytes, tag_len) = varint32_bytes(tag.varint());
+            let prefix_len = tag_len + head.len();
+
+            let old_len = buf.len();
+            buf.pb_reserve(prefix_len + payload_len);
+            let spare_cap = buf.pb_spare_cap();
+            if spare_cap.len() < prefix_len + payload_len {
+                return Ok(false);
+            }
+            let (prefix, payload) = spare_cap.split_at_mut(prefix_len);
+            let (tag_slot, head_slot) = prefix.split_at_mut(tag_len);
+            maybe_uninit_write_slice(tag_slot, &tag_bytes[..tag_len]);
+            maybe_uninit_write_slice(head_slot, head);
+            self.read_into_buf(payload, payload_len)$(.$await)*?;
+            // SAFETY: the tag, the varint head, and the payload have all been written into the
+            // spare capacity, which covers `prefix_len + payload_len` bytes
+            unsafe { buf.pb_set_len(old_len + prefix_len + payload_len) };
+            Ok(true)
+        }
+
+        /// Append the field denoted by `tag` to `buf` as raw bytes, including the tag itself.
+        ///
+        /// Groups are captured along with their end-group tags. Returns `false` without consuming
+        /// anything if the wire type can't be captured.
+        pub(crate) $($async)* fn decode_raw_field<B: PbBytes + Deref<Target = [u8]>>(
+            &mut self,
+            tag: Tag,
+            buf: &mut B,
+        ) -> Result<bool, DecodeError<R::Error>> {
+            let mut head = [0; 10];
+            let (head_len, payload_len) = match tag.wire_type() {
+                WIRE_TYPE_SGROUP => {
+                    self.decode_raw_group(tag, buf)$(.$await)*?;
+                    return Ok(true);
+                }
+                wire_type => match self.read_raw_head(wire_type, &mut head)$(.$await)* {
+                    Ok(Some(res)) => res,
+                    Ok(None) | Err(DecodeError::UnknownWireType) => return Ok(false),
+                    Err(e) => return Err(e),
+                },
+            };
+            if !self.append_raw(buf, tag, &head[..head_len], payload_len)$(.$await)*? {
+                if self.ignore_repeated_cap_err {
+                    self.skip_bytes(payload_len)$(.$await)*?;
+                    return Ok(true);
+                }
+                return Err(DecodeError::Capacity);
+            }
+            Ok(true)
+        }
+
+        /// Append a group to `buf` as raw bytes, from its start tag to its end tag. If the group
+        /// doesn't fit, none of it is appended.
+        $($async)* fn decode_raw_group<B: PbBytes + Deref<Target = [u8]>>(
+            &mut self,
+            start_tag: Tag,
+            buf: &mut B,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let old_len = buf.len();
+            let mut fits = self.append_raw(buf, start_tag, &[], 0)$(.$await)*?;
+            let mut nested = 0usize;
+            let mut closed = false;
+            while fits && !closed {
+                let tag = self.decode_tag()$(.$await)*?;
+                let mut head = [0; 10];
+                let (head_len, payload_len) = self
+                    .read_raw_head(tag.wire_type(), &mut head)$(.$await)*?
+                    .unwrap_or((0, 0));
+                fits = self.append_raw(buf, tag, &head[..head_len], payload_len)$(.$await)*?;
+                match tag.wire_type() {
+                    WIRE_TYPE_SGROUP => nested += 1,
+                    WIRE_TYPE_EGROUP if nested == 0 => closed = true,
+                    WIRE_TYPE_EGROUP => nested -= 1,
+                    _ if !fits => self.skip_bytes(payload_len)$(.$await)*?,
+                    _ => {}
+                }
+            }
+            if fits {
                 return Ok(());
             }
+
+            // SAFETY: shrinking the length only drops initialized bytes
+            unsafe { buf.pb_set_len(old_len) };
+            if !self.ignore_repeated_cap_err {
+                return Err(DecodeError::Capacity);
+            }
+            if !closed {
+                for _ in 0..=nested {
+                    self.skip_group()$(.$await)*?;
+                }
+            }
+            Ok(())
         }
-        Err(DecodeError::VarIntLimit)
-    }
 
-    /// Consume some bytes from the reader.
-    ///
-    /// If reader reached EOF before the specified number of bytes are skipped, return
-    /// [`DecodeError::UnexpectedEof`].
-    pub fn skip_bytes(&mut self, bytes: usize) -> Result<(), DecodeError<R::Error>> {
-        let mut total = 0;
-        while total < bytes {
-            let chunk = self.reader.pb_read_chunk().map_err(DecodeError::Reader)?;
-            if chunk.is_empty() {
-                return Err(DecodeError::UnexpectedEof);
+        /// Attach the location of the last decoding error to `error`.
+        ///
+        /// The location consists of the byte offset and the [`FieldPath`](crate::FieldPath) of the
+        /// field that was being decoded when the error happened. The path is tracked via the tags
+        /// and nested messages decoded by generated code, so this should be called right after a
+        /// decoding function returns `error`, before decoding anything else.
+        #[cfg(feature = "detailed-errors")]
+        pub fn detailed_error(
+            &self,
+            error: DecodeError<R::Error>,
+        ) -> DetailedDecodeError<R::Error> {
+            self.trace.attach(error, self.idx)
+        }
+
+        /// Decode a length-delimited message field into `msg`, one nesting level deeper than the
+        /// current message.
+        ///
+        /// Used by generated code to decode message fields. If the nesting depth would exceed
+        /// [`max_depth`](Self::max_depth), return [`DecodeError::MaxDepth`] without decoding
+        /// anything.
+        pub $($async)* fn decode_nested_message<M: $MessageDecode>(
+            &mut self,
+            msg: &mut M,
+        ) -> Result<(), DecodeError<R::Error>> {
+            if self.depth >= self.max_depth {
+                return Err(DecodeError::MaxDepth);
             }
-            let n = chunk.len().min(bytes - total);
-            self.advance(n);
-            total += n;
+            self.depth += 1;
+            #[cfg(feature = "detailed-errors")]
+            self.trace.enter();
+            let group = self.group.take();
+            let res = msg.$decode_len_delimited(self)$(.$await)*;
+            self.group = group;
+            #[cfg(feature = "detailed-errors")]
+            self.trace.exit(&res, self.idx);
+            self.depth -= 1;
+            res
         }
-        debug_assert_eq!(total, bytes);
-        Ok(())
-    }
 
-    /// Skip the next Protobuf value/payload on the wire.
-    ///
-    /// The type of the Protobuf payload is determined by `wire_type`, which must be a valid
-    /// Protobuf wire type. This is mainly used to skip unknown fields.
-    pub fn skip_wire_value(&mut self, wire_type: u8) -> Result<(), DecodeError<R::Error>> {
-        match wire_type {
-            WIRE_TYPE_VARINT => self.skip_varint()?,
-            WIRE_TYPE_I64 => self.skip_bytes(8)?,
-            WIRE_TYPE_LEN => {
-                let len = self.decode_varint32()? as usize;
-                self.skip_bytes(len)?;
+        /// Decode a group field with the field number `field_num` into `msg`, one nesting level
+        /// deeper than the current message. The start-group tag must have already been decoded.
+        ///
+        /// Used by generated code to decode proto2 groups and fields with `DELIMITED` message
+        /// encoding. The group ends at the end-group tag with the same field number, which is
+        /// consumed by [`end_group`](Self::end_group) when the message decodes it. If the nesting
+        /// depth would exceed [`max_depth`](Self::max_depth), return [`DecodeError::MaxDepth`]
+        /// without decoding anything.
+        pub $($async)* fn decode_nested_group<M: $MessageDecode>(
+            &mut self,
+            msg: &mut M,
+            field_num: u32,
+        ) -> Result<(), DecodeError<R::Error>> {
+            if self.depth >= self.max_depth {
+                return Err(DecodeError::MaxDepth);
             }
-            3 | 4 => return Err(DecodeError::Deprecation),
-            WIRE_TYPE_I32 => self.skip_bytes(4)?,
-            _ => return Err(DecodeError::UnknownWireType),
+            self.depth += 1;
+            #[cfg(feature = "detailed-errors")]
+            self.trace.enter();
+            let group = self.group.replace(field_num);
+            // Since the length of the group is unknown, the message decodes until it reaches the
+            // end-group tag, which clears the group number
+            let res = match msg.$decode(self, usize::MAX)$(.$await)* {
+                Ok(()) if self.group.is_some() => Err(DecodeError::UnexpectedEof),
+                res => res,
+            };
+            self.group = group;
+            #[cfg(feature = "detailed-errors")]
+            self.trace.exit(&res, self.idx);
+            self.depth -= 1;
+            res
+        }
+
+        /// Handle an end-group tag decoded by a message.
+        ///
+        /// Used by generated code. If `tag` closes the group being decoded by
+        /// [`decode_nested_group`](Self::decode_nested_group), the message is complete and should
+        /// stop decoding. Otherwise, return [`DecodeError::UnexpectedEndGroup`].
+        pub fn end_group(&mut self, tag: Tag) -> Result<(), DecodeError<R::Error>> {
+            if tag.wire_type() == WIRE_TYPE_EGROUP && self.group == Some(tag.field_num()) {
+                self.group = None;
+                Ok(())
+            } else {
+                Err(DecodeError::UnexpectedEndGroup)
+            }
+        }
+
+        /// Decode a new message from the wire.
+        pub $($async)* fn decode_message<M: $MessageDecode + Default>(
+            &mut self,
+            len: usize,
+        ) -> Result<M, DecodeError<R::Error>> {
+            let mut msg = M::default();
+            msg.$decode(self, len)$(.$await)*?;
+            Ok(msg)
+        }
+    };
+}
+#[cfg(feature = "async")]
+pub(crate) use decoder_methods;
+
+impl<R: PbRead> PbDecoder<R> {
+    #[inline]
+    /// Construct a new decoder from a [`PbRead`].
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            idx: 0,
+            ignore_repeated_cap_err: false,
+            ignore_wrong_len: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_field_len: None,
+            alloc_budget: None,
+            depth: 0,
+            group: None,
+            clean_eof: false,
+            #[cfg(feature = "detailed-errors")]
+            trace: ErrorTrace::default(),
         }
+    }
+
+    decoder_methods!(
+        PbDecoder,
+        [],
+        [],
+        Fn,
+        FnMut,
+        FnOnce,
+        FieldDecode::decode_field,
+        MessageDecode::{decode, decode_len_delimited},
+    );
+
+    /// Decode a length-delimited message followed by a checksum, as written by
+    /// [`MessageEncode::encode_framed`](crate::MessageEncode::encode_framed).
+    pub(crate) fn decode_framed<C: Crc, M: MessageDecode + ?Sized>(
+        &mut self,
+        msg: &mut M,
+    ) -> Result<(), DecodeError<R::Error>> {
+        // Decode through a checksummed reader, using the same settings and state as this decoder
+        let mut framed = PbDecoder {
+            reader: CrcReader::<_, C>::new(&mut self.reader),
+            idx: self.idx,
+            ignore_repeated_cap_err: self.ignore_repeated_cap_err,
+            ignore_wrong_len: self.ignore_wrong_len,
+            max_depth: self.max_depth,
+            max_field_len: self.max_field_len,
+            alloc_budget: self.alloc_budget,
+            depth: self.depth,
+            group: None,
+            clean_eof: false,
+            #[cfg(feature = "detailed-errors")]
+            trace: core::mem::take(&mut self.trace),
+        };
+        let res = msg.decode_len_delimited(&mut framed);
+        self.idx = framed.idx;
+        self.alloc_budget = framed.alloc_budget;
+        #[cfg(feature = "detailed-errors")]
+        {
+            self.trace = core::mem::take(&mut framed.trace);
+        }
+        if let Err(e) = res {
+            // Consume the bytes that were decoded before the error from the underlying reader
+            framed.reader.into_inner();
+            return Err(e);
+        }
+        framed.reader.verify()?;
+        self.idx += C::LEN;
         Ok(())
     }
 
-    /// Decode a new message from the wire.
-    pub fn decode_message<M: MessageDecode + Default>(
+    /// Iterate over a stream of length-delimited messages of type `M`.
+    ///
+    /// Each record in the stream is a message preceded by its length, same as the format written
+    /// by [`MessageEncode::encode_len_delimited`](crate::MessageEncode::encode_len_delimited).
+    /// See [`LenDelimitedMessages`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use micropb::{MessageDecode, PbDecoder, PbRead};
+    ///
+    /// # #[derive(Default)]
+    /// # struct ProtoMessage;
+    /// # impl MessageDecode for ProtoMessage {
+    /// #   fn decode<R: PbRead>(&mut self, decoder: &mut PbDecoder<R>, len: usize) -> Result<(), micropb::DecodeError<R::Error>> { todo!() }
+    /// # }
+    /// let data = [0x02, 0x08, 0x01, 0x02, 0x08, 0x02];
+    /// let mut decoder = PbDecoder::new(data.as_slice());
+    /// for msg in decoder.len_delimited_messages::<ProtoMessage>() {
+    ///     let msg = msg?;
+    /// }
+    /// # Ok::<(), micropb::DecodeError<core::convert::Infallible>>(())
+    /// ```
+    pub fn len_delimited_messages<M: MessageDecode + Default>(
         &mut self,
-        len: usize,
-    ) -> Result<M, DecodeError<R::Error>> {
+    ) -> LenDelimitedMessages<'_, R, M> {
+        LenDelimitedMessages {
+            decoder: self,
+            done: false,
+            _msg: PhantomData,
+        }
+    }
+}
+
+/// Iterator over a stream of length-delimited messages, returned by
+/// [`PbDecoder::len_delimited_messages`].
+///
+/// The iterator returns `None` once the reader reaches EOF at a record boundary. If the stream
+/// ends in the middle of a record, the iterator returns [`DecodeError::UnexpectedEof`] instead.
+/// After returning an error, the iterator is exhausted, since the position of the next record is
+/// unknown.
+///
+/// To avoid moving a new message out of the iterator on each record, use
+/// [`next_into`](Self::next_into) to decode into an existing message instead.
+#[derive(Debug)]
+pub struct LenDelimitedMessages<'a, R: PbRead, M> {
+    decoder: &'a mut PbDecoder<R>,
+    done: bool,
+    _msg: PhantomData<fn() -> M>,
+}
+
+impl<R: PbRead, M: MessageDecode + Default> LenDelimitedMessages<'_, R, M> {
+    /// Decode the next record into `msg`, which is reset to its default value beforehand.
+    ///
+    /// Since `msg` is overwritten with `M::default()` rather than cleared in place, any heap
+    /// allocations it holds are freed and not reused. The only saving over [`Iterator::next`] is
+    /// that the decoded message isn't moved out of the iterator, which matters for large messages
+    /// with fixed-capacity containers.
+    ///
+    /// Returns `None` on a clean EOF, same as [`Iterator::next`].
+    pub fn next_into(&mut self, msg: &mut M) -> Option<Result<(), DecodeError<R::Error>>> {
+        if self.done {
+            return None;
+        }
+        let res = match self.decoder.is_eof() {
+            Ok(true) => {
+                self.done = true;
+                return None;
+            }
+            Ok(false) => {
+                *msg = M::default();
+                msg.decode_len_delimited(self.decoder)
+            }
+            Err(e) => Err(e),
+        };
+        self.done = res.is_err();
+        Some(res)
+    }
+}
+
+impl<R: PbRead, M: MessageDecode + Default> Iterator for LenDelimitedMessages<'_, R, M> {
+    type Item = Result<M, DecodeError<R::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
         let mut msg = M::default();
-        msg.decode(self, len)?;
-        Ok(msg)
+        self.next_into(&mut msg).map(|res| res.map(|_| msg))
     }
 }
 
+impl<R: PbRead, M: MessageDecode + Default> core::iter::FusedIterator
+    for LenDelimitedMessages<'_, R, M>
+{
+}
+
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
@@ -1052,8 +1903,22 @@ mod tests {
             skip_wire_value(WIRE_TYPE_LEN)
         );
 
-        assert_decode!(Err(DecodeError::Deprecation), [], skip_wire_value(3));
-        assert_decode!(Err(DecodeError::Deprecation), [], skip_wire_value(4));
+        // Nested group with a varint and a LEN, followed by the end-group tag
+        assert_decode!(
+            Ok(()),
+            [0x08, 0x01, 0x13, 0x12, 0x01, 0xAA, 0x14, 0x0C],
+            skip_wire_value(WIRE_TYPE_SGROUP)
+        );
+        assert_decode!(
+            Err(DecodeError::UnexpectedEof),
+            [0x08, 0x01, 0x13, 0x14],
+            skip_wire_value(WIRE_TYPE_SGROUP)
+        );
+        assert_decode!(
+            Err(DecodeError::UnexpectedEndGroup),
+            [],
+            skip_wire_value(WIRE_TYPE_EGROUP)
+        );
         assert_decode!(Err(DecodeError::UnknownWireType), [], skip_wire_value(10));
     }
 
@@ -1263,44 +2128,279 @@ mod tests {
     container_test!(packed, packed_alloc, Vec<_>, false, false);
     container_test!(packed, packed_cow, Cow::<'static, [_]>, false, false);
 
-    //#[cfg(target_endian = "little")]
-    //fn packed_fixed<S: PbVec<u32>>(fixed_cap: bool) {
-    //let mut vec1 = S::default();
-    //let mut vec2 = S::default();
-    //assert_decode_vec!(Ok(&[]), [0], decode_packed_fixed(vec1 | vec2));
-    //assert_decode_vec!(
-    //Ok(&[0x04030201]),
-    //[4, 0x01, 0x02, 0x03, 0x04],
-    //decode_packed_fixed(vec1 | vec2)
-    //);
-    //assert_decode_vec!(
-    //Ok(&[0x04030201, 0x0D0C0B0A, 0x44332211]),
-    //[8, 0x0A, 0x0B, 0x0C, 0x0D, 0x11, 0x22, 0x33, 0x44],
-    //decode_packed_fixed(vec1 | vec2)
-    //);
-    //if fixed_cap {
-    //assert_decode_vec!(
-    //Err(DecodeError::Capacity),
-    //[4, 0x01, 0x02, 0x03, 0x04],
-    //decode_packed_fixed(vec1 | vec2)
-    //);
-    //}
-    //assert_decode_vec!(
-    //Err(DecodeError::WrongLen {
-    //expected: 1,
-    //actual: 0
-    //}),
-    //[1, 0x01],
-    //decode_packed_fixed(vec1 | vec2)
-    //);
-    //}
-
-    //#[cfg(target_endian = "little")]
-    //container_test!(packed_fixed, pf_arrayvec, ArrayVec::<_, 3>, true);
-    //#[cfg(target_endian = "little")]
-    //container_test!(packed_fixed, pf_heapless, heapless::Vec::<_, 3>, true);
-    //#[cfg(target_endian = "little")]
-    //container_test!(packed_fixed, pf_alloc, Vec<_>, false);
+    fn packed_fixed<S: PbVec<u32> + AsRef<[u32]> + Default>(fixed_cap: bool, _fixed_len: bool) {
+        let mut vec1 = S::default();
+        let mut vec2 = S::default();
+        assert_decode_vec!(Ok(&[]), [0], decode_packed_fixed(vec1 | vec2));
+        assert_decode_vec!(
+            Ok(&[0x04030201]),
+            [4, 0x01, 0x02, 0x03, 0x04],
+            decode_packed_fixed(vec1 | vec2)
+        );
+        assert_decode_vec!(
+            Ok(&[0x04030201, 0x0D0C0B0A, 0x44332211]),
+            [8, 0x0A, 0x0B, 0x0C, 0x0D, 0x11, 0x22, 0x33, 0x44],
+            decode_packed_fixed(vec1 | vec2)
+        );
+        if fixed_cap {
+            // Not enough capacity for bulk copy, so elements are pushed until the vec is full
+            assert_decode_vec!(
+                Err(DecodeError::Capacity),
+                [8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                decode_packed_fixed(vec1 | vec2)
+            );
+            assert_eq!(
+                vec1.as_ref(),
+                &[0x04030201, 0x0D0C0B0A, 0x44332211, 0x04030201]
+            );
+        }
+
+        let mut vec1 = S::default();
+        let mut vec2 = S::default();
+        assert_decode_vec!(
+            Err(DecodeError::WrongLen),
+            [5, 0x01, 0x02, 0x03, 0x04, 0x05],
+            decode_packed_fixed(vec1 | vec2)
+        );
+        assert_decode_vec!(
+            Err(DecodeError::UnexpectedEof),
+            [4, 0x01, 0x02],
+            decode_packed_fixed(vec1 | vec2)
+        );
+        assert_eq!(vec1.as_ref(), &[]);
+    }
+
+    container_test!(packed_fixed, pf_arrayvec, ArrayVec::<_, 4>, true, false);
+    container_test!(
+        packed_fixed,
+        pf_heapless,
+        heapless::Vec::<_, 4>,
+        true,
+        false
+    );
+    container_test!(packed_fixed, pf_alloc, Vec<_>, false, false);
+    container_test!(packed_fixed, pf_cow, Cow::<'static, [_]>, false, false);
+
+    #[test]
+    fn packed_fixed_float() {
+        let mut vec = Vec::<f32>::new();
+        let data = [8, 0x00, 0x00, 0xC0, 0x3F, 0x00, 0x00, 0x10, 0xC0];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.decode_packed_fixed(&mut vec).unwrap();
+        assert_eq!(vec, [1.5, -2.25]);
+
+        let mut vec = Vec::<i64>::new();
+        let data = [8, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.decode_packed_fixed(&mut vec).unwrap();
+        assert_eq!(vec, [-2]);
+    }
+
+    #[test]
+    fn max_field_len() {
+        let mut decoder = PbDecoder::new([3, b'a', b'b', b'c', 2, b'd', b'e'].as_slice());
+        decoder.max_field_len = Some(2);
+        let mut string = String::from("old");
+        assert_eq!(
+            decoder.decode_string(&mut string, Presence::Explicit),
+            Err(DecodeError::BudgetExceeded)
+        );
+        // Container is untouched
+        assert_eq!(string, "old");
+
+        let mut decoder = PbDecoder::new([2, b'd', b'e'].as_slice());
+        decoder.max_field_len = Some(2);
+        let mut bytes = Vec::new();
+        decoder
+            .decode_bytes(&mut bytes, Presence::Explicit)
+            .unwrap();
+        assert_eq!(bytes, b"de");
+
+        let data = [3, 0x96, 0x01, 0x05];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.max_field_len = Some(2);
+        assert_eq!(
+            decoder.decode_packed(&mut Vec::new(), |rd| rd.decode_varint32()),
+            Err(DecodeError::BudgetExceeded)
+        );
+
+        let data = [8, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.max_field_len = Some(4);
+        assert_eq!(
+            decoder.decode_packed_fixed(&mut Vec::<u32>::new()),
+            Err(DecodeError::BudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn alloc_budget() {
+        let data = [
+            3, b'a', b'b', b'c', // string
+            2, 0x96, 0x01, // packed varint
+            4, 0x01, 0x02, 0x03, 0x04, // packed fixed
+            1, 0xFF, // bytes
+        ];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.alloc_budget = Some(9);
+        let mut string = String::new();
+        decoder
+            .decode_string(&mut string, Presence::Explicit)
+            .unwrap();
+        assert_eq!(decoder.alloc_budget, Some(6));
+        let mut vec = Vec::new();
+        decoder
+            .decode_packed(&mut vec, |rd| rd.decode_varint32())
+            .unwrap();
+        assert_eq!(decoder.alloc_budget, Some(4));
+        let mut fixed = Vec::<u32>::new();
+        decoder.decode_packed_fixed(&mut fixed).unwrap();
+        assert_eq!(decoder.alloc_budget, Some(0));
+        // Budget is exhausted
+        assert_eq!(
+            decoder.decode_bytes(&mut Vec::new(), Presence::Explicit),
+            Err(DecodeError::BudgetExceeded)
+        );
+
+        // Huge length prefix is rejected before reserving any memory
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.alloc_budget = Some(1024);
+        assert_eq!(
+            decoder.decode_bytes(&mut Vec::new(), Presence::Explicit),
+            Err(DecodeError::BudgetExceeded)
+        );
+        assert_eq!(decoder.alloc_budget, Some(1024));
+
+        let mut decoder = PbDecoder::new([].as_slice());
+        decoder.alloc_budget = Some(10);
+        decoder.spend_elem_budget::<u64>().unwrap();
+        assert_eq!(decoder.alloc_budget, Some(2));
+        assert_eq!(
+            decoder.spend_elem_budget::<u32>(),
+            Err(DecodeError::BudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn unknown_varint() {
+        // Records the fields it's given, rejecting field 9
+        #[derive(Default)]
+        struct Unknown(Vec<(Tag, u64)>);
+        impl FieldDecode for Unknown {
+            fn decode_field<R: PbRead>(
+                &mut self,
+                tag: Tag,
+                decoder: &mut PbDecoder<R>,
+            ) -> Result<bool, DecodeError<R::Error>> {
+                if tag.field_num() == 9 {
+                    return Ok(false);
+                }
+                self.0.push((tag, decoder.decode_varint64()?));
+                Ok(true)
+            }
+        }
+
+        // Packed odd numbers get passed on to the unknown handler, even ones are kept
+        let data = [5, 0x01, 0x02, 0xFF, 0xFF, 0x03];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let mut vec = Vec::new();
+        let mut unknown = Unknown::default();
+        decoder
+            .decode_packed_filtered(&mut vec, |rd| {
+                let n = rd.decode_varint32()?;
+                if n % 2 == 0 {
+                    return Ok(Some(n));
+                }
+                rd.decode_unknown_varint(&mut unknown, 3, n as u64)?;
+                Ok(None)
+            })
+            .unwrap();
+        assert_eq!(vec, [2]);
+        assert_eq!(
+            unknown.0,
+            [
+                (Tag::from_parts(3, WIRE_TYPE_VARINT), 1),
+                (Tag::from_parts(3, WIRE_TYPE_VARINT), 0xFFFF)
+            ]
+        );
+        assert!(decoder.is_eof().unwrap());
+
+        // Negative values are sign-extended to 64 bits
+        decoder
+            .decode_unknown_varint(&mut unknown, 4, -1i32 as u64)
+            .unwrap();
+        assert_eq!(
+            unknown.0[2],
+            (Tag::from_parts(4, WIRE_TYPE_VARINT), u64::MAX)
+        );
+        // Rejected fields are dropped
+        decoder.decode_unknown_varint(&mut unknown, 9, 1).unwrap();
+        assert_eq!(unknown.0.len(), 3);
+    }
+
+    #[test]
+    fn unknown_map_entry() {
+        // Records the bytes of the entries it's given
+        #[derive(Default)]
+        struct Unknown(Vec<(Tag, Vec<u8>)>);
+        impl FieldDecode for Unknown {
+            fn decode_field<R: PbRead>(
+                &mut self,
+                tag: Tag,
+                decoder: &mut PbDecoder<R>,
+            ) -> Result<bool, DecodeError<R::Error>> {
+                let mut bytes = Vec::new();
+                decoder.decode_bytes(&mut bytes, Presence::Explicit)?;
+                self.0.push((tag, bytes));
+                Ok(true)
+            }
+        }
+
+        let mut decoder = PbDecoder::new([].as_slice());
+        let mut unknown = Unknown::default();
+        let tag = Tag::from_parts(5, WIRE_TYPE_LEN);
+        decoder
+            .decode_unknown_map_entry(&mut unknown, 5, MapKey::Varint(300), -1i32 as u64)
+            .unwrap();
+        assert_eq!(
+            unknown.0[0],
+            (
+                tag,
+                [&[0x08, 0xAC, 0x02, 0x10][..], &[0xFF; 9], &[0x01]].concat()
+            )
+        );
+        decoder
+            .decode_unknown_map_entry(&mut unknown, 5, MapKey::I32(1), 3)
+            .unwrap();
+        assert_eq!(unknown.0[1], (tag, vec![0x0D, 1, 0, 0, 0, 0x10, 3]));
+        decoder
+            .decode_unknown_map_entry(&mut unknown, 5, MapKey::I64(2), 3)
+            .unwrap();
+        assert_eq!(
+            unknown.0[2],
+            (tag, vec![0x09, 2, 0, 0, 0, 0, 0, 0, 0, 0x10, 3])
+        );
+        decoder
+            .decode_unknown_map_entry(&mut unknown, 5, MapKey::Len(b"key"), 4)
+            .unwrap();
+        assert_eq!(
+            unknown.0[3],
+            (tag, vec![0x0A, 3, b'k', b'e', b'y', 0x10, 4])
+        );
+    }
+
+    #[test]
+    fn packed_fixed_ignore_wrong_len() {
+        let mut vec = Vec::<u32>::new();
+        let data = [5, 0x01, 0x00, 0x00, 0x00, 0xFF, 0x08];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.ignore_wrong_len = true;
+        decoder.decode_packed_fixed(&mut vec).unwrap();
+        assert_eq!(vec, [1]);
+        // The leftover byte in the record is skipped
+        assert_eq!(decoder.decode_varint32().unwrap(), 8);
+    }
 
     /// Test decoding of a map element with varint32 key and string value
     macro_rules! assert_decode_map_elem {
@@ -1374,8 +2474,95 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "embedded-io-0-6")]
+    #[test]
+    fn embedded_io_reader() {
+        use embedded_io_0_6::{BufRead, ErrorKind, ErrorType};
+
+        let data = [0x96, 0x01, 0x02, b'a', b'b'];
+        let mut decoder = PbDecoder::new(EmbeddedIoReader(data.as_slice()));
+        assert_eq!(decoder.decode_varint32(), Ok(150));
+        let mut string = ArrayString::<4>::new();
+        decoder
+            .decode_string(&mut string, Presence::Implicit)
+            .unwrap();
+        assert_eq!(string.as_str(), "ab");
+        assert_eq!(decoder.bytes_read(), data.len());
+        assert!(decoder.as_reader().0.is_empty());
+
+        struct FailingReader;
+        impl ErrorType for FailingReader {
+            type Error = ErrorKind;
+        }
+        impl BufRead for FailingReader {
+            fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+                Err(ErrorKind::TimedOut)
+            }
+            fn consume(&mut self, _amt: usize) {}
+        }
+
+        let mut decoder = PbDecoder::new(EmbeddedIoReader(FailingReader));
+        assert_eq!(
+            decoder.decode_varint32(),
+            Err(DecodeError::Reader(ErrorKind::TimedOut))
+        );
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            DecodeError::<Infallible>::UnexpectedEof.to_string(),
+            "unexpected end of input"
+        );
+        let err = DecodeError::Reader(std::io::Error::other("broken"));
+        assert_eq!(err.to_string(), "reader error: broken");
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(std::error::Error::source(&DecodeError::<std::io::Error>::Utf8).is_none());
+    }
+
+    #[cfg(feature = "detailed-errors")]
+    #[test]
+    fn detailed_error() {
+        // Invalid UTF-8 in the value of a map entry
+        let data = [0x0A, 7, 0x08, 0x01, 0x12, 3, b'a', 0xFF, b'c'];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let tag = decoder.decode_tag().unwrap();
+        assert_eq!(tag.field_num(), 1);
+        let err = decoder
+            .decode_map_elem(
+                |v, rd| rd.decode_varint32().map(|u| *v = u),
+                |v, rd| rd.decode_string::<String>(v, Presence::Explicit),
+            )
+            .unwrap_err();
+        let err = decoder.detailed_error(err);
+        assert_eq!(err.error, DecodeError::Utf8);
+        assert_eq!(err.offset, data.len());
+        assert_eq!(err.path.field_nums(), &[1, 2]);
+        assert_eq!(
+            err.to_string(),
+            "string is not valid UTF-8 (at byte 9, field path 1.2)"
+        );
+
+        // Decoding another field resets the location
+        let data = [0x0A, 2, 0x08, 0x01, 0x18, 0xFF];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.decode_tag().unwrap();
+        decoder
+            .decode_map_elem(
+                |v, rd| rd.decode_varint32().map(|u| *v = u),
+                |v: &mut u32, rd| rd.decode_varint32().map(|u| *v = u),
+            )
+            .unwrap();
+        decoder.decode_tag().unwrap();
+        let err = decoder.decode_varint32().unwrap_err();
+        let err = decoder.detailed_error(err);
+        assert_eq!(err.offset, 6);
+        assert_eq!(err.path.field_nums(), &[3]);
+    }
+
     // The following proptests exercise unsafe code in micropb. Since we only care about catching
     // UB, we only need to run the tests under miri.
+
     #[cfg(miri)]
     mod r#unsafe {
         use super::*;
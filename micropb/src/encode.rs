@@ -1,6 +1,6 @@
 use num_traits::{AsPrimitive, PrimInt};
 
-use crate::{MessageEncode, Tag};
+use crate::{misc::fixed_width_as_bytes, FixedWidth, MessageEncode, Tag};
 
 /// A writer to which Protobuf data is written, similar to [`std::io::Write`].
 ///
@@ -98,10 +98,82 @@ impl<W: std::io::Write> PbWrite for StdWriter<W> {
     }
 }
 
-trait VarInt: PrimInt + From<u8> + AsPrimitive<u8> {}
+#[cfg(feature = "embedded-io-0-6")]
+#[derive(Debug, Clone)]
+/// Adapter that implements [`PbWrite`] for all implementers of [`embedded_io::Write`](embedded_io_0_6::Write)
+/// from `embedded-io` v0.6, allowing the encoder to write to HAL peripherals and other
+/// `embedded-io` writers.
+///
+/// With the `embedded-io-async-0-6` feature, this also implements
+/// [`AsyncPbWrite`](crate::AsyncPbWrite) for implementers of `embedded_io_async::Write`.
+pub struct EmbeddedIoWriter<W>(pub W);
+
+#[cfg(feature = "embedded-io-0-6")]
+impl<W: embedded_io_0_6::Write> PbWrite for EmbeddedIoWriter<W> {
+    type Error = W::Error;
+
+    #[inline]
+    fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(data)
+    }
+}
+
+pub(crate) trait VarInt: PrimInt + From<u8> + AsPrimitive<u8> {}
 impl VarInt for u32 {}
 impl VarInt for u64 {}
 
+/// Serialize a varint into `buf`, returning the number of bytes written.
+pub(crate) fn write_varint<U: VarInt>(
+    buf: &mut [u8; 10],
+    mut varint: U,
+    negative_int32: bool,
+) -> usize {
+    let mut len = 0;
+    while {
+        let mut b = varint.as_() & 0x7F;
+        varint = varint >> 7;
+        let zero = varint.is_zero();
+        if !zero {
+            b |= 0x80;
+        } else if negative_int32 {
+            // The last encoded byte of an i32 only writes the lower 4 bits, so if it's a
+            // negative int, then we need to sign extend to the upper 3 bits. Also set the
+            // highest bit since we also need to sign extend for 5 more bytes.
+            b |= 0b11110000;
+        }
+        // Use non-panicking accesses, since the compiler can't prove that varints fit in 10 bytes
+        if let Some(slot) = buf.get_mut(len) {
+            *slot = b;
+        }
+        len += 1;
+        !zero
+    } {}
+
+    // Sign extend for 5 bytes
+    if negative_int32 {
+        if let Some(ext) = buf.get_mut(len..len + 5) {
+            ext.copy_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+        }
+        len += 5;
+    }
+    len
+}
+
+/// Error returned when an encoded message doesn't fit in the provided buffer.
+///
+/// Returned by slice and array encoding helpers such as
+/// [`MessageEncode::encode_to_slice`](crate::MessageEncode::encode_to_slice).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+impl core::fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("buffer too small for encoded message")
+    }
+}
+
+impl core::error::Error for BufferTooSmall {}
+
 #[derive(Debug)]
 /// Encoder that serializes Rust types into Protobuf messages and values.
 ///
@@ -142,250 +214,265 @@ pub struct PbEncoder<W: PbWrite> {
     writer: W,
 }
 
-impl<W: PbWrite> PbEncoder<W> {
-    #[inline]
-    /// Construct a new encoder from a [`PbWrite`].
-    pub fn new(writer: W) -> Self {
-        Self { writer }
-    }
+/// Generate the encoding methods shared by `PbEncoder` and `AsyncPbEncoder`.
+///
+/// Both encoders run the same logic, except that the async one awaits every write, takes async
+/// closures as callbacks, and encodes messages through the async trait. The `async` and `await`
+/// arguments are left empty for the blocking encoder.
+macro_rules! encoder_methods {
+    (
+        [$($async:tt)*],
+        [$($await:tt)*],
+        $FnMut:ident,
+        $MessageEncode:ident::$encode:ident $(,)?
+    ) => {
+        $($async)* fn encode_varint<U: VarInt>(
+            &mut self,
+            varint: U,
+            negative_int32: bool,
+        ) -> Result<(), W::Error> {
+            // Serialize the varint into a stack buffer so that it's written out in one go
+            let mut buf = [0; 10];
+            let len = write_varint(&mut buf, varint, negative_int32);
+            self.write(buf.get(..len).unwrap_or(&buf))$(.$await)*
+        }
 
-    #[inline]
-    /// Transform the encoder into the underlying writer.
-    pub fn into_writer(self) -> W {
-        self.writer
-    }
+        #[inline]
+        /// Encode an `uint32`.
+        pub $($async)* fn encode_varint32(&mut self, u: u32) -> Result<(), W::Error> {
+            self.encode_varint(u, false)$(.$await)*
+        }
 
-    #[inline]
-    /// Get reference to underlying writer.
-    pub fn as_writer(&self) -> &W {
-        &self.writer
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Encode an `uint64`.
+        pub $($async)* fn encode_varint64(&mut self, u: u64) -> Result<(), W::Error> {
+            self.encode_varint(u, false)$(.$await)*
+        }
 
-    #[inline]
-    fn write(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
-        self.writer.pb_write(bytes)?;
-        Ok(())
-    }
+        #[inline]
+        /// Encode an `int32`.
+        pub $($async)* fn encode_int32(&mut self, i: i32) -> Result<(), W::Error> {
+            self.encode_varint(i as u32, i < 0)$(.$await)*
+        }
 
-    #[inline]
-    fn encode_byte(&mut self, b: u8) -> Result<(), W::Error> {
-        self.write(&[b])
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Encode an `int64`.
+        pub $($async)* fn encode_int64(&mut self, i: i64) -> Result<(), W::Error> {
+            self.encode_varint64(i as u64)$(.$await)*
+        }
 
-    fn encode_varint<U: VarInt>(
-        &mut self,
-        mut varint: U,
-        negative_int32: bool,
-    ) -> Result<(), W::Error> {
-        if varint <= From::from(0x7F) {
-            return self.encode_byte(varint.as_());
+        #[inline]
+        /// Encode an `sint32`.
+        pub $($async)* fn encode_sint32(&mut self, i: i32) -> Result<(), W::Error> {
+            self.encode_varint32(((i << 1) ^ (i >> 31)) as u32)$(.$await)*
         }
 
-        while {
-            let mut b = varint.as_() & 0x7F;
-            varint = varint >> 7;
-            let zero = varint.is_zero();
-            if !zero {
-                b |= 0x80;
-            } else if negative_int32 {
-                // The last encoded byte of an i32 only writes the lower 4 bits, so if it's a
-                // negative int, then we need to sign extend to the upper 3 bits. Also set the
-                // highest bit since we also need to sign extend for 5 more bytes.
-                b |= 0b11110000;
-            }
-            self.encode_byte(b)?;
-            !zero
-        } {}
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Encode an `sint64`.
+        pub $($async)* fn encode_sint64(&mut self, i: i64) -> Result<(), W::Error> {
+            self.encode_varint64(((i << 1) ^ (i >> 63)) as u64)$(.$await)*
+        }
 
-        // Sign extend for 5 bytes
-        if negative_int32 {
-            self.write(&[0xFF, 0xFF, 0xFF, 0xFF, 0x01])?;
+        #[inline]
+        /// Encode a `bool`.
+        pub $($async)* fn encode_bool(&mut self, b: bool) -> Result<(), W::Error> {
+            self.write(&[b as u8])$(.$await)*
         }
 
-        Ok(())
-    }
+        #[inline]
+        /// Encode a `fixed32`.
+        pub $($async)* fn encode_fixed32(&mut self, u: u32) -> Result<(), W::Error> {
+            self.write(&u.to_le_bytes())$(.$await)*
+        }
 
-    #[inline]
-    /// Encode an `uint32`.
-    pub fn encode_varint32(&mut self, u: u32) -> Result<(), W::Error> {
-        self.encode_varint(u, false)
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Encode a `fixed64`.
+        pub $($async)* fn encode_fixed64(&mut self, u: u64) -> Result<(), W::Error> {
+            self.write(&u.to_le_bytes())$(.$await)*
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Encode an `uint64`.
-    pub fn encode_varint64(&mut self, u: u64) -> Result<(), W::Error> {
-        self.encode_varint(u, false)
-    }
+        /// Encode a 32-bit number as `fixed64`.
+        ///
+        /// Avoids 64-bit operations, which can have benefits on 32-bit architectures.
+        pub $($async)* fn encode_fixed64_as_32(&mut self, u: u32) -> Result<(), W::Error> {
+            let mut bytes = [0; 8];
+            bytes[..4].copy_from_slice(&u.to_le_bytes());
+            self.write(&bytes)$(.$await)*
+        }
 
-    #[inline]
-    /// Encode an `int32`.
-    pub fn encode_int32(&mut self, i: i32) -> Result<(), W::Error> {
-        self.encode_varint(i as u32, i < 0)
-    }
+        #[inline]
+        /// Encode a `sfixed32`.
+        pub $($async)* fn encode_sfixed32(&mut self, i: i32) -> Result<(), W::Error> {
+            self.encode_fixed32(i as u32)$(.$await)*
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Encode an `int64`.
-    pub fn encode_int64(&mut self, i: i64) -> Result<(), W::Error> {
-        self.encode_varint64(i as u64)
-    }
+        #[inline]
+        #[cfg(feature = "enable-64bit")]
+        /// Encode a `sfixed64`.
+        pub $($async)* fn encode_sfixed64(&mut self, i: i64) -> Result<(), W::Error> {
+            self.encode_fixed64(i as u64)$(.$await)*
+        }
 
-    #[inline]
-    /// Encode an `sint32`.
-    pub fn encode_sint32(&mut self, i: i32) -> Result<(), W::Error> {
-        self.encode_varint32(((i << 1) ^ (i >> 31)) as u32)
-    }
+        /// Encode a 32-bit number as `sfixed64`.
+        ///
+        /// Avoids 64-bit operations, which can have benefits on 32-bit architectures.
+        pub $($async)* fn encode_sfixed64_as_32(&mut self, i: i32) -> Result<(), W::Error> {
+            // If i is -ve, then write the extra bits to 1
+            let mut bytes = if i < 0 { [0xFF; 8] } else { [0; 8] };
+            bytes[..4].copy_from_slice(&i.to_le_bytes());
+            self.write(&bytes)$(.$await)*
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Encode an `sint64`.
-    pub fn encode_sint64(&mut self, i: i64) -> Result<(), W::Error> {
-        self.encode_varint64(((i << 1) ^ (i >> 63)) as u64)
-    }
+        #[inline]
+        /// Encode a `float`.
+        pub $($async)* fn encode_float(&mut self, f: f32) -> Result<(), W::Error> {
+            self.write(&f.to_le_bytes())$(.$await)*
+        }
 
-    #[inline]
-    /// Encode a `bool`.
-    pub fn encode_bool(&mut self, b: bool) -> Result<(), W::Error> {
-        self.encode_byte(b as u8)
-    }
+        #[inline]
+        /// Encode a `double`.
+        pub $($async)* fn encode_double(&mut self, f: f64) -> Result<(), W::Error> {
+            self.write(&f.to_le_bytes())$(.$await)*
+        }
 
-    #[inline]
-    /// Encode a `fixed32`.
-    pub fn encode_fixed32(&mut self, u: u32) -> Result<(), W::Error> {
-        self.write(&u.to_le_bytes())
-    }
+        #[inline(always)]
+        /// Encode a Protobuf tag.
+        pub $($async)* fn encode_tag(&mut self, tag: Tag) -> Result<(), W::Error> {
+            self.encode_varint32(tag.varint())$(.$await)*
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Encode a `fixed64`.
-    pub fn encode_fixed64(&mut self, u: u64) -> Result<(), W::Error> {
-        self.write(&u.to_le_bytes())
-    }
+        /// Encode a `bytes` field.
+        pub $($async)* fn encode_bytes(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
+            self.encode_varint32(bytes.len() as u32)$(.$await)*?;
+            self.write(bytes)$(.$await)*
+        }
 
-    /// Encode a 32-bit number as `fixed64`.
-    ///
-    /// Avoids 64-bit operations, which can have benefits on 32-bit architectures.
-    pub fn encode_fixed64_as_32(&mut self, u: u32) -> Result<(), W::Error> {
-        let mut bytes = [0; 8];
-        bytes[..4].copy_from_slice(&u.to_le_bytes());
-        self.write(&bytes)
-    }
+        #[inline]
+        /// Encode a `string` field.
+        pub $($async)* fn encode_string(&mut self, string: &str) -> Result<(), W::Error> {
+            self.encode_bytes(string.as_bytes())$(.$await)*
+        }
 
-    #[inline]
-    /// Encode a `sfixed32`.
-    pub fn encode_sfixed32(&mut self, i: i32) -> Result<(), W::Error> {
-        self.encode_fixed32(i as u32)
-    }
+        /// Encode a repeated packed field of fixed-size elements from a slice, including the
+        /// length prefix.
+        ///
+        /// Produces the same output as [`encode_packed`](Self::encode_packed), but on
+        /// little-endian targets the elements are written to the wire all at once, rather than
+        /// one at a time.
+        pub $($async)* fn encode_packed_fixed<T: FixedWidth>(
+            &mut self,
+            elems: &[T],
+        ) -> Result<(), W::Error> {
+            self.encode_varint32(core::mem::size_of_val(elems) as u32)$(.$await)*?;
+            #[cfg(target_endian = "little")]
+            self.write(fixed_width_as_bytes(elems))$(.$await)*?;
+            #[cfg(target_endian = "big")]
+            for &e in elems {
+                self.write(fixed_width_as_bytes(&[e.swap_le()]))$(.$await)*?;
+            }
+            Ok(())
+        }
 
-    #[inline]
-    #[cfg(feature = "enable-64bit")]
-    /// Encode a `sfixed64`.
-    pub fn encode_sfixed64(&mut self, i: i64) -> Result<(), W::Error> {
-        self.encode_fixed64(i as u64)
-    }
+        /// Encode a repeated packed field from a slice of elements.
+        ///
+        /// The `encoder` callback determines how each element is encoded onto the wire, and `len`
+        /// is the length of the packed record on the wire.
+        pub $($async)* fn encode_packed<
+            T: Copy,
+            F: $FnMut(&mut Self, T) -> Result<(), W::Error>,
+        >(
+            &mut self,
+            len: usize,
+            elems: &[T],
+            mut encoder: F,
+        ) -> Result<(), W::Error> {
+            self.encode_varint32(len as u32)$(.$await)*?;
+            for &e in elems {
+                encoder(self, e)$(.$await)*?;
+            }
+            Ok(())
+        }
 
-    /// Encode a 32-bit number as `sfixed64`.
-    ///
-    /// Avoids 64-bit operations, which can have benefits on 32-bit architectures.
-    pub fn encode_sfixed64_as_32(&mut self, i: i32) -> Result<(), W::Error> {
-        // If i is -ve, then write the extra bits to 1
-        let mut bytes = if i < 0 { [0xFF; 8] } else { [0; 8] };
-        bytes[..4].copy_from_slice(&i.to_le_bytes());
-        self.write(&bytes)
-    }
+        #[allow(clippy::too_many_arguments)]
+        /// Encode a Protobuf map key-value pair onto the wire.
+        ///
+        /// The key-value pair is encoded as a Protobuf message with the key in field 1 and value in
+        /// field 2. The wire types of the key and value need to be provided, as well as the length
+        /// of the key-value pair on the wire.
+        pub $($async)* fn encode_map_elem<
+            K: ?Sized,
+            V: ?Sized,
+            EK: $FnMut(&mut Self, &K) -> Result<(), W::Error>,
+            EV: $FnMut(&mut Self, &V) -> Result<(), W::Error>,
+        >(
+            &mut self,
+            len: usize,
+            key: &K,
+            key_wtype: u8,
+            val: &V,
+            val_wtype: u8,
+            mut key_encoder: EK,
+            mut val_encoder: EV,
+        ) -> Result<(), W::Error> {
+            self.encode_varint32(len as u32)$(.$await)*?;
+            let key_tag = Tag::from_parts(1, key_wtype);
+            let val_tag = Tag::from_parts(2, val_wtype);
+
+            self.encode_tag(key_tag)$(.$await)*?;
+            key_encoder(self, key)$(.$await)*?;
+            self.encode_tag(val_tag)$(.$await)*?;
+            val_encoder(self, val)$(.$await)*?;
+            Ok(())
+        }
 
-    #[inline]
-    /// Encode a `float`.
-    pub fn encode_float(&mut self, f: f32) -> Result<(), W::Error> {
-        self.write(&f.to_le_bytes())
-    }
+        /// Encode a message to the wire.
+        #[inline]
+        pub $($async)* fn encode_message<M: $MessageEncode>(
+            &mut self,
+            msg: &M,
+        ) -> Result<(), W::Error> {
+            msg.$encode(self)$(.$await)*
+        }
+    };
+}
+#[cfg(feature = "async")]
+pub(crate) use encoder_methods;
 
+impl<W: PbWrite> PbEncoder<W> {
     #[inline]
-    /// Encode a `double`.
-    pub fn encode_double(&mut self, f: f64) -> Result<(), W::Error> {
-        self.write(&f.to_le_bytes())
-    }
-
-    #[inline(always)]
-    /// Encode a Protobuf tag.
-    pub fn encode_tag(&mut self, tag: Tag) -> Result<(), W::Error> {
-        self.encode_varint32(tag.varint())
+    /// Construct a new encoder from a [`PbWrite`].
+    pub fn new(writer: W) -> Self {
+        Self { writer }
     }
 
-    /// Encode a `bytes` field.
-    pub fn encode_bytes(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
-        self.encode_varint32(bytes.len() as u32)?;
-        self.write(bytes)
+    #[inline]
+    /// Transform the encoder into the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
     }
 
     #[inline]
-    /// Encode a `string` field.
-    pub fn encode_string(&mut self, string: &str) -> Result<(), W::Error> {
-        self.encode_bytes(string.as_bytes())
+    /// Get reference to underlying writer.
+    pub fn as_writer(&self) -> &W {
+        &self.writer
     }
 
-    //pub fn encode_packed_fixed<T: Copy>(&mut self, elems: &[T]) -> Result<(), W::Error> {
-    //// O(1) operation that gets total size of slice
-    //let len = sizeof_packed_fixed(elems);
-    //let bytes = unsafe { core::slice::from_raw_parts(elems.as_ptr() as *const u8, len) };
-    //self.encode_bytes(bytes)
-    //}
-
-    /// Encode a repeated packed field from a slice of elements.
-    ///
-    /// The `encoder` callback determines how each element is encoded onto the wire, and `len` is
-    /// the length of the packed record on the wire.
-    pub fn encode_packed<T: Copy, F: FnMut(&mut Self, T) -> Result<(), W::Error>>(
-        &mut self,
-        len: usize,
-        elems: &[T],
-        mut encoder: F,
-    ) -> Result<(), W::Error> {
-        self.encode_varint32(len as u32)?;
-        for &e in elems {
-            encoder(self, e)?;
-        }
-        Ok(())
+    #[inline]
+    pub(crate) fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
     }
 
-    #[allow(clippy::too_many_arguments)]
-    /// Encode a Protobuf map key-value pair onto the wire.
-    ///
-    /// The key-value pair is encoded as a Protobuf message with the key in field 1 and value in
-    /// field 2. The wire types of the key and value need to be provided, as well as the length of
-    /// the key-value pair on the wire.
-    pub fn encode_map_elem<
-        K: ?Sized,
-        V: ?Sized,
-        EK: FnMut(&mut Self, &K) -> Result<(), W::Error>,
-        EV: FnMut(&mut Self, &V) -> Result<(), W::Error>,
-    >(
-        &mut self,
-        len: usize,
-        key: &K,
-        key_wtype: u8,
-        val: &V,
-        val_wtype: u8,
-        mut key_encoder: EK,
-        mut val_encoder: EV,
-    ) -> Result<(), W::Error> {
-        self.encode_varint32(len as u32)?;
-        let key_tag = Tag::from_parts(1, key_wtype);
-        let val_tag = Tag::from_parts(2, val_wtype);
-
-        self.encode_tag(key_tag)?;
-        key_encoder(self, key)?;
-        self.encode_tag(val_tag)?;
-        val_encoder(self, val)?;
+    #[inline]
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<(), W::Error> {
+        self.writer.pb_write(bytes)?;
         Ok(())
     }
 
-    /// Encode a message to the wire.
-    #[inline]
-    pub fn encode_message<M: MessageEncode>(&mut self, msg: &M) -> Result<(), W::Error> {
-        msg.encode(self)
-    }
+    encoder_methods!([], [], FnMut, MessageEncode::encode);
 }
 
 #[cfg(test)]
@@ -596,19 +683,32 @@ mod tests {
         assert_encode_nosize!(&[4, 208, 151, 208, 180], encode_string("Зд"));
     }
 
-    //#[test]
-    //#[cfg(target_endian = "little")]
-    //fn packed_fixed() {
-    //assert_encode_nosize!([0], encode_packed_fixed(&[0u32; 0]));
-    //assert_encode_nosize!(
-    //[4, 0x1, 0x0, 0x0, 0x1],
-    //encode_packed_fixed(&[true, false, false, true])
-    //);
-    //assert_encode_nosize!(
-    //[8, 0x1, 0x0, 0x0, 0x0, 0x6, 0x0, 0x0, 0x0],
-    //encode_packed_fixed(&[1u32, 6u32])
-    //);
-    //}
+    #[test]
+    fn packed_fixed() {
+        assert_encode_nosize!(&[0], encode_packed_fixed(&[0u32; 0]));
+        assert_encode_nosize!(
+            &[8, 0x1, 0x0, 0x0, 0x0, 0x6, 0x0, 0x0, 0x0],
+            encode_packed_fixed(&[1u32, 6u32])
+        );
+        assert_encode_nosize!(
+            &[8, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            encode_packed_fixed(&[-2i64])
+        );
+        assert_encode_nosize!(
+            &[8, 0x00, 0x00, 0xC0, 0x3F, 0x00, 0x00, 0x10, 0xC0],
+            encode_packed_fixed(&[1.5f32, -2.25])
+        );
+
+        // Same output as the per-element encoding
+        let elems = [1.0f64, -0.5, f64::MAX];
+        let mut encoder = PbEncoder::new(ArrayVec::<u8, 30>::new());
+        encoder
+            .encode_packed(elems.len() * 8, &elems, PbEncoder::encode_double)
+            .unwrap();
+        let mut fixed_encoder = PbEncoder::new(ArrayVec::<u8, 30>::new());
+        fixed_encoder.encode_packed_fixed(&elems).unwrap();
+        assert_eq!(encoder.writer, fixed_encoder.writer);
+    }
 
     #[test]
     fn packed() {
@@ -685,4 +785,23 @@ mod tests {
         let res = encoder.encode_fixed64(0x1234567890ABCDEF);
         assert!(res.is_err());
     }
+
+    #[cfg(feature = "embedded-io-0-6")]
+    #[test]
+    fn embedded_io_writer() {
+        let mut buf = [0u8; 4];
+        let mut encoder = PbEncoder::new(EmbeddedIoWriter(buf.as_mut_slice()));
+        encoder.encode_varint32(150).unwrap();
+        encoder.encode_bool(true).unwrap();
+        let remaining = encoder.into_writer().0.len();
+        assert_eq!(remaining, 1);
+        assert_eq!(&buf[..3], &[0x96, 0x01, 0x01]);
+
+        // Try to write more than remaining space
+        let mut encoder = PbEncoder::new(EmbeddedIoWriter(buf.as_mut_slice()));
+        assert_eq!(
+            encoder.encode_fixed64(0x1234567890ABCDEF),
+            Err(embedded_io_0_6::SliceWriteError::Full)
+        );
+    }
 }
@@ -0,0 +1,209 @@
+use core::fmt;
+
+use crate::DecodeError;
+
+/// Sequence of field numbers leading from the top-level message to a field.
+///
+/// For example, the path `2.1` refers to field 1 of the message in field 2 of the top-level
+/// message. Map entries count as a nesting level, with the key in field 1 and the value in field
+/// 2, same as their representation on the wire.
+///
+/// Only the first [`CAPACITY`](Self::CAPACITY) field numbers are stored. Deeper paths are
+/// truncated, but [`depth`](Self::depth) still reports the full depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldPath {
+    nums: [u32; Self::CAPACITY],
+    len: usize,
+}
+
+impl Default for FieldPath {
+    fn default() -> Self {
+        Self {
+            nums: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+}
+
+impl FieldPath {
+    /// Maximum number of field numbers stored in the path.
+    pub const CAPACITY: usize = 16;
+
+    /// Field numbers in the path, starting from the top-level message.
+    pub fn field_nums(&self) -> &[u32] {
+        &self.nums[..self.len.min(Self::CAPACITY)]
+    }
+
+    /// Full depth of the path, including truncated field numbers.
+    pub fn depth(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the path is deeper than [`CAPACITY`](Self::CAPACITY).
+    pub fn is_truncated(&self) -> bool {
+        self.len > Self::CAPACITY
+    }
+
+    fn set(&mut self, level: usize, num: u32) {
+        if let Some(n) = self.nums.get_mut(level) {
+            *n = num;
+        }
+        self.len = level + 1;
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.len == 0 {
+            return f.write_str("<root>");
+        }
+        for (i, num) in self.field_nums().iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{num}")?;
+        }
+        if self.is_truncated() {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+/// [`DecodeError`] with information about where in the input it happened.
+///
+/// Obtained from [`PbDecoder::detailed_error`](crate::PbDecoder::detailed_error).
+#[derive(Debug, PartialEq, Eq)]
+pub struct DetailedDecodeError<E> {
+    /// The underlying error
+    pub error: DecodeError<E>,
+    /// Number of bytes the decoder had read when the error happened
+    pub offset: usize,
+    /// Path of the field that was being decoded when the error happened
+    pub path: FieldPath,
+}
+
+impl<E: fmt::Display> fmt::Display for DetailedDecodeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (at byte {}, field path {})",
+            self.error, self.offset, self.path
+        )
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for DetailedDecodeError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Tracks the path of the field being decoded, so that it can be reported on errors.
+///
+/// The path is updated by the tags and nested messages decoded by the generated code.
+#[derive(Debug, Default)]
+pub(crate) struct ErrorTrace {
+    path: FieldPath,
+    level: usize,
+    /// Path length and offset at the innermost level where an error happened
+    error: Option<(usize, usize)>,
+}
+
+impl ErrorTrace {
+    /// Record the field number of a tag decoded at the current level
+    pub(crate) fn on_tag(&mut self, num: u32) {
+        // Decoding has resumed after an error, so the old error location is stale
+        self.error = None;
+        self.path.set(self.level, num);
+    }
+
+    pub(crate) fn enter(&mut self) {
+        self.level += 1;
+        self.path.len = self.level;
+    }
+
+    pub(crate) fn exit<T, E>(&mut self, res: &Result<T, E>, offset: usize) {
+        if res.is_err() && self.error.is_none() {
+            self.error = Some((self.path.len, offset));
+        }
+        self.level -= 1;
+        self.path.len = self.level + 1;
+    }
+
+    pub(crate) fn attach<E>(&self, error: DecodeError<E>, offset: usize) -> DetailedDecodeError<E> {
+        let mut path = self.path;
+        let offset = if let Some((len, err_offset)) = self.error {
+            path.len = len;
+            err_offset
+        } else {
+            offset
+        };
+        DetailedDecodeError {
+            error,
+            offset,
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_display() {
+        let mut path = FieldPath::default();
+        assert_eq!(path.to_string(), "<root>");
+        path.set(0, 2);
+        path.set(1, 15);
+        assert_eq!(path.to_string(), "2.15");
+        assert_eq!(path.field_nums(), &[2, 15]);
+        assert!(!path.is_truncated());
+
+        path.set(FieldPath::CAPACITY, 1);
+        assert!(path.is_truncated());
+        assert_eq!(path.depth(), FieldPath::CAPACITY + 1);
+        assert_eq!(path.field_nums().len(), FieldPath::CAPACITY);
+        assert!(path.to_string().ends_with("..."));
+    }
+
+    #[test]
+    fn trace() {
+        let mut trace = ErrorTrace::default();
+        trace.on_tag(3);
+        trace.enter();
+        trace.on_tag(1);
+        trace.enter();
+        trace.on_tag(7);
+        trace.exit(&Err::<(), ()>(()), 20);
+        trace.exit(&Err::<(), ()>(()), 21);
+
+        let err = trace.attach(DecodeError::<core::convert::Infallible>::Utf8, 22);
+        assert_eq!(err.offset, 20);
+        assert_eq!(err.path.field_nums(), &[3, 1, 7]);
+
+        // Error before any tag is decoded at the nested level
+        let mut trace = ErrorTrace::default();
+        trace.on_tag(4);
+        trace.enter();
+        trace.exit(&Err::<(), ()>(()), 5);
+        let err = trace.attach(DecodeError::<core::convert::Infallible>::UnexpectedEof, 5);
+        assert_eq!(err.path.field_nums(), &[4]);
+
+        // Error at the top level
+        let mut trace = ErrorTrace::default();
+        trace.on_tag(4);
+        trace.enter();
+        trace.on_tag(1);
+        trace.exit(&Ok::<(), ()>(()), 5);
+        trace.on_tag(9);
+        let err = trace.attach(DecodeError::<core::convert::Infallible>::Capacity, 8);
+        assert_eq!(err.offset, 8);
+        assert_eq!(err.path.field_nums(), &[9]);
+        assert_eq!(
+            err.to_string(),
+            "capacity of fixed container exceeded (at byte 8, field path 9)"
+        );
+    }
+}
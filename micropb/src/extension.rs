@@ -0,0 +1,492 @@
+//! Proto2 extension fields.
+//!
+//! Extensions are fields declared outside of the message they belong to, via `extend` blocks.
+//! Messages with `extensions` ranges can be generated with an `_extensions` field of type
+//! [`ExtensionFields`], which retains every extension field on the wire as raw bytes, similar to
+//! [`UnknownFields`](crate::UnknownFields). Such messages implement [`Extendable`].
+//!
+//! For every extension declared in an `extend` block, `micropb-gen` generates a unit struct that
+//! implements [`Extension`], which describes the field number, the value type, and the message
+//! being extended. Passing the descriptor to [`Extendable::get_extension`] decodes the value from
+//! the retained bytes, while [`Extendable::set_extension`] encodes a new value in place of the old
+//! one. Since only the raw bytes are stored, the value type is checked at compile time and the
+//! storage works the same way with fixed-capacity and heap-allocated containers.
+//!
+//! # Example
+//!
+//! ```
+//! use micropb::extension::{Extendable, ExtensionFields};
+//! # use micropb::extension::{Extension, ExtensionDecode, ExtensionEncode};
+//! # use micropb::{DecodeError, PbDecoder, PbEncoder, PbRead, PbWrite, Tag};
+//! # #[derive(Default)]
+//! # struct Request { _extensions: ExtensionFields<Vec<u8>> }
+//! # impl Extendable for Request {
+//! #   type Buffer = Vec<u8>;
+//! #   fn extensions(&self) -> &ExtensionFields<Vec<u8>> { &self._extensions }
+//! #   fn extensions_mut(&mut self) -> &mut ExtensionFields<Vec<u8>> { &mut self._extensions }
+//! # }
+//! # #[derive(Clone, Copy)]
+//! # struct PRIORITY;
+//! # impl Extension for PRIORITY {
+//! #   type Extendee = Request;
+//! #   type Value = u32;
+//! #   const NUMBER: u32 = 100;
+//! #   const FULL_NAME: &'static str = "vendor.priority";
+//! # }
+//! # impl ExtensionDecode for PRIORITY {
+//! #   fn decode_value<R: PbRead>(value: &mut u32, _tag: Tag, decoder: &mut PbDecoder<R>) -> Result<(), DecodeError<R::Error>> {
+//! #       *value = decoder.decode_varint32()?;
+//! #       Ok(())
+//! #   }
+//! # }
+//! # impl ExtensionEncode for PRIORITY {
+//! #   fn encode_value<W: PbWrite>(value: &u32, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> {
+//! #       encoder.encode_varint32(800)?;
+//! #       encoder.encode_varint32(*value)
+//! #   }
+//! #   fn compute_value_size(value: &u32) -> usize { 2 + micropb::size::sizeof_varint32(*value) }
+//! # }
+//! // PRIORITY is generated from `extend Request { optional uint32 priority = 100; }`
+//! let mut req = Request::default();
+//! assert_eq!(req.get_extension(PRIORITY), Ok(None));
+//!
+//! req.set_extension(PRIORITY, &3).unwrap();
+//! assert!(req.has_extension(PRIORITY));
+//! assert_eq!(req.get_extension(PRIORITY), Ok(Some(3)));
+//!
+//! req.clear_extension(PRIORITY);
+//! assert!(req.extensions().is_empty());
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    misc::maybe_uninit_write_slice, unknown::UnknownFieldsIter, CapacityExceeded, PbBytes,
+};
+#[cfg(feature = "encode")]
+use crate::{misc::maybe_uninit_zero_bytes, BufferTooSmall};
+#[cfg(all(feature = "async", feature = "decode"))]
+use crate::{AsyncFieldDecode, AsyncPbDecoder, AsyncPbRead};
+#[cfg(all(feature = "async", feature = "encode"))]
+use crate::{AsyncFieldEncode, AsyncPbEncoder, AsyncPbWrite};
+#[cfg(feature = "decode")]
+use crate::{DecodeError, FieldDecode, PbDecoder, PbRead, Tag};
+#[cfg(feature = "encode")]
+use crate::{FieldEncode, PbEncoder, PbWrite};
+
+/// Descriptor of an extension field, generated by `micropb-gen` for each field in an `extend`
+/// block.
+pub trait Extension {
+    /// Message type being extended
+    type Extendee;
+    /// Rust type of the extension value. For repeated extensions, this is the container type.
+    type Value: Default;
+    /// Field number of the extension
+    const NUMBER: u32;
+    /// Fully-qualified Protobuf name of the extension, without the leading dot
+    const FULL_NAME: &'static str;
+}
+
+/// Decoding of extension values.
+#[cfg(feature = "decode")]
+pub trait ExtensionDecode: Extension {
+    /// Decode a single occurrence of the extension field into `value`, after its tag has been
+    /// read.
+    ///
+    /// Scalar values are overwritten, message values are merged, and repeated values are
+    /// appended to, same as with regular fields.
+    fn decode_value<R: PbRead>(
+        value: &mut Self::Value,
+        tag: Tag,
+        decoder: &mut PbDecoder<R>,
+    ) -> Result<(), DecodeError<R::Error>>;
+}
+
+/// Encoding of extension values.
+#[cfg(feature = "encode")]
+pub trait ExtensionEncode: Extension {
+    /// Encode the extension value, including the field tag.
+    fn encode_value<W: PbWrite>(
+        value: &Self::Value,
+        encoder: &mut PbEncoder<W>,
+    ) -> Result<(), W::Error>;
+
+    /// Compute the size of the encoded extension value, including the field tag.
+    fn compute_value_size(value: &Self::Value) -> usize;
+}
+
+/// Message with extension ranges that retains its extension fields.
+///
+/// Implementations are generated by `micropb-gen` for messages configured with
+/// [`Config::retain_extensions`](https://docs.rs/micropb-gen/latest/micropb_gen/config/struct.Config.html#method.retain_extensions).
+pub trait Extendable {
+    /// Bytes container backing the extension storage
+    type Buffer: PbBytes + DerefMut<Target = [u8]>;
+
+    /// Reference the extension storage
+    fn extensions(&self) -> &ExtensionFields<Self::Buffer>;
+
+    /// Mutably reference the extension storage
+    fn extensions_mut(&mut self) -> &mut ExtensionFields<Self::Buffer>;
+
+    /// Check if the extension is present.
+    fn has_extension<E: Extension<Extendee = Self>>(&self, _ext: E) -> bool {
+        self.extensions().contains(E::NUMBER)
+    }
+
+    /// Decode the value of the extension, or return `None` if it's not present.
+    #[cfg(feature = "decode")]
+    fn get_extension<E: ExtensionDecode<Extendee = Self>>(
+        &self,
+        _ext: E,
+    ) -> Result<Option<E::Value>, DecodeError<core::convert::Infallible>> {
+        let bytes = self.extensions().as_bytes();
+        let mut decoder = PbDecoder::new(bytes);
+        let mut value = None;
+        while decoder.bytes_read() < bytes.len() {
+            let tag = decoder.decode_tag()?;
+            if tag.field_num() == E::NUMBER {
+                E::decode_value(
+                    value.get_or_insert_with(E::Value::default),
+                    tag,
+                    &mut decoder,
+                )?;
+            } else {
+                decoder.skip_wire_value(tag.wire_type())?;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Set the value of the extension, replacing any existing occurrences of it.
+    ///
+    /// Returns an error if the storage doesn't have space for the encoded value, in which case the
+    /// storage is left unchanged.
+    #[cfg(feature = "encode")]
+    fn set_extension<E: ExtensionEncode<Extendee = Self>>(
+        &mut self,
+        _ext: E,
+        value: &E::Value,
+    ) -> Result<(), BufferTooSmall> {
+        self.extensions_mut()
+            .replace(E::NUMBER, E::compute_value_size(value), |encoder| {
+                E::encode_value(value, encoder)
+            })
+    }
+
+    /// Remove the extension.
+    fn clear_extension<E: Extension<Extendee = Self>>(&mut self, _ext: E) {
+        self.extensions_mut().remove(E::NUMBER);
+    }
+}
+
+/// Storage of extension fields as raw bytes.
+///
+/// Each extension field is stored exactly as it appeared on the wire, tag included. Decoding
+/// appends the extension fields to the storage, and encoding writes them back out unchanged.
+/// Values are read and written through the methods of [`Extendable`].
+///
+/// If a fixed-capacity container runs out of space while decoding, decoding fails with
+/// [`DecodeError::Capacity`], unless [`PbDecoder::ignore_repeated_cap_err`] is set, in which case
+/// the field is dropped instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ExtensionFields<B> {
+    buf: B,
+}
+
+/// [`ExtensionFields`] backed by a `heapless::Vec` (v0.9) with a fixed capacity of `N` bytes.
+#[cfg(feature = "container-heapless-0-9")]
+pub type HeaplessExtensionFields<const N: usize> = ExtensionFields<heapless_0_9::Vec<u8, N>>;
+
+/// [`ExtensionFields`] backed by a growable `Vec`.
+#[cfg(feature = "alloc")]
+pub type VecExtensionFields = ExtensionFields<alloc::vec::Vec<u8>>;
+
+impl<B: Default> ExtensionFields<B> {
+    /// Create an empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: PbBytes + Deref<Target = [u8]>> ExtensionFields<B> {
+    /// Raw wire bytes of all extension fields.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Total size of the extension fields in bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if there are no extension fields.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Discard all extension fields.
+    pub fn clear(&mut self) {
+        self.buf.pb_clear();
+    }
+
+    /// Iterate over the raw extension fields.
+    ///
+    /// Each item is the tag of the field along with its value, in the same format as
+    /// [`UnknownFields::iter`](crate::UnknownFields::iter).
+    pub fn iter(&self) -> UnknownFieldsIter<'_> {
+        UnknownFieldsIter { bytes: &self.buf }
+    }
+
+    /// Check if there's a field with the given number.
+    pub fn contains(&self, field_num: u32) -> bool {
+        self.iter().any(|(tag, _)| tag.field_num() == field_num)
+    }
+
+    /// Append all extension fields from `other`.
+    ///
+    /// Since decoding the concatenated fields is equivalent to merging them, this follows the
+    /// same semantics as [`MessageMerge::merge_from`](crate::MessageMerge::merge_from). Nothing
+    /// is appended if the container doesn't have enough capacity.
+    pub fn merge_from(&mut self, other: &Self) -> Result<(), CapacityExceeded> {
+        let len = other.buf.len();
+        let old_len = self.buf.len();
+        self.buf.pb_reserve(len);
+        let spare_cap = self
+            .buf
+            .pb_spare_cap()
+            .get_mut(..len)
+            .ok_or(CapacityExceeded)?;
+        maybe_uninit_write_slice(spare_cap, &other.buf);
+        // SAFETY: the `len` bytes after the old length have been initialized from `other`
+        unsafe { self.buf.pb_set_len(old_len + len) };
+        Ok(())
+    }
+
+    /// Total size of all fields with the given number, in bytes
+    #[cfg(feature = "encode")]
+    fn size_of(&self, field_num: u32) -> usize {
+        let mut iter = self.iter();
+        let mut size = 0;
+        loop {
+            let before = iter.bytes.len();
+            let Some((tag, _)) = iter.next() else {
+                return size;
+            };
+            if tag.field_num() == field_num {
+                size += before - iter.bytes.len();
+            }
+        }
+    }
+}
+
+impl<B: PbBytes + DerefMut<Target = [u8]>> ExtensionFields<B> {
+    /// Remove all fields with the given number.
+    pub fn remove(&mut self, field_num: u32) {
+        let total = self.buf.len();
+        let mut read = 0;
+        let mut write = 0;
+        while read < total {
+            let mut iter = UnknownFieldsIter {
+                bytes: &self.buf[read..],
+            };
+            let Some((tag, _)) = iter.next() else {
+                break;
+            };
+            let field_len = total - read - iter.bytes.len();
+            if tag.field_num() != field_num {
+                self.buf.copy_within(read..read + field_len, write);
+                write += field_len;
+            }
+            read += field_len;
+        }
+        // SAFETY: `write` is never greater than the original length, so all bytes up to it are
+        // initialized
+        unsafe { self.buf.pb_set_len(write) };
+    }
+
+    /// Replace all fields with the given number with `len` bytes written by `encode`
+    #[cfg(feature = "encode")]
+    fn replace(
+        &mut self,
+        field_num: u32,
+        len: usize,
+        encode: impl FnOnce(&mut PbEncoder<&mut [u8]>) -> Result<(), ()>,
+    ) -> Result<(), BufferTooSmall> {
+        let removed = self.size_of(field_num);
+        self.buf.pb_reserve(len.saturating_sub(removed));
+        if self.buf.pb_spare_cap().len() + removed < len {
+            return Err(BufferTooSmall);
+        }
+        self.remove(field_num);
+
+        let old_len = self.buf.len();
+        let buf = maybe_uninit_zero_bytes(&mut self.buf.pb_spare_cap()[..len]);
+        let mut encoder = PbEncoder::new(buf);
+        encode(&mut encoder).map_err(|_| BufferTooSmall)?;
+        // SAFETY: the first `len` bytes of the spare capacity have been initialized
+        unsafe { self.buf.pb_set_len(old_len + len) };
+        Ok(())
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<B: PbBytes + Deref<Target = [u8]>> FieldDecode for ExtensionFields<B> {
+    fn decode_field<R: PbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut PbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        decoder.decode_raw_field(tag, &mut self.buf)
+    }
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+impl<B: PbBytes + Deref<Target = [u8]>> AsyncFieldDecode for ExtensionFields<B> {
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        decoder.decode_raw_field(tag, &mut self.buf).await
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<B: Deref<Target = [u8]>> FieldEncode for ExtensionFields<B> {
+    const MAX_SIZE: Result<usize, &'static str> = Err("unbounded extension fields");
+
+    fn encode_fields<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> {
+        encoder.write(&self.buf)
+    }
+
+    fn compute_fields_size(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+impl<B: Deref<Target = [u8]>> AsyncFieldEncode for ExtensionFields<B> {
+    async fn encode_fields_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        encoder.write(&self.buf).await
+    }
+}
+
+#[cfg(all(test, feature = "encode", feature = "decode"))]
+mod tests {
+    use arrayvec::ArrayVec;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Msg {
+        ext: ExtensionFields<ArrayVec<u8, 8>>,
+    }
+
+    impl Extendable for Msg {
+        type Buffer = ArrayVec<u8, 8>;
+
+        fn extensions(&self) -> &ExtensionFields<Self::Buffer> {
+            &self.ext
+        }
+
+        fn extensions_mut(&mut self) -> &mut ExtensionFields<Self::Buffer> {
+            &mut self.ext
+        }
+    }
+
+    // Repeated unpacked uint32 extension with field number 2
+    struct Nums;
+
+    impl Extension for Nums {
+        type Extendee = Msg;
+        type Value = ArrayVec<u32, 4>;
+        const NUMBER: u32 = 2;
+        const FULL_NAME: &'static str = "test.nums";
+    }
+
+    impl ExtensionDecode for Nums {
+        fn decode_value<R: PbRead>(
+            value: &mut Self::Value,
+            _tag: Tag,
+            decoder: &mut PbDecoder<R>,
+        ) -> Result<(), DecodeError<R::Error>> {
+            value
+                .try_push(decoder.decode_varint32()?)
+                .map_err(|_| DecodeError::Capacity)
+        }
+    }
+
+    impl ExtensionEncode for Nums {
+        fn encode_value<W: PbWrite>(
+            value: &Self::Value,
+            encoder: &mut PbEncoder<W>,
+        ) -> Result<(), W::Error> {
+            for &n in value {
+                encoder.encode_varint32(0x10)?;
+                encoder.encode_varint32(n)?;
+            }
+            Ok(())
+        }
+
+        fn compute_value_size(value: &Self::Value) -> usize {
+            value
+                .iter()
+                .map(|&n| 1 + crate::size::sizeof_varint32(n))
+                .sum()
+        }
+    }
+
+    #[test]
+    fn get_set() {
+        let mut msg = Msg::default();
+        // Field 1 varint, field 2 varint, field 2 varint
+        msg.ext
+            .buf
+            .try_extend_from_slice(&[0x08, 0x05, 0x10, 0x01, 0x10, 0x02])
+            .unwrap();
+        assert!(msg.has_extension(Nums));
+        assert_eq!(
+            msg.get_extension(Nums).unwrap().unwrap().as_slice(),
+            &[1, 2]
+        );
+
+        msg.set_extension(Nums, &[150].into_iter().collect())
+            .unwrap();
+        assert_eq!(msg.ext.as_bytes(), &[0x08, 0x05, 0x10, 0x96, 0x01]);
+        assert_eq!(msg.get_extension(Nums).unwrap().unwrap().as_slice(), &[150]);
+
+        // Doesn't fit, so storage is left unchanged
+        let too_big = [1, 2, 3, 4].into_iter().collect();
+        assert_eq!(msg.set_extension(Nums, &too_big), Err(BufferTooSmall));
+        assert_eq!(msg.ext.as_bytes(), &[0x08, 0x05, 0x10, 0x96, 0x01]);
+
+        msg.clear_extension(Nums);
+        assert!(!msg.has_extension(Nums));
+        assert_eq!(msg.get_extension(Nums), Ok(None));
+        assert_eq!(msg.ext.as_bytes(), &[0x08, 0x05]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut msg = Msg::default();
+        msg.set_extension(Nums, &[1].into_iter().collect()).unwrap();
+        let mut other = Msg::default();
+        other
+            .set_extension(Nums, &[2, 3].into_iter().collect())
+            .unwrap();
+
+        msg.ext.merge_from(&other.ext).unwrap();
+        assert_eq!(
+            msg.get_extension(Nums).unwrap().unwrap().as_slice(),
+            &[1, 2, 3]
+        );
+
+        // Doesn't fit, so storage is left unchanged
+        assert_eq!(msg.ext.merge_from(&other.ext), Err(CapacityExceeded));
+        assert_eq!(msg.ext.len(), 6);
+    }
+}
@@ -8,6 +8,10 @@
 use crate::decode::{DecodeError, PbDecoder, PbRead};
 #[cfg(feature = "encode")]
 use crate::encode::{PbEncoder, PbWrite};
+#[cfg(all(feature = "async", feature = "decode"))]
+use crate::{AsyncPbDecoder, AsyncPbRead};
+#[cfg(all(feature = "async", feature = "encode"))]
+use crate::{AsyncPbEncoder, AsyncPbWrite};
 
 #[cfg(feature = "decode")]
 /// One or more Protobuf fields that can be decoded from the wire.
@@ -171,3 +175,82 @@ impl<T: FieldEncode> FieldEncode for Option<T> {
         0
     }
 }
+
+#[cfg(all(feature = "async", feature = "decode"))]
+/// Async counterpart of [`FieldDecode`].
+///
+/// Custom fields must implement this trait if the generator is configured to output async
+/// decoding logic.
+#[allow(async_fn_in_trait)]
+pub trait AsyncFieldDecode {
+    /// Decode one single field from the async decoder.
+    ///
+    /// Same semantics as [`FieldDecode::decode_field`].
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: crate::Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>>;
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+impl<T: AsyncFieldDecode> AsyncFieldDecode for &mut T {
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: crate::Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        (*self).decode_field_async(tag, decoder).await
+    }
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+impl<T: Default + AsyncFieldDecode> AsyncFieldDecode for Option<T> {
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: crate::Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        let f = self.get_or_insert_with(Default::default);
+        f.decode_field_async(tag, decoder).await
+    }
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+/// Async counterpart of [`FieldEncode`].
+///
+/// Custom fields must implement this trait if the generator is configured to output async
+/// encoding logic. Sizes are still computed with [`FieldEncode::compute_fields_size`].
+#[allow(async_fn_in_trait)]
+pub trait AsyncFieldEncode: FieldEncode {
+    /// Encode all fields, including the tags, using the async encoder.
+    ///
+    /// Same semantics as [`FieldEncode::encode_fields`].
+    async fn encode_fields_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error>;
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+impl<T: AsyncFieldEncode> AsyncFieldEncode for &T {
+    async fn encode_fields_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        (*self).encode_fields_async(encoder).await
+    }
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+impl<T: AsyncFieldEncode> AsyncFieldEncode for Option<T> {
+    async fn encode_fields_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        if let Some(f) = self {
+            f.encode_fields_async(encoder).await?;
+        }
+        Ok(())
+    }
+}
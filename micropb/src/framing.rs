@@ -0,0 +1,982 @@
+//! Byte-stuffed framing of messages for serial transports.
+//!
+//! Streams such as UARTs have no notion of message boundaries, so each encoded message needs to be
+//! delimited before being sent. This module provides [`PbWrite`] wrappers that frame the encoded
+//! bytes on the fly, and [`PbRead`] wrappers that return the contents of one frame at a time,
+//! reporting EOF at the end of each frame. Two framing schemes are supported:
+//!
+//! - [COBS](https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing), which uses `0x00`
+//!   as the frame delimiter and has an overhead of at most 1 byte for every 254 bytes of data.
+//! - [SLIP](https://datatracker.ietf.org/doc/html/rfc1055), which uses `0xC0` as the frame
+//!   delimiter and escapes occurrences of the delimiter in the data.
+//!
+//! For noisy links, [`CrcWriter`] and [`CrcReader`] append and verify a checksum after each
+//! message, using [`Crc16Ccitt`], [`Crc32`], or any other [`Crc`] implementation. These are used
+//! by [`MessageEncode::encode_framed`](crate::MessageEncode::encode_framed) and
+//! [`MessageDecode::decode_framed`](crate::MessageDecode::decode_framed), which write each message
+//! as a length prefix, the message, and the checksum of both.
+//!
+//! None of the wrappers buffer frames in memory. The only buffer is the 254-byte block inside
+//! [`CobsWriter`], which COBS needs because each block is prefixed with its own length.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use micropb::framing::{CobsReader, CobsWriter};
+//! use micropb::{MessageDecode, MessageEncode, PbDecoder, PbEncoder, PbRead, PbWrite};
+//!
+//! # #[derive(Default)]
+//! # struct ProtoMessage;
+//! # impl MessageEncode for ProtoMessage {
+//! #   const MAX_SIZE: Result<usize, &str> = Ok(0);
+//! #   fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> { todo!() }
+//! #   fn compute_size(&self) -> usize { 0 }
+//! # }
+//! # impl MessageDecode for ProtoMessage {
+//! #   fn decode<R: PbRead>(&mut self, decoder: &mut PbDecoder<R>, len: usize) -> Result<(), micropb::DecodeError<R::Error>> { todo!() }
+//! # }
+//! let message = ProtoMessage::default();
+//! let mut encoder = PbEncoder::new(CobsWriter::new(Vec::new()));
+//! message.encode(&mut encoder).unwrap();
+//! // Write the last block and the frame delimiter
+//! let data = encoder.into_writer().finish().unwrap();
+//!
+//! let mut reader = CobsReader::new(data.as_slice());
+//! let mut decoder = PbDecoder::new(&mut reader);
+//! let mut output = ProtoMessage::default();
+//! decoder.decode_until_eof(&mut output).unwrap();
+//! // Move on to the next frame
+//! reader.next_frame().unwrap();
+//! ```
+
+#[cfg(feature = "encode")]
+use crate::PbWrite;
+#[cfg(feature = "decode")]
+use crate::{DecodeError, PbRead};
+
+/// Max number of data bytes in a COBS block
+#[cfg(feature = "encode")]
+const COBS_BLOCK: usize = 254;
+
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+#[cfg(feature = "encode")]
+/// Writer that COBS-encodes all written bytes into a single frame.
+///
+/// Call [`finish`](Self::finish) after encoding the message to terminate the frame. Writing more
+/// data after that requires a new `CobsWriter`.
+#[derive(Debug, Clone)]
+pub struct CobsWriter<W> {
+    inner: W,
+    block: [u8; COBS_BLOCK],
+    len: usize,
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite> CobsWriter<W> {
+    /// Construct a new frame writer around `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            block: [0; COBS_BLOCK],
+            len: 0,
+        }
+    }
+
+    /// Get reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    fn write_block(&mut self, code: u8) -> Result<(), W::Error> {
+        self.inner.pb_write(&[code])?;
+        self.inner.pb_write(&self.block[..self.len])?;
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Write the last block and the frame delimiter, returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, W::Error> {
+        self.write_block(self.len as u8 + 1)?;
+        self.inner.pb_write(&[0])?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite> PbWrite for CobsWriter<W> {
+    type Error = W::Error;
+
+    fn pb_write(&mut self, mut data: &[u8]) -> Result<(), Self::Error> {
+        while !data.is_empty() {
+            let space = COBS_BLOCK - self.len;
+            let window = &data[..data.len().min(space)];
+            match window.iter().position(|&b| b == 0) {
+                // Zeroes terminate the current block, and are implied by the block's code
+                Some(n) => {
+                    self.block[self.len..self.len + n].copy_from_slice(&window[..n]);
+                    self.len += n;
+                    self.write_block(self.len as u8 + 1)?;
+                    data = &data[n + 1..];
+                }
+                None => {
+                    let n = window.len();
+                    self.block[self.len..self.len + n].copy_from_slice(window);
+                    self.len += n;
+                    if self.len == COBS_BLOCK {
+                        self.write_block(0xFF)?;
+                    }
+                    data = &data[n..];
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "decode")]
+/// Reader that returns the decoded contents of one COBS frame at a time.
+///
+/// The reader reports EOF at the end of each frame, so messages should be decoded with
+/// [`PbDecoder::decode_until_eof`](crate::PbDecoder::decode_until_eof). Afterwards, call
+/// [`next_frame`](Self::next_frame) to move on to the next frame.
+///
+/// Malformed frames are truncated at the first unexpected `0x00`, which will likely cause the
+/// decoder to report [`DecodeError::UnexpectedEof`].
+#[derive(Debug, Clone)]
+pub struct CobsReader<R> {
+    inner: R,
+    /// Number of data bytes left in the current block
+    run: usize,
+    /// Whether the current block is followed by an implicit zero
+    zero_after: bool,
+    /// Whether the implicit zero has been confirmed and is being returned
+    zero_pending: bool,
+    frame_end: bool,
+    /// Whether the delimiter of the current frame has been consumed
+    delimited: bool,
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead> CobsReader<R> {
+    /// Construct a new frame reader around `inner`, starting at the beginning of a frame.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            run: 0,
+            zero_after: false,
+            zero_pending: false,
+            frame_end: false,
+            delimited: false,
+        }
+    }
+
+    /// Get reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Transform the frame reader into the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Skip the rest of the current frame and start reading the next one.
+    pub fn next_frame(&mut self) -> Result<(), R::Error> {
+        if !self.delimited {
+            skip_frame(&mut self.inner, 0)?;
+        }
+        self.run = 0;
+        self.zero_after = false;
+        self.zero_pending = false;
+        self.frame_end = false;
+        self.delimited = false;
+        Ok(())
+    }
+
+    fn end_frame(&mut self, delimited: bool) {
+        self.frame_end = true;
+        self.delimited = delimited;
+    }
+}
+
+/// Consume bytes from `reader` up to and including the next `delimiter`
+#[cfg(feature = "decode")]
+fn skip_frame<R: PbRead>(reader: &mut R, delimiter: u8) -> Result<(), R::Error> {
+    loop {
+        let chunk = reader.pb_read_chunk()?;
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        if let Some(n) = chunk.iter().position(|&b| b == delimiter) {
+            reader.pb_advance(n + 1);
+            return Ok(());
+        }
+        let n = chunk.len();
+        reader.pb_advance(n);
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead> PbRead for CobsReader<R> {
+    type Error = R::Error;
+
+    fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        loop {
+            if self.frame_end {
+                return Ok(&[]);
+            }
+            if self.run > 0 {
+                break;
+            }
+            if self.zero_pending {
+                return Ok(&[0]);
+            }
+
+            let next = self.inner.pb_read_chunk()?.first().copied();
+            if self.zero_after {
+                // The implicit zero after a block is only present if the frame continues
+                match next {
+                    None => self.end_frame(false),
+                    Some(0) => {
+                        self.inner.pb_advance(1);
+                        self.end_frame(true);
+                    }
+                    Some(_) => {
+                        self.zero_after = false;
+                        self.zero_pending = true;
+                    }
+                }
+                continue;
+            }
+            match next {
+                None => self.end_frame(false),
+                Some(0) => {
+                    self.inner.pb_advance(1);
+                    self.end_frame(true);
+                }
+                Some(code) => {
+                    self.inner.pb_advance(1);
+                    self.run = code as usize - 1;
+                    self.zero_after = code != 0xFF;
+                }
+            }
+        }
+
+        let run = self.run;
+        let (len, early_zero) = {
+            let chunk = self.inner.pb_read_chunk()?;
+            let chunk = &chunk[..chunk.len().min(run)];
+            match chunk.iter().position(|&b| b == 0) {
+                Some(n) => (n, true),
+                None => (chunk.len(), false),
+            }
+        };
+        if early_zero {
+            // Malformed block that ends before its length, so end the frame at the zero
+            self.run = len;
+            self.zero_after = false;
+            if len == 0 {
+                self.inner.pb_advance(1);
+                self.end_frame(true);
+                return Ok(&[]);
+            }
+        } else if len == 0 {
+            self.end_frame(false);
+            return Ok(&[]);
+        }
+        let chunk = self.inner.pb_read_chunk()?;
+        Ok(&chunk[..len])
+    }
+
+    fn pb_advance(&mut self, bytes: usize) {
+        if self.run > 0 {
+            self.inner.pb_advance(bytes);
+            self.run -= bytes;
+        } else if self.zero_pending && bytes > 0 {
+            self.zero_pending = false;
+        }
+    }
+}
+
+#[cfg(feature = "encode")]
+/// Writer that SLIP-encodes all written bytes into a single frame.
+///
+/// Call [`finish`](Self::finish) after encoding the message to terminate the frame.
+#[derive(Debug, Clone)]
+pub struct SlipWriter<W> {
+    inner: W,
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite> SlipWriter<W> {
+    /// Construct a new frame writer around `inner`.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Get reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Write the frame delimiter, returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, W::Error> {
+        self.inner.pb_write(&[SLIP_END])?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite> PbWrite for SlipWriter<W> {
+    type Error = W::Error;
+
+    fn pb_write(&mut self, mut data: &[u8]) -> Result<(), Self::Error> {
+        while let Some(n) = data.iter().position(|&b| b == SLIP_END || b == SLIP_ESC) {
+            self.inner.pb_write(&data[..n])?;
+            let escaped = if data[n] == SLIP_END {
+                SLIP_ESC_END
+            } else {
+                SLIP_ESC_ESC
+            };
+            self.inner.pb_write(&[SLIP_ESC, escaped])?;
+            data = &data[n + 1..];
+        }
+        self.inner.pb_write(data)
+    }
+}
+
+#[cfg(feature = "decode")]
+/// Reader that returns the decoded contents of one SLIP frame at a time.
+///
+/// Same as [`CobsReader`], the reader reports EOF at the end of each frame. Delimiters at the
+/// start of a frame are skipped, so empty frames are never returned. Invalid escape sequences are
+/// passed through without the escape byte, except for an escaped delimiter, which still ends the
+/// frame.
+#[derive(Debug, Clone)]
+pub struct SlipReader<R> {
+    inner: R,
+    /// Unescaped byte that is being returned, if `escaped` is set
+    escaped_byte: u8,
+    escaped: bool,
+    started: bool,
+    frame_end: bool,
+    delimited: bool,
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead> SlipReader<R> {
+    /// Construct a new frame reader around `inner`, starting at the beginning of a frame.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            escaped_byte: 0,
+            escaped: false,
+            started: false,
+            frame_end: false,
+            delimited: false,
+        }
+    }
+
+    /// Get reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Transform the frame reader into the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Skip the rest of the current frame and start reading the next one.
+    pub fn next_frame(&mut self) -> Result<(), R::Error> {
+        if !self.delimited {
+            skip_frame(&mut self.inner, SLIP_END)?;
+        }
+        self.escaped = false;
+        self.started = false;
+        self.frame_end = false;
+        self.delimited = false;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead> PbRead for SlipReader<R> {
+    type Error = R::Error;
+
+    fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        loop {
+            if self.frame_end {
+                return Ok(&[]);
+            }
+            if self.escaped {
+                return Ok(core::slice::from_ref(&self.escaped_byte));
+            }
+
+            match self.inner.pb_read_chunk()?.first().copied() {
+                None => self.frame_end = true,
+                Some(SLIP_END) => {
+                    self.inner.pb_advance(1);
+                    if self.started {
+                        self.frame_end = true;
+                        self.delimited = true;
+                    }
+                }
+                Some(SLIP_ESC) => {
+                    self.inner.pb_advance(1);
+                    self.started = true;
+                    match self.inner.pb_read_chunk()?.first().copied() {
+                        None => self.frame_end = true,
+                        Some(b) => {
+                            self.inner.pb_advance(1);
+                            if b == SLIP_END {
+                                self.frame_end = true;
+                                self.delimited = true;
+                            } else {
+                                self.escaped = true;
+                                self.escaped_byte = match b {
+                                    SLIP_ESC_END => SLIP_END,
+                                    SLIP_ESC_ESC => SLIP_ESC,
+                                    _ => b,
+                                };
+                            }
+                        }
+                    }
+                }
+                Some(_) => break,
+            }
+        }
+
+        self.started = true;
+        let chunk = self.inner.pb_read_chunk()?;
+        let n = chunk
+            .iter()
+            .position(|&b| b == SLIP_END || b == SLIP_ESC)
+            .unwrap_or(chunk.len());
+        Ok(&chunk[..n])
+    }
+
+    fn pb_advance(&mut self, bytes: usize) {
+        if self.escaped {
+            if bytes > 0 {
+                self.escaped = false;
+            }
+        } else {
+            self.inner.pb_advance(bytes);
+        }
+    }
+}
+
+/// Checksum algorithm used by [`CrcWriter`] and [`CrcReader`].
+///
+/// Implemented by [`Crc16Ccitt`] and [`Crc32`]. Other algorithms can be plugged in by implementing
+/// this trait.
+pub trait Crc: Default {
+    /// Size of the checksum in bytes, which must be at most 4.
+    const LEN: usize;
+
+    /// Feed `data` into the checksum.
+    fn update(&mut self, data: &[u8]);
+
+    /// Checksum of all data fed so far.
+    fn value(&self) -> u32;
+}
+
+/// CRC-16/CCITT-FALSE, with polynomial `0x1021` and initial value `0xFFFF`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc16Ccitt(u16);
+
+impl Default for Crc16Ccitt {
+    fn default() -> Self {
+        Self(0xFFFF)
+    }
+}
+
+impl Crc for Crc16Ccitt {
+    const LEN: usize = 2;
+
+    fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 ^= (b as u16) << 8;
+            for _ in 0..8 {
+                self.0 = if self.0 & 0x8000 != 0 {
+                    (self.0 << 1) ^ 0x1021
+                } else {
+                    self.0 << 1
+                };
+            }
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.0 as u32
+    }
+}
+
+/// CRC-32 (ISO-HDLC), the checksum used by Ethernet and zlib.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self(0xFFFFFFFF)
+    }
+}
+
+impl Crc for Crc32 {
+    const LEN: usize = 4;
+
+    fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 ^= b as u32;
+            for _ in 0..8 {
+                self.0 = if self.0 & 1 != 0 {
+                    (self.0 >> 1) ^ 0xEDB88320
+                } else {
+                    self.0 >> 1
+                };
+            }
+        }
+    }
+
+    fn value(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// Little-endian checksum trailer
+fn crc_trailer<C: Crc>(crc: &C) -> ([u8; 4], usize) {
+    (crc.value().to_le_bytes(), C::LEN.min(4))
+}
+
+#[cfg(feature = "encode")]
+/// Writer that computes a checksum of all written bytes.
+///
+/// Call [`finish`](Self::finish) after encoding the message to append the checksum.
+#[derive(Debug, Clone)]
+pub struct CrcWriter<W, C> {
+    inner: W,
+    crc: C,
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite, C: Crc> CrcWriter<W, C> {
+    /// Construct a new checksumming writer around `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            crc: C::default(),
+        }
+    }
+
+    /// Get reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Checksum of the bytes written so far.
+    pub fn crc(&self) -> &C {
+        &self.crc
+    }
+
+    /// Write the checksum in little-endian order, returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, W::Error> {
+        let (trailer, len) = crc_trailer(&self.crc);
+        self.inner
+            .pb_write(trailer.get(..len).unwrap_or(&trailer))?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<W: PbWrite, C: Crc> PbWrite for CrcWriter<W, C> {
+    type Error = W::Error;
+
+    #[inline]
+    fn pb_write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.crc.update(data);
+        self.inner.pb_write(data)
+    }
+}
+
+#[cfg(feature = "decode")]
+/// Reader that computes a checksum of all consumed bytes.
+///
+/// After decoding the message, call [`verify`](Self::verify) to check the checksum trailer that
+/// follows the message.
+#[derive(Debug, Clone)]
+pub struct CrcReader<R, C> {
+    inner: R,
+    crc: C,
+    /// Bytes consumed from the last chunk that haven't been checksummed or advanced in `inner` yet
+    pending: usize,
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead, C: Crc> CrcReader<R, C> {
+    /// Construct a new checksumming reader around `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            crc: C::default(),
+            pending: 0,
+        }
+    }
+
+    /// Get reference to the underlying reader.
+    ///
+    /// Bytes consumed since the last [`pb_read_chunk`](PbRead::pb_read_chunk) call may not have
+    /// been consumed from the underlying reader yet.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Transform the checksumming reader into the underlying reader.
+    pub fn into_inner(mut self) -> R {
+        self.inner.pb_advance(self.pending);
+        self.inner
+    }
+
+    /// Checksum of the bytes consumed so far.
+    pub fn crc(&mut self) -> Result<&C, R::Error> {
+        self.flush()?;
+        Ok(&self.crc)
+    }
+
+    /// Checksum the pending bytes and consume them from the underlying reader.
+    ///
+    /// The pending bytes haven't been consumed from the underlying reader, so they're still at the
+    /// start of its next chunk.
+    fn flush(&mut self) -> Result<(), R::Error> {
+        if self.pending > 0 {
+            let chunk = self.inner.pb_read_chunk()?;
+            self.crc.update(chunk.get(..self.pending).unwrap_or(chunk));
+            self.inner.pb_advance(self.pending);
+            self.pending = 0;
+        }
+        Ok(())
+    }
+
+    /// Read the little-endian checksum trailer and compare it against the checksum of the bytes
+    /// consumed so far, returning [`DecodeError::ChecksumMismatch`] if they differ.
+    ///
+    /// Afterwards, the checksum is reset so that the next message can be verified.
+    pub fn verify(&mut self) -> Result<(), DecodeError<R::Error>> {
+        self.flush().map_err(DecodeError::Reader)?;
+        let (expected, len) = crc_trailer(&self.crc);
+        let mut trailer = [0; 4];
+        for b in trailer.iter_mut().take(len) {
+            let chunk = self.inner.pb_read_chunk().map_err(DecodeError::Reader)?;
+            *b = *chunk.first().ok_or(DecodeError::UnexpectedEof)?;
+            self.inner.pb_advance(1);
+        }
+        self.crc = C::default();
+        if trailer[..len] != expected[..len] {
+            return Err(DecodeError::ChecksumMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: PbRead, C: Crc> PbRead for CrcReader<R, C> {
+    type Error = R::Error;
+
+    #[inline]
+    fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        self.flush()?;
+        self.inner.pb_read_chunk()
+    }
+
+    #[inline]
+    fn pb_advance(&mut self, bytes: usize) {
+        // Checksumming the consumed bytes requires reading the chunk again, which can fail, so
+        // it's deferred to the next read where errors can be returned
+        self.pending += bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use super::*;
+
+    /// Reader that returns one byte at a time, to exercise chunk boundaries
+    struct Trickle<'a>(&'a [u8]);
+
+    impl PbRead for Trickle<'_> {
+        type Error = core::convert::Infallible;
+
+        fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+            Ok(&self.0[..self.0.len().min(1)])
+        }
+
+        fn pb_advance(&mut self, bytes: usize) {
+            self.0 = &self.0[bytes..];
+        }
+    }
+
+    /// Reader that fails on a specific call to `pb_read_chunk`
+    struct FailingReader<'a> {
+        data: &'a [u8],
+        reads: usize,
+        fail_at: usize,
+    }
+
+    impl PbRead for FailingReader<'_> {
+        type Error = ();
+
+        fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+            self.reads += 1;
+            if self.reads == self.fail_at {
+                return Err(());
+            }
+            Ok(self.data)
+        }
+
+        fn pb_advance(&mut self, bytes: usize) {
+            self.data = &self.data[bytes..];
+        }
+    }
+
+    fn read_frame<R: PbRead>(reader: &mut R) -> Vec<u8> {
+        let mut out = vec![];
+        loop {
+            let chunk = reader.pb_read_chunk().ok().unwrap();
+            if chunk.is_empty() {
+                return out;
+            }
+            out.extend_from_slice(chunk);
+            let n = chunk.len();
+            reader.pb_advance(n);
+        }
+    }
+
+    fn cobs_encode(data: &[u8], fragment: usize) -> Vec<u8> {
+        let mut writer = CobsWriter::new(vec![]);
+        for chunk in data.chunks(fragment) {
+            writer.pb_write(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn cobs_vectors() -> Vec<(Vec<u8>, Vec<u8>)> {
+        let long: Vec<u8> = (1..=0xFE).collect();
+        vec![
+            (vec![], vec![0x01, 0x00]),
+            (vec![0x00], vec![0x01, 0x01, 0x00]),
+            (vec![0x00, 0x00], vec![0x01, 0x01, 0x01, 0x00]),
+            (vec![0x00, 0x11, 0x00], vec![0x01, 0x02, 0x11, 0x01, 0x00]),
+            (
+                vec![0x11, 0x22, 0x00, 0x33],
+                vec![0x03, 0x11, 0x22, 0x02, 0x33, 0x00],
+            ),
+            (
+                vec![0x11, 0x22, 0x33, 0x44],
+                vec![0x05, 0x11, 0x22, 0x33, 0x44, 0x00],
+            ),
+            (
+                vec![0x11, 0x00, 0x00, 0x00],
+                vec![0x02, 0x11, 0x01, 0x01, 0x01, 0x00],
+            ),
+            (
+                long.clone(),
+                [&[0xFF], long.as_slice(), &[0x01, 0x00]].concat(),
+            ),
+            (
+                [long.as_slice(), &[0x00]].concat(),
+                [&[0xFF], long.as_slice(), &[0x01, 0x01, 0x00]].concat(),
+            ),
+            (
+                [long.as_slice(), &[0xFF]].concat(),
+                [&[0xFF], long.as_slice(), &[0x02, 0xFF, 0x00]].concat(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn cobs_write() {
+        for (data, encoded) in cobs_vectors() {
+            for fragment in [1, 3, 300] {
+                assert_eq!(cobs_encode(&data, fragment), encoded, "{data:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn cobs_read() {
+        for (data, encoded) in cobs_vectors() {
+            let mut reader = CobsReader::new(encoded.as_slice());
+            assert_eq!(read_frame(&mut reader), data);
+            // EOF is sticky until the next frame
+            assert_eq!(reader.pb_read_chunk().unwrap(), &[]);
+
+            let mut reader = CobsReader::new(Trickle(&encoded));
+            assert_eq!(read_frame(&mut reader), data);
+        }
+    }
+
+    #[test]
+    fn cobs_frames() {
+        let stream = [
+            cobs_encode(&[0x01, 0x00, 0x02], 2),
+            cobs_encode(&[0x03, 0x04], 2),
+            cobs_encode(&[], 1),
+            cobs_encode(&[0x05, 0x00], 1),
+        ]
+        .concat();
+        let mut reader = CobsReader::new(stream.as_slice());
+        assert_eq!(read_frame(&mut reader), &[0x01, 0x00, 0x02]);
+        reader.next_frame().unwrap();
+        // Skip the rest of a partially read frame
+        assert_eq!(reader.pb_read_chunk().unwrap(), &[0x03, 0x04]);
+        reader.pb_advance(1);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[0x05, 0x00]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[]);
+        assert!(reader.into_inner().is_empty());
+    }
+
+    #[test]
+    fn cobs_malformed() {
+        // Block is cut short by a delimiter
+        let stream = [0x04, 0x01, 0x02, 0x00, 0x02, 0x03, 0x00];
+        let mut reader = CobsReader::new(stream.as_slice());
+        assert_eq!(read_frame(&mut reader), &[0x01, 0x02]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[0x03]);
+
+        // Missing delimiter at the end of the stream
+        let stream = [0x03, 0x01, 0x02, 0x02, 0x03];
+        let mut reader = CobsReader::new(Trickle(&stream));
+        assert_eq!(read_frame(&mut reader), &[0x01, 0x02, 0x00, 0x03]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[]);
+    }
+
+    #[test]
+    fn slip() {
+        let data = [0x01, SLIP_END, 0x02, SLIP_ESC, SLIP_ESC_END];
+        let encoded = [
+            0x01,
+            SLIP_ESC,
+            SLIP_ESC_END,
+            0x02,
+            SLIP_ESC,
+            SLIP_ESC_ESC,
+            SLIP_ESC_END,
+            SLIP_END,
+        ];
+        for fragment in [1, 2, 10] {
+            let mut writer = SlipWriter::new(vec![]);
+            for chunk in data.chunks(fragment) {
+                writer.pb_write(chunk).unwrap();
+            }
+            assert_eq!(writer.finish().unwrap(), encoded);
+        }
+
+        let mut reader = SlipReader::new(encoded.as_slice());
+        assert_eq!(read_frame(&mut reader), data);
+        let mut reader = SlipReader::new(Trickle(&encoded));
+        assert_eq!(read_frame(&mut reader), data);
+    }
+
+    #[test]
+    fn slip_frames() {
+        let stream = [
+            SLIP_END, SLIP_END, 0x01, 0x02, SLIP_END, // leading delimiters are skipped
+            0x03, SLIP_ESC, 0x04, SLIP_END, // invalid escape
+            0x05, SLIP_ESC, SLIP_END, // escaped delimiter
+            0x06, 0x07, SLIP_END, // skipped
+            0x08,
+        ];
+        let mut reader = SlipReader::new(stream.as_slice());
+        assert_eq!(read_frame(&mut reader), &[0x01, 0x02]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[0x03, 0x04]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[0x05]);
+        reader.next_frame().unwrap();
+        assert_eq!(reader.pb_read_chunk().unwrap(), &[0x06, 0x07]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[0x08]);
+        reader.next_frame().unwrap();
+        assert_eq!(read_frame(&mut reader), &[]);
+    }
+
+    #[test]
+    fn crc_check_values() {
+        let mut crc = Crc16Ccitt::default();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.value(), 0x29B1);
+
+        let mut crc = Crc32::default();
+        crc.update(b"123456789");
+        assert_eq!(crc.value(), 0xCBF43926);
+    }
+
+    #[test]
+    fn crc_round_trip() {
+        let mut writer = CrcWriter::<_, Crc16Ccitt>::new(vec![]);
+        writer.pb_write(b"1234").unwrap();
+        writer.pb_write(b"56789").unwrap();
+        let data = writer.finish().unwrap();
+        assert_eq!(&data[9..], &[0xB1, 0x29]);
+
+        let mut reader = CrcReader::<_, Crc16Ccitt>::new(Trickle(&data));
+        let mut buf = [MaybeUninit::uninit(); 9];
+        assert_eq!(reader.pb_read_exact(&mut buf).unwrap(), 9);
+        reader.verify().unwrap();
+
+        // Corrupted data
+        let mut data = data;
+        data[3] ^= 0x10;
+        let mut reader = CrcReader::<_, Crc16Ccitt>::new(data.as_slice());
+        reader.pb_advance(9);
+        assert_eq!(reader.verify(), Err(DecodeError::ChecksumMismatch));
+
+        // Missing trailer
+        let mut reader = CrcReader::<_, Crc32>::new(&data[..10]);
+        reader.pb_advance(9);
+        assert_eq!(reader.verify(), Err(DecodeError::UnexpectedEof));
+    }
+
+    #[test]
+    fn crc_read_error() {
+        let mut writer = CrcWriter::<_, Crc16Ccitt>::new(vec![]);
+        writer.pb_write(b"123456789").unwrap();
+        let data = writer.finish().unwrap();
+
+        // Only the first read succeeds, so the consumed bytes can't be checksummed
+        let mut reader = CrcReader::<_, Crc16Ccitt>::new(FailingReader {
+            data: &data,
+            reads: 0,
+            fail_at: 2,
+        });
+        assert_eq!(reader.pb_read_chunk().unwrap().len(), data.len());
+        reader.pb_advance(9);
+        assert_eq!(reader.verify(), Err(DecodeError::Reader(())));
+
+        let mut reader = CrcReader::<_, Crc16Ccitt>::new(FailingReader {
+            data: &data,
+            reads: 0,
+            fail_at: 0,
+        });
+        reader.pb_read_chunk().unwrap();
+        reader.pb_advance(4);
+        reader.pb_read_chunk().unwrap();
+        reader.pb_advance(5);
+        assert_eq!(reader.crc().unwrap().value(), 0x29B1);
+        reader.verify().unwrap();
+        assert!(reader.into_inner().data.is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Schema-less inspection of encoded Protobuf messages.
+//!
+//! Similar to `protoc --decode_raw`, this module shows the structure of a Protobuf payload without
+//! knowing its message type. [`PbDecoder::raw_fields`] iterates over the fields of an encoded
+//! message as pairs of [`Tag`] and [`WireValue`], while [`RawMessage`] prints the whole message
+//! in a human-readable format. Since the wire format doesn't say whether a length-delimited value
+//! is a string, a packed field, or a nested message, [`WireValue::as_message`] treats any value
+//! that parses cleanly as a nested message.
+//!
+//! Everything in this module works on byte slices without allocating, so it can be used on
+//! devices, such as for dumping received payloads to a debug console.
+//!
+//! # Example
+//!
+//! ```
+//! use micropb::inspect::{RawMessage, WireValue};
+//! use micropb::PbDecoder;
+//!
+//! // Field 1 with varint 150, followed by field 3 containing a message with field 1 set to 5
+//! let data = [0x08, 0x96, 0x01, 0x1A, 0x02, 0x08, 0x05];
+//!
+//! let mut decoder = PbDecoder::new(data.as_slice());
+//! let mut fields = decoder.raw_fields();
+//! let (tag, value) = fields.next().unwrap().unwrap();
+//! assert_eq!(tag.field_num(), 1);
+//! assert_eq!(value, WireValue::Varint(150));
+//!
+//! let output = format!("{}", RawMessage::new(&data).recursive(true));
+//! assert_eq!(output, "1: 150\n3 {\n  1: 5\n}\n");
+//! ```
+
+use core::{convert::Infallible, fmt, iter::FusedIterator};
+
+use crate::{
+    misc::varint64_from_bytes, unknown::split_group, DecodeError, PbDecoder, Tag,
+    DEFAULT_MAX_DEPTH, WIRE_TYPE_I32, WIRE_TYPE_I64, WIRE_TYPE_SGROUP, WIRE_TYPE_VARINT,
+};
+
+/// Value of a field on the wire, without any schema information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireValue<'a> {
+    /// `VARINT` value. Signed and zigzag-encoded integers are not distinguished from unsigned ones.
+    Varint(u64),
+    /// `I64` value, which can be a `fixed64`, `sfixed64`, or `double`.
+    Fixed64(u64),
+    /// `I32` value, which can be a `fixed32`, `sfixed32`, or `float`.
+    Fixed32(u32),
+    /// Payload of a `LEN` value, excluding the length prefix.
+    Len(&'a [u8]),
+    /// Contents of a group, excluding the start-group and end-group tags.
+    Group(&'a [u8]),
+}
+
+impl<'a> WireValue<'a> {
+    /// Interpret a `LEN` value or a group as a nested message.
+    ///
+    /// Groups are always messages. For `LEN` values, returns `None` if the value is empty or
+    /// doesn't parse as a sequence of fields with non-zero field numbers. Strings and bytes can
+    /// happen to parse as messages, so this is only a guess.
+    pub fn as_message(&self) -> Option<RawMessage<'a>> {
+        match self {
+            WireValue::Len(data) if !data.is_empty() && is_message(data) => {
+                Some(RawMessage::new(data))
+            }
+            WireValue::Group(data) => Some(RawMessage::new(data)),
+            _ => None,
+        }
+    }
+}
+
+/// Formats `VARINT` values as decimal, `I32` and `I64` values as hexadecimal, and `LEN` values as
+/// escaped strings, same as `protoc --decode_raw`. The contents of groups are also formatted as
+/// escaped strings.
+impl fmt::Display for WireValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireValue::Varint(v) => write!(f, "{v}"),
+            WireValue::Fixed64(v) => write!(f, "0x{v:016x}"),
+            WireValue::Fixed32(v) => write!(f, "0x{v:08x}"),
+            WireValue::Len(data) | WireValue::Group(data) => {
+                write!(f, "\"{}\"", data.escape_ascii())
+            }
+        }
+    }
+}
+
+fn is_message(data: &[u8]) -> bool {
+    PbDecoder::new(data)
+        .raw_fields()
+        .all(|field| field.is_ok_and(|(tag, _)| tag.field_num() != 0))
+}
+
+impl<'a> PbDecoder<&'a [u8]> {
+    /// Iterate over the remaining fields on the wire without a schema.
+    ///
+    /// Each item is the tag of a field along with its [`WireValue`]. Iteration ends when the
+    /// input runs out. If a field fails to parse, such as when it's truncated or has a stray
+    /// end-group tag, the error is returned and iteration stops.
+    pub fn raw_fields(&mut self) -> RawFields<'_, 'a> {
+        RawFields {
+            decoder: self,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over raw fields, created by [`PbDecoder::raw_fields`].
+pub struct RawFields<'d, 'a> {
+    decoder: &'d mut PbDecoder<&'a [u8]>,
+    done: bool,
+}
+
+impl<'a> RawFields<'_, 'a> {
+    fn next_field(&mut self) -> Result<(Tag, WireValue<'a>), DecodeError<Infallible>> {
+        let tag = self.decoder.decode_tag()?;
+        let start: &'a [u8] = self.decoder.as_reader();
+        self.decoder.skip_wire_value(tag.wire_type())?;
+        let raw = &start[..start.len() - self.decoder.as_reader().len()];
+
+        let value = match tag.wire_type() {
+            WIRE_TYPE_VARINT => WireValue::Varint(varint64_from_bytes(raw)),
+            WIRE_TYPE_I64 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(raw);
+                WireValue::Fixed64(u64::from_le_bytes(bytes))
+            }
+            WIRE_TYPE_I32 => {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(raw);
+                WireValue::Fixed32(u32::from_le_bytes(bytes))
+            }
+            // The group has already been parsed by skip_wire_value, so it must have an end tag
+            WIRE_TYPE_SGROUP => WireValue::Group(split_group(raw).map_or(raw, |(group, _)| group)),
+            // skip_wire_value only accepts LEN for the remaining wire types
+            _ => {
+                let prefix = raw.iter().position(|b| b & 0x80 == 0).map_or(0, |i| i + 1);
+                WireValue::Len(&raw[prefix..])
+            }
+        };
+        Ok((tag, value))
+    }
+}
+
+impl<'a> Iterator for RawFields<'_, 'a> {
+    type Item = Result<(Tag, WireValue<'a>), DecodeError<Infallible>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.decoder.as_reader().is_empty() {
+            return None;
+        }
+        let res = self.next_field();
+        self.done = res.is_err();
+        Some(res)
+    }
+}
+
+impl FusedIterator for RawFields<'_, '_> {}
+
+/// Pretty-printer for encoded messages, in the same format as `protoc --decode_raw`.
+///
+/// Each field is printed on its own line as the field number followed by the [`WireValue`]. Groups
+/// are printed as indented blocks. If [`recursive`](Self::recursive) is set, `LEN` values that
+/// parse as messages are also printed as indented blocks instead of strings. Recursion stops at
+/// [`DEFAULT_MAX_DEPTH`] levels. If the
+/// message fails to parse, the error is printed in angle brackets after the fields preceding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawMessage<'a> {
+    data: &'a [u8],
+    recursive: bool,
+}
+
+impl<'a> RawMessage<'a> {
+    /// Wrap an encoded message for printing, without recursing into nested messages.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            recursive: false,
+        }
+    }
+
+    /// Set whether `LEN` values that parse as messages are printed as nested messages.
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Encoded bytes of the message.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        let mut decoder = PbDecoder::new(self.data);
+        for field in decoder.raw_fields() {
+            let (tag, value) = match field {
+                Ok(field) => field,
+                Err(e) => return writeln!(f, "{:indent$}<{e}>", ""),
+            };
+            let num = tag.field_num();
+            let is_group = matches!(value, WireValue::Group(_));
+            let nested = if (self.recursive || is_group) && depth < DEFAULT_MAX_DEPTH {
+                value.as_message()
+            } else {
+                None
+            };
+            match nested {
+                Some(msg) => {
+                    writeln!(f, "{:indent$}{num} {{", "")?;
+                    msg.recursive(self.recursive).fmt_indented(f, depth + 1)?;
+                    writeln!(f, "{:indent$}}}", "")?;
+                }
+                None => writeln!(f, "{:indent$}{num}: {value}", "")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RawMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WIRE_TYPE_LEN, WIRE_TYPE_VARINT};
+
+    // Encoding of:
+    // 1: 150, 2: "testing", 3: { 1: 5, 2: { 1: 1 } }, 4: fixed32, 5: fixed64, 6: ""
+    const MSG: &[u8] = &[
+        0x08, 0x96, 0x01, 0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g', 0x1A, 0x06, 0x08,
+        0x05, 0x12, 0x02, 0x08, 0x01, 0x25, 0x00, 0x00, 0x80, 0x3F, 0x29, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08, 0x32, 0x00,
+    ];
+
+    #[test]
+    fn raw_fields() {
+        let mut decoder = PbDecoder::new(MSG);
+        let fields: Vec<_> = decoder.raw_fields().map(Result::unwrap).collect();
+        assert_eq!(
+            fields,
+            [
+                (Tag::from_parts(1, WIRE_TYPE_VARINT), WireValue::Varint(150)),
+                (
+                    Tag::from_parts(2, WIRE_TYPE_LEN),
+                    WireValue::Len(b"testing")
+                ),
+                (
+                    Tag::from_parts(3, WIRE_TYPE_LEN),
+                    WireValue::Len(&[0x08, 0x05, 0x12, 0x02, 0x08, 0x01])
+                ),
+                (
+                    Tag::from_parts(4, WIRE_TYPE_I32),
+                    WireValue::Fixed32(1.0f32.to_bits())
+                ),
+                (
+                    Tag::from_parts(5, WIRE_TYPE_I64),
+                    WireValue::Fixed64(0x0807060504030201)
+                ),
+                (Tag::from_parts(6, WIRE_TYPE_LEN), WireValue::Len(&[])),
+            ]
+        );
+
+        assert!(fields[1].1.as_message().is_none());
+        assert!(fields[2].1.as_message().is_some());
+        assert!(fields[5].1.as_message().is_none());
+        assert!(fields[0].1.as_message().is_none());
+    }
+
+    #[test]
+    fn raw_fields_error() {
+        // Group start tag without an end tag
+        let data = [0x08, 0x01, 0x0B, 0x08, 0x01];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let mut fields = decoder.raw_fields();
+        assert!(fields.next().unwrap().is_ok());
+        assert_eq!(fields.next().unwrap(), Err(DecodeError::UnexpectedEof));
+        assert!(fields.next().is_none());
+
+        // Stray end-group tag
+        let data = [0x0C];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let mut fields = decoder.raw_fields();
+        assert_eq!(fields.next().unwrap(), Err(DecodeError::UnexpectedEndGroup));
+
+        // Truncated LEN value
+        let data = [0x0A, 0x05, 0x01];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let mut fields = decoder.raw_fields();
+        assert_eq!(fields.next().unwrap(), Err(DecodeError::UnexpectedEof));
+        assert!(fields.next().is_none());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            format!("{}", RawMessage::new(MSG)),
+            "1: 150\n\
+             2: \"testing\"\n\
+             3: \"\\x08\\x05\\x12\\x02\\x08\\x01\"\n\
+             4: 0x3f800000\n\
+             5: 0x0807060504030201\n\
+             6: \"\"\n"
+        );
+        assert_eq!(
+            format!("{}", RawMessage::new(MSG).recursive(true)),
+            "1: 150\n\
+             2: \"testing\"\n\
+             3 {\n  1: 5\n  2 {\n    1: 1\n  }\n}\n\
+             4: 0x3f800000\n\
+             5: 0x0807060504030201\n\
+             6: \"\"\n"
+        );
+        assert_eq!(
+            format!("{}", RawMessage::new(&[0x08, 0x01, 0x0A, 0x05])),
+            "1: 1\n<unexpected end of input>\n"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        // 1: 1, 2 { 1: 5, 3 { 1: { 1: 7 } } }
+        let data = [
+            0x08, 0x01, 0x13, 0x08, 0x05, 0x1B, 0x0A, 0x02, 0x08, 0x07, 0x1C, 0x14,
+        ];
+        let mut decoder = PbDecoder::new(data.as_slice());
+        let fields: Vec<_> = decoder.raw_fields().map(Result::unwrap).collect();
+        assert_eq!(
+            fields[1],
+            (
+                Tag::from_parts(2, WIRE_TYPE_SGROUP),
+                WireValue::Group(&data[3..11])
+            )
+        );
+        assert_eq!(fields.len(), 2);
+
+        // Groups are nested even when not printing recursively
+        assert_eq!(
+            format!("{}", RawMessage::new(&data)),
+            "1: 1\n2 {\n  1: 5\n  3 {\n    1: \"\\x08\\x07\"\n  }\n}\n"
+        );
+        assert_eq!(
+            format!("{}", RawMessage::new(&data).recursive(true)),
+            "1: 1\n2 {\n  1: 5\n  3 {\n    1 {\n      1: 7\n    }\n  }\n}\n"
+        );
+    }
+}
@@ -0,0 +1,1221 @@
+//! Canonical [proto3 JSON](https://protobuf.dev/programming-guides/json/) encoding and decoding.
+//!
+//! Messages generated with
+//! [`Generator::json`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.json)
+//! implement [`JsonEncode`] and [`JsonDecode`], which follow the canonical proto3 JSON mapping:
+//!
+//! - Fields are named by their `json_name`, which defaults to the lowerCamelCase form of the field
+//!   name. The decoder also accepts the original field names.
+//! - Fields with explicit presence are only written if set, while fields with implicit presence,
+//!   repeated fields, and maps are only written if they're not empty or zero. A `null` value on
+//!   input leaves the field unchanged.
+//! - 64-bit integers are written as strings, while other integers are written as numbers. The
+//!   decoder accepts both forms for all integers.
+//! - `bytes` fields are written as standard base64 with padding. The decoder also accepts the
+//!   URL-safe alphabet and missing padding.
+//! - `string` fields without UTF-8 validation are written with invalid UTF-8 sequences replaced
+//!   by `U+FFFD`.
+//! - Enums are written by value name, or as a number if the value is unknown. The decoder accepts
+//!   both forms.
+//! - Non-finite floats are written as `"NaN"`, `"Infinity"`, and `"-Infinity"`.
+//! - Each oneof is written as the field that is set.
+//!
+//! [`JsonEncoder`] writes into any [`core::fmt::Write`], and
+//! [`JsonEncode::encode_json_pb`] writes into a [`PbWrite`](crate::PbWrite). [`JsonDecoder`]
+//! parses from a byte slice, writing strings, bytes, repeated fields, and maps into the message's
+//! own containers, reporting [`JsonError::Capacity`] when a fixed container runs out. Neither
+//! allocates, so the same generated types can be used on devices and on the host.
+//!
+//! Well-known types with special JSON representations, such as `Timestamp` and `Any`, are
+//! encoded as regular messages. Custom fields and unknown fields are not part of the JSON output,
+//! and JSON keys with escape sequences never match field names.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use micropb::json::{JsonDecode, JsonEncode};
+//!
+//! # #[derive(Default)]
+//! # struct Config;
+//! # impl JsonEncode for Config {
+//! #   fn encode_json<W: core::fmt::Write>(&self, encoder: &mut micropb::json::JsonEncoder<W>) -> core::fmt::Result { todo!() }
+//! # }
+//! # impl JsonDecode for Config {
+//! #   fn decode_json(&mut self, decoder: &mut micropb::json::JsonDecoder<'_>) -> Result<(), micropb::json::JsonError> { todo!() }
+//! # }
+//! let mut config = Config::default();
+//! config.parse_json(br#"{"baudRate": 115200, "name": "uart0"}"#).unwrap();
+//!
+//! let mut output = String::new();
+//! config.write_json(&mut output).unwrap();
+//! ```
+
+use core::fmt::{self, Write};
+use core::str::from_utf8;
+
+use crate::{
+    container::{PbBytes, PbString},
+    misc::{maybe_uninit_slice_assume_init_ref, maybe_uninit_write_slice},
+    text::TextEnum,
+};
+
+/// Error from parsing JSON.
+///
+/// Use [`JsonDecoder::position`] to find where the error happened.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonError {
+    /// Input is not valid JSON, or has the wrong type of value for a field
+    Syntax,
+    /// Input ended in the middle of a value
+    UnexpectedEof,
+    /// Key doesn't match any field in the message
+    UnknownField,
+    /// Number is malformed or out of range for the field's type
+    InvalidNumber,
+    /// Enum value name doesn't exist in the enum
+    UnknownEnumValue,
+    /// Malformed escape sequence in a string
+    InvalidEscape,
+    /// Malformed base64 in a `bytes` field
+    InvalidBase64,
+    /// String is not valid UTF-8
+    Utf8,
+    /// Exceeded capacity of fixed container for `string`, `bytes`, repeated, or `map` field
+    Capacity,
+    /// Nesting depth of objects and arrays exceeded the decoder's `max_depth`
+    MaxDepth,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax => f.write_str("syntax error"),
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnknownField => f.write_str("unknown field name"),
+            Self::InvalidNumber => f.write_str("invalid or out-of-range number"),
+            Self::UnknownEnumValue => f.write_str("unknown enum value name"),
+            Self::InvalidEscape => f.write_str("invalid escape sequence"),
+            Self::InvalidBase64 => f.write_str("invalid base64"),
+            Self::Utf8 => f.write_str("string is not valid UTF-8"),
+            Self::Capacity => f.write_str("capacity of fixed container exceeded"),
+            Self::MaxDepth => f.write_str("max nesting depth exceeded"),
+        }
+    }
+}
+
+impl core::error::Error for JsonError {}
+
+/// Protobuf message that can be encoded as JSON.
+pub trait JsonEncode {
+    /// Write all fields of the message into `encoder` as members of a JSON object.
+    ///
+    /// The enclosing braces are written by [`JsonEncoder::write_message`].
+    fn encode_json<W: Write>(&self, encoder: &mut JsonEncoder<W>) -> fmt::Result;
+
+    /// Write the message as a JSON object into `writer`.
+    fn write_json<W: Write>(&self, writer: W) -> fmt::Result {
+        JsonEncoder::new(writer).write_message(self)
+    }
+
+    /// Write the message as a JSON object into a [`PbWrite`](crate::PbWrite).
+    #[cfg(feature = "encode")]
+    fn encode_json_pb<W: crate::PbWrite>(&self, writer: W) -> Result<(), W::Error> {
+        let mut adapter = PbWriteAdapter {
+            writer,
+            error: None,
+        };
+        let _ = self.write_json(&mut adapter);
+        match adapter.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Protobuf message that can be decoded from JSON.
+pub trait JsonDecode {
+    /// Parse members of a JSON object from `decoder` into the message until the closing brace.
+    ///
+    /// Fields not present in the input are left unchanged.
+    fn decode_json(&mut self, decoder: &mut JsonDecoder<'_>) -> Result<(), JsonError>;
+
+    /// Parse a whole JSON object from `input`, merging it into `self`.
+    fn parse_json(&mut self, input: &[u8]) -> Result<(), JsonError> {
+        let mut decoder = JsonDecoder::new(input);
+        decoder.decode_message(self)?;
+        decoder.finish()
+    }
+}
+
+/// Writes to a `PbWrite` through `fmt::Write`, keeping the underlying error
+#[cfg(feature = "encode")]
+struct PbWriteAdapter<W: crate::PbWrite> {
+    writer: W,
+    error: Option<W::Error>,
+}
+
+#[cfg(feature = "encode")]
+impl<W: crate::PbWrite> Write for PbWriteAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.pb_write(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Writer of compact JSON.
+///
+/// Values are written with the `write_*` methods. Inside objects, each value must be preceded by
+/// a call to [`write_key`](Self::write_key) or [`write_key_display`](Self::write_key_display).
+/// Commas between members and elements are inserted automatically.
+#[derive(Debug)]
+pub struct JsonEncoder<W> {
+    writer: W,
+    needs_comma: bool,
+}
+
+impl<W: Write> JsonEncoder<W> {
+    /// Construct a new encoder writing into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            needs_comma: false,
+        }
+    }
+
+    /// Transform the encoder into the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn value(&mut self, f: impl FnOnce(&mut W) -> fmt::Result) -> fmt::Result {
+        if self.needs_comma {
+            self.writer.write_char(',')?;
+        }
+        f(&mut self.writer)?;
+        self.needs_comma = true;
+        Ok(())
+    }
+
+    /// Write the key of an object member, escaping it as a JSON string.
+    pub fn write_key(&mut self, key: &str) -> fmt::Result {
+        self.value(|w| write_json_str(w, key))?;
+        self.needs_comma = false;
+        self.writer.write_char(':')
+    }
+
+    /// Write the key of an object member from a `string` that isn't validated as UTF-8, replacing
+    /// invalid sequences with `U+FFFD`.
+    pub fn write_key_bytes(&mut self, key: &[u8]) -> fmt::Result {
+        self.value(|w| write_json_bytes(w, key))?;
+        self.needs_comma = false;
+        self.writer.write_char(':')
+    }
+
+    /// Write the key of an object member from a value's `Display` output, which must not require
+    /// escaping. Used for integer and `bool` map keys.
+    pub fn write_key_display<D: fmt::Display>(&mut self, key: D) -> fmt::Result {
+        self.value(|w| write!(w, "\"{key}\""))?;
+        self.needs_comma = false;
+        self.writer.write_char(':')
+    }
+
+    /// Write an integer as a JSON number.
+    pub fn write_int(&mut self, val: i64) -> fmt::Result {
+        self.value(|w| write!(w, "{val}"))
+    }
+
+    /// Write an unsigned integer as a JSON number.
+    pub fn write_uint(&mut self, val: u64) -> fmt::Result {
+        self.value(|w| write!(w, "{val}"))
+    }
+
+    /// Write a 64-bit integer as a JSON string.
+    pub fn write_int64(&mut self, val: i64) -> fmt::Result {
+        self.value(|w| write!(w, "\"{val}\""))
+    }
+
+    /// Write a 64-bit unsigned integer as a JSON string.
+    pub fn write_uint64(&mut self, val: u64) -> fmt::Result {
+        self.value(|w| write!(w, "\"{val}\""))
+    }
+
+    /// Write a `float` value.
+    pub fn write_float(&mut self, val: f32) -> fmt::Result {
+        self.write_double(val as f64)
+    }
+
+    /// Write a `double` value.
+    pub fn write_double(&mut self, val: f64) -> fmt::Result {
+        self.value(|w| {
+            if val.is_nan() {
+                w.write_str("\"NaN\"")
+            } else if val == f64::INFINITY {
+                w.write_str("\"Infinity\"")
+            } else if val == f64::NEG_INFINITY {
+                w.write_str("\"-Infinity\"")
+            } else {
+                write!(w, "{val}")
+            }
+        })
+    }
+
+    /// Write a `bool` value.
+    pub fn write_bool(&mut self, val: bool) -> fmt::Result {
+        self.value(|w| write!(w, "{val}"))
+    }
+
+    /// Write a `string` value.
+    pub fn write_str(&mut self, val: &str) -> fmt::Result {
+        self.value(|w| write_json_str(w, val))
+    }
+
+    /// Write a `string` value that isn't validated as UTF-8, replacing invalid sequences with
+    /// `U+FFFD`.
+    pub fn write_str_bytes(&mut self, val: &[u8]) -> fmt::Result {
+        self.value(|w| write_json_bytes(w, val))
+    }
+
+    /// Write a `bytes` value as a base64 string.
+    pub fn write_bytes(&mut self, val: &[u8]) -> fmt::Result {
+        self.value(|w| {
+            w.write_char('"')?;
+            for chunk in val.chunks(3) {
+                let b = [
+                    chunk[0],
+                    chunk.get(1).copied().unwrap_or(0),
+                    chunk.get(2).copied().unwrap_or(0),
+                ];
+                let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+                for i in 0..4 {
+                    if i <= chunk.len() {
+                        w.write_char(BASE64_CHARS[(n >> (18 - 6 * i)) & 0x3F] as char)?;
+                    } else {
+                        w.write_char('=')?;
+                    }
+                }
+            }
+            w.write_char('"')
+        })
+    }
+
+    /// Write an enum value as the value's name, or as a number if the value is unknown.
+    pub fn write_enum<E: TextEnum>(&mut self, val: &E) -> fmt::Result {
+        match val.text_name() {
+            Some(name) => self.value(|w| write!(w, "\"{name}\"")),
+            None => self.write_int(val.text_number()),
+        }
+    }
+
+    /// Write a message as a JSON object.
+    pub fn write_message<M: JsonEncode + ?Sized>(&mut self, msg: &M) -> fmt::Result {
+        self.begin_object()?;
+        msg.encode_json(self)?;
+        self.end_object()
+    }
+
+    /// Start a JSON object. Must be followed by [`end_object`](Self::end_object).
+    pub fn begin_object(&mut self) -> fmt::Result {
+        self.value(|w| w.write_char('{'))?;
+        self.needs_comma = false;
+        Ok(())
+    }
+
+    /// End a JSON object started by [`begin_object`](Self::begin_object).
+    pub fn end_object(&mut self) -> fmt::Result {
+        self.needs_comma = true;
+        self.writer.write_char('}')
+    }
+
+    /// Start a JSON array. Must be followed by [`end_array`](Self::end_array).
+    pub fn begin_array(&mut self) -> fmt::Result {
+        self.value(|w| w.write_char('['))?;
+        self.needs_comma = false;
+        Ok(())
+    }
+
+    /// End a JSON array started by [`begin_array`](Self::begin_array).
+    pub fn end_array(&mut self) -> fmt::Result {
+        self.needs_comma = true;
+        self.writer.write_char(']')
+    }
+}
+
+fn write_json_str<W: Write>(writer: &mut W, s: &str) -> fmt::Result {
+    writer.write_char('"')?;
+    write_json_chars(writer, s)?;
+    writer.write_char('"')
+}
+
+fn write_json_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> fmt::Result {
+    writer.write_char('"')?;
+    for chunk in bytes.utf8_chunks() {
+        write_json_chars(writer, chunk.valid())?;
+        if !chunk.invalid().is_empty() {
+            writer.write_char(char::REPLACEMENT_CHARACTER)?;
+        }
+    }
+    writer.write_char('"')
+}
+
+fn write_json_chars<W: Write>(writer: &mut W, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            '\u{08}' => writer.write_str("\\b")?,
+            '\u{0C}' => writer.write_str("\\f")?,
+            '\0'..='\u{1F}' => write!(writer, "\\u{:04x}", c as u32)?,
+            _ => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Parser of JSON from a byte slice.
+///
+/// Generated [`JsonDecode`] implementations call [`next_key`](Self::next_key) to read each key
+/// of an object, then the `decode_*` method corresponding to the field's type to read the value.
+#[derive(Debug, Clone)]
+pub struct JsonDecoder<'a> {
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+    /// Set at the start of an object, before the first key
+    first: bool,
+    /// Maximum nesting depth of objects and arrays. When parsing a value would nest deeper than
+    /// this, the decoder will report [`JsonError::MaxDepth`]. Defaults to 100.
+    pub max_depth: usize,
+    /// If set, keys that don't match any field are skipped rather than reported as
+    /// [`JsonError::UnknownField`]. Disabled by default.
+    pub ignore_unknown_fields: bool,
+}
+
+impl<'a> JsonDecoder<'a> {
+    /// Construct a new decoder over `input`.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+            first: false,
+            max_depth: 100,
+            ignore_unknown_fields: false,
+        }
+    }
+
+    /// Byte offset of the decoder in the input.
+    ///
+    /// After an error, this points at or right after the token that caused it.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.input.get(self.pos..).unwrap_or_default()
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.rest().first() {
+            self.pos += 1;
+        }
+        self.rest().first().copied()
+    }
+
+    fn eof_or(&mut self, err: JsonError) -> JsonError {
+        if self.peek().is_none() {
+            JsonError::UnexpectedEof
+        } else {
+            err
+        }
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), JsonError> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(self.eof_or(JsonError::Syntax))
+        }
+    }
+
+    fn literal(&mut self, lit: &[u8]) -> bool {
+        if self.peek().is_some() && self.rest().starts_with(lit) {
+            self.pos += lit.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check that there's nothing but whitespace left in the input.
+    pub fn finish(&mut self) -> Result<(), JsonError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(JsonError::Syntax),
+        }
+    }
+
+    /// Read the contents of a string without the quotes, leaving escape sequences as-is
+    fn raw_string(&mut self) -> Result<&'a [u8], JsonError> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        let mut escaped = false;
+        loop {
+            let Some(&b) = self.rest().first() else {
+                return Err(JsonError::UnexpectedEof);
+            };
+            self.pos += 1;
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => break,
+                0..0x20 => return Err(JsonError::Syntax),
+                _ => {}
+            }
+        }
+        Ok(&self.input[start..self.pos - 1])
+    }
+
+    /// Read a number token, or the contents of a string if `quoted` is allowed
+    fn number_token(&mut self, quoted: bool) -> Result<&'a str, JsonError> {
+        let token = if quoted && self.peek() == Some(b'"') {
+            self.raw_string()?
+        } else {
+            self.peek();
+            let len = self
+                .rest()
+                .iter()
+                .position(|b| !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+                .unwrap_or(self.rest().len());
+            if len == 0 {
+                return Err(self.eof_or(JsonError::Syntax));
+            }
+            let token = &self.rest()[..len];
+            self.pos += len;
+            token
+        };
+        from_utf8(token)
+            .ok()
+            .filter(|s| is_json_number(s))
+            .ok_or(JsonError::InvalidNumber)
+    }
+
+    /// Read the next key of the current object, along with the colon after it.
+    ///
+    /// Returns `None` after consuming the closing brace of the object. The key is returned as it
+    /// appears in the input, without processing escape sequences.
+    pub fn next_key(&mut self) -> Result<Option<&'a str>, JsonError> {
+        if self.eat(b'}') {
+            self.first = false;
+            return Ok(None);
+        }
+        if !self.first {
+            self.expect(b',')?;
+        }
+        self.first = false;
+        let key = from_utf8(self.raw_string()?).map_err(|_| JsonError::Utf8)?;
+        self.expect(b':')?;
+        Ok(Some(key))
+    }
+
+    /// Consume a `null` value, returning whether it was present.
+    pub fn decode_null(&mut self) -> Result<bool, JsonError> {
+        Ok(self.literal(b"null"))
+    }
+
+    /// Read a JSON object, calling `decode_members` to read its members.
+    ///
+    /// `decode_members` should call [`next_key`](Self::next_key) until it returns `None`.
+    pub fn decode_nested<F>(&mut self, decode_members: F) -> Result<(), JsonError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), JsonError>,
+    {
+        self.expect(b'{')?;
+        if self.depth >= self.max_depth {
+            return Err(JsonError::MaxDepth);
+        }
+        self.depth += 1;
+        self.first = true;
+        decode_members(self)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Read a message value into `msg`.
+    pub fn decode_message<M: JsonDecode + ?Sized>(&mut self, msg: &mut M) -> Result<(), JsonError> {
+        self.decode_nested(|decoder| msg.decode_json(decoder))
+    }
+
+    /// Read a JSON array, calling `decode_elem` for each element.
+    pub fn decode_array<F>(&mut self, mut decode_elem: F) -> Result<(), JsonError>
+    where
+        F: FnMut(&mut Self) -> Result<(), JsonError>,
+    {
+        self.expect(b'[')?;
+        if self.depth >= self.max_depth {
+            return Err(JsonError::MaxDepth);
+        }
+        self.depth += 1;
+        if !self.eat(b']') {
+            loop {
+                decode_elem(self)?;
+                if self.eat(b']') {
+                    break;
+                }
+                self.expect(b',')?;
+            }
+        }
+        self.depth -= 1;
+        self.first = false;
+        Ok(())
+    }
+
+    /// Handle the value of a key that doesn't match any field.
+    ///
+    /// Skips the value if [`ignore_unknown_fields`](Self::ignore_unknown_fields) is set, and
+    /// returns [`JsonError::UnknownField`] otherwise.
+    pub fn skip_unknown_field(&mut self) -> Result<(), JsonError> {
+        if self.ignore_unknown_fields {
+            self.skip_value()
+        } else {
+            Err(JsonError::UnknownField)
+        }
+    }
+
+    /// Skip over any JSON value.
+    pub fn skip_value(&mut self) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(b'{') => self.decode_nested(|decoder| {
+                while decoder.next_key()?.is_some() {
+                    decoder.skip_value()?;
+                }
+                Ok(())
+            }),
+            Some(b'[') => self.decode_array(|decoder| decoder.skip_value()),
+            Some(b'"') => self.raw_string().map(|_| ()),
+            Some(b't' | b'f') => self.decode_bool().map(|_| ()),
+            Some(b'n') if self.decode_null()? => Ok(()),
+            _ => self.number_token(false).map(|_| ()),
+        }
+    }
+
+    /// Read an integer, given either as a number or as a string, into type `T`.
+    pub fn decode_int<T: TryFrom<i64>>(&mut self) -> Result<T, JsonError> {
+        let token = self.number_token(true)?;
+        Self::parse_int(token)
+    }
+
+    /// Read an unsigned integer, given either as a number or as a string, into type `T`.
+    pub fn decode_uint<T: TryFrom<u64>>(&mut self) -> Result<T, JsonError> {
+        let token = self.number_token(true)?;
+        Self::parse_uint(token)
+    }
+
+    fn parse_int<T: TryFrom<i64>>(token: &str) -> Result<T, JsonError> {
+        let val = match token.parse::<i64>() {
+            Ok(v) => v,
+            // Integers can also be written in exponent or fractional notation
+            Err(_) => {
+                let v: f64 = token.parse().map_err(|_| JsonError::InvalidNumber)?;
+                // Check the range before the cast, so that the round trip only fails for fractions
+                if !(-9.223372036854776e18..9.223372036854776e18).contains(&v)
+                    || (v as i64) as f64 != v
+                {
+                    return Err(JsonError::InvalidNumber);
+                }
+                v as i64
+            }
+        };
+        T::try_from(val).map_err(|_| JsonError::InvalidNumber)
+    }
+
+    fn parse_uint<T: TryFrom<u64>>(token: &str) -> Result<T, JsonError> {
+        let val = match token.parse::<u64>() {
+            Ok(v) => v,
+            Err(_) => {
+                let v: f64 = token.parse().map_err(|_| JsonError::InvalidNumber)?;
+                if !(0.0..1.8446744073709552e19).contains(&v) || (v as u64) as f64 != v {
+                    return Err(JsonError::InvalidNumber);
+                }
+                v as u64
+            }
+        };
+        T::try_from(val).map_err(|_| JsonError::InvalidNumber)
+    }
+
+    /// Read a `float` value.
+    pub fn decode_float(&mut self) -> Result<f32, JsonError> {
+        let val = self.decode_double()?;
+        let narrowed = val as f32;
+        if val.is_finite() && narrowed.is_infinite() {
+            return Err(JsonError::InvalidNumber);
+        }
+        Ok(narrowed)
+    }
+
+    /// Read a `double` value, which can also be `"NaN"`, `"Infinity"`, or `"-Infinity"`.
+    pub fn decode_double(&mut self) -> Result<f64, JsonError> {
+        if self.literal(b"\"NaN\"") {
+            return Ok(f64::NAN);
+        } else if self.literal(b"\"Infinity\"") {
+            return Ok(f64::INFINITY);
+        } else if self.literal(b"\"-Infinity\"") {
+            return Ok(f64::NEG_INFINITY);
+        }
+        let val: f64 = self
+            .number_token(true)?
+            .parse()
+            .map_err(|_| JsonError::InvalidNumber)?;
+        // Numbers too large for a double are parsed as infinity
+        if val.is_infinite() {
+            return Err(JsonError::InvalidNumber);
+        }
+        Ok(val)
+    }
+
+    /// Read a `bool` value.
+    pub fn decode_bool(&mut self) -> Result<bool, JsonError> {
+        if self.literal(b"true") {
+            Ok(true)
+        } else if self.literal(b"false") {
+            Ok(false)
+        } else {
+            Err(self.eof_or(JsonError::Syntax))
+        }
+    }
+
+    /// Read an enum value, given either as a value name or as a number.
+    pub fn decode_enum<E: TextEnum>(&mut self) -> Result<E, JsonError> {
+        if self.peek() == Some(b'"') {
+            let name = from_utf8(self.raw_string()?).map_err(|_| JsonError::Utf8)?;
+            E::from_text_name(name).ok_or(JsonError::UnknownEnumValue)
+        } else {
+            let num = self.decode_int()?;
+            E::from_text_number(num).ok_or(JsonError::InvalidNumber)
+        }
+    }
+
+    /// Read a `string` value into a [`PbString`] container, replacing its existing contents.
+    pub fn decode_string<S: PbString>(&mut self, string: &mut S) -> Result<(), JsonError> {
+        let raw = self.raw_string()?;
+        Self::decode_key_str(raw_str(raw)?, string)
+    }
+
+    /// Read a `string` value into a [`PbBytes`] container without validating it as UTF-8,
+    /// replacing its existing contents.
+    pub fn decode_string_bytes<B: PbBytes>(&mut self, bytes: &mut B) -> Result<(), JsonError> {
+        let raw = self.raw_string()?;
+        Self::decode_key_bytes(raw_str(raw)?, bytes)
+    }
+
+    /// Read a `bytes` value encoded as base64 into a [`PbBytes`] container, replacing its
+    /// existing contents.
+    pub fn decode_bytes<B: PbBytes>(&mut self, bytes: &mut B) -> Result<(), JsonError> {
+        let raw = self.raw_string()?;
+        // Padding is optional
+        let data = raw
+            .strip_suffix(b"==")
+            .or(raw.strip_suffix(b"="))
+            .unwrap_or(raw);
+        if data.len() % 4 == 1 {
+            return Err(JsonError::InvalidBase64);
+        }
+        let len = data.len() * 3 / 4;
+
+        bytes.pb_clear();
+        bytes.pb_reserve(len);
+        let spare_cap = bytes.pb_spare_cap();
+        if spare_cap.len() < len {
+            return Err(JsonError::Capacity);
+        }
+        let mut written = 0;
+        for chunk in data.chunks(4) {
+            let mut n = 0;
+            for (i, c) in chunk.iter().enumerate() {
+                n |= (base64_value(*c).ok_or(JsonError::InvalidBase64)? as u32) << (18 - 6 * i);
+            }
+            let decoded = &n.to_be_bytes()[1..chunk.len()];
+            written += maybe_uninit_write_slice(&mut spare_cap[written..], decoded);
+        }
+        // SAFETY: `len` bytes have been written into the spare capacity
+        unsafe { bytes.pb_set_len(len) };
+        Ok(())
+    }
+
+    /// Unescape a map key returned by [`next_key`](Self::next_key) into a [`PbString`]
+    /// container, replacing its existing contents.
+    pub fn decode_key_str<S: PbString>(key: &str, string: &mut S) -> Result<(), JsonError> {
+        let len = unescape_into(key.as_bytes(), string)?;
+        // SAFETY: `len` bytes have been written into the spare capacity
+        let written = unsafe { maybe_uninit_slice_assume_init_ref(&string.pb_spare_cap()[..len]) };
+        // Escaped surrogates can be unpaired
+        if from_utf8(written).is_err() {
+            string.pb_clear();
+            return Err(JsonError::Utf8);
+        }
+        // SAFETY: `len` bytes have been written, and we just checked that they're valid UTF-8
+        unsafe { string.pb_set_len(len) };
+        Ok(())
+    }
+
+    /// Unescape a map key returned by [`next_key`](Self::next_key) into a [`PbBytes`] container
+    /// without validating it as UTF-8, replacing its existing contents.
+    pub fn decode_key_bytes<B: PbBytes>(key: &str, bytes: &mut B) -> Result<(), JsonError> {
+        let len = unescape_into(key.as_bytes(), bytes)?;
+        // SAFETY: `len` bytes have been written into the spare capacity
+        unsafe { bytes.pb_set_len(len) };
+        Ok(())
+    }
+
+    /// Parse a map key returned by [`next_key`](Self::next_key) as an integer.
+    pub fn decode_key_int<T: TryFrom<i64>>(key: &str) -> Result<T, JsonError> {
+        if !is_json_number(key) {
+            return Err(JsonError::InvalidNumber);
+        }
+        Self::parse_int(key)
+    }
+
+    /// Parse a map key returned by [`next_key`](Self::next_key) as an unsigned integer.
+    pub fn decode_key_uint<T: TryFrom<u64>>(key: &str) -> Result<T, JsonError> {
+        if !is_json_number(key) {
+            return Err(JsonError::InvalidNumber);
+        }
+        Self::parse_uint(key)
+    }
+
+    /// Parse a map key returned by [`next_key`](Self::next_key) as a `bool`.
+    pub fn decode_key_bool(key: &str) -> Result<bool, JsonError> {
+        match key {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(JsonError::Syntax),
+        }
+    }
+}
+
+fn raw_str(raw: &[u8]) -> Result<&str, JsonError> {
+    from_utf8(raw).map_err(|_| JsonError::Utf8)
+}
+
+/// Check number syntax, which is stricter than what Rust's parser accepts
+fn is_json_number(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s).as_bytes();
+    let int_len = s
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(s.len());
+    if int_len == 0 || (int_len > 1 && s[0] == b'0') {
+        return false;
+    }
+    let mut rest = &s[int_len..];
+    if let Some(frac) = rest.strip_prefix(b".") {
+        let frac_len = frac
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(frac.len());
+        if frac_len == 0 {
+            return false;
+        }
+        rest = &frac[frac_len..];
+    }
+    if let Some(exp) = rest.strip_prefix(b"e").or(rest.strip_prefix(b"E")) {
+        let exp = exp
+            .strip_prefix(b"-")
+            .or(exp.strip_prefix(b"+"))
+            .unwrap_or(exp);
+        return !exp.is_empty() && exp.iter().all(u8::is_ascii_digit);
+    }
+    rest.is_empty()
+}
+
+/// Map both the standard and URL-safe base64 alphabets to their values
+fn base64_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+fn hex4(digits: &[u8]) -> Result<u32, JsonError> {
+    let digits = digits.get(..4).ok_or(JsonError::InvalidEscape)?;
+    let digits = from_utf8(digits).map_err(|_| JsonError::InvalidEscape)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(JsonError::InvalidEscape);
+    }
+    u32::from_str_radix(digits, 16).map_err(|_| JsonError::InvalidEscape)
+}
+
+/// Process escape sequences in the contents of a string, passing the result to `emit` in chunks
+/// Unescape a JSON string into the spare capacity of a cleared container, returning the number
+/// of bytes written. The length of the container is left for the caller to set.
+fn unescape_into<S: PbString>(raw: &[u8], string: &mut S) -> Result<usize, JsonError> {
+    // Measure the unescaped length first, so that we can check the capacity
+    let mut len = 0;
+    unescape(raw, |chunk| len += chunk.len())?;
+
+    string.pb_clear();
+    string.pb_reserve(len);
+    let spare_cap = string.pb_spare_cap();
+    if spare_cap.len() < len {
+        return Err(JsonError::Capacity);
+    }
+    let mut written = 0;
+    unescape(raw, |chunk| {
+        written += maybe_uninit_write_slice(&mut spare_cap[written..], chunk)
+    })?;
+    Ok(len)
+}
+
+fn unescape<F: FnMut(&[u8])>(mut raw: &[u8], mut emit: F) -> Result<(), JsonError> {
+    while !raw.is_empty() {
+        let plain = raw.iter().position(|b| *b == b'\\').unwrap_or(raw.len());
+        if plain > 0 {
+            emit(&raw[..plain]);
+            raw = &raw[plain..];
+            continue;
+        }
+
+        let byte = match raw.get(1) {
+            Some(b'"') => b'"',
+            Some(b'\\') => b'\\',
+            Some(b'/') => b'/',
+            Some(b'b') => 0x08,
+            Some(b'f') => 0x0C,
+            Some(b'n') => b'\n',
+            Some(b'r') => b'\r',
+            Some(b't') => b'\t',
+            Some(b'u') => {
+                let mut code = hex4(&raw[2..])?;
+                raw = &raw[6..];
+                // Combine surrogate pairs
+                if (0xD800..0xDC00).contains(&code) && raw.starts_with(b"\\u") {
+                    let low = hex4(&raw[2..])?;
+                    if (0xDC00..0xE000).contains(&low) {
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                        raw = &raw[6..];
+                    }
+                }
+                match char::from_u32(code) {
+                    Some(c) => emit(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    // Unpaired surrogates are encoded as-is, which fails UTF-8 validation later
+                    None => emit(&[
+                        0xED,
+                        0xA0 | (code >> 6 & 0x1F) as u8,
+                        0x80 | (code & 0x3F) as u8,
+                    ]),
+                }
+                continue;
+            }
+            _ => return Err(JsonError::InvalidEscape),
+        };
+        emit(&[byte]);
+        raw = &raw[2..];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Color(i32);
+
+    impl TextEnum for Color {
+        fn text_name(&self) -> Option<&'static str> {
+            (self.0 == 1).then_some("GREEN")
+        }
+
+        fn from_text_name(name: &str) -> Option<Self> {
+            (name == "GREEN").then_some(Self(1))
+        }
+
+        fn text_number(&self) -> i64 {
+            self.0 as i64
+        }
+
+        fn from_text_number(num: i64) -> Option<Self> {
+            i32::try_from(num).ok().map(Self)
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Msg {
+        num: i64,
+        name: heapless_0_9::String<8>,
+        list: heapless_0_9::Vec<u32, 3>,
+        inner: Option<Box<Msg>>,
+    }
+
+    impl JsonEncode for Msg {
+        fn encode_json<W: Write>(&self, encoder: &mut JsonEncoder<W>) -> fmt::Result {
+            if self.num != 0 {
+                encoder.write_key("num")?;
+                encoder.write_int64(self.num)?;
+            }
+            if !self.name.is_empty() {
+                encoder.write_key("name")?;
+                encoder.write_str(&self.name)?;
+            }
+            if !self.list.is_empty() {
+                encoder.write_key("list")?;
+                encoder.begin_array()?;
+                for elem in &self.list {
+                    encoder.write_uint(*elem as u64)?;
+                }
+                encoder.end_array()?;
+            }
+            if let Some(inner) = &self.inner {
+                encoder.write_key("innerMsg")?;
+                encoder.write_message(inner.as_ref())?;
+            }
+            Ok(())
+        }
+    }
+
+    impl JsonDecode for Msg {
+        fn decode_json(&mut self, decoder: &mut JsonDecoder<'_>) -> Result<(), JsonError> {
+            while let Some(key) = decoder.next_key()? {
+                if decoder.decode_null()? {
+                    continue;
+                }
+                match key {
+                    "num" => self.num = decoder.decode_int()?,
+                    "name" => decoder.decode_string(&mut self.name)?,
+                    "list" => decoder.decode_array(|decoder| {
+                        let elem = decoder.decode_uint()?;
+                        self.list.push(elem).map_err(|_| JsonError::Capacity)
+                    })?,
+                    "innerMsg" | "inner_msg" => decoder
+                        .decode_message(self.inner.get_or_insert_with(Default::default).as_mut())?,
+                    _ => decoder.skip_unknown_field()?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn encode_with(f: impl FnOnce(&mut JsonEncoder<String>) -> fmt::Result) -> String {
+        let mut encoder = JsonEncoder::new(String::new());
+        f(&mut encoder).unwrap();
+        encoder.into_writer()
+    }
+
+    #[test]
+    fn encode_values() {
+        assert_eq!(encode_with(|e| e.write_int(-5)), "-5");
+        assert_eq!(
+            encode_with(|e| e.write_uint64(u64::MAX)),
+            "\"18446744073709551615\""
+        );
+        assert_eq!(encode_with(|e| e.write_float(1.5)), "1.5");
+        assert_eq!(encode_with(|e| e.write_float(f32::NAN)), "\"NaN\"");
+        assert_eq!(
+            encode_with(|e| e.write_double(f64::NEG_INFINITY)),
+            "\"-Infinity\""
+        );
+        assert_eq!(
+            encode_with(|e| e.write_str("\"é\\\n\u{1}")),
+            r#""\"é\\\n\u0001""#
+        );
+        assert_eq!(
+            encode_with(|e| e.write_str_bytes(b"a\xFF\xC3\xA9\"")),
+            "\"a\u{FFFD}é\\\"\""
+        );
+        assert_eq!(encode_with(|e| e.write_bytes(b"")), r#""""#);
+        assert_eq!(encode_with(|e| e.write_bytes(b"f")), r#""Zg==""#);
+        assert_eq!(encode_with(|e| e.write_bytes(b"fo")), r#""Zm8=""#);
+        assert_eq!(
+            encode_with(|e| e.write_bytes(&[0xFB, 0xFF, 0x00])),
+            r#""+/8A""#
+        );
+        assert_eq!(encode_with(|e| e.write_enum(&Color(1))), r#""GREEN""#);
+        assert_eq!(encode_with(|e| e.write_enum(&Color(7))), "7");
+        assert_eq!(
+            encode_with(|e| {
+                e.begin_object()?;
+                e.write_key_display(true)?;
+                e.write_bool(false)?;
+                e.write_key_display(-3)?;
+                e.begin_array()?;
+                e.end_array()?;
+                e.end_object()
+            }),
+            r#"{"true":false,"-3":[]}"#
+        );
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let msg = Msg {
+            num: -9,
+            name: "top".try_into().unwrap(),
+            list: heapless_0_9::Vec::from_slice(&[1, 2]).unwrap(),
+            inner: Some(Box::new(Msg {
+                num: 4,
+                ..Default::default()
+            })),
+        };
+        let mut output = String::new();
+        msg.write_json(&mut output).unwrap();
+        assert_eq!(
+            output,
+            r#"{"num":"-9","name":"top","list":[1,2],"innerMsg":{"num":"4"}}"#
+        );
+
+        let mut parsed = Msg::default();
+        parsed.parse_json(output.as_bytes()).unwrap();
+        assert_eq!(parsed, msg);
+
+        let mut buf = heapless_0_9::Vec::<u8, 64>::new();
+        msg.encode_json_pb(&mut buf).unwrap();
+        assert_eq!(buf, output.as_bytes());
+        let mut small = heapless_0_9::Vec::<u8, 8>::new();
+        assert!(msg.encode_json_pb(&mut small).is_err());
+    }
+
+    #[test]
+    fn decode_message() {
+        let mut msg = Msg::default();
+        msg.parse_json(
+            r#" { "num" : 1e2, "name": "aé😀", "list": [ 3 ], "inner_msg": {}, "list": null } "#
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(msg.num, 100);
+        assert_eq!(msg.name, "aé😀");
+        assert_eq!(msg.list, [3]);
+        assert_eq!(msg.inner.unwrap().num, 0);
+
+        let mut msg = Msg::default();
+        assert_eq!(
+            msg.parse_json(br#"{"list": [1, 2, 3, 4]}"#),
+            Err(JsonError::Capacity)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"name": "too long!!"}"#),
+            Err(JsonError::Capacity)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"name": "\ud800"}"#),
+            Err(JsonError::Utf8)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"name": "\q"}"#),
+            Err(JsonError::InvalidEscape)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"name": "abc"#),
+            Err(JsonError::UnexpectedEof)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"num": 1.5}"#),
+            Err(JsonError::InvalidNumber)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"num": 01}"#),
+            Err(JsonError::InvalidNumber)
+        );
+        assert_eq!(
+            msg.parse_json(br#"{"list": [-1]}"#),
+            Err(JsonError::InvalidNumber)
+        );
+        assert_eq!(msg.parse_json(br#"{"num": 1,}"#), Err(JsonError::Syntax));
+        assert_eq!(msg.parse_json(br#"{"num": 1} x"#), Err(JsonError::Syntax));
+        assert_eq!(
+            msg.parse_json(br#"{"innerMsg": {"num": 1"#),
+            Err(JsonError::UnexpectedEof)
+        );
+
+        let mut decoder = JsonDecoder::new(br#"{"extra": [1, {"a": null}], "num": "5"}"#);
+        assert_eq!(
+            decoder.decode_message(&mut msg),
+            Err(JsonError::UnknownField)
+        );
+        assert_eq!(decoder.position(), 10);
+        let mut decoder = JsonDecoder::new(br#"{"extra": [1, {"a": null}], "num": "5"}"#);
+        decoder.ignore_unknown_fields = true;
+        decoder.decode_message(&mut msg).unwrap();
+        assert_eq!(msg.num, 5);
+
+        let mut decoder = JsonDecoder::new(br#"{"innerMsg": {"innerMsg": {"innerMsg": {}}}}"#);
+        decoder.max_depth = 2;
+        assert_eq!(decoder.decode_message(&mut msg), Err(JsonError::MaxDepth));
+    }
+
+    #[test]
+    fn decode_values() {
+        let mut decoder =
+            JsonDecoder::new(br#""-9223372036854775808" "NaN" "-Infinity" 1e39 3.5e38 "GREEN" 4"#);
+        assert_eq!(decoder.decode_int::<i64>(), Ok(i64::MIN));
+        assert!(decoder.decode_double().unwrap().is_nan());
+        assert_eq!(decoder.decode_float(), Ok(f32::NEG_INFINITY));
+        assert_eq!(decoder.decode_float(), Err(JsonError::InvalidNumber));
+        assert_eq!(decoder.decode_float(), Err(JsonError::InvalidNumber));
+        assert_eq!(decoder.decode_enum(), Ok(Color(1)));
+        assert_eq!(decoder.decode_enum(), Ok(Color(4)));
+        assert_eq!(decoder.decode_bool(), Err(JsonError::UnexpectedEof));
+
+        // Integers in exponent or fractional notation
+        let mut decoder = JsonDecoder::new(br#"1e2 "-2.0" 1.5 -1e0 1e20 -9.3e18"#);
+        assert_eq!(decoder.decode_int::<i32>(), Ok(100));
+        assert_eq!(decoder.decode_int::<i32>(), Ok(-2));
+        assert_eq!(decoder.decode_int::<i32>(), Err(JsonError::InvalidNumber));
+        assert_eq!(decoder.decode_uint::<u32>(), Err(JsonError::InvalidNumber));
+        assert_eq!(decoder.decode_uint::<u64>(), Err(JsonError::InvalidNumber));
+        assert_eq!(decoder.decode_int::<i64>(), Err(JsonError::InvalidNumber));
+
+        let mut bytes = heapless_0_9::Vec::<u8, 4>::new();
+        let mut decoder = JsonDecoder::new(br#""-_8A" "Zm8" "Zg==" "Zm9vYmE" "Z""#);
+        decoder.decode_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [0xFB, 0xFF, 0x00]);
+        decoder.decode_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, b"fo");
+        decoder.decode_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, b"f");
+        assert_eq!(decoder.decode_bytes(&mut bytes), Err(JsonError::Capacity));
+        assert_eq!(
+            decoder.decode_bytes(&mut bytes),
+            Err(JsonError::InvalidBase64)
+        );
+
+        assert_eq!(JsonDecoder::decode_key_int::<i8>("-5"), Ok(-5));
+        assert_eq!(
+            JsonDecoder::decode_key_uint::<u8>(" 5"),
+            Err(JsonError::InvalidNumber)
+        );
+        assert_eq!(JsonDecoder::decode_key_bool("true"), Ok(true));
+        let mut key = heapless_0_9::String::<4>::new();
+        JsonDecoder::decode_key_str(r"a\tb", &mut key).unwrap();
+        assert_eq!(key, "a\tb");
+
+        // Strings decoded into bytes containers skip the UTF-8 check
+        let mut decoder = JsonDecoder::new(br#""\u00e9\n" "\ud800""#);
+        decoder.decode_string_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, "é\n".as_bytes());
+        decoder.decode_string_bytes(&mut bytes).unwrap();
+        assert!(core::str::from_utf8(&bytes).is_err());
+        JsonDecoder::decode_key_bytes(r"\u0041", &mut bytes).unwrap();
+        assert_eq!(bytes, b"A");
+    }
+}
@@ -64,6 +64,9 @@
 //! let mut encoder = PbEncoder::new(&mut output);
 //! // Encode a `ProtoMessage` to the encoder
 //! message.encode(&mut encoder).unwrap();
+//! // Or, encode directly into a byte slice
+//! let mut buf = [0; 16];
+//! let encoded: &[u8] = message.encode_to_slice(&mut buf).unwrap();
 //! ```
 //!
 //! # Container Traits
@@ -121,39 +124,120 @@
 //!   fields. Corresponds with
 //!   [`Generator::use_container_arrayvec`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.use_container_arrayvec)
 //!   from `micropb-gen`.
+//!
+//! - **async**: Adds async counterparts of the decoder and encoder APIs, namely `AsyncPbRead`,
+//!   `AsyncPbWrite`, `AsyncPbDecoder`, `AsyncPbEncoder`, `AsyncMessageDecode`, and
+//!   `AsyncMessageEncode`. These allow messages to be decoded from and encoded to async I/O
+//!   streams without buffering the whole message. Also adds the `push_decode` module for decoding
+//!   messages from byte fragments as they arrive, which doesn't need an async executor and can be
+//!   used from blocking code. Corresponds with
+//!   [`Generator::async_encode_decode`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.async_encode_decode)
+//!   from `micropb-gen`.
+//!
+//! - **embedded-io-0-6**: Adds `EmbeddedIoReader` and `EmbeddedIoWriter`, which adapt readers
+//!   and writers from [`embedded-io`](https://docs.rs/embedded-io/0.6) v0.6 into [`PbRead`] and
+//!   [`PbWrite`].
+//!
+//! - **detailed-errors**: Tracks the location of decoding errors in the decoders, which can be
+//!   attached to errors as a `DetailedDecodeError` containing the byte offset and the
+//!   `FieldPath` of the failing field. Increases the size of the decoders.
+//!
+//! - **text-format**: Adds the `text` module for printing and parsing messages in the Protobuf
+//!   text format. Corresponds with
+//!   [`Generator::text_format`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.text_format)
+//!   from `micropb-gen`.
+//!
+//! - **json**: Adds the `json` module for encoding and decoding messages in the canonical
+//!   proto3 JSON format. Corresponds with
+//!   [`Generator::json`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.json)
+//!   from `micropb-gen`. Enables **text-format**.
+//!
+//! - **serde**: Adds the `serde` module, which supports the `Serialize` and
+//!   `Deserialize` impls generated by
+//!   [`Generator::serde`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.serde).
+//!
+//! - **embedded-io-async-0-6**: Implements `AsyncPbRead` and `AsyncPbWrite` on
+//!   `EmbeddedIoReader` and `EmbeddedIoWriter` for async readers and writers from
+//!   [`embedded-io-async`](https://docs.rs/embedded-io-async/0.6) v0.6. Enables **async** and
+//!   **embedded-io-0-6**.
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod any;
+#[cfg(all(feature = "async", feature = "decode"))]
+mod async_decode;
+#[cfg(all(feature = "async", feature = "encode"))]
+mod async_encode;
 pub mod container;
 #[cfg(feature = "decode")]
 mod decode;
 #[cfg(feature = "encode")]
 mod encode;
+#[cfg(all(feature = "decode", feature = "detailed-errors"))]
+mod error_context;
+pub mod extension;
 pub mod field;
+#[cfg(any(feature = "encode", feature = "decode"))]
+pub mod framing;
+#[cfg(feature = "decode")]
+pub mod inspect;
+#[cfg(feature = "json")]
+pub mod json;
 mod message;
 mod misc;
+#[cfg(all(feature = "async", feature = "decode"))]
+pub mod push_decode;
+#[cfg(feature = "serde")]
+pub mod serde;
 #[cfg(feature = "encode")]
 pub mod size;
+#[cfg(feature = "text-format")]
+pub mod text;
+mod unknown;
 
+#[cfg(all(feature = "async", feature = "decode"))]
+pub use async_decode::{AsyncPbDecoder, AsyncPbRead};
+#[cfg(all(feature = "async", feature = "encode"))]
+pub use async_encode::{AsyncPbEncoder, AsyncPbWrite};
 pub use container::impl_fixed_len::FixedLenString;
 pub use container::{PbBytes, PbMap, PbString, PbVec};
+#[cfg(all(feature = "decode", feature = "embedded-io-0-6"))]
+pub use decode::EmbeddedIoReader;
 #[cfg(all(feature = "decode", feature = "std"))]
 pub use decode::StdReader;
 #[cfg(feature = "decode")]
-pub use decode::{DecodeError, PbDecoder, PbRead};
+pub use decode::{DecodeError, LenDelimitedMessages, MapKey, PbDecoder, PbRead, DEFAULT_MAX_DEPTH};
+#[cfg(all(feature = "encode", feature = "embedded-io-0-6"))]
+pub use encode::EmbeddedIoWriter;
 #[cfg(all(feature = "encode", feature = "std"))]
 pub use encode::StdWriter;
 #[cfg(feature = "encode")]
-pub use encode::{PbEncoder, PbWrite};
+pub use encode::{BufferTooSmall, PbEncoder, PbWrite};
+#[cfg(all(feature = "decode", feature = "detailed-errors"))]
+pub use error_context::{DetailedDecodeError, FieldPath};
+#[cfg(all(feature = "async", feature = "decode"))]
+pub use field::AsyncFieldDecode;
+#[cfg(all(feature = "async", feature = "encode"))]
+pub use field::AsyncFieldEncode;
 #[cfg(feature = "decode")]
 pub use field::FieldDecode;
 #[cfg(feature = "encode")]
 pub use field::FieldEncode;
+#[cfg(all(feature = "async", feature = "decode"))]
+pub use message::AsyncMessageDecode;
+#[cfg(all(feature = "async", feature = "encode"))]
+pub use message::AsyncMessageEncode;
 #[cfg(feature = "decode")]
 pub use message::MessageDecode;
 #[cfg(feature = "encode")]
 pub use message::{MessageEncode, MessageEncodeCached};
+pub use message::{MessageMerge, MessageName};
+#[cfg(feature = "container-heapless-0-9")]
+pub use unknown::HeaplessUnknownFields;
+#[cfg(feature = "alloc")]
+pub use unknown::VecUnknownFields;
+pub use unknown::{UnknownFields, UnknownFieldsIter};
 
 #[macro_export]
 #[doc(hidden)]
@@ -172,6 +256,10 @@ pub const WIRE_TYPE_VARINT: u8 = 0;
 pub const WIRE_TYPE_I64: u8 = 1;
 /// Protobuf wire type for length-delimited records.
 pub const WIRE_TYPE_LEN: u8 = 2;
+/// Protobuf wire type for the start of a group.
+pub const WIRE_TYPE_SGROUP: u8 = 3;
+/// Protobuf wire type for the end of a group.
+pub const WIRE_TYPE_EGROUP: u8 = 4;
 /// Protobuf wire type for fixed 32-bit values.
 pub const WIRE_TYPE_I32: u8 = 5;
 
@@ -220,6 +308,79 @@ pub enum Presence {
     Explicit,
 }
 
+/// Error returned when parsing a generated enum from a name that doesn't match any of its
+/// variants.
+///
+/// Returned by the `FromStr` impls of generated enums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumName;
+
+impl core::fmt::Display for UnknownEnumName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("unknown enum variant name")
+    }
+}
+
+impl core::error::Error for UnknownEnumName {}
+
+/// Error returned when a fixed-capacity container runs out of space while merging messages.
+///
+/// Returned by [`MessageMerge::merge_from`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityExceeded;
+
+impl core::fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("container capacity exceeded")
+    }
+}
+
+impl core::error::Error for CapacityExceeded {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Scalar type whose in-memory representation matches its fixed-width Protobuf wire encoding,
+/// modulo byte order.
+///
+/// Used for bulk decoding and encoding of packed `fixed32`, `sfixed32`, `float`, `fixed64`,
+/// `sfixed64`, and `double` fields. This trait is sealed and cannot be implemented outside of this
+/// crate.
+pub trait FixedWidth: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    /// Convert between the native byte order and the little-endian wire byte order.
+    fn swap_le(self) -> Self;
+}
+
+macro_rules! impl_fixed_width {
+    ($($ty:ty => $bits:ty),+) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl FixedWidth for $ty {
+                #[inline(always)]
+                fn swap_le(self) -> Self {
+                    <$ty>::from_bits(<$bits>::from_le(self.to_bits()))
+                }
+            }
+        )+
+    };
+    ($($ty:ty),+) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl FixedWidth for $ty {
+                #[inline(always)]
+                fn swap_le(self) -> Self {
+                    <$ty>::from_le(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_fixed_width!(u32, i32, u64, i64);
+impl_fixed_width!(f32 => u32, f64 => u64);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,58 @@
 #[cfg(feature = "decode")]
 use crate::decode::{DecodeError, PbDecoder, PbRead};
 #[cfg(feature = "encode")]
-use crate::encode::{PbEncoder, PbWrite};
+use crate::encode::{BufferTooSmall, PbEncoder, PbWrite};
+#[cfg(any(feature = "encode", feature = "decode"))]
+use crate::framing::Crc;
+#[cfg(feature = "encode")]
+use crate::framing::CrcWriter;
+use crate::CapacityExceeded;
+#[cfg(all(feature = "async", feature = "decode"))]
+use crate::{AsyncPbDecoder, AsyncPbRead};
+#[cfg(all(feature = "async", feature = "encode"))]
+use crate::{AsyncPbEncoder, AsyncPbWrite};
+#[cfg(feature = "encode")]
+use crate::{Tag, WIRE_TYPE_EGROUP};
+
+/// Protobuf message with a fully-qualified name.
+///
+/// Implementations are auto-generated by `micropb-gen`. Used for packing messages into
+/// `google.protobuf.Any` via [`PbAny`](crate::any::PbAny).
+pub trait MessageName {
+    /// Fully-qualified Protobuf name of the message, without a leading dot, such as
+    /// `google.protobuf.Timestamp`.
+    const FULL_NAME: &'static str;
+
+    /// Type URL of the message when packed into `google.protobuf.Any`, which is `FULL_NAME`
+    /// prefixed with `type.googleapis.com/`.
+    const TYPE_URL: &'static str;
+}
+
+/// Protobuf message that can merge another instance of itself into `self`.
+///
+/// Implementations are generated by `micropb-gen` when
+/// [`Generator::generate_merge`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.generate_merge)
+/// is enabled, which is the default, and follow the Protobuf merge rules:
+/// - Singular scalar fields are overwritten if they're present in `other`. For fields with implicit
+///   presence, this means fields that aren't set to their default values.
+/// - Repeated fields are appended to.
+/// - Message fields are merged recursively. If a message field isn't present in `self`, it becomes
+///   a copy of the field in `other`.
+/// - Map entries from `other` are inserted, replacing any entry with the same key.
+/// - Oneofs are replaced by the variant in `other`, unless both sides are set to the same message
+///   variant, in which case the messages are merged.
+/// - Extension fields and retained unknown fields from `other` are appended.
+///
+/// Custom fields, custom oneofs, and custom unknown handlers are left as-is.
+pub trait MessageMerge {
+    /// Merge `other` into `self`.
+    ///
+    /// Returns [`CapacityExceeded`] if a fixed-capacity container in `self` can't hold all the
+    /// repeated elements, map entries, or extension and unknown fields from `other`. In that case,
+    /// `self` may have been partially merged, so merge into a copy of `self` if it needs to stay
+    /// intact on failure.
+    fn merge_from(&mut self, other: &Self) -> Result<(), CapacityExceeded>;
+}
 
 #[cfg(feature = "decode")]
 /// Protobuf message that can be decoded from the wire.
@@ -34,6 +85,18 @@ pub trait MessageDecode {
     ) -> Result<(), DecodeError<R::Error>> {
         decoder.decode_len_record(|len, _, decoder| self.decode(decoder, len))
     }
+
+    /// Decode an instance of the message written by
+    /// [`MessageEncode::encode_framed`], verifying the checksum `C` after the message.
+    ///
+    /// Returns [`DecodeError::ChecksumMismatch`] if the checksum doesn't match. In that case, the
+    /// message may have been partially updated with corrupted data.
+    fn decode_framed<C: Crc, R: PbRead>(
+        &mut self,
+        decoder: &mut PbDecoder<R>,
+    ) -> Result<(), DecodeError<R::Error>> {
+        decoder.decode_framed::<C, _>(self)
+    }
 }
 
 #[cfg(feature = "decode")]
@@ -85,8 +148,154 @@ pub trait MessageEncode {
         self.encode(encoder)
     }
 
+    /// Encode this message as the contents of the group with the field number `field_num`,
+    /// followed by the end-group tag. The start-group tag is not written.
+    fn encode_group<W: PbWrite>(
+        &self,
+        encoder: &mut PbEncoder<W>,
+        field_num: u32,
+    ) -> Result<(), W::Error> {
+        self.encode(encoder)?;
+        encoder.encode_varint32(Tag::from_parts(field_num, WIRE_TYPE_EGROUP).varint())
+    }
+
     /// Compute the size of this message on the wire.
     fn compute_size(&self) -> usize;
+
+    /// Encode this message as a length-delimited record followed by the checksum `C` of the
+    /// record, including the length prefix.
+    ///
+    /// The checksum is written in little-endian order. Use [`MessageDecode::decode_framed`] to
+    /// decode the message and verify the checksum on the receiving side.
+    ///
+    /// ```no_run
+    /// use micropb::{MessageEncode, PbEncoder, PbWrite, framing::Crc32};
+    ///
+    /// # #[derive(Default)]
+    /// # struct ProtoMessage;
+    /// # impl MessageEncode for ProtoMessage {
+    /// #   const MAX_SIZE: Result<usize, &str> = Ok(0);
+    /// #   fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> { todo!() }
+    /// #   fn compute_size(&self) -> usize { 0 }
+    /// # }
+    /// let message = ProtoMessage::default();
+    /// let mut encoder = PbEncoder::new(Vec::new());
+    /// message.encode_framed::<Crc32, _>(&mut encoder).unwrap();
+    /// ```
+    fn encode_framed<C: Crc, W: PbWrite>(
+        &self,
+        encoder: &mut PbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        let mut framed = PbEncoder::new(CrcWriter::<_, C>::new(encoder.writer_mut()));
+        self.encode_len_delimited(&mut framed)?;
+        framed.into_writer().finish()?;
+        Ok(())
+    }
+
+    /// Encode this message into a new array, returning the array and the number of bytes written.
+    ///
+    /// `N` must be at least [`MAX_SIZE`](Self::MAX_SIZE), which is checked at compile time, so
+    /// this method can only be used on messages with a bounded size. The easiest way to pick `N`
+    /// is via [`max_encoded_size`](crate::size::max_encoded_size).
+    ///
+    /// Returns an error only if the message is larger than `MAX_SIZE`, which indicates a faulty
+    /// `MessageEncode` implementation.
+    ///
+    /// ```no_run
+    /// use micropb::{MessageEncode, PbEncoder, PbWrite, size::max_encoded_size};
+    ///
+    /// # #[derive(Default)]
+    /// # struct ProtoMessage(u32);
+    /// # impl MessageEncode for ProtoMessage {
+    /// #   const MAX_SIZE: Result<usize, &str> = Ok(6);
+    /// #   fn encode<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> { todo!() }
+    /// #   fn compute_size(&self) -> usize { 0 }
+    /// # }
+    /// let message = ProtoMessage::default();
+    /// let (buf, len) = message.encode_to_array::<{ max_encoded_size::<ProtoMessage>() }>()?;
+    /// let data = &buf[..len];
+    /// # Ok::<(), micropb::BufferTooSmall>(())
+    /// ```
+    fn encode_to_array<const N: usize>(&self) -> Result<([u8; N], usize), BufferTooSmall> {
+        const { assert!(N >= crate::size::max_encoded_size::<Self>()) };
+        encode_array(|encoder| self.encode(encoder))
+    }
+
+    /// Encode this message as a length-delimited record into a new array, returning the array and
+    /// the number of bytes written.
+    ///
+    /// Same as [`encode_to_array`](Self::encode_to_array), except `N` must be at least
+    /// [`max_len_delimited_size`](crate::size::max_len_delimited_size) to account for the length
+    /// prefix.
+    fn encode_len_delimited_to_array<const N: usize>(
+        &self,
+    ) -> Result<([u8; N], usize), BufferTooSmall> {
+        const { assert!(N >= crate::size::max_len_delimited_size::<Self>()) };
+        encode_array(|encoder| self.encode_len_delimited(encoder))
+    }
+
+    /// Encode this message into the start of `buf`, returning the written portion of `buf`.
+    ///
+    /// Returns an error if the message doesn't fit in `buf`, in which case the contents of `buf`
+    /// are unspecified.
+    fn encode_to_slice<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], BufferTooSmall> {
+        encode_slice(buf, |encoder| self.encode(encoder))
+    }
+
+    /// Encode this message as a length-delimited record into the start of `buf`, returning the
+    /// written portion of `buf`.
+    fn encode_len_delimited_to_slice<'a>(
+        &self,
+        buf: &'a mut [u8],
+    ) -> Result<&'a mut [u8], BufferTooSmall> {
+        encode_slice(buf, |encoder| self.encode_len_delimited(encoder))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Encode this message into a new `Vec`.
+    fn encode_to_vec(&self) -> alloc::vec::Vec<u8> {
+        encode_vec(self.compute_size(), |encoder| self.encode(encoder))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Encode this message as a length-delimited record into a new `Vec`.
+    fn encode_len_delimited_to_vec(&self) -> alloc::vec::Vec<u8> {
+        let size = crate::size::sizeof_len_record(self.compute_size());
+        encode_vec(size, |encoder| self.encode_len_delimited(encoder))
+    }
+}
+
+#[cfg(feature = "encode")]
+fn encode_slice(
+    buf: &mut [u8],
+    encode: impl FnOnce(&mut PbEncoder<&mut [u8]>) -> Result<(), ()>,
+) -> Result<&mut [u8], BufferTooSmall> {
+    let total = buf.len();
+    let mut encoder = PbEncoder::new(&mut *buf);
+    encode(&mut encoder).map_err(|_| BufferTooSmall)?;
+    let written = total - encoder.into_writer().len();
+    Ok(&mut buf[..written])
+}
+
+#[cfg(feature = "encode")]
+fn encode_array<const N: usize>(
+    encode: impl FnOnce(&mut PbEncoder<&mut [u8]>) -> Result<(), ()>,
+) -> Result<([u8; N], usize), BufferTooSmall> {
+    let mut buf = [0; N];
+    let len = encode_slice(&mut buf, encode)?.len();
+    Ok((buf, len))
+}
+
+#[cfg(all(feature = "encode", feature = "alloc"))]
+fn encode_vec(
+    size: usize,
+    encode: impl FnOnce(&mut PbEncoder<alloc::vec::Vec<u8>>) -> Result<(), core::convert::Infallible>,
+) -> alloc::vec::Vec<u8> {
+    let mut encoder = PbEncoder::new(alloc::vec::Vec::with_capacity(size));
+    match encode(&mut encoder) {
+        Ok(()) => encoder.into_writer(),
+        Err(e) => match e {},
+    }
 }
 
 #[doc(hidden)]
@@ -114,6 +323,61 @@ pub trait MessageEncodeCached {
         encoder.encode_varint32(self.compute_size_cached(cache) as u32)?;
         self.encode_cached(encoder, cache)
     }
+
+    fn encode_to_array_cached<const N: usize>(
+        &self,
+        cache: &Self::Cache,
+    ) -> Result<([u8; N], usize), BufferTooSmall>
+    where
+        Self: Sized,
+    {
+        const { assert!(N >= crate::size::max_encoded_size::<Self>()) };
+        encode_array(|encoder| self.encode_cached(encoder, cache))
+    }
+
+    fn encode_len_delimited_to_array_cached<const N: usize>(
+        &self,
+        cache: &Self::Cache,
+    ) -> Result<([u8; N], usize), BufferTooSmall>
+    where
+        Self: Sized,
+    {
+        const { assert!(N >= crate::size::max_len_delimited_size::<Self>()) };
+        encode_array(|encoder| self.encode_len_delimited_cached(encoder, cache))
+    }
+
+    fn encode_to_slice_cached<'a>(
+        &self,
+        buf: &'a mut [u8],
+        cache: &Self::Cache,
+    ) -> Result<&'a mut [u8], BufferTooSmall> {
+        encode_slice(buf, |encoder| self.encode_cached(encoder, cache))
+    }
+
+    fn encode_len_delimited_to_slice_cached<'a>(
+        &self,
+        buf: &'a mut [u8],
+        cache: &Self::Cache,
+    ) -> Result<&'a mut [u8], BufferTooSmall> {
+        encode_slice(buf, |encoder| {
+            self.encode_len_delimited_cached(encoder, cache)
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn encode_to_vec_cached(&self, cache: &Self::Cache) -> alloc::vec::Vec<u8> {
+        encode_vec(self.compute_size_cached(cache), |encoder| {
+            self.encode_cached(encoder, cache)
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn encode_len_delimited_to_vec_cached(&self, cache: &Self::Cache) -> alloc::vec::Vec<u8> {
+        let size = crate::size::sizeof_len_record(self.compute_size_cached(cache));
+        encode_vec(size, |encoder| {
+            self.encode_len_delimited_cached(encoder, cache)
+        })
+    }
 }
 
 #[cfg(feature = "encode")]
@@ -134,4 +398,104 @@ impl<T: MessageEncodeCached> MessageEncode for T {
         let cache = self.populate_cache();
         self.encode_len_delimited_cached(encoder, &cache)
     }
+
+    // Populate the cache only once, since it's needed for both sizing and encoding
+    #[cfg(feature = "alloc")]
+    fn encode_to_vec(&self) -> alloc::vec::Vec<u8> {
+        self.encode_to_vec_cached(&self.populate_cache())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn encode_len_delimited_to_vec(&self) -> alloc::vec::Vec<u8> {
+        self.encode_len_delimited_to_vec_cached(&self.populate_cache())
+    }
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+/// Protobuf message that can be decoded asynchronously from the wire.
+///
+/// This is the async counterpart of [`MessageDecode`]. Implementations are auto-generated by
+/// `micropb-gen` if
+/// [`Generator::async_encode_decode`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.async_encode_decode)
+/// is set.
+#[allow(async_fn_in_trait)]
+pub trait AsyncMessageDecode {
+    /// Decode an instance of the message from the async decoder and merge it into `self`.
+    ///
+    /// Length of the message needs to be known beforehand.
+    async fn decode_async<R: AsyncPbRead>(
+        &mut self,
+        decoder: &mut AsyncPbDecoder<R>,
+        len: usize,
+    ) -> Result<(), DecodeError<R::Error>>;
+
+    /// Decode an instance of the message from the async decoder as a length-delimited record,
+    /// starting with a length prefix.
+    async fn decode_len_delimited_async<R: AsyncPbRead>(
+        &mut self,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<(), DecodeError<R::Error>> {
+        decoder
+            .decode_len_record(async |len, _, decoder: &mut AsyncPbDecoder<R>| {
+                self.decode_async(decoder, len).await
+            })
+            .await
+    }
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+impl<T: AsyncMessageDecode> AsyncMessageDecode for &mut T {
+    async fn decode_async<R: AsyncPbRead>(
+        &mut self,
+        decoder: &mut AsyncPbDecoder<R>,
+        len: usize,
+    ) -> Result<(), DecodeError<R::Error>> {
+        (*self).decode_async(decoder, len).await
+    }
+
+    async fn decode_len_delimited_async<R: AsyncPbRead>(
+        &mut self,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<(), DecodeError<R::Error>> {
+        (*self).decode_len_delimited_async(decoder).await
+    }
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+/// Protobuf message that can be encoded asynchronously onto the wire.
+///
+/// This is the async counterpart of [`MessageEncode`], which is a supertrait because the size of
+/// the message still needs to be computed before it's written out. Implementations are
+/// auto-generated by `micropb-gen` if
+/// [`Generator::async_encode_decode`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.async_encode_decode)
+/// is set.
+#[allow(async_fn_in_trait)]
+pub trait AsyncMessageEncode: MessageEncode {
+    /// Encode this message using the async encoder.
+    async fn encode_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error>;
+
+    /// Encode this message as a length-delimited record, starting with a length prefix.
+    async fn encode_len_delimited_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        encoder.encode_varint32(self.compute_size() as u32).await?;
+        self.encode_async(encoder).await
+    }
+
+    /// Encode this message as the contents of the group with the field number `field_num`,
+    /// followed by the end-group tag. The start-group tag is not written.
+    async fn encode_group_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+        field_num: u32,
+    ) -> Result<(), W::Error> {
+        self.encode_async(encoder).await?;
+        encoder
+            .encode_varint32(Tag::from_parts(field_num, WIRE_TYPE_EGROUP).varint())
+            .await
+    }
 }
@@ -2,6 +2,8 @@
 
 use core::mem::{self, MaybeUninit};
 
+use crate::FixedWidth;
+
 pub(crate) fn maybe_uninit_write_slice<T>(this: &mut [MaybeUninit<T>], src: &[T]) -> usize
 where
     T: Copy,
@@ -34,3 +36,77 @@ pub(crate) unsafe fn maybe_uninit_slice_assume_init_ref<T>(slice: &[MaybeUninit<
     // reference and thus guaranteed to be valid for reads.
     unsafe { &*(slice as *const [MaybeUninit<T>] as *const [T]) }
 }
+
+/// Zero out a slice of uninitialized bytes, returning it as an initialized slice
+#[inline]
+pub(crate) fn maybe_uninit_zero_bytes(slice: &mut [MaybeUninit<u8>]) -> &mut [u8] {
+    for b in slice.iter_mut() {
+        b.write(0);
+    }
+    // SAFETY: all bytes in the slice were initialized above, and `MaybeUninit<u8>` has the same
+    // layout as `u8`
+    unsafe { &mut *(slice as *mut [MaybeUninit<u8>] as *mut [u8]) }
+}
+
+#[inline]
+pub(crate) fn fixed_width_as_bytes<T: FixedWidth>(slice: &[T]) -> &[u8] {
+    // SAFETY: `FixedWidth` is only implemented for primitive number types, which have no padding
+    // bytes, so all bytes in the slice are initialized. The byte slice covers exactly the memory
+    // of the original slice.
+    unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const u8, mem::size_of_val(slice)) }
+}
+
+/// Write `value` as a varint into a fixed buffer, returning the buffer and the number of bytes used
+#[inline]
+pub(crate) fn varint32_bytes(mut value: u32) -> ([u8; 5], usize) {
+    let mut bytes = [0; 5];
+    let mut len = 0;
+    for b in bytes.iter_mut() {
+        len += 1;
+        if value < 0x80 {
+            *b = value as u8;
+            break;
+        }
+        *b = (value as u8 & 0x7F) | 0x80;
+        value >>= 7;
+    }
+    (bytes, len)
+}
+
+/// Write `value` as a 64-bit varint into a fixed buffer, returning the buffer and the number of
+/// bytes used
+#[inline]
+pub(crate) fn varint64_bytes(mut value: u64) -> ([u8; 10], usize) {
+    let mut bytes = [0; 10];
+    let mut len = 0;
+    for b in bytes.iter_mut() {
+        len += 1;
+        if value < 0x80 {
+            *b = value as u8;
+            break;
+        }
+        *b = (value as u8 & 0x7F) | 0x80;
+        value >>= 7;
+    }
+    (bytes, len)
+}
+
+/// Interpret raw varint bytes as a `uint32`, truncating the higher bits like `decode_varint32`
+#[inline]
+pub(crate) fn varint32_from_bytes(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .take(5)
+        .enumerate()
+        .fold(0, |acc, (i, b)| acc | ((*b as u32 & 0x7F) << (i * 7)))
+}
+
+/// Interpret raw varint bytes as a `uint64`
+#[inline]
+pub(crate) fn varint64_from_bytes(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .take(10)
+        .enumerate()
+        .fold(0, |acc, (i, b)| acc | ((*b as u64 & 0x7F) << (i * 7)))
+}
@@ -0,0 +1,496 @@
+//! Push-based decoding of messages from byte fragments.
+//!
+//! See [`PushDecoder`] for details.
+//!
+//! # Requirements
+//!
+//! The push decoder reuses the async decoding logic, so this module requires both the `decode`
+//! and `async` features, and the messages must be generated with
+//! [`Generator::async_encode_decode`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.async_encode_decode)
+//! enabled. This doesn't require an async executor or runtime. The `async` feature has no
+//! dependencies, and [`PushDecoder::push`] drives the decoding logic to completion by itself, so
+//! the push decoder works the same way in blocking firmware, such as from an interrupt handler or
+//! a DMA completion callback.
+
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{AsyncMessageDecode, AsyncPbDecoder, AsyncPbRead, DecodeError};
+
+/// Result of feeding a fragment into a [`PushDecoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushStatus {
+    /// The message is still incomplete.
+    ///
+    /// Contains the number of bytes accepted from the fragment. This is only less than the length
+    /// of the fragment if the decoding logic is waiting on something other than the pushed bytes,
+    /// such as a custom field that awaits another future. In that case, the remaining bytes must
+    /// be pushed again.
+    NeedMoreData(usize),
+    /// The message has been fully decoded.
+    ///
+    /// Contains the number of bytes consumed from the last fragment. Any bytes after that belong
+    /// to whatever comes after the message, and are left untouched.
+    Done(usize),
+}
+
+/// Staging buffer that passes byte fragments from a [`PushDecoder`] to the decoding logic.
+///
+/// The buffer holds up to `N` bytes at a time, and the reader used by the decoding logic holds
+/// another `N` bytes, so a push decoder requires `2 * N` bytes of memory on top of the message
+/// itself. `N` can be much smaller than the size of the message. Larger `N` results in fewer
+/// round trips between the pushed fragments and the decoding logic.
+///
+/// This is a separate type from [`PushDecoder`] because the decoding logic needs to borrow the
+/// buffer while the decoder is alive.
+#[derive(Debug)]
+pub struct PushBuffer<const N: usize> {
+    inbox: Cell<[u8; N]>,
+    inbox_len: Cell<usize>,
+    fed: Cell<usize>,
+    consumed: Cell<usize>,
+}
+
+impl<const N: usize> Default for PushBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PushBuffer<N> {
+    /// Create an empty buffer.
+    pub const fn new() -> Self {
+        assert!(N > 0, "push buffer must have non-zero capacity");
+        Self {
+            inbox: Cell::new([0; N]),
+            inbox_len: Cell::new(0),
+            fed: Cell::new(0),
+            consumed: Cell::new(0),
+        }
+    }
+
+    /// Create a push decoder that decodes a message of length `len` into `msg`.
+    ///
+    /// Fields are merged into `msg` as they arrive, so the message shouldn't be read until the
+    /// decoder returns [`PushStatus::Done`].
+    pub fn decoder<'a, M: AsyncMessageDecode>(
+        &'a self,
+        msg: &'a mut M,
+        len: usize,
+    ) -> PushDecoder<'a, impl Future<Output = Result<(), DecodeError<Infallible>>> + 'a, N> {
+        let reader = PushReader::new(self);
+        PushDecoder::new(self, async move {
+            let mut decoder = AsyncPbDecoder::new(reader);
+            msg.decode_async(&mut decoder, len).await
+        })
+    }
+
+    /// Create a push decoder that decodes a message prefixed by its length into `msg`.
+    ///
+    /// Like [`decoder`](Self::decoder), except the length of the message is decoded from the
+    /// pushed bytes.
+    pub fn len_delimited_decoder<'a, M: AsyncMessageDecode>(
+        &'a self,
+        msg: &'a mut M,
+    ) -> PushDecoder<'a, impl Future<Output = Result<(), DecodeError<Infallible>>> + 'a, N> {
+        let reader = PushReader::new(self);
+        PushDecoder::new(self, async move {
+            let mut decoder = AsyncPbDecoder::new(reader);
+            msg.decode_len_delimited_async(&mut decoder).await
+        })
+    }
+
+    /// Copy as much of `data` into the inbox as possible, returning the number of bytes copied.
+    ///
+    /// The reader always empties the inbox before waiting for more data, so we only fill the inbox
+    /// when it's empty.
+    fn fill(&self, data: &[u8]) -> usize {
+        if self.inbox_len.get() != 0 {
+            return 0;
+        }
+        let n = data.len().min(N);
+        let mut inbox = [0; N];
+        inbox[..n].copy_from_slice(&data[..n]);
+        self.inbox.set(inbox);
+        self.inbox_len.set(n);
+        self.fed.set(self.fed.get() + n);
+        n
+    }
+
+    /// Number of bytes that have been fed into the buffer but not consumed by the decoding logic
+    fn unconsumed(&self) -> usize {
+        self.fed.get() - self.consumed.get()
+    }
+}
+
+/// Reader that gets its bytes from a [`PushBuffer`], returning `Pending` when the buffer is empty
+struct PushReader<'a, const N: usize> {
+    buffer: &'a PushBuffer<N>,
+    chunk: [u8; N],
+    start: usize,
+    end: usize,
+}
+
+impl<'a, const N: usize> PushReader<'a, N> {
+    fn new(buffer: &'a PushBuffer<N>) -> Self {
+        Self {
+            buffer,
+            chunk: [0; N],
+            start: 0,
+            end: 0,
+        }
+    }
+}
+
+impl<const N: usize> AsyncPbRead for PushReader<'_, N> {
+    type Error = Infallible;
+
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        if self.start == self.end {
+            poll_fn(|_| {
+                let len = self.buffer.inbox_len.replace(0);
+                if len == 0 {
+                    // The waker is never used, since the push decoder polls again on every push
+                    return Poll::Pending;
+                }
+                self.chunk = self.buffer.inbox.get();
+                self.start = 0;
+                self.end = len;
+                Poll::Ready(())
+            })
+            .await;
+        }
+        Ok(&self.chunk[self.start..self.end])
+    }
+
+    fn pb_advance(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.end - self.start);
+        self.start += bytes;
+        self.buffer.consumed.set(self.buffer.consumed.get() + bytes);
+    }
+}
+
+enum State<F> {
+    Decoding(F),
+    Done,
+}
+
+/// Push-based decoder that decodes a message from byte fragments as they arrive.
+///
+/// Unlike [`PbDecoder`](crate::PbDecoder), which pulls bytes from a reader and fails if the reader
+/// runs out, this decoder is fed fragments of arbitrary size via [`push`](Self::push), and keeps
+/// its place in the message across calls. This allows messages to be decoded directly from DMA
+/// rings or interrupt handlers, without buffering the whole message first.
+///
+/// The decoding logic is driven by [`AsyncMessageDecode`], so the message type must be generated
+/// with
+/// [`Generator::async_encode_decode`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.async_encode_decode)
+/// enabled. Custom fields and unknown handlers must only await the decoder, otherwise the decoding
+/// will stall.
+///
+/// Push decoders are created from a [`PushBuffer`], and need to be pinned before use.
+///
+/// # Example
+///
+/// ```no_run
+/// use core::pin::pin;
+/// use micropb::{AsyncPbRead, AsyncPbDecoder, AsyncMessageDecode, DecodeError};
+/// use micropb::push_decode::{PushBuffer, PushStatus};
+///
+/// # #[derive(Default)]
+/// # struct ProtoMessage;
+/// # impl micropb::AsyncMessageDecode for ProtoMessage {
+/// #   async fn decode_async<R: AsyncPbRead>(&mut self, decoder: &mut AsyncPbDecoder<R>, len: usize) -> Result<(), micropb::DecodeError<R::Error>> { todo!() }
+/// # }
+/// # fn receive_fragment() -> &'static [u8] { todo!() }
+/// let mut message = ProtoMessage::default();
+/// // Only 16 bytes are buffered at a time, regardless of the message size
+/// let buffer = PushBuffer::<16>::new();
+/// {
+///     let mut decoder = pin!(buffer.len_delimited_decoder(&mut message));
+///     loop {
+///         let fragment = receive_fragment();
+///         match decoder.as_mut().push(fragment) {
+///             Ok(PushStatus::NeedMoreData(_)) => continue,
+///             Ok(PushStatus::Done(_)) => break,
+///             Err(e) => panic!("decode error: {e:?}"),
+///         }
+///     }
+/// }
+/// // `message` is now fully decoded
+/// ```
+pub struct PushDecoder<'a, F, const N: usize> {
+    buffer: &'a PushBuffer<N>,
+    state: State<F>,
+}
+
+impl<'a, F: Future<Output = Result<(), DecodeError<Infallible>>>, const N: usize>
+    PushDecoder<'a, F, N>
+{
+    fn new(buffer: &'a PushBuffer<N>, fut: F) -> Self {
+        Self {
+            buffer,
+            state: State::Decoding(fut),
+        }
+    }
+
+    /// Feed a fragment of the message into the decoder.
+    ///
+    /// Returns [`PushStatus::NeedMoreData`] if the message is still incomplete, or
+    /// [`PushStatus::Done`] once the message has been fully decoded. Both contain the number of
+    /// bytes taken from the fragment. Pushing into a decoder that's already done will consume
+    /// nothing and return `Done(0)`.
+    ///
+    /// Errors are returned as soon as they're detected. Since the reader never fails, the only
+    /// errors returned are those of the decoding logic. After an error, the decoder is done.
+    pub fn push(
+        self: Pin<&mut Self>,
+        mut data: &[u8],
+    ) -> Result<PushStatus, DecodeError<Infallible>> {
+        // SAFETY: The future is never moved out of `state`. Replacing the state drops the future
+        // in place, which is permitted by the pinning guarantees.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut accepted = 0;
+
+        loop {
+            let State::Decoding(fut) = &mut this.state else {
+                return Ok(PushStatus::Done(0));
+            };
+            // SAFETY: `this` is pinned, so the future inside it is also pinned
+            let fut = unsafe { Pin::new_unchecked(fut) };
+
+            let n = this.buffer.fill(data);
+            data = &data[n..];
+            accepted += n;
+
+            match fut.poll(&mut cx) {
+                Poll::Ready(res) => {
+                    this.state = State::Done;
+                    res?;
+                    // The reader only waits for more data after consuming everything, so any
+                    // unconsumed bytes must have come from this fragment
+                    return Ok(PushStatus::Done(accepted - this.buffer.unconsumed()));
+                }
+                Poll::Pending => {
+                    // If the inbox hasn't been emptied, then the decoding logic is stuck on
+                    // something other than the reader, so pushing more bytes won't help
+                    if data.is_empty() || this.buffer.inbox_len.get() != 0 {
+                        return Ok(PushStatus::NeedMoreData(accepted));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the decoder has finished, either successfully or with an error.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::pin;
+
+    use arrayvec::ArrayVec;
+
+    use crate::Presence;
+
+    use super::*;
+
+    /// Message with a varint in field 1 and a bytes field in field 2
+    #[derive(Debug, Default, PartialEq)]
+    struct TestMsg {
+        num: u32,
+        bytes: ArrayVec<u8, 32>,
+    }
+
+    impl AsyncMessageDecode for TestMsg {
+        async fn decode_async<R: AsyncPbRead>(
+            &mut self,
+            decoder: &mut AsyncPbDecoder<R>,
+            len: usize,
+        ) -> Result<(), DecodeError<R::Error>> {
+            let before = decoder.bytes_read();
+            while decoder.bytes_read() - before < len {
+                let tag = decoder.decode_tag().await?;
+                match tag.field_num() {
+                    1 => self.num = decoder.decode_varint32().await?,
+                    2 => {
+                        decoder
+                            .decode_bytes(&mut self.bytes, Presence::Implicit)
+                            .await?
+                    }
+                    _ => decoder.skip_wire_value(tag.wire_type()).await?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    const MSG: [u8; 26] = [
+        0x08, 0x96, 0x01, // field 1
+        0x12, 20, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+        19,   // field 2
+        0x08, // trailing byte
+    ];
+
+    fn expected() -> TestMsg {
+        TestMsg {
+            num: 150,
+            bytes: (0..20).collect(),
+        }
+    }
+
+    #[test]
+    fn fragments() {
+        for frag_size in 1..=MSG.len() {
+            let mut msg = TestMsg::default();
+            let buffer = PushBuffer::<4>::new();
+            {
+                let mut decoder = pin!(buffer.decoder(&mut msg, MSG.len() - 1));
+                let mut status = PushStatus::NeedMoreData(0);
+                let mut total = 0;
+                for frag in MSG.chunks(frag_size) {
+                    status = decoder.as_mut().push(frag).unwrap();
+                    match status {
+                        PushStatus::NeedMoreData(n) => {
+                            assert_eq!(n, frag.len());
+                            total += n;
+                        }
+                        PushStatus::Done(n) => {
+                            total += n;
+                            break;
+                        }
+                    }
+                }
+                assert!(decoder.is_done());
+                // The trailing byte isn't consumed
+                assert_eq!(total, MSG.len() - 1);
+                assert!(matches!(status, PushStatus::Done(_)));
+                assert_eq!(decoder.as_mut().push(&[1, 2]), Ok(PushStatus::Done(0)));
+            }
+            assert_eq!(msg, expected());
+        }
+    }
+
+    #[test]
+    fn len_delimited() {
+        let mut data = [0; MSG.len()];
+        data[0] = MSG.len() as u8 - 1;
+        data[1..].copy_from_slice(&MSG[..MSG.len() - 1]);
+
+        let mut msg = TestMsg::default();
+        let buffer = PushBuffer::<8>::new();
+        {
+            let mut decoder = pin!(buffer.len_delimited_decoder(&mut msg));
+            assert_eq!(
+                decoder.as_mut().push(&data[..10]),
+                Ok(PushStatus::NeedMoreData(10))
+            );
+            assert_eq!(decoder.as_mut().push(&[]), Ok(PushStatus::NeedMoreData(0)));
+            assert_eq!(
+                decoder.as_mut().push(&data[10..]),
+                Ok(PushStatus::Done(data.len() - 10))
+            );
+        }
+        assert_eq!(msg, expected());
+    }
+
+    #[test]
+    fn error() {
+        let mut msg = TestMsg::default();
+        let buffer = PushBuffer::<4>::new();
+        let mut decoder = pin!(buffer.decoder(&mut msg, 100));
+        assert_eq!(
+            decoder.as_mut().push(&[0x08, 0x01]),
+            Ok(PushStatus::NeedMoreData(2))
+        );
+        // Field 3 with wire type 7
+        assert_eq!(
+            decoder.as_mut().push(&[0x1F, 0x01]),
+            Err(DecodeError::UnknownWireType)
+        );
+        assert!(decoder.is_done());
+    }
+
+    #[test]
+    fn empty_message() {
+        let mut msg = TestMsg::default();
+        let buffer = PushBuffer::<4>::new();
+        let mut decoder = pin!(buffer.decoder(&mut msg, 0));
+        assert_eq!(decoder.as_mut().push(&[0x08]), Ok(PushStatus::Done(0)));
+    }
+
+    #[test]
+    fn fragment_larger_than_buffer() {
+        let mut msg = TestMsg::default();
+        let buffer = PushBuffer::<4>::new();
+        {
+            let mut decoder = pin!(buffer.decoder(&mut msg, MSG.len() - 1));
+            assert_eq!(
+                decoder.as_mut().push(&MSG[..15]),
+                Ok(PushStatus::NeedMoreData(15))
+            );
+            assert_eq!(
+                decoder.as_mut().push(&MSG[15..]),
+                Ok(PushStatus::Done(MSG.len() - 16))
+            );
+        }
+        assert_eq!(msg, expected());
+    }
+
+    /// Future that returns `Pending` once before completing
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Message that waits on something other than the decoder before decoding a `TestMsg`
+    #[derive(Debug, Default)]
+    struct StallingMsg(TestMsg);
+
+    impl AsyncMessageDecode for StallingMsg {
+        async fn decode_async<R: AsyncPbRead>(
+            &mut self,
+            decoder: &mut AsyncPbDecoder<R>,
+            len: usize,
+        ) -> Result<(), DecodeError<R::Error>> {
+            YieldOnce(false).await;
+            self.0.decode_async(decoder, len).await
+        }
+    }
+
+    #[test]
+    fn stalled_decoder() {
+        let mut msg = StallingMsg::default();
+        let buffer = PushBuffer::<4>::new();
+        {
+            let mut decoder = pin!(buffer.decoder(&mut msg, MSG.len() - 1));
+            // Only the bytes that fit in the buffer are taken, since the decoding logic isn't
+            // reading them yet
+            assert_eq!(decoder.as_mut().push(&MSG), Ok(PushStatus::NeedMoreData(4)));
+            assert_eq!(
+                decoder.as_mut().push(&MSG[4..]),
+                Ok(PushStatus::Done(MSG.len() - 5))
+            );
+        }
+        assert_eq!(msg.0, expected());
+    }
+}
@@ -0,0 +1,552 @@
+//! Support code for the [`Serialize`] and [`Deserialize`] impls generated by
+//! [`Generator::serde`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.serde).
+//!
+//! Generated messages serialize as structs whose members are the fields that are present, named
+//! by their `json_name`:
+//!
+//! - Fields with explicit presence are skipped if they're not set, and fields with implicit
+//!   presence are skipped if they have the default value. Repeated and `map` fields are skipped
+//!   if empty.
+//! - Each oneof is serialized as the field that is set, if any.
+//! - `string` fields are serialized as strings, and `bytes` fields as sequences of bytes. `string`
+//!   fields without UTF-8 validation fail to serialize if they're not valid UTF-8.
+//! - Enums are serialized by value name in human-readable formats. Unknown values are serialized
+//!   as the value number written as a string, such as `"-3"`, so that they can be read back.
+//!   Non-human-readable formats always use the number.
+//!
+//! Deserialization accepts both the JSON names and the original field names, and ignores unknown
+//! keys. Strings, bytes, repeated fields, and maps are deserialized directly into the message's
+//! own containers, which report an error when a fixed container runs out of capacity. None of
+//! this requires allocation or [`Deserializer::deserialize_any`], so it works with `no_std`
+//! formats such as `serde-json-core`. As a consequence, human-readable formats read enums as
+//! either a value name or a value number written as a string, which is also how unknown values
+//! are written.
+//!
+//! The types in this module are used by generated code, and are not meant to be used directly.
+
+use core::{fmt, marker::PhantomData, ops::Deref, str::FromStr};
+
+pub use ::serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use ::serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use ::serde::ser::SerializeSeq;
+
+use crate::{
+    container::{PbBytes, PbMap, PbString, PbVec},
+    misc::maybe_uninit_write_slice,
+};
+
+/// Method of serializing a field value of type `T`.
+pub trait SerializeAs<T: ?Sized> {
+    /// Serialize `val` into `serializer`.
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Method of deserializing into a field value of type `T`.
+pub trait DeserializeAs<'de, T> {
+    /// Deserialize a value from `deserializer` into `place`.
+    fn deserialize_as<D: Deserializer<'de>>(place: &mut T, deserializer: D)
+        -> Result<(), D::Error>;
+}
+
+/// Values that implement the serde traits themselves, such as numbers, enums, and messages.
+pub struct Native;
+
+/// `string` values, which are read into a [`PbString`].
+pub struct Str;
+
+/// `bytes` values, which are serialized as sequences of bytes and read into a [`PbBytes`].
+pub struct Bytes;
+
+/// `string` values without UTF-8 validation, which are read into a [`PbBytes`]. Serializing
+/// them fails if they're not valid UTF-8.
+pub struct StrBytes;
+
+/// Repeated fields with elements of type `E`, which are serialized as `A`.
+pub struct Repeated<E, A>(PhantomData<(E, A)>);
+
+/// `map` fields with keys of type `K` serialized as `KA` and values of type `V` serialized as
+/// `VA`.
+pub struct Map<K, V, KA, VA>(PhantomData<(K, V, KA, VA)>);
+
+/// Wrapper that serializes a value of type `T` as `A`.
+pub struct With<'a, T: ?Sized, A> {
+    val: &'a T,
+    _as: PhantomData<A>,
+}
+
+impl<'a, T: ?Sized, A> With<'a, T, A> {
+    /// Wrap `val`.
+    pub fn new(val: &'a T) -> Self {
+        Self {
+            val,
+            _as: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, A: SerializeAs<T>> Serialize for With<'_, T, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        A::serialize_as(self.val, serializer)
+    }
+}
+
+/// Seed that deserializes a value into an existing place of type `T` as `A`.
+pub struct Place<'a, T, A> {
+    place: &'a mut T,
+    _as: PhantomData<A>,
+}
+
+impl<'a, T, A> Place<'a, T, A> {
+    /// Wrap `place`.
+    pub fn new(place: &'a mut T) -> Self {
+        Self {
+            place,
+            _as: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, A: DeserializeAs<'de, T>> DeserializeSeed<'de> for Place<'_, T, A> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        A::deserialize_as(self.place, deserializer)
+    }
+}
+
+fn capacity_error<E: de::Error>() -> E {
+    E::custom("capacity of fixed container exceeded")
+}
+
+impl<T: Serialize + ?Sized> SerializeAs<T> for Native {
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        val.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeAs<'de, T> for Native {
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        *place = T::deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
+impl<T: Deref<Target = str> + ?Sized> SerializeAs<T> for Str {
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(val)
+    }
+}
+
+/// Replace the contents of a string container with `data`
+fn write_slice<S: PbString, E: de::Error>(container: &mut S, data: &[u8]) -> Result<(), E> {
+    container.pb_clear();
+    container.pb_reserve(data.len());
+    let spare_cap = container.pb_spare_cap();
+    if spare_cap.len() < data.len() {
+        return Err(capacity_error());
+    }
+    maybe_uninit_write_slice(spare_cap, data);
+    Ok(())
+}
+
+struct StrVisitor<'a, T>(&'a mut T);
+
+impl<T: PbString> Visitor<'_> for StrVisitor<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+        write_slice(self.0, v.as_bytes())?;
+        // SAFETY: The bytes we just wrote came from a `str`, so they're valid UTF-8
+        unsafe { self.0.pb_set_len(v.len()) };
+        Ok(())
+    }
+}
+
+impl<'de, T: PbString> DeserializeAs<'de, T> for Str {
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_str(StrVisitor(place))
+    }
+}
+
+impl<T: Deref<Target = [u8]> + ?Sized> SerializeAs<T> for StrBytes {
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        // Replacing invalid sequences would need `collect_str`, which not every format supports
+        let s = core::str::from_utf8(val)
+            .map_err(|_| <S::Error as ser::Error>::custom("string is not valid UTF-8"))?;
+        serializer.serialize_str(s)
+    }
+}
+
+struct StrBytesVisitor<'a, T>(&'a mut T);
+
+impl<T: PbBytes> Visitor<'_> for StrBytesVisitor<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+        self.visit_bytes(v.as_bytes())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<(), E> {
+        write_slice(self.0, v)?;
+        // SAFETY: `PbBytes` can contain any bytes, and we just wrote `v.len()` bytes
+        unsafe { self.0.pb_set_len(v.len()) };
+        Ok(())
+    }
+}
+
+impl<'de, T: PbBytes> DeserializeAs<'de, T> for StrBytes {
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_str(StrBytesVisitor(place))
+    }
+}
+
+impl<T: Deref<Target = [u8]> + ?Sized> SerializeAs<T> for Bytes {
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(val.len()))?;
+        for b in val.iter() {
+            seq.serialize_element(b)?;
+        }
+        seq.end()
+    }
+}
+
+struct BytesVisitor<'a, T>(&'a mut T);
+
+impl<'de, T: PbBytes> Visitor<'de> for BytesVisitor<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<(), E> {
+        write_slice(self.0, v)?;
+        // SAFETY: `PbBytes` can contain any bytes, and we just wrote `v.len()` bytes
+        unsafe { self.0.pb_set_len(v.len()) };
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        self.0.pb_clear();
+        self.0.pb_reserve(seq.size_hint().unwrap_or(0));
+        let mut len = 0;
+        while let Some(b) = seq.next_element::<u8>()? {
+            self.0.pb_reserve(1);
+            let Some(slot) = self.0.pb_spare_cap().first_mut() else {
+                return Err(capacity_error());
+            };
+            slot.write(b);
+            len += 1;
+            // SAFETY: `PbBytes` can contain any bytes, and we just wrote one more byte
+            unsafe { self.0.pb_set_len(len) };
+        }
+        Ok(())
+    }
+}
+
+impl<'de, T: PbBytes> DeserializeAs<'de, T> for Bytes {
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(BytesVisitor(place))
+    }
+}
+
+impl<E, T, A> SerializeAs<T> for Repeated<E, A>
+where
+    T: Deref<Target = [E]> + ?Sized,
+    A: SerializeAs<E>,
+{
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(val.len()))?;
+        for elem in val.iter() {
+            seq.serialize_element(&With::<_, A>::new(elem))?;
+        }
+        seq.end()
+    }
+}
+
+struct RepeatedVisitor<'a, T, E, A>(&'a mut T, PhantomData<(E, A)>);
+
+impl<'de, T, E, A> Visitor<'de> for RepeatedVisitor<'_, T, E, A>
+where
+    T: PbVec<E>,
+    E: Default,
+    A: DeserializeAs<'de, E>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<(), S::Error> {
+        loop {
+            let mut elem = E::default();
+            if seq
+                .next_element_seed(Place::<_, A>::new(&mut elem))?
+                .is_none()
+            {
+                return Ok(());
+            }
+            self.0.pb_push(elem).map_err(|_| capacity_error())?;
+        }
+    }
+}
+
+impl<'de, E, T, A> DeserializeAs<'de, T> for Repeated<E, A>
+where
+    T: PbVec<E>,
+    E: Default,
+    A: DeserializeAs<'de, E>,
+{
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(RepeatedVisitor::<_, _, A>(place, PhantomData))
+    }
+}
+
+impl<K, V, T, KA, VA> SerializeAs<T> for Map<K, V, KA, VA>
+where
+    for<'a> &'a T: IntoIterator<Item = (&'a K, &'a V)>,
+    KA: SerializeAs<K>,
+    VA: SerializeAs<V>,
+{
+    fn serialize_as<S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            val.into_iter()
+                .map(|(k, v)| (With::<_, KA>::new(k), With::<_, VA>::new(v))),
+        )
+    }
+}
+
+struct MapVisitor<'a, T, K, V, KA, VA>(&'a mut T, PhantomData<(K, V, KA, VA)>);
+
+impl<'de, T, K, V, KA, VA> Visitor<'de> for MapVisitor<'_, T, K, V, KA, VA>
+where
+    T: PbMap<K, V>,
+    K: Default,
+    V: Default,
+    KA: DeserializeAs<'de, K>,
+    VA: DeserializeAs<'de, V>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<(), M::Error> {
+        loop {
+            let mut key = K::default();
+            if map.next_key_seed(Place::<_, KA>::new(&mut key))?.is_none() {
+                return Ok(());
+            }
+            let mut val = V::default();
+            map.next_value_seed(Place::<_, VA>::new(&mut val))?;
+            self.0.pb_insert(key, val).map_err(|_| capacity_error())?;
+        }
+    }
+}
+
+impl<'de, K, V, T, KA, VA> DeserializeAs<'de, T> for Map<K, V, KA, VA>
+where
+    T: PbMap<K, V>,
+    K: Default,
+    V: Default,
+    KA: DeserializeAs<'de, K>,
+    VA: DeserializeAs<'de, V>,
+{
+    fn deserialize_as<D: Deserializer<'de>>(
+        place: &mut T,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_map(MapVisitor::<_, _, _, KA, VA>(place, PhantomData))
+    }
+}
+
+/// Message whose fields can be deserialized one at a time. Implemented by generated code.
+pub trait DeserializeMessage<'de>: Default {
+    /// Name of the message.
+    const NAME: &'static str;
+    /// All keys accepted by [`deserialize_field`](Self::deserialize_field).
+    const FIELDS: &'static [&'static str];
+
+    /// Deserialize the value of the field named by `key` from `map`.
+    fn deserialize_field<M: MapAccess<'de>>(
+        &mut self,
+        key: &'static str,
+        map: &mut M,
+    ) -> Result<(), M::Error>;
+}
+
+/// Seed that matches a key against a list of field names
+struct FieldKey(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for FieldKey {
+    type Value = Option<&'static str>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl Visitor<'_> for FieldKey {
+    type Value = Option<&'static str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a field name")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.0.iter().copied().find(|name| *name == v))
+    }
+}
+
+struct MessageVisitor<M>(PhantomData<M>);
+
+impl<'de, M: DeserializeMessage<'de>> Visitor<'de> for MessageVisitor<M> {
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "message {}", M::NAME)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<M, A::Error> {
+        let mut msg = M::default();
+        while let Some(key) = map.next_key_seed(FieldKey(M::FIELDS))? {
+            match key {
+                Some(key) => msg.deserialize_field(key, &mut map)?,
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(msg)
+    }
+}
+
+/// Deserialize a message from `deserializer`. Called by generated [`Deserialize`] impls.
+pub fn deserialize_message<'de, M, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: DeserializeMessage<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct(M::NAME, M::FIELDS, MessageVisitor(PhantomData))
+}
+
+/// Stack buffer for formatting enum value numbers, which fits any 64-bit integer
+struct NumBuf {
+    buf: [u8; 20],
+    len: usize,
+}
+
+impl fmt::Write for NumBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let dest = self
+            .buf
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+/// Serialize an enum value with the integer `num`, using `name` if the value is known. Called
+/// by generated [`Serialize`] impls.
+///
+/// In human-readable formats, unknown values are serialized as the number written as a string,
+/// which is what [`deserialize_enum`] reads back.
+pub fn serialize_enum<S, I>(
+    serializer: S,
+    num: I,
+    name: Option<&'static str>,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    I: Serialize + fmt::Display,
+{
+    if !serializer.is_human_readable() {
+        return num.serialize(serializer);
+    }
+    match name {
+        Some(name) => serializer.serialize_str(name),
+        None => {
+            let mut buf = NumBuf {
+                buf: [0; 20],
+                len: 0,
+            };
+            fmt::Write::write_fmt(&mut buf, format_args!("{num}")).map_err(ser::Error::custom)?;
+            let s = core::str::from_utf8(&buf.buf[..buf.len]).map_err(ser::Error::custom)?;
+            serializer.serialize_str(s)
+        }
+    }
+}
+
+struct EnumVisitor<F, I>(F, PhantomData<I>);
+
+impl<F, I> Visitor<'_> for EnumVisitor<F, I>
+where
+    F: FnOnce(&str) -> Option<I>,
+    I: FromStr + TryFrom<i64> + TryFrom<u64>,
+{
+    type Value = I;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an enum value name or number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<I, E> {
+        match (self.0)(v) {
+            Some(num) => Ok(num),
+            None => v
+                .parse()
+                .map_err(|_| E::invalid_value(Unexpected::Str(v), &"an enum value name or number")),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<I, E> {
+        I::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<I, E> {
+        I::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+}
+
+/// Deserialize the integer of an enum value, using `from_name` to look up value names. Called by
+/// generated [`Deserialize`] impls.
+pub fn deserialize_enum<'de, D, I, F>(deserializer: D, from_name: F) -> Result<I, D::Error>
+where
+    D: Deserializer<'de>,
+    I: Deserialize<'de> + FromStr + TryFrom<i64> + TryFrom<u64>,
+    F: FnOnce(&str) -> Option<I>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(EnumVisitor(from_name, PhantomData))
+    } else {
+        I::deserialize(deserializer)
+    }
+}
@@ -7,13 +7,22 @@ use crate::Tag;
 ///
 /// If the message type doesn't have a bounded size, this function panics, leading to a compile
 /// error in const context.
-pub const fn max_encoded_size<T: crate::MessageEncode>() -> usize {
+pub const fn max_encoded_size<T: crate::MessageEncode + ?Sized>() -> usize {
     match T::MAX_SIZE {
         Ok(n) => n,
         Err(err) => panic!("{}", err),
     }
 }
 
+/// Return the maximum encoded size of a length-delimited message at compile time, including the
+/// length prefix.
+///
+/// Same as [`max_encoded_size`], this function panics if the message type doesn't have a bounded
+/// size.
+pub const fn max_len_delimited_size<T: crate::MessageEncode + ?Sized>() -> usize {
+    sizeof_len_record(max_encoded_size::<T>())
+}
+
 /// Calculate size of `uint32` on the wire.
 pub const fn sizeof_varint32(v: u32) -> usize {
     match v {
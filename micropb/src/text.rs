@@ -0,0 +1,938 @@
+//! Protobuf [text format](https://protobuf.dev/reference/protobuf/textformat-spec/) output and
+//! parsing.
+//!
+//! The text format is a human-readable representation of Protobuf messages, commonly used for
+//! configuration files and debugging. Messages generated with
+//! [`Generator::text_format`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.text_format)
+//! implement [`TextEncode`] and [`core::fmt::Display`] for printing, and [`TextDecode`] for
+//! parsing. Enums implement [`TextEnum`], which maps enum values to and from the names in the
+//! `.proto` file.
+//!
+//! Output is written through [`TextEncoder`] into any [`core::fmt::Write`], and input is parsed by
+//! [`TextDecoder`] from a `&str`. Neither allocates, so the text format can be used on devices,
+//! such as for viewing and editing configuration messages from a serial shell. Parsed strings,
+//! bytes, repeated fields, and maps are written into the message's own containers, and running
+//! out of capacity in a fixed container is reported as [`TextError::Capacity`].
+//!
+//! Custom fields and unknown fields are not part of the text format. They are skipped in the
+//! output and rejected as unknown fields by the parser. Extensions and `Any` expansions are also
+//! unsupported.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use micropb::text::TextDecode;
+//!
+//! # #[derive(Default)]
+//! # struct Config;
+//! # impl core::fmt::Display for Config {
+//! #   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { todo!() }
+//! # }
+//! # impl TextDecode for Config {
+//! #   fn decode_text(&mut self, decoder: &mut micropb::text::TextDecoder<'_>) -> Result<(), micropb::text::TextError> { todo!() }
+//! # }
+//! let mut config = Config::default();
+//! config.parse_text(r#"baud_rate: 115200 name: "uart0" pins { tx: 4 rx: 5 }"#).unwrap();
+//! // Prints the message in multi-line text format
+//! println!("{config}");
+//! ```
+
+use core::fmt::{self, Write};
+use core::str::from_utf8;
+
+use crate::{
+    container::{PbBytes, PbString},
+    misc::{maybe_uninit_slice_assume_init_ref, maybe_uninit_write_slice},
+};
+
+/// Error from parsing the text format.
+///
+/// Use [`TextDecoder::position`] to find where the error happened.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextError {
+    /// Input doesn't follow the text format syntax
+    Syntax,
+    /// Input ended in the middle of a message or value
+    UnexpectedEof,
+    /// Field name doesn't exist in the message
+    UnknownField,
+    /// Number is malformed or out of range for the field's type
+    InvalidNumber,
+    /// Enum value name doesn't exist in the enum
+    UnknownEnumValue,
+    /// Malformed escape sequence in a string literal
+    InvalidEscape,
+    /// String field is not valid UTF-8
+    Utf8,
+    /// Exceeded capacity of fixed container for `string`, `bytes`, repeated, or `map` field
+    Capacity,
+    /// Nesting depth of messages exceeded the decoder's `max_depth`
+    MaxDepth,
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax => f.write_str("syntax error"),
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnknownField => f.write_str("unknown field name"),
+            Self::InvalidNumber => f.write_str("invalid or out-of-range number"),
+            Self::UnknownEnumValue => f.write_str("unknown enum value name"),
+            Self::InvalidEscape => f.write_str("invalid escape sequence"),
+            Self::Utf8 => f.write_str("string is not valid UTF-8"),
+            Self::Capacity => f.write_str("capacity of fixed container exceeded"),
+            Self::MaxDepth => f.write_str("max nesting depth exceeded"),
+        }
+    }
+}
+
+impl core::error::Error for TextError {}
+
+/// Protobuf message that can be printed in text format.
+pub trait TextEncode {
+    /// Write all fields of the message into `encoder`.
+    fn encode_text<W: Write>(&self, encoder: &mut TextEncoder<W>) -> fmt::Result;
+}
+
+/// Protobuf message that can be parsed from text format.
+pub trait TextDecode {
+    /// Parse fields from `decoder` into the message until the end of the message.
+    ///
+    /// Fields not present in the input are left unchanged.
+    fn decode_text(&mut self, decoder: &mut TextDecoder<'_>) -> Result<(), TextError>;
+
+    /// Parse a whole message in text format from `text`, merging it into `self`.
+    fn parse_text(&mut self, text: &str) -> Result<(), TextError> {
+        self.decode_text(&mut TextDecoder::new(text))
+    }
+}
+
+/// Protobuf enum with value names that can be printed and parsed in text format.
+pub trait TextEnum: Sized {
+    /// Name of the value in the `.proto` file, or `None` if the value is unknown.
+    fn text_name(&self) -> Option<&'static str>;
+
+    /// Look up a value by its name in the `.proto` file.
+    fn from_text_name(name: &str) -> Option<Self>;
+
+    /// Numeric value of the enum.
+    fn text_number(&self) -> i64;
+
+    /// Convert a number into an enum value, or return `None` if it's out of range.
+    fn from_text_number(num: i64) -> Option<Self>;
+}
+
+/// Writer of the Protobuf text format.
+///
+/// Each field is written on its own line as `name: value`, and nested messages are written as
+/// indented blocks enclosed in braces, same as the output of `DebugString` in the official
+/// Protobuf implementation.
+#[derive(Debug)]
+pub struct TextEncoder<W> {
+    writer: W,
+    indent: usize,
+}
+
+impl<W: Write> TextEncoder<W> {
+    /// Construct a new encoder writing into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer, indent: 0 }
+    }
+
+    /// Transform the encoder into the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn write_name(&mut self, name: &str) -> fmt::Result {
+        write!(self.writer, "{:1$}{name}", "", self.indent)
+    }
+
+    /// Write a signed integer field.
+    pub fn write_int(&mut self, name: &str, val: i64) -> fmt::Result {
+        self.write_name(name)?;
+        writeln!(self.writer, ": {val}")
+    }
+
+    /// Write an unsigned integer field.
+    pub fn write_uint(&mut self, name: &str, val: u64) -> fmt::Result {
+        self.write_name(name)?;
+        writeln!(self.writer, ": {val}")
+    }
+
+    /// Write a `float` field.
+    pub fn write_float(&mut self, name: &str, val: f32) -> fmt::Result {
+        self.write_name(name)?;
+        self.writer.write_str(": ")?;
+        if val.is_nan() {
+            self.writer.write_str("nan\n")
+        } else {
+            // Infinities are formatted as "inf" and "-inf"
+            writeln!(self.writer, "{val}")
+        }
+    }
+
+    /// Write a `double` field.
+    pub fn write_double(&mut self, name: &str, val: f64) -> fmt::Result {
+        self.write_name(name)?;
+        self.writer.write_str(": ")?;
+        if val.is_nan() {
+            self.writer.write_str("nan\n")
+        } else {
+            writeln!(self.writer, "{val}")
+        }
+    }
+
+    /// Write a `bool` field.
+    pub fn write_bool(&mut self, name: &str, val: bool) -> fmt::Result {
+        self.write_name(name)?;
+        writeln!(self.writer, ": {val}")
+    }
+
+    /// Write a `string` field as a quoted string literal.
+    pub fn write_str(&mut self, name: &str, val: &str) -> fmt::Result {
+        self.write_name(name)?;
+        self.writer.write_str(": \"")?;
+        self.write_str_chars(val)?;
+        self.writer.write_str("\"\n")
+    }
+
+    /// Write a `string` field that isn't validated as UTF-8 as a quoted string literal. Valid
+    /// UTF-8 is printed like [`write_str`](Self::write_str), while invalid bytes are escaped.
+    pub fn write_str_bytes(&mut self, name: &str, val: &[u8]) -> fmt::Result {
+        self.write_name(name)?;
+        self.writer.write_str(": \"")?;
+        for chunk in val.utf8_chunks() {
+            self.write_str_chars(chunk.valid())?;
+            for b in chunk.invalid() {
+                write_escaped_byte(&mut self.writer, *b)?;
+            }
+        }
+        self.writer.write_str("\"\n")
+    }
+
+    fn write_str_chars(&mut self, val: &str) -> fmt::Result {
+        for c in val.chars() {
+            if c.is_ascii() {
+                write_escaped_byte(&mut self.writer, c as u8)?;
+            } else {
+                // Non-ASCII characters are printed as-is
+                self.writer.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a `bytes` field as a quoted string literal, with non-ASCII bytes escaped.
+    pub fn write_bytes(&mut self, name: &str, val: &[u8]) -> fmt::Result {
+        self.write_name(name)?;
+        self.writer.write_str(": \"")?;
+        for b in val {
+            write_escaped_byte(&mut self.writer, *b)?;
+        }
+        self.writer.write_str("\"\n")
+    }
+
+    /// Write an enum field as the value's name, or as a number if the value is unknown.
+    pub fn write_enum<E: TextEnum>(&mut self, name: &str, val: &E) -> fmt::Result {
+        self.write_name(name)?;
+        match val.text_name() {
+            Some(val_name) => writeln!(self.writer, ": {val_name}"),
+            None => writeln!(self.writer, ": {}", val.text_number()),
+        }
+    }
+
+    /// Write a message field as an indented block.
+    pub fn write_message<M: TextEncode + ?Sized>(&mut self, name: &str, msg: &M) -> fmt::Result {
+        self.begin_message(name)?;
+        msg.encode_text(self)?;
+        self.end_message()
+    }
+
+    /// Start a message block, such as for a map entry. Must be followed by
+    /// [`end_message`](Self::end_message) after writing the fields of the block.
+    pub fn begin_message(&mut self, name: &str) -> fmt::Result {
+        self.write_name(name)?;
+        self.indent += 2;
+        self.writer.write_str(" {\n")
+    }
+
+    /// End a message block started by [`begin_message`](Self::begin_message).
+    pub fn end_message(&mut self) -> fmt::Result {
+        self.indent = self.indent.saturating_sub(2);
+        writeln!(self.writer, "{:1$}}}", "", self.indent)
+    }
+}
+
+fn write_escaped_byte<W: Write>(writer: &mut W, b: u8) -> fmt::Result {
+    match b {
+        b'\n' => writer.write_str("\\n"),
+        b'\r' => writer.write_str("\\r"),
+        b'\t' => writer.write_str("\\t"),
+        b'"' => writer.write_str("\\\""),
+        b'\'' => writer.write_str("\\'"),
+        b'\\' => writer.write_str("\\\\"),
+        0x20..0x7F => writer.write_char(b as char),
+        _ => write!(writer, "\\{b:03o}"),
+    }
+}
+
+/// Parser of the Protobuf text format.
+///
+/// Generated [`TextDecode`] implementations call [`next_field`](Self::next_field) to read each
+/// field name, then the `decode_*` method corresponding to the field's type to read the value.
+/// Both the `name: value` and `name: [value, ...]` forms of repeated fields are accepted, and
+/// nested messages can be delimited by either `{}` or `<>`. Comments start with `#` and run until
+/// the end of the line.
+#[derive(Debug, Clone)]
+pub struct TextDecoder<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+    /// Closing delimiter of the current message, or `None` for the top-level message
+    closer: Option<u8>,
+    /// Set after reading a field name, allowing a separator to precede the next field
+    in_field: bool,
+    /// Maximum nesting depth of messages. When parsing a message field would nest deeper than
+    /// this, the decoder will report [`TextError::MaxDepth`]. Defaults to 100, same as the binary
+    /// decoder.
+    pub max_depth: usize,
+}
+
+impl<'a> TextDecoder<'a> {
+    /// Construct a new decoder over `input`.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+            closer: None,
+            in_field: false,
+            max_depth: 100,
+        }
+    }
+
+    /// Byte offset of the decoder in the input.
+    ///
+    /// After an error, this points at or right after the token that caused it.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.input.as_bytes().get(self.pos..).unwrap_or_default()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.rest().first().copied()
+    }
+
+    /// Skip whitespace and comments
+    fn skip_ws(&mut self) {
+        while let Some(b) = self.peek() {
+            if b.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if b == b'#' {
+                let line_len = self
+                    .rest()
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .unwrap_or(self.rest().len());
+                self.pos += line_len;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Consume `b` if it's the next non-whitespace byte
+    fn eat(&mut self, b: u8) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), TextError> {
+        if self.eat(b) {
+            Ok(())
+        } else if self.peek().is_none() {
+            Err(TextError::UnexpectedEof)
+        } else {
+            Err(TextError::Syntax)
+        }
+    }
+
+    /// Read a token made of characters that can appear in identifiers and numbers
+    fn word(&mut self) -> Result<&'a str, TextError> {
+        self.skip_ws();
+        let len = self
+            .rest()
+            .iter()
+            .position(|b| !(b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-' | b'+')))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(if self.peek().is_none() {
+                TextError::UnexpectedEof
+            } else {
+                TextError::Syntax
+            });
+        }
+        let word = self.input.get(self.pos..self.pos + len).unwrap_or_default();
+        self.pos += len;
+        Ok(word)
+    }
+
+    /// Read the name of the next field in the current message.
+    ///
+    /// Returns `None` at the end of the message, which is the end of the input for the top-level
+    /// message, or the closing delimiter for nested messages.
+    pub fn next_field(&mut self) -> Result<Option<&'a str>, TextError> {
+        if self.in_field {
+            // Fields can optionally be separated by commas or semicolons
+            if !self.eat(b',') {
+                self.eat(b';');
+            }
+        }
+        self.skip_ws();
+        match (self.peek(), self.closer) {
+            (None, None) => return Ok(None),
+            (None, Some(_)) => return Err(TextError::UnexpectedEof),
+            (Some(b), Some(closer)) if b == closer => {
+                self.pos += 1;
+                return Ok(None);
+            }
+            (Some(b), _) if !(b.is_ascii_alphabetic() || b == b'_') => {
+                return Err(TextError::Syntax);
+            }
+            _ => {}
+        }
+        let name = self.word()?;
+        if name.contains(['.', '-', '+']) {
+            return Err(TextError::Syntax);
+        }
+        self.in_field = true;
+        Ok(Some(name))
+    }
+
+    /// Read the colon between a field name and its value.
+    ///
+    /// The colon is mandatory before scalar values, but optional before messages.
+    pub fn decode_colon(&mut self, required: bool) -> Result<(), TextError> {
+        if self.eat(b':') || !required {
+            Ok(())
+        } else {
+            Err(TextError::Syntax)
+        }
+    }
+
+    /// Read the value of a repeated field, calling `decode_elem` for each element.
+    ///
+    /// The value is either a single element or a list of elements in square brackets.
+    pub fn decode_list<F>(&mut self, mut decode_elem: F) -> Result<(), TextError>
+    where
+        F: FnMut(&mut Self) -> Result<(), TextError>,
+    {
+        if !self.eat(b'[') {
+            return decode_elem(self);
+        }
+        if self.eat(b']') {
+            return Ok(());
+        }
+        loop {
+            decode_elem(self)?;
+            if self.eat(b']') {
+                return Ok(());
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    /// Read a message value enclosed in `{}` or `<>`, calling `decode_fields` to read its
+    /// fields.
+    ///
+    /// `decode_fields` should call [`next_field`](Self::next_field) until it returns `None`.
+    pub fn decode_nested<F>(&mut self, decode_fields: F) -> Result<(), TextError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), TextError>,
+    {
+        let closer = if self.eat(b'{') {
+            b'}'
+        } else if self.eat(b'<') {
+            b'>'
+        } else if self.peek().is_none() {
+            return Err(TextError::UnexpectedEof);
+        } else {
+            return Err(TextError::Syntax);
+        };
+        if self.depth >= self.max_depth {
+            return Err(TextError::MaxDepth);
+        }
+
+        let outer_closer = self.closer.replace(closer);
+        self.depth += 1;
+        self.in_field = false;
+        decode_fields(self)?;
+        self.depth -= 1;
+        self.closer = outer_closer;
+        self.in_field = true;
+        Ok(())
+    }
+
+    /// Read a message value into `msg`.
+    pub fn decode_message<M: TextDecode + ?Sized>(&mut self, msg: &mut M) -> Result<(), TextError> {
+        self.decode_nested(|decoder| msg.decode_text(decoder))
+    }
+
+    /// Parse an integer literal, which may be decimal, hexadecimal, or octal
+    fn integer(&mut self) -> Result<(bool, u64), TextError> {
+        let word = self.word()?;
+        let (neg, digits) = match word.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, word),
+        };
+        let (radix, digits) = if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            (16, hex)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            (8, &digits[1..])
+        } else {
+            (10, digits)
+        };
+        // from_str_radix accepts a leading plus sign, which the text format doesn't
+        if digits.starts_with('+') {
+            return Err(TextError::InvalidNumber);
+        }
+        let magnitude = u64::from_str_radix(digits, radix).map_err(|_| TextError::InvalidNumber)?;
+        Ok((neg, magnitude))
+    }
+
+    /// Read a signed integer value into type `T`.
+    pub fn decode_int<T: TryFrom<i64>>(&mut self) -> Result<T, TextError> {
+        let (neg, magnitude) = self.integer()?;
+        let val = if neg {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        };
+        val.and_then(|v| T::try_from(v).ok())
+            .ok_or(TextError::InvalidNumber)
+    }
+
+    /// Read an unsigned integer value into type `T`.
+    pub fn decode_uint<T: TryFrom<u64>>(&mut self) -> Result<T, TextError> {
+        let (neg, magnitude) = self.integer()?;
+        if neg && magnitude != 0 {
+            return Err(TextError::InvalidNumber);
+        }
+        T::try_from(magnitude).map_err(|_| TextError::InvalidNumber)
+    }
+
+    /// Parse a floating-point literal, which may have an `f` suffix
+    fn float<T: core::str::FromStr>(&mut self) -> Result<T, TextError> {
+        let word = self.word()?;
+        // Rust accepts a leading plus sign, which the text format doesn't
+        if word.starts_with('+') {
+            return Err(TextError::InvalidNumber);
+        }
+        word.parse().or_else(|_| {
+            word.strip_suffix(['f', 'F'])
+                .ok_or(TextError::InvalidNumber)?
+                .parse()
+                .map_err(|_| TextError::InvalidNumber)
+        })
+    }
+
+    /// Read a `float` value.
+    pub fn decode_float(&mut self) -> Result<f32, TextError> {
+        self.float()
+    }
+
+    /// Read a `double` value.
+    pub fn decode_double(&mut self) -> Result<f64, TextError> {
+        self.float()
+    }
+
+    /// Read a `bool` value.
+    pub fn decode_bool(&mut self) -> Result<bool, TextError> {
+        match self.word()? {
+            "true" | "True" | "t" | "1" => Ok(true),
+            "false" | "False" | "f" | "0" => Ok(false),
+            _ => Err(TextError::Syntax),
+        }
+    }
+
+    /// Read an enum value, given either as a value name or as a number.
+    pub fn decode_enum<E: TextEnum>(&mut self) -> Result<E, TextError> {
+        self.skip_ws();
+        if matches!(self.peek(), Some(b'0'..=b'9' | b'-')) {
+            let num = self.decode_int()?;
+            E::from_text_number(num).ok_or(TextError::InvalidNumber)
+        } else {
+            E::from_text_name(self.word()?).ok_or(TextError::UnknownEnumValue)
+        }
+    }
+
+    /// Parse one or more adjacent quoted string literals, passing the unescaped contents to
+    /// `emit` in chunks
+    fn literal<F: FnMut(&[u8])>(&mut self, mut emit: F) -> Result<(), TextError> {
+        self.skip_ws();
+        if !matches!(self.peek(), Some(b'"' | b'\'')) {
+            return Err(if self.peek().is_none() {
+                TextError::UnexpectedEof
+            } else {
+                TextError::Syntax
+            });
+        }
+
+        while let Some(quote @ (b'"' | b'\'')) = self.peek() {
+            self.pos += 1;
+            loop {
+                let rest = self.rest();
+                let Some(&b) = rest.first() else {
+                    return Err(TextError::UnexpectedEof);
+                };
+                self.pos += 1;
+                match b {
+                    b'\n' => return Err(TextError::Syntax),
+                    b'\\' => self.escape(&mut emit)?,
+                    _ if b == quote => break,
+                    _ => emit(&rest[..1]),
+                }
+            }
+            self.skip_ws();
+        }
+        Ok(())
+    }
+
+    /// Parse the escape sequence after a backslash
+    fn escape<F: FnMut(&[u8])>(&mut self, emit: &mut F) -> Result<(), TextError> {
+        let Some(b) = self.peek() else {
+            return Err(TextError::UnexpectedEof);
+        };
+        self.pos += 1;
+        let byte = match b {
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0C,
+            b'v' => 0x0B,
+            b'\\' | b'\'' | b'"' | b'?' => b,
+            b'0'..=b'7' => {
+                self.pos -= 1;
+                let val = self.escape_digits(8, 3)?;
+                u8::try_from(val).map_err(|_| TextError::InvalidEscape)?
+            }
+            b'x' | b'X' => self.escape_digits(16, 2)? as u8,
+            b'u' | b'U' => {
+                let digits = if b == b'u' { 4 } else { 8 };
+                let c = char::from_u32(self.escape_digits(16, digits)?)
+                    .ok_or(TextError::InvalidEscape)?;
+                emit(c.encode_utf8(&mut [0; 4]).as_bytes());
+                return Ok(());
+            }
+            _ => return Err(TextError::InvalidEscape),
+        };
+        emit(&[byte]);
+        Ok(())
+    }
+
+    /// Parse up to `max` digits of an escape sequence. `\u` and `\U` require exactly `max` digits.
+    fn escape_digits(&mut self, radix: u32, max: usize) -> Result<u32, TextError> {
+        let len = self
+            .rest()
+            .iter()
+            .take(max)
+            .position(|b| !(*b as char).is_digit(radix))
+            .unwrap_or(max.min(self.rest().len()));
+        if len == 0 || (radix == 16 && max > 2 && len != max) {
+            return Err(TextError::InvalidEscape);
+        }
+        let digits = self.input.get(self.pos..self.pos + len).unwrap_or_default();
+        self.pos += len;
+        u32::from_str_radix(digits, radix).map_err(|_| TextError::InvalidEscape)
+    }
+
+    /// Parse a string literal into the spare capacity of `buf`, returning the number of bytes
+    fn literal_into<S: PbString>(&mut self, buf: &mut S) -> Result<usize, TextError> {
+        // Measure the unescaped length first, so that we can check the capacity
+        let start = self.pos;
+        let mut len = 0;
+        self.literal(|chunk| len += chunk.len())?;
+
+        buf.pb_clear();
+        buf.pb_reserve(len);
+        let spare_cap = buf.pb_spare_cap();
+        if spare_cap.len() < len {
+            return Err(TextError::Capacity);
+        }
+        self.pos = start;
+        let mut written = 0;
+        self.literal(|chunk| {
+            written += maybe_uninit_write_slice(&mut spare_cap[written..], chunk)
+        })?;
+        Ok(len)
+    }
+
+    /// Read a `string` value into a [`PbString`] container, replacing its existing contents.
+    pub fn decode_string<S: PbString>(&mut self, string: &mut S) -> Result<(), TextError> {
+        let len = self.literal_into(string)?;
+        // SAFETY: literal_into has written `len` bytes into the spare capacity
+        let written = unsafe { maybe_uninit_slice_assume_init_ref(&string.pb_spare_cap()[..len]) };
+        // Escape sequences can produce invalid UTF-8
+        if from_utf8(written).is_err() {
+            string.pb_clear();
+            return Err(TextError::Utf8);
+        }
+        // SAFETY: `len` bytes have been written, and we just checked that they're valid UTF-8
+        unsafe { string.pb_set_len(len) };
+        Ok(())
+    }
+
+    /// Read a `bytes` value into a [`PbBytes`] container, replacing its existing contents.
+    pub fn decode_bytes<B: PbBytes>(&mut self, bytes: &mut B) -> Result<(), TextError> {
+        let len = self.literal_into(bytes)?;
+        // SAFETY: literal_into has written `len` bytes into the spare capacity
+        unsafe { bytes.pb_set_len(len) };
+        Ok(())
+    }
+
+    /// Read a `string` value into a [`PbBytes`] container without validating it as UTF-8,
+    /// replacing its existing contents.
+    ///
+    /// String and bytes literals have the same syntax, so this is the same as
+    /// [`decode_bytes`](Self::decode_bytes).
+    pub fn decode_string_bytes<B: PbBytes>(&mut self, bytes: &mut B) -> Result<(), TextError> {
+        self.decode_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Color(i32);
+
+    impl TextEnum for Color {
+        fn text_name(&self) -> Option<&'static str> {
+            match self.0 {
+                0 => Some("RED"),
+                1 => Some("GREEN"),
+                _ => None,
+            }
+        }
+
+        fn from_text_name(name: &str) -> Option<Self> {
+            match name {
+                "RED" => Some(Self(0)),
+                "GREEN" => Some(Self(1)),
+                _ => None,
+            }
+        }
+
+        fn text_number(&self) -> i64 {
+            self.0 as i64
+        }
+
+        fn from_text_number(num: i64) -> Option<Self> {
+            i32::try_from(num).ok().map(Self)
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Msg {
+        num: i32,
+        name: heapless_0_9::String<8>,
+        list: heapless_0_9::Vec<u32, 3>,
+        inner: Option<Box<Msg>>,
+    }
+
+    impl TextEncode for Msg {
+        fn encode_text<W: Write>(&self, encoder: &mut TextEncoder<W>) -> fmt::Result {
+            encoder.write_int("num", self.num as i64)?;
+            encoder.write_str("name", &self.name)?;
+            for elem in &self.list {
+                encoder.write_uint("list", *elem as u64)?;
+            }
+            if let Some(inner) = &self.inner {
+                encoder.write_message("inner", inner.as_ref())?;
+            }
+            Ok(())
+        }
+    }
+
+    impl TextDecode for Msg {
+        fn decode_text(&mut self, decoder: &mut TextDecoder<'_>) -> Result<(), TextError> {
+            while let Some(name) = decoder.next_field()? {
+                match name {
+                    "num" => {
+                        decoder.decode_colon(true)?;
+                        self.num = decoder.decode_int()?;
+                    }
+                    "name" => {
+                        decoder.decode_colon(true)?;
+                        decoder.decode_string(&mut self.name)?;
+                    }
+                    "list" => {
+                        decoder.decode_colon(true)?;
+                        decoder.decode_list(|decoder| {
+                            let elem = decoder.decode_uint()?;
+                            self.list.push(elem).map_err(|_| TextError::Capacity)
+                        })?;
+                    }
+                    "inner" => {
+                        decoder.decode_colon(false)?;
+                        decoder.decode_message(
+                            self.inner.get_or_insert_with(Default::default).as_mut(),
+                        )?;
+                    }
+                    _ => return Err(TextError::UnknownField),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn encode_with(f: impl FnOnce(&mut TextEncoder<String>) -> fmt::Result) -> String {
+        let mut encoder = TextEncoder::new(String::new());
+        f(&mut encoder).unwrap();
+        encoder.into_writer()
+    }
+
+    #[test]
+    fn encode_scalars() {
+        assert_eq!(encode_with(|e| e.write_int("a", -5)), "a: -5\n");
+        assert_eq!(
+            encode_with(|e| e.write_uint("a", u64::MAX)),
+            "a: 18446744073709551615\n"
+        );
+        assert_eq!(encode_with(|e| e.write_float("a", 1.5)), "a: 1.5\n");
+        assert_eq!(encode_with(|e| e.write_float("a", f32::NAN)), "a: nan\n");
+        assert_eq!(
+            encode_with(|e| e.write_double("a", f64::NEG_INFINITY)),
+            "a: -inf\n"
+        );
+        assert_eq!(encode_with(|e| e.write_bool("a", true)), "a: true\n");
+        assert_eq!(
+            encode_with(|e| e.write_str("a", "\"hé\"\n\x01")),
+            "a: \"\\\"hé\\\"\\n\\001\"\n"
+        );
+        assert_eq!(
+            encode_with(|e| e.write_bytes("a", &[b'x', 0xFF, b'\\'])),
+            "a: \"x\\377\\\\\"\n"
+        );
+        assert_eq!(
+            encode_with(|e| e.write_str_bytes("a", b"\xC3\xA9\xFF\n")),
+            "a: \"é\\377\\n\"\n"
+        );
+        assert_eq!(encode_with(|e| e.write_enum("a", &Color(1))), "a: GREEN\n");
+        assert_eq!(encode_with(|e| e.write_enum("a", &Color(7))), "a: 7\n");
+    }
+
+    #[test]
+    fn encode_message() {
+        let msg = Msg {
+            num: 3,
+            name: "top".try_into().unwrap(),
+            list: heapless_0_9::Vec::from_slice(&[1, 2]).unwrap(),
+            inner: Some(Box::new(Msg {
+                num: -1,
+                ..Default::default()
+            })),
+        };
+        let output = encode_with(|e| msg.encode_text(e));
+        assert_eq!(
+            output,
+            "num: 3\nname: \"top\"\nlist: 1\nlist: 2\ninner {\n  num: -1\n  name: \"\"\n}\n"
+        );
+
+        let mut parsed = Msg::default();
+        parsed.parse_text(&output).unwrap();
+        assert_eq!(parsed, msg);
+    }
+
+    #[test]
+    fn decode_message() {
+        let mut msg = Msg::default();
+        msg.parse_text(
+            "# comment\n num: 0x10, name: 'a' \"b\\x63\" ; list: [1, 02] inner < num: -7 >",
+        )
+        .unwrap();
+        assert_eq!(msg.num, 16);
+        assert_eq!(msg.name, "abc");
+        assert_eq!(msg.list, [1, 2]);
+        assert_eq!(msg.inner.unwrap().num, -7);
+
+        let mut msg = Msg::default();
+        assert_eq!(
+            msg.parse_text("list: [1, 2, 3, 4]"),
+            Err(TextError::Capacity)
+        );
+        assert_eq!(
+            msg.parse_text("name: \"too long!!\""),
+            Err(TextError::Capacity)
+        );
+        assert_eq!(msg.parse_text("name: \"\\xFF\""), Err(TextError::Utf8));
+        assert_eq!(
+            msg.parse_text("name: \"\\q\""),
+            Err(TextError::InvalidEscape)
+        );
+        assert_eq!(msg.parse_text("name: \"abc"), Err(TextError::UnexpectedEof));
+        assert_eq!(
+            msg.parse_text("num: 2147483648"),
+            Err(TextError::InvalidNumber)
+        );
+        assert_eq!(msg.parse_text("list: -1"), Err(TextError::InvalidNumber));
+        assert_eq!(msg.parse_text("num 1"), Err(TextError::Syntax));
+        assert_eq!(
+            msg.parse_text("inner { num: 1"),
+            Err(TextError::UnexpectedEof)
+        );
+        assert_eq!(msg.parse_text("inner { num: 1 >"), Err(TextError::Syntax));
+        assert_eq!(msg.parse_text("}"), Err(TextError::Syntax));
+
+        let mut decoder = TextDecoder::new("extra: 1");
+        assert_eq!(msg.decode_text(&mut decoder), Err(TextError::UnknownField));
+        assert_eq!(decoder.position(), 5);
+
+        let mut decoder = TextDecoder::new("inner { inner { inner {} } }");
+        decoder.max_depth = 2;
+        assert_eq!(msg.decode_text(&mut decoder), Err(TextError::MaxDepth));
+    }
+
+    #[test]
+    fn decode_scalars() {
+        let mut decoder = TextDecoder::new("-9223372036854775808 -0 0777 1.5f -inf nan t False");
+        assert_eq!(decoder.decode_int::<i64>(), Ok(i64::MIN));
+        assert_eq!(decoder.decode_uint::<u8>(), Ok(0));
+        assert_eq!(decoder.decode_int::<i32>(), Ok(0o777));
+        assert_eq!(decoder.decode_float(), Ok(1.5));
+        assert_eq!(decoder.decode_double(), Ok(f64::NEG_INFINITY));
+        assert!(decoder.decode_float().unwrap().is_nan());
+        assert_eq!(decoder.decode_bool(), Ok(true));
+        assert_eq!(decoder.decode_bool(), Ok(false));
+        assert_eq!(decoder.decode_bool(), Err(TextError::UnexpectedEof));
+
+        let mut decoder = TextDecoder::new("GREEN 5 BLUE");
+        assert_eq!(decoder.decode_enum(), Ok(Color(1)));
+        assert_eq!(decoder.decode_enum(), Ok(Color(5)));
+        assert_eq!(
+            decoder.decode_enum::<Color>(),
+            Err(TextError::UnknownEnumValue)
+        );
+
+        let mut bytes = heapless_0_9::Vec::<u8, 8>::new();
+        let mut decoder = TextDecoder::new(r#""\001\x7f\u00e9\?""#);
+        decoder.decode_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [0x01, 0x7F, 0xC3, 0xA9, b'?']);
+    }
+}
@@ -0,0 +1,238 @@
+use core::ops::Deref;
+
+use crate::{
+    misc::{maybe_uninit_write_slice, varint32_from_bytes},
+    CapacityExceeded, PbBytes, Tag, WIRE_TYPE_EGROUP, WIRE_TYPE_I32, WIRE_TYPE_I64, WIRE_TYPE_LEN,
+    WIRE_TYPE_SGROUP, WIRE_TYPE_VARINT,
+};
+#[cfg(all(feature = "async", feature = "decode"))]
+use crate::{AsyncFieldDecode, AsyncPbDecoder, AsyncPbRead};
+#[cfg(all(feature = "async", feature = "encode"))]
+use crate::{AsyncFieldEncode, AsyncPbEncoder, AsyncPbWrite};
+#[cfg(feature = "decode")]
+use crate::{DecodeError, FieldDecode, PbDecoder, PbRead};
+#[cfg(feature = "encode")]
+use crate::{FieldEncode, PbEncoder, PbWrite};
+
+/// Container that retains unknown fields from the wire as raw bytes.
+///
+/// Meant to be used as the unknown handler of generated messages, either via
+/// [`Config::unknown_handler`](https://docs.rs/micropb-gen/latest/micropb_gen/config/struct.Config.html#method.unknown_handler)
+/// or via
+/// [`Generator::retain_unknown_fields`](https://docs.rs/micropb-gen/latest/micropb_gen/struct.Generator.html#method.retain_unknown_fields).
+/// Each unknown field is stored exactly as it appeared on the wire, tag included, and is written
+/// back out unchanged when the message is encoded, so data from newer schema versions survives a
+/// decode-encode round trip.
+///
+/// The storage is any [`PbBytes`] container. `HeaplessUnknownFields<N>` holds up to `N` bytes of
+/// unknown fields, while `VecUnknownFields` grows as needed. If a
+/// fixed-capacity container runs out of space, decoding fails with [`DecodeError::Capacity`],
+/// unless [`PbDecoder::ignore_repeated_cap_err`] is set, in which case the field is dropped
+/// instead.
+///
+/// Group fields are retained along with their end-group tags. A group that doesn't fit is dropped
+/// as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct UnknownFields<B> {
+    buf: B,
+}
+
+/// [`UnknownFields`] backed by a `heapless::Vec` (v0.9) with a fixed capacity of `N` bytes.
+#[cfg(feature = "container-heapless-0-9")]
+pub type HeaplessUnknownFields<const N: usize> = UnknownFields<heapless_0_9::Vec<u8, N>>;
+
+/// [`UnknownFields`] backed by a growable `Vec`.
+#[cfg(feature = "alloc")]
+pub type VecUnknownFields = UnknownFields<alloc::vec::Vec<u8>>;
+
+impl<B: Default> UnknownFields<B> {
+    /// Create an empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: PbBytes + Deref<Target = [u8]>> UnknownFields<B> {
+    /// Raw wire bytes of all retained fields, in the order they were decoded.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Total size of the retained fields in bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if no fields have been retained.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Discard all retained fields.
+    pub fn clear(&mut self) {
+        self.buf.pb_clear();
+    }
+
+    /// Append all retained fields from `other`.
+    ///
+    /// Since decoding the concatenated fields is equivalent to merging them, this follows the
+    /// same semantics as [`MessageMerge::merge_from`](crate::MessageMerge::merge_from). Nothing
+    /// is appended if the container doesn't have enough capacity.
+    pub fn merge_from(&mut self, other: &Self) -> Result<(), CapacityExceeded> {
+        let len = other.buf.len();
+        let old_len = self.buf.len();
+        self.buf.pb_reserve(len);
+        let spare_cap = self
+            .buf
+            .pb_spare_cap()
+            .get_mut(..len)
+            .ok_or(CapacityExceeded)?;
+        maybe_uninit_write_slice(spare_cap, &other.buf);
+        // SAFETY: the `len` bytes after the old length have been initialized from `other`
+        unsafe { self.buf.pb_set_len(old_len + len) };
+        Ok(())
+    }
+
+    /// Iterate over the retained fields.
+    ///
+    /// Each item is the tag of the field along with its value. The value of a `LEN` field excludes
+    /// the length prefix, while the value of a `VARINT` field is the raw varint bytes. The value
+    /// of a group is the raw fields between its start and end tags.
+    pub fn iter(&self) -> UnknownFieldsIter<'_> {
+        UnknownFieldsIter { bytes: &self.buf }
+    }
+}
+
+impl<'a, B: PbBytes + Deref<Target = [u8]>> IntoIterator for &'a UnknownFields<B> {
+    type Item = (Tag, &'a [u8]);
+    type IntoIter = UnknownFieldsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the fields in [`UnknownFields`].
+#[derive(Debug, Clone)]
+pub struct UnknownFieldsIter<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+/// Split a raw varint off the front of `bytes`
+fn split_varint(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = bytes.iter().take(10).position(|b| b & 0x80 == 0)? + 1;
+    Some(bytes.split_at(len))
+}
+
+/// Split a raw tag off the front of `bytes`
+fn split_tag(bytes: &[u8]) -> Option<(Tag, &[u8])> {
+    let (tag, rest) = split_varint(bytes)?;
+    let tag = varint32_from_bytes(tag);
+    Some((Tag::from_parts(tag >> 3, (tag & 0b111) as u8), rest))
+}
+
+/// Split a value of a non-group wire type off the front of `bytes`
+fn split_value(wire_type: u8, bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    match wire_type {
+        WIRE_TYPE_VARINT => split_varint(bytes),
+        WIRE_TYPE_I64 => bytes.split_at_checked(8),
+        WIRE_TYPE_LEN => {
+            let (len, rest) = split_varint(bytes)?;
+            rest.split_at_checked(varint32_from_bytes(len) as usize)
+        }
+        WIRE_TYPE_I32 => bytes.split_at_checked(4),
+        _ => None,
+    }
+}
+
+/// Split the contents of a group off the front of `bytes`, dropping the end-group tag
+pub(crate) fn split_group(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut nested = 0usize;
+    let mut rest = bytes;
+    loop {
+        let (tag, after_tag) = split_tag(rest)?;
+        match tag.wire_type() {
+            WIRE_TYPE_SGROUP => nested += 1,
+            WIRE_TYPE_EGROUP if nested == 0 => {
+                return Some((&bytes[..bytes.len() - rest.len()], after_tag));
+            }
+            WIRE_TYPE_EGROUP => nested -= 1,
+            wire_type => {
+                rest = split_value(wire_type, after_tag)?.1;
+                continue;
+            }
+        }
+        rest = after_tag;
+    }
+}
+
+impl<'a> UnknownFieldsIter<'a> {
+    fn parse_next(&mut self) -> Option<(Tag, &'a [u8])> {
+        let (tag, rest) = split_tag(self.bytes)?;
+        let (value, rest) = match tag.wire_type() {
+            WIRE_TYPE_SGROUP => split_group(rest)?,
+            wire_type => split_value(wire_type, rest)?,
+        };
+        self.bytes = rest;
+        Some((tag, value))
+    }
+}
+
+impl<'a> Iterator for UnknownFieldsIter<'a> {
+    type Item = (Tag, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.parse_next();
+        if item.is_none() {
+            self.bytes = &[];
+        }
+        item
+    }
+}
+
+impl core::iter::FusedIterator for UnknownFieldsIter<'_> {}
+
+#[cfg(feature = "decode")]
+impl<B: PbBytes + Deref<Target = [u8]>> FieldDecode for UnknownFields<B> {
+    fn decode_field<R: PbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut PbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        decoder.decode_raw_field(tag, &mut self.buf)
+    }
+}
+
+#[cfg(all(feature = "async", feature = "decode"))]
+impl<B: PbBytes + Deref<Target = [u8]>> AsyncFieldDecode for UnknownFields<B> {
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, DecodeError<R::Error>> {
+        decoder.decode_raw_field(tag, &mut self.buf).await
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<B: Deref<Target = [u8]>> FieldEncode for UnknownFields<B> {
+    const MAX_SIZE: Result<usize, &'static str> = Err("unbounded unknown fields");
+
+    fn encode_fields<W: PbWrite>(&self, encoder: &mut PbEncoder<W>) -> Result<(), W::Error> {
+        encoder.write(&self.buf)
+    }
+
+    fn compute_fields_size(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+#[cfg(all(feature = "async", feature = "encode"))]
+impl<B: Deref<Target = [u8]>> AsyncFieldEncode for UnknownFields<B> {
+    async fn encode_fields_async<W: AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        encoder.write(&self.buf).await
+    }
+}
@@ -480,13 +480,196 @@ fn default_str_escape() {
 }
 
 fn extension() {
-    let generator = Generator::new();
+    let mut generator = Generator::new();
+    generator.use_container_alloc();
     generator
         .compile_protos(
             &["proto/extension.proto"],
             std::env::var("OUT_DIR").unwrap() + "/extension.rs",
         )
         .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .configure(".", Config::new().retain_extensions(true))
+        .configure(".ext.Extendee._extensions", Config::new().max_bytes(32))
+        .configure(".ext.label", Config::new().max_bytes(8))
+        .configure(".ext.deltas", Config::new().max_len(4))
+        .configure(".ext.ids", Config::new().max_len(4))
+        .configure(".ext.payloads", Config::new().max_len(2))
+        .configure(".ext.Payload.flags", Config::new().max_len(4));
+    generator
+        .compile_protos(
+            &["proto/extension.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/extension.heapless.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .async_encode_decode(true)
+        .configure(".ext.Extendee", Config::new().retain_extensions(true));
+    generator
+        .compile_protos(
+            &["proto/extension.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/extension.alloc.rs",
+        )
+        .unwrap();
+}
+
+fn group() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .encode_cache(true)
+        .retain_unknown_fields("::micropb::HeaplessUnknownFields<32>");
+    generator.configure(".group.Catalog.Header.title", Config::new().max_bytes(8));
+    generator.configure(".group.Catalog.item", Config::new().max_len(2));
+    generator.configure(".group.Catalog.other", Config::new().max_bytes(8));
+    generator.configure(".group.Note.text", Config::new().max_bytes(8));
+    generator
+        .compile_protos(
+            &["proto/group.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/group.heapless.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .async_encode_decode(true)
+        .configure(".group.Catalog", Config::new().retain_extensions(true));
+    generator
+        .compile_protos(
+            &["proto/group.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/group.alloc.rs",
+        )
+        .unwrap();
+}
+
+fn closed_enum() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .retain_unknown_fields("::micropb::HeaplessUnknownFields<32>")
+        .text_format(true);
+    generator.configure(
+        ".",
+        Config::new()
+            .closed_enum_as_rust_enum(true)
+            .enum_int_size(IntSize::S8),
+    );
+    generator.configure(".closed_enum.Palette.packed", Config::new().max_len(2));
+    generator.configure(".closed_enum.Palette.unpacked", Config::new().max_len(2));
+    generator.configure(".closed_enum.Palette.levels", Config::new().max_len(2));
+    generator.configure(".closed_enum.Palette.named", Config::new().max_len(2));
+    generator.configure(".closed_enum.Palette.named.key", Config::new().max_bytes(4));
+    generator
+        .compile_protos(
+            &["proto/closed_enum.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/closed_enum.heapless.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .async_encode_decode(true)
+        .json(true);
+    generator.configure(".", Config::new().closed_enum_as_rust_enum(true));
+    generator.configure(
+        ".closed_enum.Palette.primary",
+        Config::new().optional_repr(OptionalRepr::Option),
+    );
+    generator.configure(".closed_enum.Palette.secondary", Config::new().boxed(true));
+    generator.configure(".closed_enum.Palette.choice", Config::new().boxed(true));
+    generator
+        .compile_protos(
+            &["proto/closed_enum.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/closed_enum.alloc.rs",
+        )
+        .unwrap();
+}
+
+fn merge() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .single_oneof_msg_as_enum(true)
+        .retain_unknown_fields("::micropb::VecUnknownFields");
+    generator.configure(
+        ".merge.Settings.retries",
+        Config::new().optional_repr(OptionalRepr::Option),
+    );
+    generator.configure(
+        ".merge.Settings.backup_limits",
+        Config::new()
+            .optional_repr(OptionalRepr::Option)
+            .boxed(true),
+    );
+    generator.configure(".merge.Settings.inline_limits", Config::new().boxed(true));
+    generator.configure(".merge.Choice.limits", Config::new().boxed(true));
+    generator
+        .compile_protos(
+            &["proto/merge.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/merge.alloc.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .single_oneof_msg_as_enum(true);
+    generator.configure(".merge.Settings.name", Config::new().max_bytes(8));
+    generator.configure(".merge.Settings.path", Config::new().max_bytes(8));
+    generator.configure(".merge.Settings.ports", Config::new().max_len(2));
+    generator.configure(".merge.Settings.ranges", Config::new().max_len(2));
+    generator.configure(".merge.Settings.zones", Config::new().max_len(2));
+    generator.configure(".merge.Settings.zones.key", Config::new().max_bytes(8));
+    generator.configure(".merge.Settings.counters", Config::new().max_len(2));
+    generator.configure(".merge.Settings.counters.key", Config::new().max_bytes(8));
+    generator
+        .compile_protos(
+            &["proto/merge.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/merge.heapless.rs",
+        )
+        .unwrap();
+}
+
+fn open_enum() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .async_encode_decode(true)
+        .text_format(true)
+        .json(true);
+    generator.configure(".open_enum", Config::new().open_enum_as_rust_enum(true));
+    generator.configure(
+        ".open_enum.Shape",
+        Config::new().enum_int_size(IntSize::S16),
+    );
+    generator
+        .compile_protos(
+            &["proto/open_enum.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/open_enum.rust_enum.rs",
+        )
+        .unwrap();
+
+    // Same enums as newtypes, for comparing against
+    let mut generator = Generator::new();
+    generator.use_container_alloc();
+    generator.configure(
+        ".open_enum.Shape",
+        Config::new().enum_int_size(IntSize::S16),
+    );
+    generator
+        .compile_protos(
+            &["proto/open_enum.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/open_enum.newtype.rs",
+        )
+        .unwrap();
 }
 
 fn files_with_same_package() {
@@ -571,6 +754,158 @@ fn single_oneof(encode_cache: bool) {
         .unwrap();
 }
 
+fn async_encode_decode(encode_cache: bool) {
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .encode_cache(encode_cache)
+        .async_encode_decode(true);
+    generator.configure(
+        ".basic.BasicTypes",
+        Config::new().unknown_handler("crate::async_encode_decode::UnknownTags"),
+    );
+    generator.configure(
+        ".Recursive",
+        Config::new().optional_repr(OptionalRepr::Option),
+    );
+
+    let mut protos = vec![
+        "proto/basic.proto",
+        "proto/nested.proto",
+        "proto/collections.proto",
+        "proto/map.proto",
+    ];
+    let filename = if encode_cache {
+        "/async_encode_decode.cached.rs"
+    } else {
+        // Cache structs can't be generated for cyclic messages
+        protos.push("proto/recursive.proto");
+        "/async_encode_decode.rs"
+    };
+    generator
+        .compile_protos(&protos, std::env::var("OUT_DIR").unwrap() + filename)
+        .unwrap();
+}
+
+fn unknown_fields() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .retain_unknown_fields("::micropb::HeaplessUnknownFields<32>");
+    generator.configure(".unknown_fields.OldInner.name", Config::new().max_bytes(8));
+    generator.configure(".unknown_fields.NewInner.name", Config::new().max_bytes(8));
+    generator.configure(".unknown_fields.New.blob", Config::new().max_bytes(64));
+    generator.configure(".unknown_fields.New.nums", Config::new().max_len(8));
+    generator
+        .compile_protos(
+            &["proto/unknown_fields.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/unknown_fields.heapless.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .async_encode_decode(true)
+        .retain_unknown_fields("::micropb::VecUnknownFields");
+    generator
+        .compile_protos(
+            &["proto/unknown_fields.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/unknown_fields.alloc.rs",
+        )
+        .unwrap();
+}
+
+fn text_format() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .single_oneof_msg_as_enum(true)
+        .text_format(true);
+    generator.configure(
+        ".basic.BasicTypes.int32_num",
+        Config::new().optional_repr(OptionalRepr::Option),
+    );
+    generator.configure(".basic.BasicTypes.flt", Config::new().boxed(true));
+    generator.configure(".nested.Nested.inner_msg", Config::new().boxed(true));
+
+    generator.configure(".Data.s", Config::new().max_bytes(3));
+    generator.configure(".Data.b", Config::new().max_bytes(5));
+    generator.configure(".List.list", Config::new().max_len(2));
+    generator.configure(".NumList.list", Config::new().max_len(2));
+    generator.configure(".StrList.list", Config::new().max_len(3));
+    generator.configure(".StrList.list.elem", Config::new().max_bytes(2));
+    generator.configure(".FixedList.list", Config::new().max_len(2));
+    generator.configure(".EnumList.list", Config::new().max_len(2));
+    generator.configure(".Map.mapping", Config::new().max_len(2));
+    generator.configure(".Map.mapping.key", Config::new().max_bytes(4));
+    generator.configure(".Map.mapping.value", Config::new().max_bytes(3));
+
+    generator
+        .compile_protos(
+            &[
+                "proto/basic.proto",
+                "proto/nested.proto",
+                "proto/collections.proto",
+                "proto/map.proto",
+                "proto/single_oneof.proto",
+            ],
+            std::env::var("OUT_DIR").unwrap() + "/text_format.rs",
+        )
+        .unwrap();
+}
+
+fn json() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .single_oneof_msg_as_enum(true)
+        .json(true);
+    generator.configure(
+        ".basic.BasicTypes.int32_num",
+        Config::new().optional_repr(OptionalRepr::Option),
+    );
+    generator.configure(".nested.Nested.inner_msg", Config::new().boxed(true));
+
+    generator.configure(".Data.s", Config::new().max_bytes(3));
+    generator.configure(".Data.b", Config::new().max_bytes(5));
+    generator.configure(".List.list", Config::new().max_len(2));
+    generator.configure(".NumList.list", Config::new().max_len(2));
+    generator.configure(".StrList.list", Config::new().max_len(3));
+    generator.configure(".StrList.list.elem", Config::new().max_bytes(2));
+    generator.configure(".FixedList.list", Config::new().max_len(2));
+    generator.configure(".EnumList.list", Config::new().max_len(2));
+    generator.configure(".Map.mapping", Config::new().max_len(2));
+    generator.configure(".Map.mapping.key", Config::new().max_bytes(4));
+    generator.configure(".Map.mapping.value", Config::new().max_bytes(3));
+
+    generator
+        .compile_protos(
+            &[
+                "proto/basic.proto",
+                "proto/nested.proto",
+                "proto/collections.proto",
+                "proto/map.proto",
+                "proto/single_oneof.proto",
+            ],
+            std::env::var("OUT_DIR").unwrap() + "/json.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator.use_container_heapless().json(true);
+    generator.configure(".NonOptional.st", Config::new().max_bytes(8));
+    generator.configure(".NonOptional.bt", Config::new().max_bytes(8));
+    generator.configure(".Optional.st", Config::new().max_bytes(8));
+    generator.configure(".Optional.bt", Config::new().max_bytes(8));
+    generator
+        .compile_protos(
+            &["proto/implicit_presence.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/json_proto3.rs",
+        )
+        .unwrap();
+}
+
 fn main() {
     no_config(true);
     no_config(false);
@@ -598,6 +933,10 @@ fn main() {
     conflicting_names();
     default_str_escape();
     extension();
+    group();
+    closed_enum();
+    open_enum();
+    merge();
     files_with_same_package();
     fixed_string_and_bytes(true);
     fixed_string_and_bytes(false);
@@ -606,4 +945,9 @@ fn main() {
     with_config_file();
     single_oneof(true);
     single_oneof(false);
+    async_encode_decode(true);
+    async_encode_decode(false);
+    unknown_fields();
+    text_format();
+    json();
 }
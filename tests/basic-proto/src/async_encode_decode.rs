@@ -0,0 +1,352 @@
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use micropb::push_decode::{PushBuffer, PushStatus};
+use micropb::{
+    field::{AsyncFieldDecode, AsyncFieldEncode, FieldDecode, FieldEncode},
+    size::sizeof_tag,
+    AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder, AsyncPbEncoder, AsyncPbRead,
+    MessageEncode, PbDecoder, PbEncoder, PbRead, Tag,
+};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/async_encode_decode.rs"));
+}
+
+mod proto_cached {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/async_encode_decode.cached.rs"));
+}
+
+/// Unknown handler that records the tags of all unknown varint fields
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnknownTags {
+    tags: Vec<(Tag, u64)>,
+}
+
+impl FieldDecode for UnknownTags {
+    fn decode_field<R: PbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut PbDecoder<R>,
+    ) -> Result<bool, micropb::DecodeError<R::Error>> {
+        if tag.wire_type() != micropb::WIRE_TYPE_VARINT {
+            return Ok(false);
+        }
+        self.tags.push((tag, decoder.decode_varint64()?));
+        Ok(true)
+    }
+}
+
+impl AsyncFieldDecode for UnknownTags {
+    async fn decode_field_async<R: AsyncPbRead>(
+        &mut self,
+        tag: Tag,
+        decoder: &mut AsyncPbDecoder<R>,
+    ) -> Result<bool, micropb::DecodeError<R::Error>> {
+        if tag.wire_type() != micropb::WIRE_TYPE_VARINT {
+            return Ok(false);
+        }
+        self.tags.push((tag, decoder.decode_varint64().await?));
+        Ok(true)
+    }
+}
+
+impl FieldEncode for UnknownTags {
+    const MAX_SIZE: Result<usize, &'static str> = Err("unbounded");
+
+    fn encode_fields<W: micropb::PbWrite>(
+        &self,
+        encoder: &mut PbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        for (tag, val) in &self.tags {
+            encoder.encode_tag(*tag)?;
+            encoder.encode_varint64(*val)?;
+        }
+        Ok(())
+    }
+
+    fn compute_fields_size(&self) -> usize {
+        self.tags
+            .iter()
+            .map(|(tag, val)| sizeof_tag(*tag) + micropb::size::sizeof_varint64(*val))
+            .sum()
+    }
+}
+
+impl AsyncFieldEncode for UnknownTags {
+    async fn encode_fields_async<W: micropb::AsyncPbWrite>(
+        &self,
+        encoder: &mut AsyncPbEncoder<W>,
+    ) -> Result<(), W::Error> {
+        for (tag, val) in &self.tags {
+            encoder.encode_tag(*tag).await?;
+            encoder.encode_varint64(*val).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Reader that returns `Pending` before every chunk and only returns 3 bytes at a time
+pub(crate) struct Trickle<'a> {
+    data: &'a [u8],
+    ready: bool,
+}
+
+impl<'a> Trickle<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, ready: false }
+    }
+}
+
+impl AsyncPbRead for Trickle<'_> {
+    type Error = Infallible;
+
+    async fn pb_read_chunk(&mut self) -> Result<&[u8], Self::Error> {
+        std::future::poll_fn(|cx| {
+            if self.ready {
+                Poll::Ready(())
+            } else {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+        Ok(&self.data[..self.data.len().min(3)])
+    }
+
+    fn pb_advance(&mut self, bytes: usize) {
+        self.ready = false;
+        PbRead::pb_advance(&mut self.data, bytes);
+    }
+}
+
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+/// Encode the message with both the sync and async encoders, make sure the outputs are the same,
+/// then decode the output with the async decoder and compare it with the original message
+fn roundtrip<M>(msg: &M) -> Vec<u8>
+where
+    M: MessageEncode
+        + AsyncMessageEncode
+        + AsyncMessageDecode
+        + Default
+        + PartialEq
+        + std::fmt::Debug,
+{
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let expected = encoder.into_writer();
+    assert_eq!(expected.len(), msg.compute_size());
+
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(msg.encode_async(&mut encoder)).unwrap();
+    let output = encoder.into_writer();
+    assert_eq!(output, expected);
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&output));
+    let decoded: M = block_on(decoder.decode_message(output.len())).unwrap();
+    assert_eq!(&decoded, msg);
+    output
+}
+
+fn basic_types() -> proto::basic_::BasicTypes {
+    let mut basic = proto::basic_::BasicTypes::default();
+    basic.set_int32_num(-1);
+    basic.set_int64_num(i64::MIN);
+    basic.set_uint32_num(300);
+    basic.set_uint64_num(u64::MAX);
+    basic.set_sint32_num(-150);
+    basic.set_sint64_num(i64::MAX);
+    basic.set_fixed32_num(0xDEADBEEF);
+    basic.set_fixed64_num(1);
+    basic.set_sfixed32_num(-2);
+    basic.set_sfixed64_num(-3);
+    basic.set_boolean(true);
+    basic.set_flt(-1.5);
+    basic.set_dbl(2.25);
+    basic.set_enumeration(proto::basic_::Enum::Two);
+    basic
+}
+
+#[test]
+fn basic() {
+    roundtrip(&proto::basic_::BasicTypes::default());
+    roundtrip(&basic_types());
+}
+
+#[test]
+fn nested() {
+    let mut nested = proto::nested_::Nested::default();
+    nested.set_basic(basic_types());
+    roundtrip(&nested);
+
+    let mut inner = proto::nested_::Nested_::InnerMsg::default();
+    inner.set_val(-20);
+    nested.inner = Some(proto::nested_::Nested_::Inner::InnerMsg(inner));
+    roundtrip(&nested);
+
+    nested.inner = Some(proto::nested_::Nested_::Inner::Scalar(true));
+    roundtrip(&nested);
+}
+
+#[test]
+fn collections() {
+    let mut list = proto::List::default();
+    let mut data = proto::Data::default();
+    data.set_s("abc".to_owned());
+    list.list.push(data);
+    list.list.push(proto::Data::default());
+    roundtrip(&list);
+
+    let numlist = proto::NumList {
+        list: vec![0, 1, 150, u32::MAX],
+    };
+    roundtrip(&numlist);
+
+    // Packed fields
+    let fixedlist = proto::FixedList {
+        list: vec![1, 2, 3],
+    };
+    let output = roundtrip(&fixedlist);
+    assert_eq!(output, [0x0A, 12, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]);
+
+    let map = proto::Map {
+        mapping: BTreeMap::from([
+            ("a".to_owned(), vec![0x01, 0x02]),
+            ("bc".to_owned(), vec![]),
+        ]),
+    };
+    roundtrip(&map);
+}
+
+#[test]
+fn recursive() {
+    let leaf = proto::Recursive {
+        of: Some(proto::Recursive_::Of::Num(-5)),
+        ..Default::default()
+    };
+    let mid = proto::Recursive {
+        multi: vec![leaf.clone(), proto::Recursive::default()],
+        of: Some(proto::Recursive_::Of::Rec(Box::new(leaf.clone()))),
+        ..Default::default()
+    };
+    let root = proto::Recursive {
+        recursive: Some(Box::new(mid)),
+        multi: vec![leaf],
+        ..Default::default()
+    };
+    roundtrip(&root);
+}
+
+#[test]
+fn unknown_handler() {
+    // Field 20 with varint 5, field 21 with a 1-byte LEN record, and field 1 with varint -1
+    let data = [
+        0xA0, 0x01, 0x05, 0xAA, 0x01, 0x01, 0xFF, 0x08, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0x01,
+    ];
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&data));
+    let basic: proto::basic_::BasicTypes = block_on(decoder.decode_message(data.len())).unwrap();
+    assert_eq!(basic.int32_num(), Some(&-1));
+    // The LEN record is skipped, since the handler rejects it
+    assert_eq!(basic._unknown.tags, [(Tag::from_parts(20, 0), 5)]);
+
+    let mut sync_decoder = PbDecoder::new(data.as_slice());
+    let sync_basic: proto::basic_::BasicTypes = sync_decoder.decode_message(data.len()).unwrap();
+    assert_eq!(basic, sync_basic);
+
+    roundtrip(&basic);
+}
+
+#[test]
+fn decode_error() {
+    // Truncated nested message
+    let data = [0x0A, 0x05, 0x08, 0x01];
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&data));
+    let res: Result<proto::nested_::Nested, _> = block_on(decoder.decode_message(data.len()));
+    assert_eq!(res, Err(micropb::DecodeError::UnexpectedEof));
+}
+
+#[test]
+fn encode_cache() {
+    let mut nested = proto_cached::nested_::Nested::default();
+    let mut basic = proto_cached::basic_::BasicTypes::default();
+    basic.set_sint64_num(-1000);
+    nested.set_basic(basic);
+    roundtrip(&nested);
+
+    let mut list = proto_cached::List::default();
+    list.list.push(proto_cached::Data::default());
+    roundtrip(&list);
+}
+
+#[test]
+fn push_decode() {
+    let mut nested = proto::nested_::Nested::default();
+    nested.set_basic(basic_types());
+    let mut encoder = PbEncoder::new(vec![]);
+    nested.encode_len_delimited(&mut encoder).unwrap();
+    let mut data = encoder.into_writer();
+    let msg_len = data.len();
+    // Bytes of the next message, which shouldn't be consumed
+    data.extend_from_slice(&[0x01, 0x02]);
+
+    for frag_size in [1, 2, 5, 7, data.len()] {
+        let mut decoded = proto::nested_::Nested::default();
+        let buffer = PushBuffer::<3>::new();
+        let mut consumed = 0;
+        {
+            let mut decoder = pin!(buffer.len_delimited_decoder(&mut decoded));
+            for frag in data.chunks(frag_size) {
+                match decoder.as_mut().push(frag).unwrap() {
+                    PushStatus::NeedMoreData(n) => {
+                        assert_eq!(n, frag.len());
+                        consumed += n;
+                    }
+                    PushStatus::Done(n) => {
+                        consumed += n;
+                        break;
+                    }
+                }
+            }
+            assert!(decoder.is_done());
+        }
+        assert_eq!(consumed, msg_len);
+        assert_eq!(decoded, nested);
+    }
+}
+
+#[test]
+fn max_depth() {
+    let mut msg = proto::Recursive::default();
+    for _ in 0..3 {
+        msg = proto::Recursive {
+            multi: vec![msg],
+            ..Default::default()
+        };
+    }
+    let data = roundtrip(&msg);
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&data));
+    decoder.max_depth = 2;
+    let res: Result<proto::Recursive, _> = block_on(decoder.decode_message(data.len()));
+    assert_eq!(res, Err(micropb::DecodeError::MaxDepth));
+}
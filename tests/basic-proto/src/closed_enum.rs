@@ -0,0 +1,212 @@
+use core::convert::Infallible;
+
+use micropb::{
+    json::{JsonDecode, JsonEncode, JsonError},
+    text::TextEnum,
+    AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder, AsyncPbEncoder, DecodeError,
+    MessageDecode, MessageEncode, PbDecoder, PbEncoder,
+};
+
+use crate::async_encode_decode::{block_on, Trickle};
+
+mod proto_heapless {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/closed_enum.heapless.rs"));
+}
+
+mod proto_alloc {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/closed_enum.alloc.rs"));
+}
+
+const PALETTE: &[u8] = &[
+    0x08, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, // primary = BLUE
+    0x10, 0x02, // secondary = GREEN
+    0x1A, 0x0B, 0x01, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0x01, // packed = [RED, BLUE]
+    0x20, 0x02, // unpacked = [GREEN]
+    0x2A, 0x04, 0x08, 0x01, 0x10, 0x01, // levels = {1: HIGH}
+    0x30, 0x01, // picked = RED
+];
+
+// Same fields as PALETTE, but with values that aren't part of the enums mixed in
+const PALETTE_UNKNOWN: &[u8] = &[
+    0x08, 0x05, // primary = 5
+    0x10, 0x01, // secondary = RED
+    0x1A, 0x03, 0x01, 0x07, 0x02, // packed = [RED, 7, GREEN]
+    0x20, 0x09, // unpacked = 9
+    0x20, 0x02, // unpacked = GREEN
+    0x2A, 0x04, 0x08, 0x01, 0x10, 0x07, // levels = {1: 7}
+    0x2A, 0x04, 0x08, 0x02, 0x10, 0x01, // levels = {2: HIGH}
+    0x30, 0x08, // picked = 8
+    0x42, 0x05, 0x0A, 0x01, b'a', 0x10, 0x07, // named = {"a": 7}
+];
+
+fn encode<M: MessageEncode>(msg: &M) -> Vec<u8> {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), msg.compute_size());
+    bytes
+}
+
+fn decode<M: MessageDecode + Default>(bytes: &[u8]) -> Result<M, DecodeError<Infallible>> {
+    let mut msg = M::default();
+    msg.decode(&mut PbDecoder::new(bytes), bytes.len())?;
+    Ok(msg)
+}
+
+#[test]
+fn enum_decl() {
+    use proto_heapless::closed_enum_::{Color, Level};
+
+    // Rust enums can be matched exhaustively
+    let num = |c: Color| match c {
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Blue => -3,
+    };
+    assert_eq!(num(Color::Blue), -3);
+    assert_eq!(Color::Crimson, Color::Red);
+    assert_eq!(Color::default(), Color::Red);
+    assert_eq!(Level::default(), Level::Low);
+    assert_eq!(core::mem::size_of::<Color>(), 1);
+    assert_eq!(i8::from(Color::Blue), -3);
+
+    assert_eq!(Color::try_from(2), Ok(Color::Green));
+    assert_eq!(Color::try_from(-3), Ok(Color::Blue));
+    assert_eq!(Color::try_from(0), Err(0));
+    assert_eq!(Color::try_from(4), Err(4));
+
+    assert_eq!(Color::_MAX_SIZE, 10);
+    assert_eq!(Level::_MAX_SIZE, 1);
+    assert_eq!(format!("{:?}", Color::Crimson), "Red");
+
+    assert_eq!(Color::Blue.text_name(), Some("BLUE"));
+    assert_eq!(Color::from_text_name("CRIMSON"), Some(Color::Red));
+    assert_eq!(Color::Blue.text_number(), -3);
+    assert_eq!(Color::from_text_number(2), Some(Color::Green));
+    assert_eq!(Color::from_text_number(3), None);
+}
+
+#[test]
+fn enum_names() {
+    use proto_heapless::closed_enum_::Color;
+
+    // Aliases are excluded from the values and only the first name is returned
+    assert_eq!(Color::VALUES, [Color::Red, Color::Green, Color::Blue]);
+    assert_eq!(Color::Crimson.as_str_name(), Some("RED"));
+    assert_eq!(Color::Blue.as_str_name(), Some("BLUE"));
+    assert_eq!(Color::from_str_name("CRIMSON"), Some(Color::Red));
+    assert_eq!(Color::from_str_name("Blue"), None);
+    assert_eq!("GREEN".parse::<Color>(), Ok(Color::Green));
+    assert_eq!("PURPLE".parse::<Color>(), Err(micropb::UnknownEnumName));
+}
+
+#[test]
+fn encode_decode() {
+    use proto_alloc::closed_enum_::{Color, Level, Palette, Palette_};
+
+    let mut msg = Palette {
+        primary: Some(Color::Blue),
+        packed: vec![Color::Red, Color::Blue],
+        unpacked: vec![Color::Green],
+        choice: Some(Box::new(Palette_::Choice::Picked(Color::Red))),
+        ..Default::default()
+    };
+    msg.set_secondary(Color::Green);
+    msg.levels.insert(1, Level::High);
+    assert_eq!(encode(&msg), PALETTE);
+
+    let decoded: Palette = decode(PALETTE).unwrap();
+    assert_eq!(decoded, msg);
+}
+
+#[test]
+fn unknown_values() {
+    use proto_heapless::closed_enum_::{Color, Level, Palette};
+
+    let msg: Palette = decode(PALETTE_UNKNOWN).unwrap();
+    // Fields with unknown values are left unset
+    assert_eq!(msg.primary(), None);
+    assert_eq!(msg.secondary(), Some(&Color::Red));
+    assert_eq!(msg.packed, [Color::Red, Color::Green]);
+    assert_eq!(msg.unpacked, [Color::Green]);
+    assert_eq!(msg.levels.len(), 1);
+    assert_eq!(msg.levels.get(&2), Some(&Level::High));
+    assert_eq!(msg.choice, None);
+    assert!(msg.named.is_empty());
+    // Unknown values are retained as unknown varint fields, including packed elements, and map
+    // entries with unknown values are retained in full
+    assert_eq!(
+        msg._unknown.as_bytes(),
+        &[
+            0x08, 0x05, 0x18, 0x07, 0x20, 0x09, 0x2A, 0x04, 0x08, 0x01, 0x10, 0x07, 0x30, 0x08,
+            0x42, 0x05, 0x0A, 0x01, b'a', 0x10, 0x07
+        ]
+    );
+
+    // Unknown values are dropped when the message has no unknown field handler
+    let msg: proto_alloc::closed_enum_::Palette = decode(PALETTE_UNKNOWN).unwrap();
+    assert_eq!(msg.primary, None);
+    assert_eq!(msg.packed.len(), 2);
+    assert_eq!(msg.choice, None);
+
+    // Unknown values don't take up capacity, but known values still do
+    assert_eq!(
+        decode::<Palette>(&[0x1A, 0x03, 0x01, 0x02, 0x01]),
+        Err(DecodeError::Capacity)
+    );
+}
+
+#[test]
+fn text_and_json() {
+    use proto_alloc::closed_enum_::{Color, Palette};
+
+    let mut msg = proto_heapless::closed_enum_::Palette::default();
+    msg.set_primary(proto_heapless::closed_enum_::Color::Blue);
+    assert_eq!(msg.to_string(), "primary: BLUE\n");
+
+    let msg = Palette {
+        primary: Some(Color::Crimson),
+        ..Default::default()
+    };
+    let mut json = String::new();
+    msg.write_json(&mut json).unwrap();
+    assert_eq!(json, r#"{"primary":"RED"}"#);
+
+    let mut parsed = Palette::default();
+    parsed.parse_json(br#"{"primary": -3}"#).unwrap();
+    assert_eq!(parsed.primary, Some(Color::Blue));
+    // Numbers that don't match any variant are rejected
+    assert_eq!(
+        parsed.parse_json(br#"{"primary": 4}"#),
+        Err(JsonError::InvalidNumber)
+    );
+}
+
+#[test]
+fn async_encode_decode() {
+    use proto_alloc::closed_enum_::{Color, Palette};
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(PALETTE));
+    let mut msg = Palette::default();
+    block_on(msg.decode_async(&mut decoder, PALETTE.len())).unwrap();
+    assert_eq!(msg.primary, Some(Color::Blue));
+    assert_eq!(msg.packed, [Color::Red, Color::Blue]);
+
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(msg.encode_async(&mut encoder)).unwrap();
+    assert_eq!(encoder.into_writer(), PALETTE);
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(PALETTE_UNKNOWN));
+    let mut msg = Palette::default();
+    block_on(msg.decode_async(&mut decoder, PALETTE_UNKNOWN.len())).unwrap();
+    assert_eq!(msg.primary, None);
+    assert_eq!(msg.secondary(), Some(&Color::Red));
+    assert_eq!(msg.packed, [Color::Red, Color::Green]);
+    assert_eq!(msg.levels.len(), 1);
+    assert_eq!(msg.choice, None);
+}
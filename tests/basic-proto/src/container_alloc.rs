@@ -3,7 +3,7 @@ use std::{
     mem::{size_of, size_of_val},
 };
 
-use micropb::{MessageDecode, MessageEncode, PbDecoder, PbEncoder};
+use micropb::{DecodeError, MessageDecode, MessageEncode, PbDecoder, PbEncoder};
 
 mod proto {
     #![allow(clippy::all)]
@@ -205,6 +205,46 @@ fn decode_map() {
     assert_eq!(map.mapping["ac"], &[0x02, 0x01, 0x02]);
 }
 
+#[test]
+fn decode_alloc_budget() {
+    // Each element of a non-packed repeated field is charged against the budget
+    let data = [0x0A, 0, 0x0A, 0, 0x0A, 0];
+    let mut list = proto::List::default();
+    let mut decoder = PbDecoder::new(data.as_slice());
+    decoder.alloc_budget = Some(size_of::<proto::Data>() * 2);
+    assert_eq!(
+        list.decode(&mut decoder, data.len()),
+        Err(DecodeError::BudgetExceeded)
+    );
+    assert_eq!(list.list.len(), 2);
+
+    let data = [0x08, 0x12, 0x08, 0x01];
+    let mut numlist = proto::NumList::default();
+    let mut decoder = PbDecoder::new(data.as_slice());
+    // Elements are configured as `u8`
+    decoder.alloc_budget = Some(size_of::<u8>() * 2);
+    numlist.decode(&mut decoder, data.len()).unwrap();
+    assert_eq!(decoder.alloc_budget, Some(0));
+
+    // Map entries are charged along with their strings and bytes
+    let data = [0x0A, 7, 0x0A, 2, b'a', b'c', 0x12, 1, 0x02];
+    let mut map = proto::Map::default();
+    let mut decoder = PbDecoder::new(data.as_slice());
+    decoder.alloc_budget = Some(1000);
+    map.decode(&mut decoder, data.len()).unwrap();
+    assert_eq!(
+        decoder.alloc_budget,
+        Some(1000 - 3 - size_of::<(String, Vec<u8>)>())
+    );
+
+    let mut decoder = PbDecoder::new(data.as_slice());
+    decoder.alloc_budget = Some(3);
+    assert_eq!(
+        map.decode(&mut decoder, data.len()),
+        Err(DecodeError::BudgetExceeded)
+    );
+}
+
 #[test]
 fn max_size() {
     assert_eq!(
@@ -370,6 +410,36 @@ macro_rules! encode_tests {
                 ]
             );
         }
+
+        #[test]
+        fn encode_to_vec_and_slice() {
+            let mut list = $mod::List::default();
+            list.list.push($mod::Data::default());
+            list.list[0].set_s(String::from("xyz"));
+            let expected = [0x0A, 5, 0x0A, 3, b'x', b'y', b'z'];
+            assert_eq!(list.encode_to_vec(), expected);
+            assert_eq!(
+                list.encode_len_delimited_to_vec(),
+                [[7].as_slice(), &expected].concat()
+            );
+
+            let mut buf = [0; 10];
+            assert_eq!(list.encode_to_slice(&mut buf).unwrap(), &expected);
+            assert_eq!(
+                list.encode_len_delimited_to_slice(&mut buf).unwrap(),
+                [[7].as_slice(), &expected].concat()
+            );
+            // Exact fit
+            assert_eq!(list.encode_to_slice(&mut buf[..7]).unwrap(), &expected);
+            assert_eq!(
+                list.encode_to_slice(&mut buf[..6]),
+                Err(micropb::BufferTooSmall)
+            );
+            assert_eq!(
+                list.encode_len_delimited_to_slice(&mut buf[..7]),
+                Err(micropb::BufferTooSmall)
+            );
+        }
     };
 }
 
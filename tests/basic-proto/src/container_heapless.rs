@@ -264,3 +264,170 @@ fn max_size() {
     let map_max_size = 8 * (1/* tags */ + 1 + (2/* kv tags */) + 5 + 4);
     assert_eq!(proto::Map::MAX_SIZE, Ok(map_max_size));
 }
+
+#[test]
+fn encode_to_array() {
+    use micropb::size::{max_encoded_size, max_len_delimited_size};
+
+    let mut data = proto::Data::default();
+    data.set_s(heapless::String::try_from("abc").unwrap());
+    data.set_b(heapless::Vec::from_slice(&[0x01, 0x02]).unwrap());
+    let expected = [0x0A, 3, b'a', b'b', b'c', 0x12, 2, 0x01, 0x02];
+
+    let (buf, len) = data
+        .encode_to_array::<{ max_encoded_size::<proto::Data>() }>()
+        .unwrap();
+    assert_eq!(buf.len(), 12);
+    assert_eq!(&buf[..len], &expected);
+
+    let (buf, len) = data
+        .encode_len_delimited_to_array::<{ max_len_delimited_size::<proto::Data>() }>()
+        .unwrap();
+    assert_eq!(buf.len(), 13);
+    assert_eq!(&buf[..len], [[9].as_slice(), &expected].concat());
+
+    // Larger arrays are fine too
+    let (buf, len): ([u8; 20], _) = data.encode_to_array().unwrap();
+    assert_eq!(&buf[..len], &expected);
+}
+
+#[test]
+fn len_delimited_messages() {
+    let mut first = proto::Data::default();
+    first.set_s(heapless::String::try_from("abc").unwrap());
+    let mut second = proto::Data::default();
+    second.set_b(heapless::Vec::from_slice(&[0x01, 0x02]).unwrap());
+    let mut data = vec![];
+    data.extend_from_slice(&first.encode_len_delimited_to_vec());
+    data.extend_from_slice(&second.encode_len_delimited_to_vec());
+    data.extend_from_slice(&proto::Data::default().encode_len_delimited_to_vec());
+
+    let mut decoder = PbDecoder::new(data.as_slice());
+    let msgs: Vec<proto::Data> = decoder
+        .len_delimited_messages()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        msgs,
+        [first.clone(), second.clone(), proto::Data::default()]
+    );
+    assert_eq!(decoder.bytes_read(), data.len());
+
+    // Reusing a message resets fields from the previous record
+    let mut decoder = PbDecoder::new(data.as_slice());
+    let mut iter = decoder.len_delimited_messages();
+    let mut msg = proto::Data::default();
+    assert_eq!(iter.next_into(&mut msg), Some(Ok(())));
+    assert_eq!(msg, first);
+    assert_eq!(iter.next_into(&mut msg), Some(Ok(())));
+    assert_eq!(msg, second);
+    assert!(msg.s().is_none());
+    assert_eq!(iter.next_into(&mut msg), Some(Ok(())));
+    assert_eq!(iter.next_into(&mut msg), None);
+
+    // Truncated trailing record
+    let data = &data[..data.len() - 3];
+    let mut decoder = PbDecoder::new(data);
+    let mut iter = decoder.len_delimited_messages::<proto::Data>();
+    assert_eq!(iter.next(), Some(Ok(first)));
+    assert_eq!(iter.next(), Some(Err(DecodeError::UnexpectedEof)));
+    assert_eq!(iter.next(), None);
+
+    // Empty stream
+    let mut decoder = PbDecoder::new([].as_slice());
+    assert!(decoder
+        .len_delimited_messages::<proto::Data>()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn framing() {
+    use micropb::framing::{CobsReader, CobsWriter, SlipReader, SlipWriter};
+    use micropb::PbEncoder;
+
+    let mut first = proto::Data::default();
+    first.set_s(heapless::String::try_from("a\0c").unwrap());
+    let mut second = proto::Data::default();
+    second.set_b(heapless::Vec::from_slice(&[0xC0, 0x00, 0xDB]).unwrap());
+    let msgs = [first, second, proto::Data::default()];
+
+    let mut stream = vec![];
+    for msg in &msgs {
+        let mut encoder = PbEncoder::new(CobsWriter::new(&mut stream));
+        msg.encode(&mut encoder).unwrap();
+        encoder.into_writer().finish().unwrap();
+    }
+    // Only the frame delimiters are zero
+    assert_eq!(stream.iter().filter(|&&b| b == 0).count(), msgs.len());
+    let mut reader = CobsReader::new(stream.as_slice());
+    for msg in &msgs {
+        let mut decoder = PbDecoder::new(&mut reader);
+        let mut output = proto::Data::default();
+        decoder.decode_until_eof(&mut output).unwrap();
+        assert_eq!(&output, msg);
+        reader.next_frame().unwrap();
+    }
+    assert!(reader.into_inner().is_empty());
+
+    let mut stream = vec![];
+    for msg in &msgs {
+        let mut encoder = PbEncoder::new(SlipWriter::new(&mut stream));
+        msg.encode(&mut encoder).unwrap();
+        encoder.into_writer().finish().unwrap();
+    }
+    let mut reader = SlipReader::new(stream.as_slice());
+    // Empty messages produce empty frames, which are skipped by SLIP
+    for msg in &msgs[..2] {
+        let mut decoder = PbDecoder::new(&mut reader);
+        let mut output = proto::Data::default();
+        decoder.decode_until_eof(&mut output).unwrap();
+        assert_eq!(&output, msg);
+        reader.next_frame().unwrap();
+    }
+    assert!(PbDecoder::new(&mut reader).is_eof().unwrap());
+}
+
+#[test]
+fn encode_decode_framed() {
+    use micropb::framing::{Crc16Ccitt, Crc32};
+    use micropb::PbEncoder;
+
+    let mut first = proto::Data::default();
+    first.set_s(heapless::String::try_from("abc").unwrap());
+    let mut second = proto::Data::default();
+    second.set_b(heapless::Vec::from_slice(&[0x01, 0x02]).unwrap());
+
+    let mut encoder = PbEncoder::new(vec![]);
+    first.encode_framed::<Crc32, _>(&mut encoder).unwrap();
+    second.encode_framed::<Crc32, _>(&mut encoder).unwrap();
+    let data = encoder.into_writer();
+    // Length prefix, message, and checksum
+    assert_eq!(data.len(), (1 + 5 + 4) + (1 + 4 + 4));
+    assert_eq!(&data[..6], &[5, 0x0A, 3, b'a', b'b', b'c']);
+
+    let mut decoder = PbDecoder::new(data.as_slice());
+    let mut msg = proto::Data::default();
+    msg.decode_framed::<Crc32, _>(&mut decoder).unwrap();
+    assert_eq!(msg, first);
+    let mut msg = proto::Data::default();
+    msg.decode_framed::<Crc32, _>(&mut decoder).unwrap();
+    assert_eq!(msg, second);
+    assert_eq!(decoder.bytes_read(), data.len());
+
+    // Wrong checksum algorithm
+    let mut decoder = PbDecoder::new(data.as_slice());
+    assert_eq!(
+        proto::Data::default().decode_framed::<Crc16Ccitt, _>(&mut decoder),
+        Err(DecodeError::ChecksumMismatch)
+    );
+
+    // Corrupted payload
+    let mut data = data;
+    data[4] = b'x';
+    let mut decoder = PbDecoder::new(data.as_slice());
+    assert_eq!(
+        proto::Data::default().decode_framed::<Crc32, _>(&mut decoder),
+        Err(DecodeError::ChecksumMismatch)
+    );
+}
@@ -1,13 +1,188 @@
+use micropb::{
+    extension::{Extendable, Extension},
+    AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder, AsyncPbEncoder, BufferTooSmall,
+    MessageDecode, MessageEncode, PbDecoder, PbEncoder,
+};
+
+use crate::async_encode_decode::{block_on, Trickle};
+
 mod proto {
     #![allow(clippy::all)]
     #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
     include!(concat!(env!("OUT_DIR"), "/extension.rs"));
 }
 
-// Extensions aren't actually supported, so just make sure things compile
+mod proto_heapless {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/extension.heapless.rs"));
+}
+
+mod proto_alloc {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/extension.alloc.rs"));
+}
+
+fn encode<M: MessageEncode>(msg: &M) -> Vec<u8> {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), msg.compute_size());
+    bytes
+}
+
+#[test]
+fn descriptors() {
+    use proto_heapless::ext_::{Payload_::NESTED, NUM};
+
+    assert_eq!(NUM::NUMBER, 126);
+    assert_eq!(NUM::FULL_NAME, "ext.num");
+    assert_eq!(NESTED::NUMBER, 1005);
+    assert_eq!(NESTED::FULL_NAME, "ext.Payload.nested");
+}
+
 #[test]
-fn dummy() {
+fn without_retention() {
+    // Extension descriptors are still generated, but the extendee drops extension fields
     let ext = proto::ext_::Extendee::default();
     assert!(!ext._has.dbl());
     let _: f64 = ext.dbl;
+    assert_eq!(proto::ext_::LABEL::NUMBER, 1000);
+
+    let bytes = [0x29, 0, 0, 0, 0, 0, 0, 0xF0, 0x3F, 0xF0, 0x07, 0x05];
+    let mut msg = proto::ext_::Extendee::default();
+    msg.decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len())
+        .unwrap();
+    assert_eq!(msg.dbl, 1.0);
+    assert_eq!(encode(&msg), &bytes[..9]);
+}
+
+#[test]
+fn decode_heapless() {
+    use proto_heapless::ext_::{Extendee, Kind, Payload_::KIND, DELTAS, IDS, LABEL, NUM};
+
+    let bytes = [
+        0xF0, 0x07, 0x05, // num = 5
+        0x29, 0, 0, 0, 0, 0, 0, 0xF0, 0x3F, // dbl = 1.0
+        0xC8, 0x3E, 0x03, // deltas = [-2]
+        0xD2, 0x3E, 0x04, 0x07, 0, 0, 0, // ids = [7]
+        0xC8, 0x3E, 0x04, // deltas = [-2, 2]
+        0xC2, 0x3E, 0x02, b'h', b'i', // label = "hi"
+        0xF0, 0x3E, 0x02, // kind = KIND_LARGE
+        0xD8, 0x04, 0x01, // unknown non-extension field
+    ];
+    let mut msg = Extendee::default();
+    msg.decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len())
+        .unwrap();
+    assert_eq!(msg.dbl, 1.0);
+    // Only fields within extension ranges are retained
+    assert_eq!(msg._extensions.len(), bytes.len() - 12);
+
+    assert!(msg.has_extension(NUM));
+    assert_eq!(msg.get_extension(NUM).unwrap(), Some(5));
+    assert_eq!(msg.get_extension(LABEL).unwrap().unwrap(), "hi");
+    // Repeated values are merged across all records of the field
+    assert_eq!(msg.get_extension(DELTAS).unwrap().unwrap(), [-2, 2]);
+    assert_eq!(msg.get_extension(IDS).unwrap().unwrap(), [7]);
+    assert_eq!(msg.get_extension(KIND).unwrap(), Some(Kind::Large));
+    assert!(!msg.has_extension(proto_heapless::ext_::PAYLOAD));
+    assert_eq!(msg.get_extension(proto_heapless::ext_::PAYLOAD), Ok(None));
+
+    // Extension fields are written out after the regular fields, in their original order
+    let mut expected = bytes[3..12].to_vec();
+    expected.extend_from_slice(&bytes[..3]);
+    expected.extend_from_slice(&bytes[12..bytes.len() - 3]);
+    assert_eq!(encode(&msg), expected);
+}
+
+#[test]
+fn set_heapless() {
+    use proto_heapless::ext_::{Extendee, Payload, Payload_::NESTED, LABEL, NUM, PAYLOADS};
+
+    let mut msg = Extendee::default();
+    msg.set_extension(NUM, &-1).unwrap();
+    msg.set_extension(LABEL, &"label".try_into().unwrap())
+        .unwrap();
+    assert_eq!(msg.get_extension(NUM).unwrap(), Some(-1));
+    assert_eq!(msg.get_extension(LABEL).unwrap().unwrap(), "label");
+
+    // Setting an extension again replaces the old value
+    msg.set_extension(NUM, &300).unwrap();
+    assert_eq!(msg.get_extension(NUM).unwrap(), Some(300));
+    assert_eq!(msg._extensions.iter().count(), 2);
+
+    let mut payload = Payload::default();
+    payload.set_code(3);
+    payload.flags.push(true).unwrap();
+    msg.set_extension(NESTED, &payload).unwrap();
+    assert_eq!(msg.get_extension(NESTED).unwrap(), Some(payload.clone()));
+
+    // Extension doesn't fit in the storage, so nothing is changed
+    let before = msg.clone();
+    let payloads = [payload.clone(), payload.clone()].into_iter().collect();
+    assert_eq!(msg.set_extension(PAYLOADS, &payloads), Err(BufferTooSmall));
+    assert_eq!(msg, before);
+
+    msg.clear_extension(NUM);
+    assert!(!msg.has_extension(NUM));
+    assert_eq!(msg.get_extension(NUM).unwrap(), None);
+    assert!(msg.has_extension(LABEL));
+
+    let bytes = encode(&msg);
+    let mut decoded = Extendee::default();
+    decoded
+        .decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len())
+        .unwrap();
+    assert_eq!(decoded, msg);
+    assert_eq!(decoded.get_extension(NESTED).unwrap(), Some(payload));
+}
+
+#[test]
+fn storage_capacity() {
+    use proto_heapless::ext_::Extendee;
+
+    // 33 bytes of extension data is over the capacity of the storage
+    let mut bytes = vec![0xC2, 0x3E, 30];
+    bytes.extend_from_slice(&[b'a'; 30]);
+    let mut msg = Extendee::default();
+    assert_eq!(
+        msg.decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len()),
+        Err(micropb::DecodeError::Capacity)
+    );
+}
+
+#[test]
+fn alloc() {
+    use proto_alloc::ext_::{Extendee, Payload, DELTAS, IDS, PAYLOAD, PAYLOADS};
+
+    let mut msg = Extendee::default();
+    msg.set_dbl(2.0);
+    msg.set_extension(IDS, &vec![1, 2, 3]).unwrap();
+    msg.set_extension(DELTAS, &vec![-1, 1]).unwrap();
+    let mut payload = Payload::default();
+    payload.set_code(9);
+    msg.set_extension(PAYLOAD, &payload).unwrap();
+    msg.set_extension(PAYLOADS, &vec![payload.clone(), Payload::default()])
+        .unwrap();
+
+    let bytes = encode(&msg);
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&bytes));
+    let mut decoded = Extendee::default();
+    block_on(decoded.decode_async(&mut decoder, bytes.len())).unwrap();
+    assert_eq!(decoded, msg);
+    assert_eq!(decoded.get_extension(IDS).unwrap().unwrap(), [1, 2, 3]);
+    assert_eq!(decoded.get_extension(DELTAS).unwrap().unwrap(), [-1, 1]);
+    assert_eq!(
+        decoded.get_extension(PAYLOAD).unwrap(),
+        Some(payload.clone())
+    );
+    assert_eq!(
+        decoded.get_extension(PAYLOADS).unwrap().unwrap(),
+        [payload, Payload::default()]
+    );
+
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(decoded.encode_async(&mut encoder)).unwrap();
+    assert_eq!(encoder.into_writer(), bytes);
 }
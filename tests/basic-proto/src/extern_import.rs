@@ -1,4 +1,6 @@
-use micropb::{DecodeError, MessageDecode, MessageEncode, PbDecoder, PbEncoder};
+use micropb::{
+    CapacityExceeded, DecodeError, MessageDecode, MessageEncode, MessageMerge, PbDecoder, PbEncoder,
+};
 
 mod proto {
     #![allow(clippy::all)]
@@ -38,6 +40,12 @@ impl MessageDecode for Empty {
     }
 }
 
+impl MessageMerge for Empty {
+    fn merge_from(&mut self, _other: &Self) -> Result<(), CapacityExceeded> {
+        Ok(())
+    }
+}
+
 #[test]
 #[allow(unused)]
 fn imported_types() {
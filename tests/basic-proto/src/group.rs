@@ -0,0 +1,176 @@
+use core::convert::Infallible;
+
+use micropb::{
+    extension::Extendable, AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder, AsyncPbEncoder,
+    DecodeError, MessageDecode, MessageEncode, PbDecoder, PbEncoder,
+};
+
+use crate::async_encode_decode::{block_on, Trickle};
+
+mod proto_heapless {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/group.heapless.rs"));
+}
+
+mod proto_alloc {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/group.alloc.rs"));
+}
+
+const CATALOG: &[u8] = &[
+    0x0B, // header start
+    0x12, 0x02, b'a', b'b', // title = "ab"
+    0x18, 0x01, // version = 1
+    0x0C, // header end
+    0x23, // item start
+    0x28, 0x02, // id = 2
+    0x33, // detail start
+    0x38, 0x01, // delta = -1
+    0x34, // detail end
+    0x24, // item end
+    0x40, 0x03, // count = 3
+    0x4B, // picked start
+    0x50, 0x04, // index = 4
+    0x4C, // picked end
+];
+
+fn encode<M: MessageEncode>(msg: &M) -> Vec<u8> {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), msg.compute_size());
+    bytes
+}
+
+fn decode<M: MessageDecode + Default>(bytes: &[u8]) -> Result<M, DecodeError<Infallible>> {
+    let mut msg = M::default();
+    msg.decode(&mut PbDecoder::new(bytes), bytes.len())?;
+    Ok(msg)
+}
+
+fn catalog() -> proto_heapless::group_::Catalog {
+    use proto_heapless::group_::{Catalog, Catalog_};
+
+    let mut header = Catalog_::Header::default();
+    header.set_title("ab".try_into().unwrap());
+    header.set_version(1);
+    let mut detail = Catalog_::Item_::Detail::default();
+    detail.set_delta(-1);
+    let mut item = Catalog_::Item::default();
+    item.set_id(2);
+    item.set_detail(detail);
+    let mut picked = Catalog_::Picked::default();
+    picked.set_index(4);
+
+    let mut msg = Catalog::default();
+    msg.set_header(header);
+    msg.item.push(item).unwrap();
+    msg.set_count(3);
+    msg.choice = Some(Catalog_::Choice::Picked(picked));
+    msg
+}
+
+#[test]
+fn encode_decode() {
+    let msg = catalog();
+    assert_eq!(encode(&msg), CATALOG);
+    assert_eq!(msg.compute_size(), CATALOG.len());
+    assert_eq!(
+        decode::<proto_heapless::group_::Catalog>(CATALOG).unwrap(),
+        msg
+    );
+}
+
+#[test]
+fn decode_errors() {
+    use proto_heapless::group_::Catalog;
+
+    // End tag doesn't match the open group
+    assert_eq!(
+        decode::<Catalog>(&[0x0B, 0x18, 0x01, 0x24]),
+        Err(DecodeError::UnexpectedEndGroup)
+    );
+    // End tag outside of any group
+    assert_eq!(
+        decode::<Catalog>(&[0x40, 0x03, 0x0C]),
+        Err(DecodeError::UnexpectedEndGroup)
+    );
+    // Input ends before the group is closed
+    assert_eq!(
+        decode::<Catalog>(&[0x0B, 0x18, 0x01]),
+        Err(DecodeError::UnexpectedEof)
+    );
+
+    // Groups count towards the nesting depth just like regular sub-messages
+    let mut msg = Catalog::default();
+    let mut decoder = PbDecoder::new(CATALOG);
+    decoder.max_depth = 1;
+    assert_eq!(
+        msg.decode(&mut decoder, CATALOG.len()),
+        Err(DecodeError::MaxDepth)
+    );
+}
+
+#[test]
+fn unknown_groups() {
+    use proto_heapless::group_::CatalogV0;
+
+    // The older schema keeps the groups it doesn't know about, end tags included
+    let msg: CatalogV0 = decode(CATALOG).unwrap();
+    assert_eq!(msg.count, 3);
+    assert_eq!(msg._unknown.len(), CATALOG.len() - 2);
+
+    let mut expected = vec![0x40, 0x03];
+    expected.extend_from_slice(&CATALOG[..16]);
+    expected.extend_from_slice(&CATALOG[18..]);
+    assert_eq!(encode(&msg), expected);
+
+    // Re-encoded groups are readable by the newer schema
+    assert_eq!(
+        decode::<proto_heapless::group_::Catalog>(&expected).unwrap(),
+        catalog()
+    );
+}
+
+#[test]
+fn extension_group() {
+    use proto_alloc::group_::{Catalog, Note, NOTE};
+
+    let mut note = Note::default();
+    note.set_text("hi".to_owned());
+    let mut msg = Catalog::default();
+    msg.set_count(1);
+    msg.set_extension(NOTE, &note).unwrap();
+    assert_eq!(
+        encode(&msg),
+        [0x40, 0x01, 0xA3, 0x06, 0x0A, 0x02, b'h', b'i', 0xA4, 0x06]
+    );
+
+    let bytes = encode(&msg);
+    let decoded: Catalog = decode(&bytes).unwrap();
+    assert_eq!(decoded.get_extension(NOTE).unwrap(), Some(note));
+}
+
+#[test]
+fn async_encode_decode() {
+    use proto_alloc::group_::Catalog;
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(CATALOG));
+    let mut msg = Catalog::default();
+    block_on(msg.decode_async(&mut decoder, CATALOG.len())).unwrap();
+    assert_eq!(msg.header().unwrap().title().unwrap(), "ab");
+    assert_eq!(msg.item[0].detail().unwrap().delta, -1);
+
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(msg.encode_async(&mut encoder)).unwrap();
+    assert_eq!(encoder.into_writer(), CATALOG);
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&[0x23, 0x28, 0x02, 0x0C]));
+    let mut msg = Catalog::default();
+    assert_eq!(
+        block_on(msg.decode_async(&mut decoder, 4)),
+        Err(DecodeError::UnexpectedEndGroup)
+    );
+}
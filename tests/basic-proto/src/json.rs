@@ -0,0 +1,224 @@
+use micropb::json::{JsonDecode, JsonDecoder, JsonEncode, JsonError};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/json.rs"));
+}
+
+mod proto3 {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/json_proto3.rs"));
+}
+
+use proto::basic_::{BasicTypes, Enum};
+use proto::nested_::{Nested, Nested_};
+
+fn to_json<M: JsonEncode>(msg: &M) -> String {
+    let mut output = String::new();
+    msg.write_json(&mut output).unwrap();
+    output
+}
+
+#[test]
+fn implicit_presence() {
+    let mut msg = proto3::NonOptional::default();
+    assert_eq!(to_json(&msg), "{}");
+
+    msg.int32_num = -1;
+    msg.int64_num = 2;
+    msg.uint64_num = u64::MAX;
+    msg.sfixed64_num = -3;
+    msg.flt = 1.5;
+    msg.dbl = -0.25;
+    msg.enumeration = proto3::Enum::Two;
+    msg.st = "a\"b".try_into().unwrap();
+    msg.bt = heapless::Vec::from_slice(&[0xFF, 0, 1]).unwrap();
+    let json = to_json(&msg);
+    assert_eq!(
+        json,
+        r#"{"int32Num":-1,"int64Num":"2","uint64Num":"18446744073709551615","sfixed64Num":"-3","flt":1.5,"dbl":-0.25,"enumeration":"ENUM_TWO","st":"a\"b","bt":"/wAB"}"#
+    );
+
+    let mut parsed = proto3::NonOptional::default();
+    parsed.parse_json(json.as_bytes()).unwrap();
+    assert_eq!(parsed, msg);
+
+    // Original field names, numbers, and nulls are accepted as well
+    let mut parsed = proto3::NonOptional::default();
+    parsed
+        .parse_json(
+            br#"{"int64_num": 7, "uint32Num": "8", "dbl": "-Infinity", "enumeration": 1, "st": null}"#,
+        )
+        .unwrap();
+    assert_eq!(parsed.int64_num, 7);
+    assert_eq!(parsed.uint32_num, 8);
+    assert_eq!(parsed.dbl, f64::NEG_INFINITY);
+    assert_eq!(parsed.enumeration, proto3::Enum::One);
+    assert!(parsed.st.is_empty());
+}
+
+#[test]
+fn explicit_presence() {
+    let mut msg = proto3::Optional::default();
+    msg.set_int32_num(0)
+        .set_boolean(false)
+        .set_st("".try_into().unwrap());
+    assert_eq!(to_json(&msg), r#"{"int32Num":0,"boolean":false,"st":""}"#);
+
+    let mut parsed = proto3::Optional::default();
+    parsed.parse_json(to_json(&msg).as_bytes()).unwrap();
+    assert_eq!(parsed, msg);
+
+    let basic = BasicTypes {
+        int32_num: None,
+        ..Default::default()
+    };
+    assert_eq!(to_json(&basic), "{}");
+    let mut basic = BasicTypes {
+        int32_num: Some(4),
+        ..Default::default()
+    };
+    basic.set_enumeration(Enum(9)).set_fixed64_num(1);
+    assert_eq!(
+        to_json(&basic),
+        r#"{"int32Num":4,"fixed64Num":"1","enumeration":9}"#
+    );
+}
+
+#[test]
+fn nested_round_trip() {
+    let mut inner = Nested_::InnerMsg::default();
+    inner.set_val(4);
+    let mut nested = Nested {
+        inner: Some(Nested_::Inner::InnerMsg(Box::new(inner))),
+        ..Default::default()
+    };
+    nested.set_basic(BasicTypes {
+        int32_num: None,
+        ..Default::default()
+    });
+
+    let json = to_json(&nested);
+    assert_eq!(json, r#"{"basic":{},"innerMsg":{"val":4}}"#);
+    let mut parsed = Nested::default();
+    parsed.parse_json(json.as_bytes()).unwrap();
+    assert_eq!(parsed.basic().unwrap().int32_num, None);
+    assert_eq!(parsed.inner, nested.inner);
+
+    // Setting another oneof field replaces the variant
+    parsed.parse_json(br#"{"scalar": true}"#).unwrap();
+    assert_eq!(parsed.inner, Some(Nested_::Inner::Scalar(true)));
+    assert_eq!(to_json(&parsed), r#"{"basic":{},"scalar":true}"#);
+}
+
+#[test]
+fn collections() {
+    let mut list = proto::List::default();
+    list.parse_json(br#"{"list": [{"s": "ab", "b": "AQI="}, {"s": "c"}]}"#)
+        .unwrap();
+    assert_eq!(list.list.len(), 2);
+    assert_eq!(list.list[0].s().unwrap().as_str(), "ab");
+    assert_eq!(list.list[0].b().unwrap().as_slice(), &[1, 2]);
+    assert_eq!(list.list[1].s().unwrap().as_str(), "c");
+    assert_eq!(
+        to_json(&list),
+        r#"{"list":[{"s":"ab","b":"AQI="},{"s":"c"}]}"#
+    );
+
+    let mut nums = proto::NumList::default();
+    assert_eq!(to_json(&nums), "{}");
+    nums.parse_json(br#"{"list": [1, "2"]}"#).unwrap();
+    assert_eq!(nums.list, [1, 2]);
+    assert_eq!(to_json(&nums), r#"{"list":[1,2]}"#);
+
+    let mut map = proto::Map::default();
+    assert_eq!(to_json(&map), "{}");
+    map.parse_json(br#"{"mapping": {"a": "eHl6", "b!": ""}}"#)
+        .unwrap();
+    assert_eq!(map.mapping.get("a").unwrap().as_slice(), b"xyz");
+    assert!(map.mapping.get("b!").unwrap().is_empty());
+    assert_eq!(to_json(&map), r#"{"mapping":{"a":"eHl6","b!":""}}"#);
+}
+
+#[test]
+fn capacity_errors() {
+    let mut data = proto::Data::default();
+    assert_eq!(
+        data.parse_json(br#"{"s": "abcd"}"#),
+        Err(JsonError::Capacity)
+    );
+    assert_eq!(
+        data.parse_json(br#"{"b": "AAECAwQF"}"#),
+        Err(JsonError::Capacity)
+    );
+
+    let mut nums = proto::NumList::default();
+    assert_eq!(
+        nums.parse_json(br#"{"list": [1, 2, 3]}"#),
+        Err(JsonError::Capacity)
+    );
+    let mut map = proto::Map::default();
+    assert_eq!(
+        map.parse_json(br#"{"mapping": {"a": "", "b": "", "c": ""}}"#),
+        Err(JsonError::Capacity)
+    );
+
+    let mut buf = heapless::Vec::<u8, 8>::new();
+    let mut list = proto::List::default();
+    list.parse_json(br#"{"list": [{"s": "abc"}]}"#).unwrap();
+    assert!(list.encode_json_pb(&mut buf).is_err());
+    let mut buf = heapless::Vec::<u8, 32>::new();
+    list.encode_json_pb(&mut buf).unwrap();
+    assert_eq!(buf, br#"{"list":[{"s":"abc"}]}"#);
+}
+
+#[test]
+fn parse_errors() {
+    let mut basic = BasicTypes::default();
+    let mut decoder = JsonDecoder::new(br#"{"int32Num": 1, "bogus": {"a": [2]}, "dbl": 3}"#);
+    assert_eq!(
+        decoder.decode_message(&mut basic),
+        Err(JsonError::UnknownField)
+    );
+    assert_eq!(decoder.position(), 24);
+
+    let mut decoder = JsonDecoder::new(br#"{"int32Num": 1, "bogus": {"a": [2]}, "dbl": 3}"#);
+    decoder.ignore_unknown_fields = true;
+    decoder.decode_message(&mut basic).unwrap();
+    assert_eq!(basic.dbl(), Some(&3.0));
+
+    assert_eq!(
+        basic.parse_json(br#"{"enumeration": "ENUM_THREE"}"#),
+        Err(JsonError::UnknownEnumValue)
+    );
+    assert_eq!(
+        basic.parse_json(br#"{"uint32Num": -1}"#),
+        Err(JsonError::InvalidNumber)
+    );
+    assert_eq!(
+        basic.parse_json(br#"{"boolean": "true"}"#),
+        Err(JsonError::Syntax)
+    );
+
+    let mut nested = Nested::default();
+    assert_eq!(
+        nested.parse_json(br#"{"basic": {"#),
+        Err(JsonError::UnexpectedEof)
+    );
+}
+
+#[test]
+fn single_oneof() {
+    let mut oneof = proto::SingleOneof::default();
+    assert_eq!(to_json(&oneof), "{}");
+    oneof
+        .parse_json(br#"{"innerMsg": {"val": 1}, "inner_msg": {"val2": 2}}"#)
+        .unwrap();
+    assert_eq!(to_json(&oneof), r#"{"innerMsg":{"val":1,"val2":2}}"#);
+    oneof
+        .parse_json(br#"{"innerEnum": "INNER_ENUM_VAL"}"#)
+        .unwrap();
+    assert_eq!(to_json(&oneof), r#"{"innerEnum":"INNER_ENUM_VAL"}"#);
+}
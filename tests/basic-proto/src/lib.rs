@@ -1,8 +1,12 @@
 extern crate alloc;
 
+#[cfg(test)]
+mod async_encode_decode;
 #[cfg(test)]
 mod boxed_and_option;
 #[cfg(test)]
+mod closed_enum;
+#[cfg(test)]
 mod conflicting_names;
 #[cfg(test)]
 mod container_alloc;
@@ -25,22 +29,30 @@ mod files_with_same_package;
 #[cfg(test)]
 mod fixed_string_and_bytes;
 #[cfg(test)]
+mod group;
+#[cfg(test)]
 mod implicit_presence;
 #[cfg(test)]
 mod int_type;
 #[cfg(test)]
+mod json;
+#[cfg(test)]
 mod keyword_fields;
 #[cfg(test)]
 mod large_field_nums;
 #[cfg(test)]
 mod lifetime_fields;
 #[cfg(test)]
+mod merge;
+#[cfg(test)]
 mod minimal_accessors;
 #[cfg(test)]
 mod no_config;
 #[cfg(test)]
 mod no_suffix;
 #[cfg(test)]
+mod open_enum;
+#[cfg(test)]
 mod recursive;
 #[cfg(test)]
 mod single_oneof;
@@ -49,4 +61,8 @@ mod skip;
 #[cfg(test)]
 mod static_lifetime_fields;
 #[cfg(test)]
+mod text_format;
+#[cfg(test)]
+mod unknown_fields;
+#[cfg(test)]
 mod with_config_file;
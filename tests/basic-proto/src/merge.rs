@@ -0,0 +1,224 @@
+use micropb::{CapacityExceeded, MessageDecode, MessageEncode, MessageMerge, PbDecoder, PbEncoder};
+
+mod proto_alloc {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/merge.alloc.rs"));
+}
+
+mod proto_heapless {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/merge.heapless.rs"));
+}
+
+use proto_alloc::merge_::{Choice, Limits, Settings, Settings_};
+
+fn limits(min: u32, max: u32) -> Limits {
+    Limits {
+        min,
+        max,
+        ..Default::default()
+    }
+}
+
+fn base() -> Settings {
+    let mut msg = Settings {
+        name: "base".to_owned(),
+        level: 3,
+        enabled: true,
+        ports: vec![80],
+        ranges: vec![limits(0, 1)],
+        source: Some(Settings_::Source::InlineLimits(Box::new(limits(2, 3)))),
+        ..Default::default()
+    };
+    msg.set_timeout(10).set_limits(limits(1, 5));
+    msg.zones.insert("a".to_owned(), limits(1, 2));
+    msg.zones.insert("b".to_owned(), limits(3, 4));
+    msg.counters.insert("x".to_owned(), 1);
+    msg
+}
+
+#[test]
+fn scalars_and_messages() {
+    let mut msg = base();
+    // Default values of implicit-presence fields are treated as absent
+    let mut overlay = Settings {
+        name: "over".to_owned(),
+        enabled: false,
+        retries: Some(2),
+        ..Default::default()
+    };
+    overlay
+        .set_limits(limits(0, 9))
+        .set_backup_limits(limits(4, 0));
+    msg.merge_from(&overlay).unwrap();
+
+    assert_eq!(msg.name, "over");
+    assert_eq!(msg.level, 3);
+    assert!(msg.enabled);
+    assert_eq!(msg.timeout(), Some(&10));
+    assert_eq!(msg.retries(), Some(&2));
+    assert_eq!(msg.limits(), Some(&limits(1, 9)));
+    assert_eq!(msg.backup_limits(), Some(&limits(4, 0)));
+
+    // Stale data in a message that isn't present isn't merged
+    let mut msg = Settings {
+        limits: limits(7, 7),
+        ..Default::default()
+    };
+    msg.merge_from(&overlay).unwrap();
+    assert_eq!(msg.limits(), Some(&limits(0, 9)));
+
+    // Merging an empty message changes nothing
+    let mut msg = base();
+    msg.merge_from(&Settings::default()).unwrap();
+    assert_eq!(msg, base());
+}
+
+#[test]
+fn repeated_and_maps() {
+    let mut msg = base();
+    let mut overlay = Settings {
+        ports: vec![443],
+        ranges: vec![limits(5, 6)],
+        ..Default::default()
+    };
+    overlay.zones.insert("b".to_owned(), limits(7, 0));
+    overlay.zones.insert("c".to_owned(), limits(8, 9));
+    overlay.counters.insert("x".to_owned(), 0);
+    msg.merge_from(&overlay).unwrap();
+
+    assert_eq!(msg.ports, &[80, 443]);
+    assert_eq!(msg.ranges, &[limits(0, 1), limits(5, 6)]);
+    assert_eq!(msg.zones.len(), 3);
+    assert_eq!(msg.zones["a"], limits(1, 2));
+    // Map values are replaced rather than merged
+    assert_eq!(msg.zones["b"], limits(7, 0));
+    assert_eq!(msg.zones["c"], limits(8, 9));
+    assert_eq!(msg.counters["x"], 0);
+}
+
+#[test]
+fn oneofs() {
+    let mut msg = base();
+    let overlay = Settings {
+        source: Some(Settings_::Source::InlineLimits(Box::new(limits(0, 8)))),
+        ..Default::default()
+    };
+    // Same message variant is merged
+    msg.merge_from(&overlay).unwrap();
+    assert_eq!(
+        msg.source,
+        Some(Settings_::Source::InlineLimits(Box::new(limits(2, 8))))
+    );
+
+    // Different variant is replaced
+    msg.merge_from(&Settings {
+        source: Some(Settings_::Source::Path("/etc".to_owned())),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(msg.source, Some(Settings_::Source::Path("/etc".to_owned())));
+    msg.merge_from(&overlay).unwrap();
+    assert_eq!(
+        msg.source,
+        Some(Settings_::Source::InlineLimits(Box::new(limits(0, 8))))
+    );
+
+    // Empty oneof changes nothing
+    msg.merge_from(&Settings::default()).unwrap();
+    assert_eq!(
+        msg.source,
+        Some(Settings_::Source::InlineLimits(Box::new(limits(0, 8))))
+    );
+}
+
+#[test]
+fn oneof_enum_msg() {
+    let mut choice = Choice::Limits(Box::new(limits(1, 0)));
+    choice
+        .merge_from(&Choice::Limits(Box::new(limits(0, 2))))
+        .unwrap();
+    assert_eq!(choice, Choice::Limits(Box::new(limits(1, 2))));
+
+    choice.merge_from(&Choice::None).unwrap();
+    assert_eq!(choice, Choice::Limits(Box::new(limits(1, 2))));
+
+    choice.merge_from(&Choice::Num(5)).unwrap();
+    assert_eq!(choice, Choice::Num(5));
+
+    let mut choice = Choice::None;
+    choice
+        .merge_from(&Choice::Limits(Box::new(limits(3, 4))))
+        .unwrap();
+    assert_eq!(choice, Choice::Limits(Box::new(limits(3, 4))));
+}
+
+#[test]
+fn same_as_decoding_concatenation() {
+    let mut overlay = Settings {
+        name: "over".to_owned(),
+        ports: vec![443],
+        retries: Some(2),
+        source: Some(Settings_::Source::InlineLimits(Box::new(limits(0, 8)))),
+        ..Default::default()
+    };
+    overlay.set_limits(limits(0, 9));
+    overlay.zones.insert("b".to_owned(), limits(7, 0));
+
+    let mut encoder = PbEncoder::new(vec![]);
+    base().encode(&mut encoder).unwrap();
+    overlay.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    let mut decoded = Settings::default();
+    decoded
+        .decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len())
+        .unwrap();
+
+    let mut merged = base();
+    merged.merge_from(&overlay).unwrap();
+    assert_eq!(merged, decoded);
+}
+
+#[test]
+fn unknown_fields() {
+    fn decode(bytes: &[u8]) -> Settings {
+        let mut msg = Settings::default();
+        msg.decode(&mut PbDecoder::new(bytes), bytes.len()).unwrap();
+        msg
+    }
+
+    // Retained unknown fields are appended
+    let mut msg = decode(&[0xF8, 0x07, 0x01]);
+    msg.merge_from(&decode(&[0xF8, 0x07, 0x02])).unwrap();
+    assert_eq!(
+        msg._unknown.as_bytes(),
+        &[0xF8, 0x07, 0x01, 0xF8, 0x07, 0x02]
+    );
+}
+
+#[test]
+fn capacity() {
+    use proto_heapless::merge_::Settings;
+
+    let mut msg = Settings::default();
+    msg.ports.push(1).unwrap();
+    msg.counters.insert("a".try_into().unwrap(), 1).unwrap();
+    msg.counters.insert("b".try_into().unwrap(), 2).unwrap();
+
+    // Existing keys are replaced without needing more space
+    let mut overlay = Settings::default();
+    overlay.counters.insert("b".try_into().unwrap(), 3).unwrap();
+    msg.merge_from(&overlay).unwrap();
+    assert_eq!(msg.counters.get("b"), Some(&3));
+
+    overlay.counters.insert("c".try_into().unwrap(), 4).unwrap();
+    assert_eq!(msg.merge_from(&overlay), Err(CapacityExceeded));
+
+    let mut overlay = Settings::default();
+    overlay.ports.push(2).unwrap();
+    msg.merge_from(&overlay).unwrap();
+    assert_eq!(msg.ports, [1, 2]);
+    assert_eq!(msg.merge_from(&overlay), Err(CapacityExceeded));
+}
@@ -33,6 +33,20 @@ fn enum_debug_print() {
     assert_eq!(format!("{:?}", proto::basic_::Enum(3)), "_Unknown(3)");
 }
 
+#[test]
+fn enum_names() {
+    use proto::basic_::Enum;
+
+    assert_eq!(Enum::VALUES, [Enum::Two, Enum::One, Enum::Zero]);
+    // Proto names keep the prefix that was stripped from the Rust names
+    assert_eq!(Enum::One.as_str_name(), Some("ENUM_ONE"));
+    assert_eq!(Enum(3).as_str_name(), None);
+    assert_eq!(Enum::from_str_name("ENUM_ZERO"), Some(Enum::Zero));
+    assert_eq!(Enum::from_str_name("Zero"), None);
+    assert_eq!("ENUM_TWO".parse::<Enum>(), Ok(Enum::Two));
+    assert_eq!("".parse::<Enum>(), Err(micropb::UnknownEnumName));
+}
+
 #[test]
 fn basic_msg() {
     let mut basic = proto::basic_::BasicTypes::default();
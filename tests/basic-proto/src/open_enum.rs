@@ -0,0 +1,177 @@
+use core::convert::Infallible;
+
+use micropb::{
+    json::JsonEncode, text::TextEnum, AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder,
+    AsyncPbEncoder, DecodeError, MessageDecode, MessageEncode, PbDecoder, PbEncoder,
+};
+
+use crate::async_encode_decode::{block_on, Trickle};
+
+mod proto_rust_enum {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/open_enum.rust_enum.rs"));
+}
+
+mod proto_newtype {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/open_enum.newtype.rs"));
+}
+
+use proto_rust_enum::open_enum_::{Drawing, Drawing_, Mode, Shape};
+
+fn encode<M: MessageEncode>(msg: &M) -> Vec<u8> {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), msg.compute_size());
+    bytes
+}
+
+fn decode<M: MessageDecode + Default>(bytes: &[u8]) -> Result<M, DecodeError<Infallible>> {
+    let mut msg = M::default();
+    msg.decode(&mut PbDecoder::new(bytes), bytes.len())?;
+    Ok(msg)
+}
+
+fn drawing() -> Drawing {
+    let mut msg = Drawing {
+        shape: Shape::Triangle,
+        shapes: vec![Shape::Square, Shape::Unknown(7), Shape::Circle],
+        choice: Some(Drawing_::Choice::Mode(Mode::_Unknown(3))),
+        current: Mode::Enabled,
+        ..Default::default()
+    };
+    msg.set_outline(Shape::Unknown(-9));
+    msg.named.insert("a".to_owned(), Shape::Square);
+    msg
+}
+
+#[test]
+fn enum_decl() {
+    use proto_newtype::open_enum_ as newtype;
+
+    // Rust enums can be matched exhaustively
+    let num = |s: Shape| match s {
+        Shape::Circle => 0,
+        Shape::Square => 1,
+        Shape::Triangle => -2,
+        Shape::Unknown(n) => n,
+    };
+    assert_eq!(num(Shape::Unknown(4)), 4);
+    assert_eq!(Shape::default(), Shape::Circle);
+    // Proto variant named UNKNOWN takes precedence over the catch-all variant
+    assert_eq!(Mode::default(), Mode::Unknown);
+    assert_eq!(Mode::Enabled, Mode::On);
+
+    assert_eq!(Shape::from(-2), Shape::Triangle);
+    assert_eq!(Shape::from(5), Shape::Unknown(5));
+    assert_eq!(i16::from(Shape::Square), 1);
+    assert_eq!(i16::from(Shape::Unknown(-7)), -7);
+    assert_eq!(i32::from(Mode::On), 1);
+    assert_eq!(Mode::from(2), Mode::_Unknown(2));
+
+    // Same max size and debug output as the newtype enums
+    assert_eq!(Shape::_MAX_SIZE, newtype::Shape::_MAX_SIZE);
+    assert_eq!(Mode::_MAX_SIZE, newtype::Mode::_MAX_SIZE);
+    assert_eq!(
+        format!("{:?}", Shape::Triangle),
+        format!("{:?}", newtype::Shape::Triangle)
+    );
+    assert_eq!(
+        format!("{:?}", Shape::Unknown(5)),
+        format!("{:?}", newtype::Shape(5))
+    );
+    assert_eq!(
+        format!("{:?}", Mode::Enabled),
+        format!("{:?}", newtype::Mode::Enabled)
+    );
+
+    assert_eq!(Shape::Triangle.text_name(), Some("SHAPE_TRIANGLE"));
+    assert_eq!(Shape::Unknown(5).text_name(), None);
+    assert_eq!(Mode::from_text_name("MODE_ENABLED"), Some(Mode::On));
+    assert_eq!(Shape::Unknown(5).text_number(), 5);
+    assert_eq!(Shape::from_text_number(1), Some(Shape::Square));
+    assert_eq!(Shape::from_text_number(1 << 20), None);
+}
+
+#[test]
+fn enum_names() {
+    assert_eq!(
+        Shape::VALUES,
+        [Shape::Circle, Shape::Square, Shape::Triangle]
+    );
+    assert_eq!(Mode::VALUES, [Mode::Unknown, Mode::On]);
+    assert_eq!(Shape::Triangle.as_str_name(), Some("SHAPE_TRIANGLE"));
+    assert_eq!(Mode::Enabled.as_str_name(), Some("MODE_ON"));
+    assert_eq!(Shape::Unknown(5).as_str_name(), None);
+    assert_eq!(Mode::from_str_name("MODE_ENABLED"), Some(Mode::On));
+    assert_eq!(Shape::from_str_name("5"), None);
+    assert_eq!("SHAPE_SQUARE".parse::<Shape>(), Ok(Shape::Square));
+    assert_eq!("SQUARE".parse::<Shape>(), Err(micropb::UnknownEnumName));
+}
+
+#[test]
+fn encode_decode() {
+    use proto_newtype::open_enum_ as newtype;
+
+    // Default values aren't encoded for implicit presence fields
+    assert!(encode(&Drawing::default()).is_empty());
+
+    let msg = drawing();
+    let bytes = encode(&msg);
+    assert_eq!(decode::<Drawing>(&bytes).unwrap(), msg);
+
+    // Wire format is the same as the newtype enums
+    let old: newtype::Drawing = decode(&bytes).unwrap();
+    assert_eq!(old.shape, newtype::Shape::Triangle);
+    assert_eq!(old.outline(), Some(&newtype::Shape(-9)));
+    assert_eq!(
+        old.shapes,
+        [
+            newtype::Shape::Square,
+            newtype::Shape(7),
+            newtype::Shape::Circle
+        ]
+    );
+    assert_eq!(old.named["a"], newtype::Shape::Square);
+    assert_eq!(
+        old.choice,
+        Some(newtype::Drawing_::Choice::Mode(newtype::Mode(3)))
+    );
+    assert_eq!(old.current, newtype::Mode::On);
+    assert_eq!(encode(&old), bytes);
+
+    // Unpacked elements are accepted too
+    let msg: Drawing = decode(&[0x18, 0x01, 0x18, 0x09]).unwrap();
+    assert_eq!(msg.shapes, [Shape::Square, Shape::Unknown(9)]);
+}
+
+#[test]
+fn text_and_json() {
+    let msg = Drawing {
+        shape: Shape::Unknown(3),
+        current: Mode::On,
+        ..Default::default()
+    };
+    assert_eq!(msg.to_string(), "shape: 3\ncurrent: MODE_ON\n");
+
+    let mut json = String::new();
+    msg.write_json(&mut json).unwrap();
+    assert_eq!(json, r#"{"shape":3,"current":"MODE_ON"}"#);
+}
+
+#[test]
+fn async_encode_decode() {
+    let msg = drawing();
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(msg.encode_async(&mut encoder)).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes, encode(&msg));
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&bytes));
+    let mut decoded = Drawing::default();
+    block_on(decoded.decode_async(&mut decoder, bytes.len())).unwrap();
+    assert_eq!(decoded, msg);
+}
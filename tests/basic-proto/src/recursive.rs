@@ -1,4 +1,4 @@
-use micropb::MessageEncode;
+use micropb::{DecodeError, MessageEncode, PbDecoder};
 use proto::Recursive;
 
 mod proto {
@@ -28,3 +28,110 @@ fn recursive_types() {
         Err("(.Recursive.recursive) cyclical reference")
     );
 }
+
+/// Encode a chain of `depth` nested messages, using `link` to nest each message in its parent
+fn nested_chain(depth: usize, link: fn(&mut Recursive, Recursive)) -> Vec<u8> {
+    let mut msg = Recursive::default();
+    for _ in 0..depth {
+        let mut parent = Recursive::default();
+        link(&mut parent, msg);
+        msg = parent;
+    }
+    let mut encoder = micropb::PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    encoder.into_writer()
+}
+
+#[test]
+fn max_depth() {
+    let links: [fn(&mut Recursive, Recursive); 3] = [
+        |p, m| p.recursive = Some(Box::new(m)),
+        |p, m| p.of = Some(proto::Recursive_::Of::Rec(Box::new(m))),
+        |p, m| p.multi.push(m),
+    ];
+
+    for link in links {
+        let data = nested_chain(5, link);
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.max_depth = 5;
+        assert!(decoder.decode_message::<Recursive>(data.len()).is_ok());
+
+        let mut decoder = PbDecoder::new(data.as_slice());
+        decoder.max_depth = 4;
+        assert_eq!(
+            decoder.decode_message::<Recursive>(data.len()).err(),
+            Some(DecodeError::MaxDepth)
+        );
+
+        // Default limit
+        let data = nested_chain(micropb::DEFAULT_MAX_DEPTH, link);
+        let mut decoder = PbDecoder::new(data.as_slice());
+        assert!(decoder.decode_message::<Recursive>(data.len()).is_ok());
+        let data = nested_chain(micropb::DEFAULT_MAX_DEPTH + 1, link);
+        let mut decoder = PbDecoder::new(data.as_slice());
+        assert_eq!(
+            decoder.decode_message::<Recursive>(data.len()).err(),
+            Some(DecodeError::MaxDepth)
+        );
+    }
+}
+
+#[test]
+fn depth_resets_between_fields() {
+    // Two sibling chains of depth 3 shouldn't add up to depth 6
+    let chain = nested_chain(3, |p, m| p.recursive = Some(Box::new(m)));
+    let mut data = vec![];
+    for _ in 0..2 {
+        data.push(0x22); // field 4 (multi), LEN
+        data.push(chain.len() as u8);
+        data.extend_from_slice(&chain);
+    }
+    let mut decoder = PbDecoder::new(data.as_slice());
+    decoder.max_depth = 4;
+    let msg: Recursive = decoder.decode_message(data.len()).unwrap();
+    assert_eq!(msg.multi.len(), 2);
+}
+
+#[test]
+fn detailed_error() {
+    let data = nested_chain(3, |p, m| p.recursive = Some(Box::new(m)));
+    let mut decoder = PbDecoder::new(data.as_slice());
+    decoder.max_depth = 2;
+    let err = decoder
+        .decode_message::<Recursive>(data.len())
+        .err()
+        .unwrap();
+    let err = decoder.detailed_error(err);
+    assert_eq!(err.error, DecodeError::MaxDepth);
+    assert_eq!(err.path.field_nums(), &[1, 1, 1]);
+    assert_eq!(err.offset, 5);
+
+    // Truncated input in a deeply nested field
+    let data = nested_chain(3, |p, m| p.multi.push(m));
+    let mut decoder = PbDecoder::new(&data[..data.len() - 1]);
+    let err = decoder
+        .decode_message::<Recursive>(data.len())
+        .err()
+        .unwrap();
+    let err = decoder.detailed_error(err);
+    assert_eq!(err.error, DecodeError::UnexpectedEof);
+    assert_eq!(err.path.field_nums(), &[4, 4, 4]);
+}
+
+#[test]
+fn decode_until_eof() {
+    let data = nested_chain(3, |p, m| p.multi.push(m));
+    let mut decoder = PbDecoder::new(data.as_slice());
+    let mut msg = Recursive::default();
+    decoder.decode_until_eof(&mut msg).unwrap();
+    assert_eq!(msg.multi[0].multi[0].multi.len(), 1);
+
+    // Input ending at a field boundary of a nested message is still truncated
+    for end in 1..data.len() {
+        let mut decoder = PbDecoder::new(&data[..end]);
+        assert_eq!(
+            decoder.decode_until_eof(&mut Recursive::default()),
+            Err(DecodeError::UnexpectedEof)
+        );
+    }
+}
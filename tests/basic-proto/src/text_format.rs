@@ -0,0 +1,172 @@
+use micropb::text::{TextDecode, TextDecoder, TextEnum, TextError};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/text_format.rs"));
+}
+
+use proto::basic_::{BasicTypes, Enum};
+use proto::nested_::{Nested, Nested_};
+
+#[test]
+fn enum_names() {
+    assert_eq!(Enum::Two.text_name(), Some("ENUM_TWO"));
+    assert_eq!(Enum(7).text_name(), None);
+    assert_eq!(Enum::from_text_name("ENUM_ONE"), Some(Enum::One));
+    assert_eq!(Enum::from_text_name("One"), None);
+    assert_eq!(Enum::from_text_number(-1), Some(Enum(-1)));
+    assert_eq!(Enum::from_text_number(1 << 40), None);
+}
+
+#[test]
+fn display_presence() {
+    // Only fields that are set get printed
+    let basic = BasicTypes {
+        int32_num: None,
+        ..Default::default()
+    };
+    assert_eq!(basic.to_string(), "");
+
+    let mut basic = BasicTypes {
+        int32_num: Some(-5),
+        ..Default::default()
+    };
+    basic
+        .set_uint64_num(u64::MAX)
+        .set_sint32_num(-3)
+        .set_boolean(false)
+        .set_flt(0.5)
+        .set_enumeration(Enum::Two);
+    assert_eq!(
+        basic.to_string(),
+        "int32_num: -5\n\
+         uint64_num: 18446744073709551615\n\
+         sint32_num: -3\n\
+         boolean: false\n\
+         flt: 0.5\n\
+         enumeration: ENUM_TWO\n"
+    );
+}
+
+#[test]
+fn nested_round_trip() {
+    let mut basic = BasicTypes {
+        int32_num: None,
+        ..Default::default()
+    };
+    basic.set_dbl(-1.25).set_enumeration(Enum(9));
+    let mut inner = Nested_::InnerMsg::default();
+    inner.set_val(4);
+    let mut nested = Nested {
+        inner: Some(Nested_::Inner::InnerMsg(Box::new(inner))),
+        ..Default::default()
+    };
+    nested.set_basic(basic);
+
+    let text = nested.to_string();
+    assert_eq!(
+        text,
+        "basic {\n  dbl: -1.25\n  enumeration: 9\n}\ninner_msg {\n  val: 4\n}\n"
+    );
+    let mut parsed = Nested {
+        basic: BasicTypes {
+            int32_num: None,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    parsed.parse_text(&text).unwrap();
+    assert_eq!(parsed, nested);
+
+    // Setting another oneof field replaces the variant
+    parsed.parse_text("enumeration: ENUM_ONE").unwrap();
+    assert_eq!(parsed.inner, Some(Nested_::Inner::Enumeration(Enum::One)));
+    assert_eq!(parsed.basic().unwrap().dbl(), Some(&-1.25));
+}
+
+#[test]
+fn parse_collections() {
+    let mut list = proto::List::default();
+    list.parse_text(r#"list { s: "ab" b: "\x01\x02" } list: < s: 'c' >"#)
+        .unwrap();
+    assert_eq!(list.list.len(), 2);
+    assert_eq!(list.list[0].s(), Some(&"ab".try_into().unwrap()));
+    assert_eq!(list.list[0].b().unwrap().as_slice(), &[1, 2]);
+    assert_eq!(list.list[1].s().unwrap().as_str(), "c");
+    assert_eq!(
+        list.to_string(),
+        "list {\n  s: \"ab\"\n  b: \"\\001\\002\"\n}\nlist {\n  s: \"c\"\n}\n"
+    );
+
+    let mut nums = proto::NumList::default();
+    nums.parse_text("list: [1, 0x2]").unwrap();
+    assert_eq!(nums.list, [1, 2]);
+
+    let mut map = proto::Map::default();
+    map.parse_text(r#"mapping { key: "a" value: "xyz" } mapping [{ key: "b" }]"#)
+        .unwrap();
+    assert_eq!(map.mapping.len(), 2);
+    assert_eq!(map.mapping.get("a").unwrap().as_slice(), b"xyz");
+    assert!(map.mapping.get("b").unwrap().is_empty());
+    assert_eq!(
+        map.to_string(),
+        "mapping {\n  key: \"a\"\n  value: \"xyz\"\n}\nmapping {\n  key: \"b\"\n  value: \"\"\n}\n"
+    );
+}
+
+#[test]
+fn capacity_errors() {
+    let mut data = proto::Data::default();
+    assert_eq!(data.parse_text(r#"s: "abcd""#), Err(TextError::Capacity));
+    assert_eq!(
+        data.parse_text(r#"b: "\000\001\002\003\004\005""#),
+        Err(TextError::Capacity)
+    );
+
+    let mut nums = proto::NumList::default();
+    assert_eq!(nums.parse_text("list: [1, 2, 3]"), Err(TextError::Capacity));
+    let mut strs = proto::StrList::default();
+    assert_eq!(strs.parse_text(r#"list: "abc""#), Err(TextError::Capacity));
+    let mut map = proto::Map::default();
+    assert_eq!(
+        map.parse_text(r#"mapping { key: "a" } mapping { key: "b" } mapping { key: "c" }"#),
+        Err(TextError::Capacity)
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let mut basic = BasicTypes::default();
+    let mut decoder = TextDecoder::new("int32_num: 1\nbogus: 2");
+    assert_eq!(
+        basic.decode_text(&mut decoder),
+        Err(TextError::UnknownField)
+    );
+    assert_eq!(decoder.position(), 18);
+
+    assert_eq!(
+        basic.parse_text("enumeration: ENUM_THREE"),
+        Err(TextError::UnknownEnumValue)
+    );
+    assert_eq!(
+        basic.parse_text("uint32_num: -1"),
+        Err(TextError::InvalidNumber)
+    );
+    assert_eq!(basic.parse_text("boolean: yes"), Err(TextError::Syntax));
+
+    let mut nested = Nested::default();
+    assert_eq!(nested.parse_text("basic {"), Err(TextError::UnexpectedEof));
+}
+
+#[test]
+fn single_oneof() {
+    let mut oneof = proto::SingleOneof::default();
+    assert_eq!(oneof.to_string(), "");
+    oneof
+        .parse_text("inner_msg { val: 1 } inner_msg { val2: 2 }")
+        .unwrap();
+    assert_eq!(oneof.to_string(), "inner_msg {\n  val: 1\n  val2: 2\n}\n");
+    oneof.parse_text("inner_enum: INNER_ENUM_VAL").unwrap();
+    assert_eq!(oneof.to_string(), "inner_enum: INNER_ENUM_VAL\n");
+}
@@ -0,0 +1,140 @@
+use micropb::{
+    AsyncMessageDecode, AsyncMessageEncode, AsyncPbDecoder, AsyncPbEncoder, DecodeError,
+    MessageDecode, MessageEncode, PbDecoder, PbEncoder, WIRE_TYPE_I32, WIRE_TYPE_I64,
+    WIRE_TYPE_LEN, WIRE_TYPE_VARINT,
+};
+
+use crate::async_encode_decode::{block_on, Trickle};
+
+mod proto_heapless {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/unknown_fields.heapless.rs"));
+}
+
+mod proto_alloc {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/unknown_fields.alloc.rs"));
+}
+
+fn new_msg() -> proto_alloc::unknown_fields_::New {
+    let inner = proto_alloc::unknown_fields_::NewInner {
+        name: "abc".to_owned(),
+        extra: 7,
+        ..Default::default()
+    };
+    let mut msg = proto_alloc::unknown_fields_::New {
+        id: 12,
+        delta: -300,
+        fixed: 0xDEADBEEF,
+        ratio: 0.5,
+        blob: b"ab".to_vec(),
+        nums: vec![1, 2, 3],
+        ..Default::default()
+    };
+    msg.set_inner(inner);
+    msg
+}
+
+fn encode<M: MessageEncode>(msg: &M) -> Vec<u8> {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), msg.compute_size());
+    bytes
+}
+
+#[test]
+fn forward_heapless() {
+    let bytes = encode(&new_msg());
+
+    let mut decoder = PbDecoder::new(bytes.as_slice());
+    let mut old = proto_heapless::unknown_fields_::Old::default();
+    old.decode(&mut decoder, bytes.len()).unwrap();
+    assert_eq!(old.id, 12);
+    assert_eq!(old.inner().unwrap().name, "abc");
+    assert_eq!(old.inner().unwrap()._unknown.as_bytes(), &[0x10, 0x07]);
+
+    let fields: Vec<_> = old
+        ._unknown
+        .iter()
+        .map(|(tag, value)| (tag.field_num(), tag.wire_type(), value))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            (3, WIRE_TYPE_VARINT, &[0xD7, 0x04][..]),
+            (4, WIRE_TYPE_I32, &0xDEADBEEFu32.to_le_bytes()[..]),
+            (5, WIRE_TYPE_I64, &0.5f64.to_le_bytes()[..]),
+            (6, WIRE_TYPE_LEN, b"ab"),
+            (7, WIRE_TYPE_LEN, &[1, 2, 3]),
+        ]
+    );
+
+    // Re-encoding the old message emits the unknown fields as they were on the wire
+    assert_eq!(encode(&old), bytes);
+    let mut decoder = PbDecoder::new(bytes.as_slice());
+    let mut new = proto_heapless::unknown_fields_::New::default();
+    new.decode(&mut decoder, bytes.len()).unwrap();
+    assert!(new._unknown.is_empty());
+    assert_eq!(new.delta, -300);
+    assert_eq!(new.inner().unwrap().extra, 7);
+
+    old._unknown.clear();
+    assert!(old._unknown.is_empty());
+    assert_eq!(old._unknown.iter().count(), 0);
+}
+
+#[test]
+fn heapless_capacity() {
+    let mut msg = new_msg();
+    msg.blob = vec![0xAA; 40];
+    let bytes = encode(&msg);
+
+    let mut decoder = PbDecoder::new(bytes.as_slice());
+    let mut old = proto_heapless::unknown_fields_::Old::default();
+    assert_eq!(
+        old.decode(&mut decoder, bytes.len()),
+        Err(DecodeError::Capacity)
+    );
+
+    // The blob is dropped, but the fields around it are still retained
+    let mut decoder = PbDecoder::new(bytes.as_slice());
+    decoder.ignore_repeated_cap_err = true;
+    let mut old = proto_heapless::unknown_fields_::Old::default();
+    old.decode(&mut decoder, bytes.len()).unwrap();
+    let nums: Vec<_> = old
+        ._unknown
+        .iter()
+        .map(|(tag, _)| tag.field_num())
+        .collect();
+    assert_eq!(nums, [3, 4, 5, 7]);
+}
+
+#[test]
+fn forward_alloc() {
+    let mut msg = new_msg();
+    msg.blob = vec![0xAA; 300];
+    let bytes = encode(&msg);
+
+    let mut decoder = PbDecoder::new(bytes.as_slice());
+    let mut old = proto_alloc::unknown_fields_::Old::default();
+    old.decode(&mut decoder, bytes.len()).unwrap();
+    assert_eq!(old._unknown.iter().nth(3).unwrap().1, &[0xAA; 300][..]);
+    assert_eq!(encode(&old), bytes);
+}
+
+#[test]
+fn forward_async() {
+    let bytes = encode(&new_msg());
+
+    let mut decoder = AsyncPbDecoder::new(Trickle::new(&bytes));
+    let mut old = proto_alloc::unknown_fields_::Old::default();
+    block_on(old.decode_async(&mut decoder, bytes.len())).unwrap();
+    assert_eq!(old.id, 12);
+
+    let mut encoder = AsyncPbEncoder::new(vec![]);
+    block_on(old.encode_async(&mut encoder)).unwrap();
+    assert_eq!(encoder.into_writer(), bytes);
+}
@@ -11,8 +11,46 @@ fn scoping() {
             std::env::var("OUT_DIR").unwrap() + "/scoping.rs",
         )
         .unwrap();
+
+    let mut generator = Generator::new();
+    generator.use_container_alloc().configure(
+        ".",
+        Config::new()
+            .optional_repr(OptionalRepr::Option)
+            .closed_enum_as_rust_enum(true),
+    );
+    generator
+        .compile_protos(
+            &["proto/scoping.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/scoping_closed.rs",
+        )
+        .unwrap();
+}
+
+fn delimited() {
+    let mut generator = Generator::new();
+    generator.use_container_alloc();
+    generator
+        .compile_protos(
+            &["proto/delimited.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/delimited.rs",
+        )
+        .unwrap();
+}
+
+fn utf8() {
+    let mut generator = Generator::new();
+    generator.use_container_alloc().text_format(true).json(true);
+    generator
+        .compile_protos(
+            &["proto/utf8.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/utf8.rs",
+        )
+        .unwrap();
 }
 
 fn main() {
     scoping();
+    delimited();
+    utf8();
 }
@@ -0,0 +1,49 @@
+use micropb::{MessageDecode, MessageEncode, PbDecoder, PbEncoder};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/delimited.rs"));
+}
+
+use proto::delimited_::{Point, Shape, Shape_};
+
+fn point(x: Option<i32>, y: Option<i32>) -> Point {
+    let mut point = Point::default();
+    if let Some(x) = x {
+        point.set_x(x);
+    }
+    if let Some(y) = y {
+        point.set_y(y);
+    }
+    point
+}
+
+#[test]
+fn encode_decode() {
+    let mut shape = Shape::default();
+    shape.set_origin(point(Some(1), None));
+    shape.corners.push(point(None, Some(2)));
+    shape.set_center(point(Some(3), None));
+    shape.labels.insert(7, point(Some(1), None));
+    shape.extra = Some(Shape_::Extra::Anchor(Point::default()));
+
+    let bytes = [
+        0x0B, 0x08, 0x01, 0x0C, // origin, encoded as a group
+        0x13, 0x10, 0x02, 0x14, // corners, encoded as groups
+        0x1A, 0x02, 0x08, 0x03, // center is overridden to be length-prefixed
+        0x22, 0x06, 0x08, 0x07, 0x12, 0x02, 0x08,
+        0x01, // map values are always length-prefixed
+        0x2B, 0x2C, // empty anchor, encoded as a group
+    ];
+    let mut encoder = PbEncoder::new(vec![]);
+    shape.encode(&mut encoder).unwrap();
+    assert_eq!(encoder.into_writer(), bytes);
+    assert_eq!(shape.compute_size(), bytes.len());
+
+    let mut decoded = Shape::default();
+    decoded
+        .decode(&mut PbDecoder::new(bytes.as_slice()), bytes.len())
+        .unwrap();
+    assert_eq!(decoded, shape);
+}
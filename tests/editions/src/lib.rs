@@ -1,4 +1,8 @@
 extern crate alloc;
 
+#[cfg(test)]
+mod delimited;
 #[cfg(test)]
 mod scoping;
+#[cfg(test)]
+mod utf8;
@@ -1,4 +1,4 @@
-use micropb::{MessageEncode, PbEncoder};
+use micropb::{MessageDecode, MessageEncode, PbDecoder, PbEncoder};
 
 mod proto {
     #![allow(clippy::all)]
@@ -6,6 +6,12 @@ mod proto {
     include!(concat!(env!("OUT_DIR"), "/scoping.rs"));
 }
 
+mod proto_closed {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/scoping_closed.rs"));
+}
+
 #[test]
 fn check_types() {
     let msg = proto::Example::default();
@@ -15,6 +21,31 @@ fn check_types() {
     let _: Option<_> = inner.status;
 }
 
+#[test]
+fn closed_enum() {
+    use proto_closed::Example_::{Inner, Status};
+
+    let status = |s: Status| match s {
+        Status::Unknown => 0,
+        Status::Active => 1,
+        Status::Inactive => 2,
+    };
+    assert_eq!(status(Status::Inactive), 2);
+
+    let mut inner = Inner::default();
+    inner
+        .decode(&mut PbDecoder::new([0x08, 0x01].as_slice()), 2)
+        .unwrap();
+    assert_eq!(inner.status, Some(Status::Active));
+
+    // Values outside of the closed enum are ignored
+    let mut inner = Inner::default();
+    inner
+        .decode(&mut PbDecoder::new([0x08, 0x05].as_slice()), 2)
+        .unwrap();
+    assert_eq!(inner.status, None);
+}
+
 #[test]
 fn packed() {
     let mut packed = proto::Packed::default();
@@ -0,0 +1,93 @@
+use micropb::{
+    DecodeError, MessageDecode, MessageEncode, PbDecoder, PbEncoder,
+    json::{JsonDecode, JsonEncode},
+    text::TextDecode,
+};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/utf8.rs"));
+}
+
+use proto::utf8_::Log;
+
+const INVALID: &[u8] = &[b'a', 0xFF, b'b'];
+
+fn decode(bytes: &[u8]) -> Result<Log, DecodeError<core::convert::Infallible>> {
+    let mut log = Log::default();
+    log.decode(&mut PbDecoder::new(bytes), bytes.len())?;
+    Ok(log)
+}
+
+#[test]
+fn field_types() {
+    let log = Log::default();
+    let _: &String = &log.checked;
+    let _: &Vec<u8> = &log.raw;
+    let _: &Vec<Vec<u8>> = &log.raw_lines;
+    let _: Option<&Vec<u8>> = log.raw_tags.get(b"key".as_slice());
+    assert_eq!(log.raw_default, "hé".as_bytes());
+}
+
+#[test]
+fn str_accessors() {
+    let mut log = Log::default();
+    assert_eq!(log.raw_str(), None);
+    assert_eq!(log.raw_default_str(), None);
+    assert_eq!(log.raw_implicit_str(), Ok(""));
+
+    log.set_raw(INVALID.to_vec());
+    log.set_raw_default("é".as_bytes().to_vec());
+    log.raw_implicit = "abc".as_bytes().to_vec();
+    assert!(log.raw_str().unwrap().is_err());
+    assert_eq!(log.raw_default_str(), Some(Ok("é")));
+    assert_eq!(log.raw_implicit_str(), Ok("abc"));
+}
+
+#[test]
+fn encode_decode() {
+    let mut log = Log::default();
+    log.set_raw(INVALID.to_vec());
+    log.raw_lines.push(INVALID.to_vec());
+    log.raw_tags.insert(INVALID.to_vec(), INVALID.to_vec());
+
+    let mut encoder = PbEncoder::new(vec![]);
+    log.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_writer();
+    assert_eq!(bytes.len(), log.compute_size());
+    assert_eq!(decode(&bytes).unwrap(), log);
+
+    // Only the validated field rejects invalid UTF-8
+    assert_eq!(
+        decode(&[0x12, 0x03, b'a', 0xFF, b'b']).unwrap().raw(),
+        Some(&INVALID.to_vec())
+    );
+    assert_eq!(
+        decode(&[0x0A, 0x03, b'a', 0xFF, b'b']),
+        Err(DecodeError::Utf8)
+    );
+}
+
+#[test]
+fn text_and_json() {
+    let mut log = Log::default();
+    log.set_raw(INVALID.to_vec());
+    log.set_raw_default("é".as_bytes().to_vec());
+
+    assert_eq!(log.to_string(), "raw: \"a\\377b\"\nraw_default: \"é\"\n");
+    let mut parsed = Log::default();
+    parsed.parse_text(&log.to_string()).unwrap();
+    assert_eq!(parsed, log);
+
+    // JSON has to be valid UTF-8, so invalid sequences are replaced
+    let mut json = String::new();
+    log.write_json(&mut json).unwrap();
+    assert_eq!(json, r#"{"raw":"a�b","rawDefault":"é"}"#);
+    let mut parsed = Log::default();
+    parsed
+        .parse_json(r#"{"raw": "é", "rawTags": {"k": "v"}}"#.as_bytes())
+        .unwrap();
+    assert_eq!(parsed.raw(), Some(&"é".as_bytes().to_vec()));
+    assert_eq!(parsed.raw_tags[b"k".as_slice()], b"v");
+}
@@ -40,4 +40,62 @@ fn main() {
             std::env::var("OUT_DIR").unwrap() + "/serde_proto.rs",
         )
         .unwrap();
+
+    // Generate the serde impls instead
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .serde(true)
+        .single_oneof_msg_as_enum(true)
+        // Enable Eq derive on the proto type
+        .configure(
+            ".Data",
+            Config::new()
+                .type_attributes("#[derive(Eq)]")
+                .max_bytes(4)
+                .max_len(2),
+        )
+        // Don't enable Eq on the enum, which already implements Eq by default
+        .configure(".Data.Enum", Config::new().type_attributes(""))
+        .configure(".Outer.data", Config::new().boxed(true))
+        .configure(".Choice.data", Config::new().boxed(true))
+        .configure(".Outer.counts", Config::new().max_len(2))
+        .configure(".Outer.counts.key", Config::new().max_bytes(4))
+        .configure(".Outer.enums", Config::new().max_len(2));
+
+    generator
+        .compile_protos(
+            &["proto/data.proto", "proto/outer.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/serde_proto_impls.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .serde(true)
+        .configure(".", Config::new().max_bytes(4).max_len(2))
+        .configure(".Data.Enum", Config::new().closed_enum_as_rust_enum(true));
+
+    generator
+        .compile_protos(
+            &["proto/data.proto", "proto/outer.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/serde_proto_closed.rs",
+        )
+        .unwrap();
+
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .serde(true)
+        .configure(".", Config::new().max_bytes(4).max_len(2))
+        .configure(".Data.s", Config::new().validate_utf8(false))
+        .configure(".Outer.counts.key", Config::new().validate_utf8(false));
+
+    generator
+        .compile_protos(
+            &["proto/data.proto", "proto/outer.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/serde_proto_unvalidated.rs",
+        )
+        .unwrap();
 }
@@ -1,11 +1,49 @@
 #![no_std]
 
+extern crate alloc;
+
 mod proto {
     #![allow(clippy::all)]
     #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
     include!(concat!(env!("OUT_DIR"), "/serde_proto.rs"));
 }
 
+mod proto_impls {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/serde_proto_impls.rs"));
+}
+
+mod proto_closed {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/serde_proto_closed.rs"));
+}
+
+mod proto_unvalidated {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/serde_proto_unvalidated.rs"));
+}
+
+#[cfg(test)]
+use proto_impls::{Choice, Data, Data_, Outer};
+
+#[cfg(test)]
+fn round_trip<T>(msg: &T, json: &str)
+where
+    T: micropb::serde::Serialize
+        + micropb::serde::de::DeserializeOwned
+        + PartialEq
+        + core::fmt::Debug,
+{
+    let buf = serde_json_core::ser::to_vec::<_, 200>(msg).unwrap();
+    assert_eq!(core::str::from_utf8(&buf).unwrap(), json);
+    let (decoded, len): (T, _) = serde_json_core::de::from_slice(&buf).unwrap();
+    assert_eq!(len, buf.len());
+    assert_eq!(&decoded, msg);
+}
+
 #[cfg(test)]
 #[test]
 fn serde_test() {
@@ -22,9 +60,119 @@ fn serde_test() {
     assert_eq!(decoded, msg);
 }
 
+#[cfg(test)]
+#[test]
+fn serialize() {
+    let mut msg = Data::default();
+    round_trip(&msg, "{}");
+
+    msg.set_int(12);
+    msg.set_s(heapless::String::try_from("abc").unwrap());
+    msg.list
+        .push(heapless::String::try_from("bc").unwrap())
+        .unwrap();
+    round_trip(&msg, r#"{"int":12,"s":"abc","list":["bc"]}"#);
+
+    msg.set_b(heapless::Vec::from_slice(&[0, 255]).unwrap());
+    msg.inner = Some(Data_::Inner::En(Data_::Enum::One));
+    round_trip(
+        &msg,
+        r#"{"int":12,"s":"abc","b":[0,255],"list":["bc"],"en":"ENUM_ONE"}"#,
+    );
+
+    // Unknown enum values are written as numbers in strings
+    msg.inner = Some(Data_::Inner::En(Data_::Enum(-3)));
+    msg.clear_int();
+    round_trip(&msg, r#"{"s":"abc","b":[0,255],"list":["bc"],"en":"-3"}"#);
+    msg.inner = Some(Data_::Inner::En(Data_::Enum(i32::MIN)));
+    round_trip(
+        &msg,
+        r#"{"s":"abc","b":[0,255],"list":["bc"],"en":"-2147483648"}"#,
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn nested_and_map() {
+    let mut outer = Outer::default();
+    outer.set_data(Data {
+        inner: Some(Data_::Inner::Flag(false)),
+        ..Default::default()
+    });
+    outer.counts.insert("a".try_into().unwrap(), -1).unwrap();
+    outer.enums.push(Data_::Enum::One).unwrap();
+    outer.set_snake_field(5);
+    round_trip(
+        &outer,
+        r#"{"data":{"flag":false},"counts":{"a":-1},"enums":["ENUM_ONE"],"snakeField":5}"#,
+    );
+
+    // Original field names are accepted, and unknown fields are ignored
+    let (decoded, _): (Outer, _) =
+        serde_json_core::de::from_str(r#"{"snake_field":-2,"other":[1,{"x":null}]}"#).unwrap();
+    assert_eq!(decoded.snake_field(), Some(&-2));
+    assert!(decoded.data().is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn single_oneof() {
+    round_trip(&Choice::None, "{}");
+    round_trip(&Choice::Num(3), r#"{"num":3}"#);
+    let mut data = Data::default();
+    data.set_int(1);
+    round_trip(
+        &Choice::Data(alloc::boxed::Box::new(data)),
+        r#"{"data":{"int":1}}"#,
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn capacity_errors() {
+    assert!(serde_json_core::de::from_str::<Data>(r#"{"s":"abcde"}"#).is_err());
+    assert!(serde_json_core::de::from_str::<Data>(r#"{"b":[1,2,3,4,5]}"#).is_err());
+    assert!(serde_json_core::de::from_str::<Data>(r#"{"list":["a","b","c"]}"#).is_err());
+    assert!(serde_json_core::de::from_str::<Outer>(r#"{"counts":{"a":1,"b":2,"c":3}}"#).is_err());
+    assert!(serde_json_core::de::from_str::<Data>(r#"{"en":"ENUM_TWO"}"#).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn closed_enum() {
+    use proto_closed::{Data, Data_};
+
+    let msg = Data {
+        inner: Some(Data_::Inner::En(Data_::Enum::One)),
+        ..Default::default()
+    };
+    round_trip(&msg, r#"{"en":"ENUM_ONE"}"#);
+
+    // Closed enums can't hold values outside of their variants
+    assert!(serde_json_core::de::from_str::<Data>(r#"{"en":"-3"}"#).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn unvalidated_string() {
+    use proto_unvalidated::{Data, Outer};
+
+    let mut msg = Data::default();
+    msg.set_s(heapless::Vec::from_slice(b"ab").unwrap());
+    round_trip(&msg, r#"{"s":"ab"}"#);
+
+    let mut outer = Outer::default();
+    outer
+        .counts
+        .insert(heapless::Vec::from_slice(b"k").unwrap(), 1)
+        .unwrap();
+    round_trip(&outer, r#"{"counts":{"k":1}}"#);
+}
+
 #[cfg(test)]
 #[test]
 fn impl_eq() {
     fn is_eq<T: Eq>() {}
     is_eq::<proto::Data>();
+    is_eq::<Data>();
 }
@@ -0,0 +1,46 @@
+use micropb_gen::{Config, Generator};
+
+fn generate(encode_cache: bool, out: &str) {
+    let mut generator = Generator::new();
+    generator
+        .use_container_alloc()
+        .encode_cache(encode_cache)
+        .add_protoc_arg("-Iproto")
+        .add_protoc_arg("-I../../micropb-types/proto");
+    generator
+        .compile_protos(
+            &["wkt.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/" + out,
+        )
+        .unwrap();
+}
+
+// Generate our own `Any` with fixed-capacity containers, along with a registry of commands
+fn commands() {
+    let mut generator = Generator::new();
+    generator
+        .use_container_heapless()
+        .configure(".google.protobuf.Any.type_url", Config::new().max_bytes(40))
+        .configure(".google.protobuf.Any.value", Config::new().max_bytes(8))
+        .configure(".cmd.Say.text", Config::new().max_bytes(8))
+        .configure_many(
+            &[".cmd.Move", ".cmd.Say", ".cmd.Stop"],
+            Config::new().any_registry(true),
+        )
+        .add_protoc_arg("-Iproto")
+        .add_protoc_arg("-I../../micropb-types/proto");
+    generator
+        .compile_protos(
+            &["command.proto", "google/protobuf/any.proto"],
+            std::env::var("OUT_DIR").unwrap() + "/command.rs",
+        )
+        .unwrap();
+}
+
+fn main() {
+    commands();
+    generate(false, "wkt.rs");
+    // Well-known types should also work with the caching encoder
+    generate(true, "wkt_cached.rs");
+    println!("cargo:rerun-if-changed=proto");
+}
@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+extern crate alloc;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+use micropb::any::PbAny;
+use micropb::{BufferTooSmall, MessageDecode, MessageEncode, MessageName, PbDecoder, PbEncoder};
+use micropb_types::{Any, Struct, Timestamp, Value, Value_};
+
+mod proto {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/wkt.rs"));
+}
+
+mod proto_cached {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/wkt_cached.rs"));
+}
+
+mod command {
+    #![allow(clippy::all)]
+    #![allow(nonstandard_style, unused, irrefutable_let_patterns)]
+    include!(concat!(env!("OUT_DIR"), "/command.rs"));
+}
+
+fn round_trip<M: MessageEncode + MessageDecode + Default + PartialEq + std::fmt::Debug>(msg: &M) {
+    let mut encoder = PbEncoder::new(vec![]);
+    msg.encode(&mut encoder).unwrap();
+    let data = encoder.into_writer();
+    assert_eq!(data.len(), msg.compute_size());
+
+    let mut decoded = M::default();
+    decoded
+        .decode(&mut PbDecoder::new(data.as_slice()), data.len())
+        .unwrap();
+    assert_eq!(&decoded, msg);
+}
+
+#[test]
+fn extern_types() {
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 5);
+    let mut event = proto::wkt_::Event::default();
+    event
+        .set_time(Timestamp::try_from(time).unwrap())
+        .set_elapsed(Duration::from_millis(250).try_into().unwrap())
+        .set_count(micropb_types::Int32Value { value: -3 })
+        .set_name(micropb_types::StringValue {
+            value: "name".into(),
+        })
+        .set_attrs(Struct {
+            fields: BTreeMap::from([(
+                "key".into(),
+                Value {
+                    kind: Some(Value_::Kind::BoolValue(true)),
+                },
+            )]),
+        })
+        .set_detail(Any {
+            type_url: "type.googleapis.com/google.protobuf.Empty".into(),
+            value: vec![],
+        })
+        .set_empty(Default::default());
+    event.values.push(Value {
+        kind: Some(Value_::Kind::StringValue("str".into())),
+    });
+    event.null = micropb_types::NullValue::NullValue;
+    round_trip(&event);
+    assert_eq!(
+        std::time::SystemTime::try_from(*event.time().unwrap()),
+        Ok(time)
+    );
+
+    let mut cached = proto_cached::wkt_::Event::default();
+    cached
+        .set_time(event.time().copied().unwrap())
+        .set_attrs(event.attrs().cloned().unwrap());
+    cached.values = event.values.clone();
+    round_trip(&cached);
+}
+
+#[test]
+fn pack_extern_any() {
+    let mut event = proto::wkt_::Event::default();
+    event.set_count(micropb_types::Int32Value { value: 7 });
+    assert_eq!(proto::wkt_::Event::FULL_NAME, "wkt.Event");
+
+    let any = Any::pack(&event).unwrap();
+    assert_eq!(any.type_url, "type.googleapis.com/wkt.Event");
+    assert!(any.is::<proto::wkt_::Event>());
+    assert!(!any.is::<Timestamp>());
+    assert_eq!(any.unpack(), Ok(Some(event)));
+    assert_eq!(any.unpack::<Timestamp>(), Ok(None));
+}
+
+#[test]
+fn any_registry() {
+    use command::cmd_::{Envelope, Move, Say, Stop};
+    use command::google_::protobuf_::Any as HeaplessAny;
+    use command::AnyMessage;
+
+    let mv = Move { x: -2, y: 3 };
+    let mut envelope = Envelope::default();
+    envelope.set_payload(HeaplessAny::pack(&mv).unwrap());
+    round_trip(&envelope);
+
+    let payload = envelope.payload().unwrap();
+    assert_eq!(payload.type_url.as_str(), "type.googleapis.com/cmd.Move");
+    assert_eq!(AnyMessage::unpack(payload), Ok(Some(AnyMessage::Move(mv))));
+    assert_eq!(AnyMessage::Move(mv).full_name(), Move::FULL_NAME);
+
+    let stop: HeaplessAny = AnyMessage::Stop(Stop {}).pack().unwrap();
+    assert_eq!(stop.value.len(), 0);
+    assert_eq!(
+        AnyMessage::unpack(&stop),
+        Ok(Some(AnyMessage::Stop(Stop {})))
+    );
+
+    // Envelope isn't registered
+    let envelope_any = Any::pack(&Envelope::default()).unwrap();
+    assert_eq!(AnyMessage::unpack(&envelope_any), Ok(None));
+
+    // Message too large for the fixed-capacity value
+    let say = Say {
+        text: heapless::String::try_from("12345678").unwrap(),
+    };
+    assert_eq!(HeaplessAny::pack(&say).err(), Some(BufferTooSmall));
+    assert!(Any::pack(&say).is_ok());
+}